@@ -0,0 +1,103 @@
+//! Short-lived cache of agent availability checks.
+//!
+//! Checking availability spawns the agent binary (`--version` or `--help`).
+//! The web UI and CLI check repeatedly, so results are cached per agent type
+//! and binary path for [`AVAILABILITY_TTL`].
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio::process::Command;
+
+use crate::AgentType;
+
+/// How long an availability result is reused before the binary is probed again.
+pub const AVAILABILITY_TTL: Duration = Duration::from_secs(30);
+
+type CacheKey = (AgentType, PathBuf);
+
+fn cache() -> &'static Mutex<HashMap<CacheKey, (Instant, bool)>> {
+    static CACHE: OnceLock<Mutex<HashMap<CacheKey, (Instant, bool)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Whether `binary probe_arg` exits successfully, using a cached result when
+/// one is fresher than [`AVAILABILITY_TTL`]. `force` always re-probes.
+pub(crate) async fn probe(
+    agent_type: AgentType,
+    binary: &Path,
+    probe_arg: &str,
+    force: bool,
+) -> bool {
+    let key = (agent_type, binary.to_path_buf());
+    if !force {
+        let cached = cache().lock().ok().and_then(|c| c.get(&key).copied());
+        if let Some((checked_at, available)) = cached {
+            if checked_at.elapsed() < AVAILABILITY_TTL {
+                return available;
+            }
+        }
+    }
+
+    let available = Command::new(binary)
+        .arg(probe_arg)
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+
+    if let Ok(mut c) = cache().lock() {
+        c.insert(key, (Instant::now(), available));
+    }
+    available
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// A fake agent binary that appends a line to `calls` every time it runs.
+    fn counting_binary(dir: &Path) -> (PathBuf, PathBuf) {
+        let calls = dir.join("calls");
+        let bin = dir.join("fake-agent");
+        std::fs::write(
+            &bin,
+            format!("#!/bin/sh\necho run >> '{}'\n", calls.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o755)).unwrap();
+        (bin, calls)
+    }
+
+    fn call_count(calls: &Path) -> usize {
+        std::fs::read_to_string(calls)
+            .map(|s| s.lines().count())
+            .unwrap_or(0)
+    }
+
+    #[tokio::test]
+    async fn repeated_checks_spawn_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let (bin, calls) = counting_binary(dir.path());
+
+        assert!(probe(AgentType::OpenCode, &bin, "--version", false).await);
+        assert!(probe(AgentType::OpenCode, &bin, "--version", false).await);
+        assert_eq!(call_count(&calls), 1);
+
+        assert!(probe(AgentType::OpenCode, &bin, "--version", true).await);
+        assert_eq!(call_count(&calls), 2);
+    }
+
+    #[tokio::test]
+    async fn cache_is_keyed_by_agent_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let (bin, calls) = counting_binary(dir.path());
+
+        probe(AgentType::ClaudeCode, &bin, "--version", false).await;
+        probe(AgentType::Cursor, &bin, "--help", false).await;
+        assert_eq!(call_count(&calls), 2);
+    }
+}
@@ -1,12 +1,107 @@
 use async_trait::async_trait;
+use serde_json::Value;
 use std::path::{Path, PathBuf};
-use tokio::process::Command;
 use tracing::debug;
 
+use crate::json_events::{event_callbacks, parse_event};
 use crate::{
-    Agent, AgentConfig, AgentError, AgentOutput, AgentType, OutputCallback, ProcessSpawner,
+    availability, Agent, AgentConfig, AgentError, AgentOutput, AgentType, ExecStatus,
+    OutputCallbacks, ProcessSpawner,
 };
 
+/// Environment variable the CLI reads its response token cap from. The
+/// gateway binary reads the same one.
+pub(crate) const MAX_OUTPUT_TOKENS_ENV: &str = "CLAUDE_CODE_MAX_OUTPUT_TOKENS";
+
+/// Stop reason the API reports when the model declines the prompt.
+const REFUSAL_STOP_REASON: &str = "refusal";
+
+/// Replies the CLI prints with exit code 0 when the request never reached
+/// the model.
+const ERROR_PREFIXES: &[&str] = &["API Error", "Invalid API key", "Credit balance is too low"];
+
+/// Classify a run of the `claude` CLI (or a wrapper around it). Refusals
+/// are read from the stop reason in its `stream-json` output, never from
+/// the reply text.
+pub(crate) fn classify_claude_exit(output: &AgentOutput) -> ExecStatus {
+    if output.stop_reason.as_deref() == Some(REFUSAL_STOP_REASON) {
+        return ExecStatus::Refused;
+    }
+    let reply = output.stdout.trim_start();
+    if output.exit_code != 0 || ERROR_PREFIXES.iter().any(|p| reply.starts_with(p)) {
+        return ExecStatus::Error;
+    }
+    ExecStatus::Ok
+}
+
+/// Arguments making the CLI print one JSON event per line, so the run's
+/// cost can be read from the final `result` event.
+pub(crate) const STREAM_JSON_ARGS: &[&str] = &["--output-format", "stream-json", "--verbose"];
+
+/// The text blocks of an `assistant` event.
+fn assistant_text(event: &Value) -> Option<String> {
+    if event["type"] != "assistant" {
+        return None;
+    }
+    let text: Vec<&str> = event["message"]["content"]
+        .as_array()?
+        .iter()
+        .filter(|block| block["type"] == "text")
+        .filter_map(|block| block["text"].as_str())
+        .collect();
+    (!text.is_empty()).then(|| text.join("\n"))
+}
+
+/// Callbacks that turn `stream-json` events back into the text a
+/// `--print` run would show.
+pub(crate) fn stream_json_callbacks(callbacks: OutputCallbacks) -> OutputCallbacks {
+    event_callbacks(callbacks, assistant_text)
+}
+
+/// Replace the `stream-json` events in `output.stdout` with the reply they
+/// carry, and attach the cost the final `result` event reports and the
+/// stop reason of the last message.
+pub(crate) fn read_stream_json(mut output: AgentOutput) -> AgentOutput {
+    let mut text = Vec::new();
+    let mut result = None;
+    let mut stop_reason = None;
+    let mut saw_event = false;
+    for line in output.stdout.lines() {
+        match parse_event(line) {
+            Some(event) => {
+                saw_event = true;
+                let reason = event["stop_reason"]
+                    .as_str()
+                    .or_else(|| event["message"]["stop_reason"].as_str());
+                if let Some(reason) = reason {
+                    stop_reason = Some(reason.to_string());
+                }
+                if let Some(reply) = assistant_text(&event) {
+                    text.push(reply);
+                } else if event["type"] == "result" {
+                    result = Some(event);
+                }
+            }
+            None => text.push(line.to_string()),
+        }
+    }
+    if !saw_event {
+        return output;
+    }
+    let cost = result
+        .as_ref()
+        .and_then(|event| event["total_cost_usd"].as_f64());
+    output.stdout = match result.as_ref().and_then(|event| event["result"].as_str()) {
+        Some(reply) => reply.to_string(),
+        None => text.join("\n"),
+    };
+    output.stop_reason = stop_reason;
+    match cost {
+        Some(usd) => output.with_estimated_cost(usd),
+        None => output,
+    }
+}
+
 /// Claude Code agent implementation
 pub struct ClaudeCodeAgent {
     binary_path: PathBuf,
@@ -44,20 +139,19 @@ impl Agent for ClaudeCodeAgent {
         &self.binary_path
     }
 
-    async fn is_available(&self) -> bool {
-        Command::new(&self.binary_path)
-            .arg("--version")
-            .output()
-            .await
-            .map(|o| o.status.success())
-            .unwrap_or(false)
+    fn classify_exit(&self, output: &AgentOutput) -> ExecStatus {
+        classify_claude_exit(output)
     }
 
-    async fn execute_with_callback(
+    async fn check_available(&self, force: bool) -> bool {
+        availability::probe(self.agent_type(), &self.binary_path, "--version", force).await
+    }
+
+    async fn execute_with_callbacks(
         &self,
         prompt: &str,
         config: &AgentConfig,
-        on_output: Option<OutputCallback>,
+        callbacks: OutputCallbacks,
     ) -> Result<AgentOutput, AgentError> {
         debug!(
             agent = self.name(),
@@ -69,6 +163,7 @@ impl Agent for ClaudeCodeAgent {
             "--print",                        // Non-interactive mode, output only
             "--dangerously-skip-permissions", // Skip permission prompts
         ];
+        args.extend(STREAM_JSON_ARGS);
 
         // Add model if specified
         let model_arg;
@@ -83,6 +178,100 @@ impl Agent for ClaudeCodeAgent {
         args.push("--");
         args.push(prompt);
 
-        ProcessSpawner::spawn_with_callback(&self.binary_path, &args, config, on_output).await
+        // There is no flag for the output cap; the CLI reads it from the environment
+        let mut config = config.clone();
+        if let Some(tokens) = config.max_output_tokens {
+            config = config.with_env(MAX_OUTPUT_TOKENS_ENV.to_string(), tokens.to_string());
+        }
+
+        let callbacks = stream_json_callbacks(callbacks);
+        ProcessSpawner::spawn_with_callbacks(&self.binary_path, &args, &config, callbacks)
+            .await
+            .map(read_stream_json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OutputType;
+    use std::sync::Arc;
+
+    fn run_output(exit_code: i32, stdout: &str) -> AgentOutput {
+        AgentOutput::new(
+            stdout.to_string(),
+            String::new(),
+            exit_code,
+            std::time::Duration::ZERO,
+        )
+    }
+
+    #[test]
+    fn classifies_claude_runs() {
+        assert_eq!(
+            classify_claude_exit(&run_output(0, "Done.")),
+            ExecStatus::Ok
+        );
+        assert_eq!(classify_claude_exit(&run_output(1, "")), ExecStatus::Error);
+        // The CLI reports API failures on stdout with a zero exit code
+        assert_eq!(
+            classify_claude_exit(&run_output(0, "API Error: 529 Overloaded")),
+            ExecStatus::Error
+        );
+        // A reply quoting a refusal is not one
+        assert_eq!(
+            classify_claude_exit(&run_output(
+                0,
+                "The server answers \"unable to respond to this request\" on bad input"
+            )),
+            ExecStatus::Ok
+        );
+
+        let refused = read_stream_json(run_output(
+            0,
+            &[
+                r#"{"type":"assistant","message":{"content":[{"type":"text","text":"I can't help with that."}],"stop_reason":"refusal"}}"#,
+                r#"{"type":"result","subtype":"success","is_error":false,"result":"I can't help with that.","total_cost_usd":0.01}"#,
+            ]
+            .join("\n"),
+        ));
+        assert_eq!(refused.stop_reason.as_deref(), Some("refusal"));
+        assert_eq!(classify_claude_exit(&refused), ExecStatus::Refused);
+    }
+
+    #[test]
+    fn reads_reply_and_cost_from_stream_json() {
+        let stdout = [
+            r#"{"type":"system","subtype":"init","session_id":"abc"}"#,
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Working on it"},{"type":"tool_use","name":"Edit"}]}}"#,
+            r#"{"type":"result","subtype":"success","is_error":false,"result":"All done","total_cost_usd":0.125}"#,
+        ]
+        .join("\n");
+        let output = read_stream_json(run_output(0, &stdout));
+        assert_eq!(output.stdout, "All done");
+        assert_eq!(output.estimated_cost_usd, Some(0.125));
+
+        // Plain text from a wrapper is left alone
+        let output = read_stream_json(run_output(0, "API Error: 529 Overloaded"));
+        assert_eq!(output.stdout, "API Error: 529 Overloaded");
+        assert_eq!(output.estimated_cost_usd, None);
+    }
+
+    #[test]
+    fn streams_assistant_text_only() {
+        use std::sync::Mutex;
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let seen = lines.clone();
+        let callbacks = stream_json_callbacks(OutputCallbacks::lines(Some(Arc::new(
+            move |line: &str, _| seen.lock().unwrap().push(line.to_string()),
+        ))));
+        let on_line = callbacks.on_line.unwrap();
+        on_line(r#"{"type":"system","subtype":"init"}"#, OutputType::Stdout);
+        on_line(
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"one\ntwo"}]}}"#,
+            OutputType::Stdout,
+        );
+        on_line("plain", OutputType::Stdout);
+        assert_eq!(*lines.lock().unwrap(), vec!["one", "two", "plain"]);
     }
 }
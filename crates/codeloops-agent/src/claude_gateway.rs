@@ -1,10 +1,11 @@
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
-use tokio::process::Command;
 use tracing::debug;
 
+use crate::claude::MAX_OUTPUT_TOKENS_ENV;
 use crate::{
-    Agent, AgentConfig, AgentError, AgentOutput, AgentType, OutputCallback, ProcessSpawner,
+    availability, claude, Agent, AgentConfig, AgentError, AgentOutput, AgentType, ExecStatus,
+    OutputCallbacks, ProcessSpawner,
 };
 
 /// Claude Gateway agent implementation
@@ -49,20 +50,20 @@ impl Agent for ClaudeGatewayAgent {
         &self.binary_path
     }
 
-    async fn is_available(&self) -> bool {
-        Command::new(&self.binary_path)
-            .arg("--version")
-            .output()
-            .await
-            .map(|o| o.status.success())
-            .unwrap_or(false)
+    fn classify_exit(&self, output: &AgentOutput) -> ExecStatus {
+        // The gateway wraps the same CLI and prints its replies unchanged
+        claude::classify_claude_exit(output)
+    }
+
+    async fn check_available(&self, force: bool) -> bool {
+        availability::probe(self.agent_type(), &self.binary_path, "--version", force).await
     }
 
-    async fn execute_with_callback(
+    async fn execute_with_callbacks(
         &self,
         prompt: &str,
         config: &AgentConfig,
-        on_output: Option<OutputCallback>,
+        callbacks: OutputCallbacks,
     ) -> Result<AgentOutput, AgentError> {
         debug!(
             agent = self.name(),
@@ -74,6 +75,7 @@ impl Agent for ClaudeGatewayAgent {
             "--print",                        // Non-interactive mode, output only
             "--dangerously-skip-permissions", // Skip permission prompts
         ];
+        args.extend(claude::STREAM_JSON_ARGS);
 
         // Add model if specified
         let model_arg;
@@ -88,7 +90,16 @@ impl Agent for ClaudeGatewayAgent {
         args.push("--");
         args.push(prompt);
 
-        ProcessSpawner::spawn_with_callback(&self.binary_path, &args, config, on_output).await
+        // There is no flag for the output cap; the CLI reads it from the environment
+        let mut config = config.clone();
+        if let Some(tokens) = config.max_output_tokens {
+            config = config.with_env(MAX_OUTPUT_TOKENS_ENV.to_string(), tokens.to_string());
+        }
+
+        let callbacks = claude::stream_json_callbacks(callbacks);
+        ProcessSpawner::spawn_with_callbacks(&self.binary_path, &args, &config, callbacks)
+            .await
+            .map(claude::read_stream_json)
     }
 }
 
@@ -126,4 +137,24 @@ mod tests {
         let agent = ClaudeGatewayAgent::with_binary_path(PathBuf::from("nonexistent-binary-12345"));
         assert!(!agent.is_available().await);
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn max_output_tokens_passed_via_env() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("fake-gateway");
+        std::fs::write(
+            &bin,
+            format!("#!/bin/sh\necho \"${}\"\n", MAX_OUTPUT_TOKENS_ENV),
+        )
+        .unwrap();
+        std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let agent = ClaudeGatewayAgent::with_binary_path(bin);
+        let config = AgentConfig::new(dir.path().to_path_buf()).with_max_output_tokens(4096);
+        let output = agent.execute("hi", &config).await.unwrap();
+        assert_eq!(output.stdout.trim(), "4096");
+    }
 }
@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use std::path::Path;
+
+use crate::{
+    Agent, AgentConfig, AgentError, AgentOutput, AgentType, CustomAgent, CustomCommand,
+    OutputCallbacks,
+};
+
+/// An agent that runs a user-provided shell script, as given to
+/// `--actor-cmd`.
+///
+/// The prompt is written to a temporary file whose path is set in
+/// `CODELOOPS_PROMPT_FILE`; `{prompt_file}` in the script becomes a quoted
+/// reference to it. The script runs with `sh -c` in the working directory,
+/// and its stdout is the agent's output. Useful for scripted or
+/// deterministic actors and for testing a setup.
+pub struct CommandAgent {
+    script: String,
+    runner: CustomAgent,
+}
+
+impl CommandAgent {
+    pub fn new(script: impl Into<String>) -> Result<Self, AgentError> {
+        let script = script.into();
+        let runner = CustomAgent::new(CustomCommand::shell(&script)?);
+        Ok(Self { script, runner })
+    }
+
+    /// The script as given, before `{prompt_file}` is replaced.
+    pub fn script(&self) -> &str {
+        &self.script
+    }
+}
+
+#[async_trait]
+impl Agent for CommandAgent {
+    fn name(&self) -> &str {
+        "Command"
+    }
+
+    fn agent_type(&self) -> AgentType {
+        AgentType::Command
+    }
+
+    fn binary_path(&self) -> &Path {
+        self.runner.binary_path()
+    }
+
+    async fn check_available(&self, force: bool) -> bool {
+        // Whether the script itself works is only known by running it
+        self.runner.check_available(force).await
+    }
+
+    async fn execute_with_callbacks(
+        &self,
+        prompt: &str,
+        config: &AgentConfig,
+        callbacks: OutputCallbacks,
+    ) -> Result<AgentOutput, AgentError> {
+        self.runner
+            .execute_with_callbacks(prompt, config, callbacks)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn runs_a_shell_script_on_the_prompt_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "from the working dir\n").unwrap();
+        let agent = CommandAgent::new(
+            "cat {prompt_file}; cat notes.txt; test -f \"$CODELOOPS_PROMPT_FILE\" && echo env; echo '{prompt}'",
+        )
+        .unwrap();
+        assert_eq!(agent.name(), "Command");
+        assert_eq!(agent.agent_type(), AgentType::Command);
+        assert!(agent.is_available().await);
+        let config = AgentConfig::new(dir.path().to_path_buf());
+
+        let output = agent.execute("Fix the parser\n", &config).await.unwrap();
+        assert_eq!(output.exit_code, 0);
+        assert_eq!(
+            output.stdout,
+            "Fix the parser\nfrom the working dir\nenv\n{prompt}"
+        );
+
+        let failing = CommandAgent::new("echo oops >&2; exit 3").unwrap();
+        let output = failing.execute("", &config).await.unwrap();
+        assert_eq!(output.exit_code, 3);
+        assert_eq!(output.stderr, "oops");
+
+        assert!(CommandAgent::new("  ").is_err());
+    }
+}
@@ -1,10 +1,10 @@
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
-use tokio::process::Command;
 use tracing::debug;
 
 use crate::{
-    Agent, AgentConfig, AgentError, AgentOutput, AgentType, OutputCallback, ProcessSpawner,
+    availability, Agent, AgentConfig, AgentError, AgentOutput, AgentType, OutputCallbacks,
+    ProcessSpawner,
 };
 
 /// Cursor agent CLI implementation
@@ -44,21 +44,16 @@ impl Agent for CursorAgent {
         &self.binary_path
     }
 
-    async fn is_available(&self) -> bool {
-        // Check if cursor-agent CLI is available
-        Command::new(&self.binary_path)
-            .arg("--help")
-            .output()
-            .await
-            .map(|o| o.status.success())
-            .unwrap_or(false)
+    async fn check_available(&self, force: bool) -> bool {
+        // cursor-agent has no --version; --help exits 0 when installed
+        availability::probe(self.agent_type(), &self.binary_path, "--help", force).await
     }
 
-    async fn execute_with_callback(
+    async fn execute_with_callbacks(
         &self,
         prompt: &str,
         config: &AgentConfig,
-        on_output: Option<OutputCallback>,
+        callbacks: OutputCallbacks,
     ) -> Result<AgentOutput, AgentError> {
         debug!(
             agent = self.name(),
@@ -85,6 +80,6 @@ impl Agent for CursorAgent {
         args.push("--output-format");
         args.push("text");
 
-        ProcessSpawner::spawn_with_callback(&self.binary_path, &args, config, on_output).await
+        ProcessSpawner::spawn_with_callbacks(&self.binary_path, &args, config, callbacks).await
     }
 }
@@ -0,0 +1,482 @@
+use async_trait::async_trait;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+use tracing::debug;
+
+use crate::{
+    Agent, AgentConfig, AgentError, AgentOutput, AgentType, OutputCallbacks, ProcessSpawner,
+};
+
+/// Placeholder in a [`CustomCommand`] template replaced by its binary.
+pub const BINARY_PLACEHOLDER: &str = "{binary}";
+
+/// Placeholder replaced by the prompt, unless it is sent on stdin.
+pub const PROMPT_PLACEHOLDER: &str = "{prompt}";
+
+/// Placeholder replaced by the path of a temporary file holding the prompt.
+pub const PROMPT_FILE_PLACEHOLDER: &str = "{prompt_file}";
+
+/// Placeholder replaced by the working directory.
+pub const CWD_PLACEHOLDER: &str = "{cwd}";
+
+/// Environment variable holding the prompt file path while a command that
+/// uses one runs.
+pub const PROMPT_FILE_ENV: &str = "CODELOOPS_PROMPT_FILE";
+
+/// How to run a CLI that codeloops has no built-in agent for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomCommand {
+    binary: String,
+    /// Words of the command line; the first is the program
+    words: Vec<String>,
+    prompt_via_stdin: bool,
+    /// Whether the prompt is written to a file for the command
+    prompt_file: bool,
+    /// A shell script from `CustomCommand::shell`, passed to `sh -c`
+    /// without placeholder replacement
+    shell: bool,
+}
+
+impl CustomCommand {
+    /// Template used when none is configured.
+    pub const DEFAULT_TEMPLATE: &'static str = "{binary} {prompt}";
+
+    /// A command running `binary` (a name looked up on `PATH`, or a path)
+    /// as `template` describes, e.g. `{binary} --prompt {prompt} --cwd {cwd}`.
+    ///
+    /// The template is split into words the way a shell would, but no shell
+    /// runs it: whitespace separates words, quotes keep whitespace inside
+    /// one, and a backslash escapes the next character outside single
+    /// quotes. Placeholders are replaced within each word, so a prompt or
+    /// path is passed as part of one argument whatever it contains. The
+    /// prompt reaches the command through `{prompt}`, through the file
+    /// named by `{prompt_file}`, or on stdin with `prompt_via_stdin`; at
+    /// least one is required.
+    pub fn new(
+        binary: impl Into<String>,
+        template: &str,
+        prompt_via_stdin: bool,
+    ) -> Result<Self, AgentError> {
+        let binary = binary.into();
+        if binary.trim().is_empty() {
+            return Err(AgentError::ConfigError(
+                "the custom agent needs a binary".to_string(),
+            ));
+        }
+        let words = split_words(template).map_err(|e| {
+            AgentError::ConfigError(format!(
+                "invalid custom agent command '{}': {}",
+                template, e
+            ))
+        })?;
+        if words.is_empty() {
+            return Err(AgentError::ConfigError(
+                "the custom agent command is empty".to_string(),
+            ));
+        }
+        let mentions = |placeholder| words.iter().any(|w| w.contains(placeholder));
+        let prompt_file = mentions(PROMPT_FILE_PLACEHOLDER);
+        if !prompt_via_stdin && !prompt_file && !mentions(PROMPT_PLACEHOLDER) {
+            return Err(AgentError::ConfigError(format!(
+                "the custom agent command '{}' has no {} or {} and the prompt is not sent on stdin",
+                template, PROMPT_PLACEHOLDER, PROMPT_FILE_PLACEHOLDER
+            )));
+        }
+        Ok(Self {
+            binary,
+            words,
+            prompt_via_stdin,
+            prompt_file,
+            shell: false,
+        })
+    }
+
+    /// A shell script run with `sh -c`, for [`crate::CommandAgent`].
+    ///
+    /// The prompt is always written to a file named in `CODELOOPS_PROMPT_FILE`,
+    /// and `{prompt_file}` in the script becomes a quoted reference to that
+    /// variable. Nothing else in the script is replaced.
+    pub(crate) fn shell(script: &str) -> Result<Self, AgentError> {
+        if script.trim().is_empty() {
+            return Err(AgentError::ConfigError(
+                "the actor command is empty".to_string(),
+            ));
+        }
+        let script = script.replace(
+            PROMPT_FILE_PLACEHOLDER,
+            &format!("\"${}\"", PROMPT_FILE_ENV),
+        );
+        Ok(Self {
+            binary: "sh".to_string(),
+            words: vec!["sh".to_string(), "-c".to_string(), script],
+            prompt_via_stdin: false,
+            prompt_file: true,
+            shell: true,
+        })
+    }
+
+    pub fn binary(&self) -> &str {
+        &self.binary
+    }
+
+    pub fn prompt_via_stdin(&self) -> bool {
+        self.prompt_via_stdin
+    }
+
+    /// Whether the command line runs the binary, i.e. mentions `{binary}`.
+    /// A shell script never does.
+    pub fn uses_binary(&self) -> bool {
+        !self.shell && self.words.iter().any(|w| w.contains(BINARY_PLACEHOLDER))
+    }
+
+    /// The program the command line starts with, `{binary}` replaced.
+    pub fn program(&self) -> String {
+        substitute(&self.words[0], &[(BINARY_PLACEHOLDER, &self.binary)])
+    }
+
+    /// The program and its arguments, placeholders replaced. `prompt_file`
+    /// is the path of the prompt file, when the command uses one.
+    fn command_line(
+        &self,
+        prompt: &str,
+        prompt_file: Option<&Path>,
+        working_dir: &Path,
+    ) -> Vec<String> {
+        if self.shell {
+            return self.words.clone();
+        }
+        let cwd = working_dir.to_string_lossy();
+        let prompt_file = prompt_file.map(Path::to_string_lossy).unwrap_or_default();
+        let prompt = if self.prompt_via_stdin { "" } else { prompt };
+        // One pass per word, so a placeholder inside a substituted value
+        // (a prompt mentioning {cwd}) is left alone
+        self.words
+            .iter()
+            .map(|word| {
+                substitute(
+                    word,
+                    &[
+                        (BINARY_PLACEHOLDER, &self.binary),
+                        (PROMPT_FILE_PLACEHOLDER, &prompt_file),
+                        (PROMPT_PLACEHOLDER, prompt),
+                        (CWD_PLACEHOLDER, &cwd),
+                    ],
+                )
+            })
+            .collect()
+    }
+}
+
+/// An agent that runs any CLI from a [`CustomCommand`].
+///
+/// The command runs directly, without a shell unless it is a
+/// [`crate::CommandAgent`] script, in the working directory. Its stdout is
+/// the agent's output. Availability checks that the program the command
+/// line starts with is on `PATH`, and so is the binary when the template
+/// runs it through a wrapper (`env {binary} ...`).
+pub struct CustomAgent {
+    command: CustomCommand,
+    binary_path: PathBuf,
+}
+
+impl CustomAgent {
+    pub fn new(command: CustomCommand) -> Self {
+        Self {
+            binary_path: PathBuf::from(command.program()),
+            command,
+        }
+    }
+
+    pub fn command(&self) -> &CustomCommand {
+        &self.command
+    }
+}
+
+#[async_trait]
+impl Agent for CustomAgent {
+    fn name(&self) -> &str {
+        "Custom"
+    }
+
+    fn agent_type(&self) -> AgentType {
+        AgentType::Custom
+    }
+
+    fn binary_path(&self) -> &Path {
+        &self.binary_path
+    }
+
+    async fn check_available(&self, _force: bool) -> bool {
+        find_on_path(&self.binary_path).is_some()
+            && (!self.command.uses_binary()
+                || find_on_path(Path::new(self.command.binary())).is_some())
+    }
+
+    async fn execute_with_callbacks(
+        &self,
+        prompt: &str,
+        config: &AgentConfig,
+        callbacks: OutputCallbacks,
+    ) -> Result<AgentOutput, AgentError> {
+        let command = &self.command;
+        debug!(
+            agent = self.name(),
+            program = %self.binary_path.display(),
+            prompt_len = prompt.len(),
+            "Executing agent"
+        );
+
+        let prompt_file = match command.prompt_file {
+            true => Some(write_prompt_file(prompt)?),
+            false => None,
+        };
+        let mut config = config.clone();
+        if let Some(ref file) = prompt_file {
+            config = config.with_env(
+                PROMPT_FILE_ENV.to_string(),
+                file.path().to_string_lossy().into_owned(),
+            );
+        }
+        let line = command.command_line(
+            prompt,
+            prompt_file.as_ref().map(NamedTempFile::path),
+            &config.working_dir,
+        );
+        let args: Vec<&str> = line[1..].iter().map(String::as_str).collect();
+        ProcessSpawner::spawn_with_input(
+            Path::new(&line[0]),
+            &args,
+            &config,
+            command.prompt_via_stdin().then_some(prompt),
+            callbacks,
+        )
+        .await
+    }
+}
+
+/// Write `prompt` to a new temporary file, removed when dropped. The file
+/// gets a random name and is readable only by the current user, so other
+/// users can neither read the prompt nor plant a file in its place.
+fn write_prompt_file(prompt: &str) -> Result<NamedTempFile, AgentError> {
+    let mut file = tempfile::Builder::new()
+        .prefix("codeloops-prompt-")
+        .suffix(".md")
+        .tempfile()?;
+    file.write_all(prompt.as_bytes())?;
+    file.flush()?;
+    Ok(file)
+}
+
+/// `word` with each placeholder replaced, scanning left to right.
+fn substitute(word: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(word.len());
+    let mut rest = word;
+    'scan: while !rest.is_empty() {
+        for (placeholder, value) in values {
+            if let Some(after) = rest.strip_prefix(placeholder) {
+                out.push_str(value);
+                rest = after;
+                continue 'scan;
+            }
+        }
+        let mut chars = rest.chars();
+        out.extend(chars.next());
+        rest = chars.as_str();
+    }
+    out
+}
+
+/// Split `template` into words as a POSIX shell would, without expansion.
+fn split_words(template: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let current = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let current = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("unterminated double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => word.get_or_insert_with(String::new).push(c),
+                None => return Err("trailing backslash".to_string()),
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// `binary` as found on `PATH`, or itself when it is a path to a file.
+fn find_on_path(binary: &Path) -> Option<PathBuf> {
+    if binary.as_os_str().is_empty() {
+        return None;
+    }
+    if binary.components().count() > 1 {
+        return is_executable(binary).then(|| binary.to_path_buf());
+    }
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_split_like_a_shell() {
+        assert_eq!(
+            split_words(r#"{binary} --prompt {prompt}  -m "two words" 'it''s' a\ b"#).unwrap(),
+            vec![
+                "{binary}",
+                "--prompt",
+                "{prompt}",
+                "-m",
+                "two words",
+                "its",
+                "a b"
+            ]
+        );
+        assert_eq!(split_words(r#"x "" y"#).unwrap(), vec!["x", "", "y"]);
+        assert!(split_words("x 'open").is_err());
+    }
+
+    #[test]
+    fn placeholders_become_single_arguments() {
+        let command =
+            CustomCommand::new("mycli", "{binary} --prompt={prompt} --cwd {cwd}", false).unwrap();
+        let prompt = "Fix 'it'; rm -rf {cwd} $(x)";
+        assert_eq!(
+            command.command_line(prompt, None, Path::new("/work/my repo")),
+            vec![
+                "mycli".to_string(),
+                format!("--prompt={}", prompt),
+                "--cwd".to_string(),
+                "/work/my repo".to_string(),
+            ]
+        );
+
+        assert!(CustomCommand::new("mycli", "{binary} --yes", false).is_err());
+        assert!(CustomCommand::new("mycli", "{binary} --yes", true).is_ok());
+        assert!(CustomCommand::new("mycli", "{binary} -f {prompt_file}", false).is_ok());
+        assert!(CustomCommand::new("", CustomCommand::DEFAULT_TEMPLATE, false).is_err());
+        assert!(CustomCommand::shell("  ").is_err());
+    }
+
+    #[tokio::test]
+    async fn runs_the_command_with_the_prompt_as_argument_file_or_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AgentConfig::new(dir.path().to_path_buf());
+
+        let agent = CustomAgent::new(
+            CustomCommand::new(
+                "sh",
+                "{binary} -c 'printf \"%s|\" \"$1\"; pwd' sh {prompt}",
+                false,
+            )
+            .unwrap(),
+        );
+        assert!(agent.is_available().await);
+        let output = agent.execute("it's a \"prompt\"", &config).await.unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+        assert_eq!(
+            output.stdout,
+            format!("it's a \"prompt\"|{}", cwd.display())
+        );
+
+        let agent = CustomAgent::new(CustomCommand::new("cat", "{binary}", true).unwrap());
+        let output = agent
+            .execute("line one\nline two\n", &config)
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, "line one\nline two");
+
+        let agent =
+            CustomAgent::new(CustomCommand::new("cat", "{binary} {prompt_file}", false).unwrap());
+        let output = agent.execute("from a file\n", &config).await.unwrap();
+        assert_eq!(output.stdout, "from a file");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn prompt_files_are_private_and_removed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let file = write_prompt_file("secret").unwrap();
+        let other = write_prompt_file("secret").unwrap();
+        assert_ne!(file.path(), other.path());
+        let mode = file.path().metadata().unwrap().permissions().mode();
+        assert_eq!(mode & 0o077, 0, "mode {:o}", mode);
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "secret");
+
+        let path = file.path().to_path_buf();
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn availability_checks_the_program_and_binary_on_path() {
+        let available = |binary, template| async move {
+            let command = CustomCommand::new(binary, template, false).unwrap();
+            CustomAgent::new(command).is_available().await
+        };
+        assert!(available("sh", "{binary} {prompt}").await);
+        assert!(!available("no-such-cli-12345", "{binary} {prompt}").await);
+
+        // The program is the first word, which need not be the binary
+        assert!(!available("sh", "no-such-wrapper-12345 {binary} {prompt}").await);
+        assert!(available("sh", "env {binary} {prompt}").await);
+        assert!(!available("no-such-cli-12345", "env {binary} {prompt}").await);
+
+        // A template that never runs the binary does not need it
+        assert!(available("no-such-cli-12345", "sh -c true {prompt}").await);
+        assert!(
+            CustomAgent::new(CustomCommand::shell("true").unwrap())
+                .is_available()
+                .await
+        );
+    }
+}
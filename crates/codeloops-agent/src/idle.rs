@@ -0,0 +1,114 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::{OutputCallback, OutputType};
+
+/// Watches an agent's streamed output and reports stretches without any.
+///
+/// Helps tell a slow-but-working agent from a stuck one. The monitor stops
+/// when dropped.
+pub struct IdleMonitor {
+    task: JoinHandle<()>,
+}
+
+impl IdleMonitor {
+    /// Wrap `inner` so every streamed line resets the idle timer.
+    ///
+    /// `on_idle` is called with the time since the last line each time
+    /// `threshold` passes without output (after 30s, 60s, ... for a 30s
+    /// threshold). Must be called within a tokio runtime.
+    pub fn watch(
+        threshold: Duration,
+        inner: Option<OutputCallback>,
+        on_idle: impl Fn(Duration) + Send + Sync + 'static,
+    ) -> (OutputCallback, IdleMonitor) {
+        let last_output = Arc::new(Mutex::new(Instant::now()));
+
+        let seen_by_callback = last_output.clone();
+        let callback: OutputCallback = Arc::new(move |line: &str, output_type: OutputType| {
+            if let Ok(mut last) = seen_by_callback.lock() {
+                *last = Instant::now();
+            }
+            if let Some(ref inner) = inner {
+                inner(line, output_type);
+            }
+        });
+
+        let task = tokio::spawn(async move {
+            let latest = || {
+                last_output
+                    .lock()
+                    .map(|last| *last)
+                    .unwrap_or_else(|_| Instant::now())
+            };
+            let mut seen = latest();
+            let mut deadline = seen + threshold;
+            loop {
+                tokio::time::sleep_until(deadline).await;
+                let last = latest();
+                if last != seen {
+                    seen = last;
+                    deadline = last + threshold;
+                    continue;
+                }
+                on_idle(last.elapsed());
+                deadline += threshold;
+            }
+        });
+
+        (callback, IdleMonitor { task })
+    }
+}
+
+impl Drop for IdleMonitor {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn counter() -> (Arc<AtomicUsize>, impl Fn(Duration) + Send + Sync + 'static) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let seen = count.clone();
+        (count, move |_| {
+            seen.fetch_add(1, Ordering::SeqCst);
+        })
+    }
+
+    #[tokio::test]
+    async fn warns_repeatedly_while_silent() {
+        let (count, on_idle) = counter();
+        let (_callback, monitor) = IdleMonitor::watch(Duration::from_millis(40), None, on_idle);
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(count.load(Ordering::SeqCst) >= 2);
+
+        drop(monitor);
+        let after_drop = count.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(count.load(Ordering::SeqCst), after_drop);
+    }
+
+    #[tokio::test]
+    async fn output_resets_the_timer_and_reaches_inner() {
+        let (count, on_idle) = counter();
+        let lines = Arc::new(AtomicUsize::new(0));
+        let inner_lines = lines.clone();
+        let inner: OutputCallback = Arc::new(move |_: &str, _: OutputType| {
+            inner_lines.fetch_add(1, Ordering::SeqCst);
+        });
+        let (callback, _monitor) =
+            IdleMonitor::watch(Duration::from_millis(80), Some(inner), on_idle);
+        for _ in 0..6 {
+            tokio::time::sleep(Duration::from_millis(25)).await;
+            callback("working", OutputType::Stdout);
+        }
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        assert_eq!(lines.load(Ordering::SeqCst), 6);
+    }
+}
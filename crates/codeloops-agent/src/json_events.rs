@@ -0,0 +1,50 @@
+//! Agents that print one JSON event per line instead of plain text.
+
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::{OutputCallback, OutputCallbacks, OutputType};
+
+/// A line of JSON event output, if it is one: an object with a string
+/// `type`.
+pub(crate) fn parse_event(line: &str) -> Option<Value> {
+    if !line.starts_with('{') {
+        return None;
+    }
+    serde_json::from_str::<Value>(line)
+        .ok()
+        .filter(|event| event.get("type").is_some_and(Value::is_string))
+}
+
+/// Callbacks that show the text `text_of` finds in each event, instead of
+/// the events themselves. Events without text are dropped; lines that are
+/// not events (a wrapper's own output) pass through unchanged.
+pub(crate) fn event_callbacks(
+    callbacks: OutputCallbacks,
+    text_of: fn(&Value) -> Option<String>,
+) -> OutputCallbacks {
+    if callbacks.on_line.is_none() && callbacks.on_chunk.is_none() {
+        return callbacks;
+    }
+    let on_line: OutputCallback = Arc::new(move |line: &str, output_type| {
+        let text = match output_type {
+            OutputType::Stdout => match parse_event(line) {
+                Some(event) => match text_of(&event) {
+                    Some(text) => text,
+                    None => return,
+                },
+                None => line.to_string(),
+            },
+            OutputType::Stderr => line.to_string(),
+        };
+        if let Some(ref cb) = callbacks.on_line {
+            for line in text.lines() {
+                cb(line, output_type);
+            }
+        }
+        if let Some(ref cb) = callbacks.on_chunk {
+            cb(&format!("{}\n", text), output_type);
+        }
+    });
+    OutputCallbacks::lines(Some(on_line))
+}
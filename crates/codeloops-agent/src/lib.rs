@@ -19,6 +19,8 @@
 //! | Claude Gateway | [`AgentType::ClaudeGateway`] | `claude-gateway` |
 //! | OpenCode | [`AgentType::OpenCode`] | `opencode` |
 //! | Cursor | [`AgentType::Cursor`] | `cursor` |
+//! | Command | [`AgentType::Command`] | `sh -c` on a user-provided script |
+//! | Custom | [`AgentType::Custom`] | Any CLI, from a command template |
 //!
 //! ## Usage
 //!
@@ -27,7 +29,7 @@
 //! use std::path::PathBuf;
 //!
 //! // Create an agent
-//! let agent = create_agent(AgentType::ClaudeCode);
+//! let agent = create_agent(AgentType::ClaudeCode)?;
 //!
 //! // Configure the execution context
 //! let config = AgentConfig::new(PathBuf::from("."));
@@ -44,30 +46,55 @@
 //! To add a new agent, implement the [`Agent`] trait. See the contributing
 //! guide for detailed instructions.
 
+pub mod availability;
 mod claude;
 mod claude_gateway;
+mod command;
 mod cursor;
+mod custom;
+mod idle;
+mod json_events;
 mod opencode;
 mod output;
 mod spawner;
+pub mod text;
 mod traits;
 
 pub use claude::ClaudeCodeAgent;
 pub use claude_gateway::ClaudeGatewayAgent;
+pub use command::CommandAgent;
 pub use cursor::CursorAgent;
+pub use custom::{
+    CustomAgent, CustomCommand, BINARY_PLACEHOLDER, CWD_PLACEHOLDER, PROMPT_FILE_ENV,
+    PROMPT_FILE_PLACEHOLDER, PROMPT_PLACEHOLDER,
+};
+pub use idle::IdleMonitor;
 pub use opencode::OpenCodeAgent;
 pub use output::AgentOutput;
-pub use spawner::{OutputCallback, OutputType, ProcessSpawner};
-pub use traits::{Agent, AgentConfig, AgentError, AgentType};
+pub use spawner::{OutputCallback, OutputCallbacks, OutputType, ProcessSpawner};
+pub use traits::{Agent, AgentConfig, AgentError, AgentType, ExecStatus};
 
-/// Create an agent by type
-pub fn create_agent(agent_type: AgentType) -> Box<dyn Agent> {
-    match agent_type {
+/// Create an agent by type. [`AgentType::Command`] and [`AgentType::Custom`]
+/// run a command this has no way to know, so they are a
+/// [`AgentError::ConfigError`] here; build them with [`CommandAgent::new`]
+/// and [`CustomAgent::new`].
+pub fn create_agent(agent_type: AgentType) -> Result<Box<dyn Agent>, AgentError> {
+    Ok(match agent_type {
         AgentType::ClaudeCode => Box::new(ClaudeCodeAgent::new()),
         AgentType::ClaudeGateway => Box::new(ClaudeGatewayAgent::new()),
         AgentType::OpenCode => Box::new(OpenCodeAgent::new()),
         AgentType::Cursor => Box::new(CursorAgent::new()),
-    }
+        AgentType::Command => {
+            return Err(AgentError::ConfigError(
+                "the command agent needs a script; build it with CommandAgent::new".to_string(),
+            ))
+        }
+        AgentType::Custom => {
+            return Err(AgentError::ConfigError(
+                "the custom agent needs a command; build it with CustomAgent::new".to_string(),
+            ))
+        }
+    })
 }
 
 #[cfg(test)]
@@ -76,8 +103,10 @@ mod tests {
 
     #[test]
     fn create_agent_claude_gateway() {
-        let agent = create_agent(AgentType::ClaudeGateway);
+        let agent = create_agent(AgentType::ClaudeGateway).unwrap();
         assert_eq!(agent.agent_type(), AgentType::ClaudeGateway);
         assert_eq!(agent.name(), "Claude Gateway");
+        assert!(create_agent(AgentType::Command).is_err());
+        assert!(create_agent(AgentType::Custom).is_err());
     }
 }
@@ -1,12 +1,54 @@
 use async_trait::async_trait;
+use serde_json::Value;
 use std::path::{Path, PathBuf};
-use tokio::process::Command;
 use tracing::debug;
 
+use crate::json_events::{event_callbacks, parse_event};
 use crate::{
-    Agent, AgentConfig, AgentError, AgentOutput, AgentType, OutputCallback, ProcessSpawner,
+    availability, Agent, AgentConfig, AgentError, AgentOutput, AgentType, OutputCallbacks,
+    ProcessSpawner,
 };
 
+/// Arguments making `opencode run` print one JSON event per line, each
+/// carrying the id of the session it belongs to.
+const FORMAT_JSON_ARGS: &[&str] = &["--format", "json"];
+
+/// The reply text of a `text` event.
+fn event_text(event: &Value) -> Option<String> {
+    if event["type"] != "text" {
+        return None;
+    }
+    event["part"]["text"].as_str().map(str::to_string)
+}
+
+/// Replace the JSON events in `output.stdout` with the reply text they
+/// carry, and attach the session id they report.
+fn read_json_events(mut output: AgentOutput) -> AgentOutput {
+    let mut text = Vec::new();
+    let mut session_id = None;
+    let mut saw_event = false;
+    for line in output.stdout.lines() {
+        match parse_event(line) {
+            Some(event) => {
+                saw_event = true;
+                if let Some(id) = event["sessionID"].as_str() {
+                    session_id = Some(id.to_string());
+                }
+                text.extend(event_text(&event));
+            }
+            None => text.push(line.to_string()),
+        }
+    }
+    if !saw_event {
+        return output;
+    }
+    output.stdout = text.join("\n");
+    match session_id {
+        Some(id) => output.with_session_id(id),
+        None => output,
+    }
+}
+
 /// OpenCode agent implementation
 pub struct OpenCodeAgent {
     binary_path: PathBuf,
@@ -44,20 +86,19 @@ impl Agent for OpenCodeAgent {
         &self.binary_path
     }
 
-    async fn is_available(&self) -> bool {
-        Command::new(&self.binary_path)
-            .arg("--version")
-            .output()
-            .await
-            .map(|o| o.status.success())
-            .unwrap_or(false)
+    fn supports_continuation(&self) -> bool {
+        true
     }
 
-    async fn execute_with_callback(
+    async fn check_available(&self, force: bool) -> bool {
+        availability::probe(self.agent_type(), &self.binary_path, "--version", force).await
+    }
+
+    async fn execute_with_callbacks(
         &self,
         prompt: &str,
         config: &AgentConfig,
-        on_output: Option<OutputCallback>,
+        callbacks: OutputCallbacks,
     ) -> Result<AgentOutput, AgentError> {
         debug!(
             agent = self.name(),
@@ -67,6 +108,7 @@ impl Agent for OpenCodeAgent {
 
         // OpenCode uses the "run" subcommand for non-interactive execution
         let mut args = vec!["run"];
+        args.extend(FORMAT_JSON_ARGS);
 
         // Add model if specified
         let model_arg;
@@ -76,11 +118,70 @@ impl Agent for OpenCodeAgent {
             args.push(&model_arg);
         }
 
+        if let Some(ref session) = config.resume_session {
+            args.push("--session");
+            args.push(session);
+        }
+
         // Add -- to signal end of options, then the prompt as positional argument
         // OpenCode expects the message as a positional arg: `opencode run [message..]`
         args.push("--");
         args.push(prompt);
 
-        ProcessSpawner::spawn_with_callback(&self.binary_path, &args, config, on_output).await
+        let callbacks = event_callbacks(callbacks, event_text);
+        ProcessSpawner::spawn_with_callbacks(&self.binary_path, &args, config, callbacks)
+            .await
+            .map(read_json_events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn resumes_the_session_its_first_run_reported() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("fake-opencode");
+        std::fs::write(
+            &bin,
+            "#!/bin/sh\n\
+             echo \"args: $*\" >&2\n\
+             echo '{\"type\":\"step_start\",\"sessionID\":\"ses_1\",\"part\":{}}'\n\
+             echo '{\"type\":\"text\",\"sessionID\":\"ses_1\",\"part\":{\"type\":\"text\",\"text\":\"Done.\"}}'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let agent = OpenCodeAgent::with_binary_path(bin);
+        assert!(agent.supports_continuation());
+        let config = AgentConfig::new(dir.path().to_path_buf());
+        let output = agent.execute("hi", &config).await.unwrap();
+        assert_eq!(output.stdout, "Done.");
+        assert_eq!(output.session_id.as_deref(), Some("ses_1"));
+        assert_eq!(output.stderr.trim(), "args: run --format json -- hi");
+
+        let config = config.with_resume_session("ses_1");
+        let output = agent.execute("hi", &config).await.unwrap();
+        assert_eq!(
+            output.stderr.trim(),
+            "args: run --format json --session ses_1 -- hi"
+        );
+    }
+
+    #[test]
+    fn plain_output_is_kept() {
+        let output = AgentOutput::new(
+            "not json\n".to_string(),
+            String::new(),
+            0,
+            std::time::Duration::ZERO,
+        );
+        let output = read_json_events(output);
+        assert_eq!(output.stdout, "not json\n");
+        assert_eq!(output.session_id, None);
     }
 }
@@ -13,6 +13,21 @@ pub struct AgentOutput {
     /// Duration of execution
     #[serde(with = "humantime_serde_compat")]
     pub duration: Duration,
+    /// Estimated cost of the run in USD, for agents that report usage
+    #[serde(default)]
+    pub estimated_cost_usd: Option<f64>,
+    /// Whether stdout or stderr had bytes that were not valid UTF-8; they
+    /// were replaced with U+FFFD
+    #[serde(default)]
+    pub output_was_lossy: bool,
+    /// Why the model stopped (e.g. `end_turn`, `refusal`), for agents that
+    /// report it
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+    /// The agent's id for the conversation this run had, for agents that can
+    /// resume one (see [`AgentConfig::resume_session`](crate::AgentConfig::resume_session))
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
 impl AgentOutput {
@@ -22,9 +37,37 @@ impl AgentOutput {
             stderr,
             exit_code,
             duration,
+            estimated_cost_usd: None,
+            output_was_lossy: false,
+            stop_reason: None,
+            session_id: None,
         }
     }
 
+    /// Attach the cost the agent reported for this run.
+    pub fn with_estimated_cost(mut self, usd: f64) -> Self {
+        self.estimated_cost_usd = Some(usd);
+        self
+    }
+
+    /// Attach the reason the model reported for stopping.
+    pub fn with_stop_reason(mut self, reason: impl Into<String>) -> Self {
+        self.stop_reason = Some(reason.into());
+        self
+    }
+
+    /// Attach the id of the conversation the run had.
+    pub fn with_session_id(mut self, id: impl Into<String>) -> Self {
+        self.session_id = Some(id.into());
+        self
+    }
+
+    /// Mark the output as decoded with replacement characters.
+    pub fn with_output_was_lossy(mut self, lossy: bool) -> Self {
+        self.output_was_lossy = lossy;
+        self
+    }
+
     /// Check if the agent exited successfully
     pub fn success(&self) -> bool {
         self.exit_code == 0
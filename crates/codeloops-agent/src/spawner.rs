@@ -2,12 +2,15 @@ use std::path::Path;
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::process::Command;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 use crate::{AgentConfig, AgentError, AgentOutput};
 
+/// Bytes read from an output stream at a time
+const READ_BUFFER_SIZE: usize = 8192;
+
 /// Type of output line (stdout or stderr)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputType {
@@ -19,6 +22,34 @@ pub enum OutputType {
 /// Takes the line content and the output type
 pub type OutputCallback = Arc<dyn Fn(&str, OutputType) + Send + Sync>;
 
+/// Callbacks for an agent's output as it is produced.
+///
+/// `on_line` gets each complete line without its newline. `on_chunk` gets
+/// text as soon as it is read, newlines included, so an agent that prints
+/// partial tokens can be shown without waiting for the end of the line.
+/// Concatenating the chunks of a stream gives its full output.
+#[derive(Clone, Default)]
+pub struct OutputCallbacks {
+    pub on_line: Option<OutputCallback>,
+    pub on_chunk: Option<OutputCallback>,
+}
+
+impl OutputCallbacks {
+    /// Only whole lines, as [`ProcessSpawner::spawn_with_callback`] gives.
+    pub fn lines(on_line: Option<OutputCallback>) -> Self {
+        Self {
+            on_line,
+            on_chunk: None,
+        }
+    }
+
+    /// Also receive partial chunks.
+    pub fn with_chunks(mut self, on_chunk: OutputCallback) -> Self {
+        self.on_chunk = Some(on_chunk);
+        self
+    }
+}
+
 /// Utility for spawning agent processes
 pub struct ProcessSpawner;
 
@@ -29,7 +60,7 @@ impl ProcessSpawner {
         args: &[&str],
         config: &AgentConfig,
     ) -> Result<AgentOutput, AgentError> {
-        Self::spawn_with_callback(binary, args, config, None).await
+        Self::spawn_with_callbacks(binary, args, config, OutputCallbacks::default()).await
     }
 
     /// Spawn a process with an optional callback for streaming output
@@ -38,6 +69,36 @@ impl ProcessSpawner {
         args: &[&str],
         config: &AgentConfig,
         on_output: Option<OutputCallback>,
+    ) -> Result<AgentOutput, AgentError> {
+        Self::spawn_with_callbacks(binary, args, config, OutputCallbacks::lines(on_output)).await
+    }
+
+    /// Spawn a process, streaming its output by line and/or by chunk.
+    ///
+    /// With a [`timeout`](AgentConfig::timeout), a process still running at
+    /// the deadline, counted from before it is spawned, is killed and reaped,
+    /// and [`AgentError::Timeout`] is returned. On Unix the process leads its
+    /// own process group, and everything left in the group is killed with
+    /// it, including processes it started. The same happens when the
+    /// returned future is dropped before the process exits.
+    pub async fn spawn_with_callbacks(
+        binary: &Path,
+        args: &[&str],
+        config: &AgentConfig,
+        callbacks: OutputCallbacks,
+    ) -> Result<AgentOutput, AgentError> {
+        Self::spawn_with_input(binary, args, config, None, callbacks).await
+    }
+
+    /// Like [`spawn_with_callbacks`](Self::spawn_with_callbacks), writing
+    /// `input` to the process's stdin and then closing it. Without input,
+    /// stdin is empty.
+    pub async fn spawn_with_input(
+        binary: &Path,
+        args: &[&str],
+        config: &AgentConfig,
+        input: Option<&str>,
+        callbacks: OutputCallbacks,
     ) -> Result<AgentOutput, AgentError> {
         let start = Instant::now();
 
@@ -49,11 +110,20 @@ impl ProcessSpawner {
         );
 
         let mut cmd = Command::new(binary);
+        // Its own process group, so a timeout also kills what it started
+        #[cfg(unix)]
+        cmd.process_group(0);
         cmd.args(args)
             .current_dir(&config.working_dir)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .stdin(Stdio::null()); // Non-interactive
+            // Non-interactive
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .kill_on_drop(true);
 
         // Add environment variables
         for (key, value) in &config.env_vars {
@@ -61,83 +131,65 @@ impl ProcessSpawner {
         }
 
         let mut child = cmd.spawn()?;
+        let mut group = GroupGuard(child.id());
+
+        // Written alongside reading the output, so a process that answers
+        // before reading all of its input cannot deadlock
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            let input = input.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = stdin.write_all(input.as_bytes()).await {
+                    debug!(error = %e, "Agent did not read all of its input");
+                }
+            });
+        }
 
         // Capture stdout and stderr
         let stdout_handle = child.stdout.take().expect("stdout not captured");
         let stderr_handle = child.stderr.take().expect("stderr not captured");
 
-        let mut stdout_reader = BufReader::new(stdout_handle).lines();
-        let mut stderr_reader = BufReader::new(stderr_handle).lines();
-
-        let mut stdout = String::new();
-        let mut stderr = String::new();
-
-        // Read both streams concurrently
-        loop {
-            tokio::select! {
-                biased;
-
-                result = stdout_reader.next_line() => {
-                    match result {
-                        Ok(Some(line)) => {
-                            trace!(line = %line, "stdout");
-                            if let Some(ref cb) = on_output {
-                                cb(&line, OutputType::Stdout);
-                            }
-                            if !stdout.is_empty() {
-                                stdout.push('\n');
-                            }
-                            stdout.push_str(&line);
-                        }
-                        Ok(None) => {
-                            // stdout closed, wait for stderr to close too
-                            while let Ok(Some(line)) = stderr_reader.next_line().await {
-                                trace!(line = %line, "stderr");
-                                if let Some(ref cb) = on_output {
-                                    cb(&line, OutputType::Stderr);
-                                }
-                                if !stderr.is_empty() {
-                                    stderr.push('\n');
-                                }
-                                stderr.push_str(&line);
-                            }
-                            break;
-                        }
-                        Err(e) => {
-                            return Err(AgentError::ExecutionFailed(format!(
-                                "Failed to read stdout: {}",
-                                e
-                            )));
-                        }
+        let mut stdout = OutputStream::new(stdout_handle, OutputType::Stdout);
+        let mut stderr = OutputStream::new(stderr_handle, OutputType::Stderr);
+        let mut lossy = false;
+
+        // Read both streams concurrently until both are closed
+        let run = async {
+            while !(stdout.closed && stderr.closed) {
+                tokio::select! {
+                    biased;
+
+                    result = stdout.read(), if !stdout.closed => {
+                        stdout.handle(result, &callbacks, &mut lossy)?;
                     }
-                }
-                result = stderr_reader.next_line() => {
-                    match result {
-                        Ok(Some(line)) => {
-                            trace!(line = %line, "stderr");
-                            if let Some(ref cb) = on_output {
-                                cb(&line, OutputType::Stderr);
-                            }
-                            if !stderr.is_empty() {
-                                stderr.push('\n');
-                            }
-                            stderr.push_str(&line);
-                        }
-                        Ok(None) => {
-                            // stderr closed, continue reading stdout
-                        }
-                        Err(e) => {
-                            return Err(AgentError::ExecutionFailed(format!(
-                                "Failed to read stderr: {}",
-                                e
-                            )));
-                        }
+                    result = stderr.read(), if !stderr.closed => {
+                        stderr.handle(result, &callbacks, &mut lossy)?;
                     }
                 }
             }
-        }
+            Ok::<_, AgentError>(child.wait().await?)
+        };
 
-        let status = child.wait().await?;
+        let status = match config.timeout {
+            Some(limit) => {
+                match tokio::time::timeout(limit.saturating_sub(start.elapsed()), run).await {
+                    Ok(status) => status?,
+                    Err(_) => {
+                        warn!(
+                            timeout_secs = limit.as_secs_f64(),
+                            "Agent timed out; killing it"
+                        );
+                        group.kill();
+                        // `kill` also waits, reaping the process so no zombie is left
+                        child.kill().await?;
+                        return Err(AgentError::Timeout(limit));
+                    }
+                }
+            }
+            None => run.await?,
+        };
+        // Exited on its own; anything it left running in the background is
+        // its business
+        group.0 = None;
         let duration = start.elapsed();
 
         debug!(
@@ -146,11 +198,355 @@ impl ProcessSpawner {
             "Agent process completed"
         );
 
+        if lossy {
+            warn!("Agent output was not valid UTF-8; invalid bytes were replaced");
+        }
+
         Ok(AgentOutput::new(
-            stdout,
-            stderr,
+            stdout.text,
+            stderr.text,
             status.code().unwrap_or(-1),
             duration,
-        ))
+        )
+        .with_output_was_lossy(lossy))
+    }
+}
+
+/// One of the child's output streams, read in chunks.
+///
+/// Bytes are read as they arrive rather than a line at a time. Bytes after
+/// the last newline wait in `partial_line` for the rest of their line, and
+/// an incomplete UTF-8 sequence at the end of a chunk waits in
+/// `partial_char` so a character split across reads is not mangled.
+struct OutputStream<R> {
+    reader: R,
+    output_type: OutputType,
+    buf: Box<[u8; READ_BUFFER_SIZE]>,
+    partial_line: Vec<u8>,
+    partial_char: Vec<u8>,
+    /// Complete lines so far, joined with `\n`
+    text: String,
+    closed: bool,
+}
+
+impl<R: AsyncRead + Unpin> OutputStream<R> {
+    fn new(reader: R, output_type: OutputType) -> Self {
+        Self {
+            reader,
+            output_type,
+            buf: Box::new([0; READ_BUFFER_SIZE]),
+            partial_line: Vec::new(),
+            partial_char: Vec::new(),
+            text: String::new(),
+            closed: false,
+        }
+    }
+
+    /// Read the next chunk; `Ok(0)` at end of stream. Cancel safe.
+    async fn read(&mut self) -> std::io::Result<usize> {
+        self.reader.read(&mut self.buf[..]).await
+    }
+
+    fn handle(
+        &mut self,
+        result: std::io::Result<usize>,
+        callbacks: &OutputCallbacks,
+        lossy: &mut bool,
+    ) -> Result<(), AgentError> {
+        match result {
+            Ok(0) => {
+                self.finish(callbacks, lossy);
+                Ok(())
+            }
+            Ok(n) => {
+                self.feed(n, callbacks, lossy);
+                Ok(())
+            }
+            Err(e) => Err(AgentError::ExecutionFailed(format!(
+                "Failed to read {}: {}",
+                match self.output_type {
+                    OutputType::Stdout => "stdout",
+                    OutputType::Stderr => "stderr",
+                },
+                e
+            ))),
+        }
+    }
+
+    /// Pass on the first `n` bytes of the buffer: all of them as a chunk,
+    /// and every line they complete.
+    fn feed(&mut self, n: usize, callbacks: &OutputCallbacks, lossy: &mut bool) {
+        let bytes = &self.buf[..n];
+
+        if let Some(ref cb) = callbacks.on_chunk {
+            self.partial_char.extend_from_slice(bytes);
+            let chunk = decode_chunk(&mut self.partial_char, lossy);
+            if !chunk.is_empty() {
+                cb(&chunk, self.output_type);
+            }
+        }
+
+        self.partial_line.extend_from_slice(bytes);
+        let mut start = 0;
+        while let Some(pos) = self.partial_line[start..].iter().position(|&b| b == b'\n') {
+            let end = start + pos;
+            let line = decode_line(&self.partial_line[start..end], lossy);
+            self.push_line(line, callbacks);
+            start = end + 1;
+        }
+        self.partial_line.drain(..start);
+    }
+
+    /// The stream closed: flush what is left of the last line and chunk.
+    fn finish(&mut self, callbacks: &OutputCallbacks, lossy: &mut bool) {
+        self.closed = true;
+
+        // Bytes of a character the stream never finished
+        if let (Some(cb), false) = (&callbacks.on_chunk, self.partial_char.is_empty()) {
+            *lossy = true;
+            cb(
+                &String::from_utf8_lossy(&self.partial_char),
+                self.output_type,
+            );
+            self.partial_char.clear();
+        }
+
+        if !self.partial_line.is_empty() {
+            let line = decode_line(&std::mem::take(&mut self.partial_line), lossy);
+            self.push_line(line, callbacks);
+        }
+    }
+
+    fn push_line(&mut self, line: String, callbacks: &OutputCallbacks) {
+        trace!(line = %line, output = ?self.output_type, "agent output");
+        if let Some(ref cb) = callbacks.on_line {
+            cb(&line, self.output_type);
+        }
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+        self.text.push_str(&line);
+    }
+}
+
+/// Decode the complete UTF-8 in `bytes`, leaving an incomplete sequence at
+/// its end in place for the next read. Invalid bytes are replaced with
+/// U+FFFD and set `lossy`.
+fn decode_chunk(bytes: &mut Vec<u8>, lossy: &mut bool) -> String {
+    let mut out = String::new();
+    let mut rest = &bytes[..];
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(text) => {
+                out.push_str(text);
+                rest = &[];
+                break;
+            }
+            Err(e) => {
+                let (valid, after) = rest.split_at(e.valid_up_to());
+                out.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                match e.error_len() {
+                    Some(len) => {
+                        out.push(char::REPLACEMENT_CHARACTER);
+                        *lossy = true;
+                        rest = &after[len..];
+                    }
+                    // Incomplete sequence at the end: wait for more bytes
+                    None => {
+                        rest = after;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    let consumed = bytes.len() - rest.len();
+    bytes.drain(..consumed);
+    out
+}
+
+/// Decode one line of output, replacing invalid UTF-8 with U+FFFD and
+/// setting `lossy` when that happens. A trailing `\r` is dropped, as
+/// `lines()` would.
+fn decode_line(bytes: &[u8], lossy: &mut bool) -> String {
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    match String::from_utf8_lossy(bytes) {
+        std::borrow::Cow::Borrowed(line) => line.to_string(),
+        std::borrow::Cow::Owned(line) => {
+            *lossy = true;
+            line
+        }
+    }
+}
+
+/// Kills the process group led by the process with this ID when dropped,
+/// unless the ID has been cleared.
+struct GroupGuard(Option<u32>);
+
+impl GroupGuard {
+    fn kill(&mut self) {
+        if let Some(pid) = self.0.take() {
+            kill_group(pid);
+        }
+    }
+}
+
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+#[cfg(unix)]
+fn kill_group(pid: u32) {
+    use nix::sys::signal::{killpg, Signal};
+    use nix::unistd::Pid;
+
+    let _ = killpg(Pid::from_raw(pid as i32), Signal::SIGKILL);
+}
+
+#[cfg(not(unix))]
+fn kill_group(_pid: u32) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[test]
+    fn chunks_hold_back_split_characters() {
+        let mut lossy = false;
+        let mut bytes = b"caf\xC3".to_vec();
+        assert_eq!(decode_chunk(&mut bytes, &mut lossy), "caf");
+        assert_eq!(bytes, b"\xC3");
+
+        bytes.extend_from_slice(b"\xA9 \xFF!");
+        assert_eq!(decode_chunk(&mut bytes, &mut lossy), "\u{e9} \u{FFFD}!");
+        assert!(bytes.is_empty());
+        assert!(lossy);
+    }
+
+    #[tokio::test]
+    async fn streams_partial_lines_as_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AgentConfig::new(dir.path().to_path_buf());
+        let record = |log: &Arc<Mutex<Vec<String>>>| -> OutputCallback {
+            let log = log.clone();
+            Arc::new(move |text: &str, output_type: OutputType| {
+                if output_type == OutputType::Stdout {
+                    log.lock().unwrap().push(text.to_string());
+                }
+            })
+        };
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let chunks = Arc::new(Mutex::new(Vec::new()));
+
+        let output = ProcessSpawner::spawn_with_callbacks(
+            Path::new("sh"),
+            &[
+                "-c",
+                "printf 'hel'; sleep 0.3; printf 'lo\\nwor'; sleep 0.3; printf 'ld'",
+            ],
+            &config,
+            OutputCallbacks::lines(Some(record(&lines))).with_chunks(record(&chunks)),
+        )
+        .await
+        .unwrap();
+
+        let chunks = chunks.lock().unwrap();
+        assert_eq!(chunks.first().map(String::as_str), Some("hel"));
+        assert_eq!(chunks.concat(), "hello\nworld");
+        assert_eq!(*lines.lock().unwrap(), vec!["hello", "world"]);
+        assert_eq!(output.stdout, "hello\nworld");
+    }
+
+    #[tokio::test]
+    async fn timeout_kills_the_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let config =
+            AgentConfig::new(dir.path().to_path_buf()).with_timeout(Duration::from_millis(300));
+
+        let started = Instant::now();
+        let result = ProcessSpawner::spawn(
+            Path::new("sh"),
+            &["-c", "echo $$ > pid; echo started; exec sleep 30"],
+            &config,
+        )
+        .await;
+        assert!(
+            matches!(result, Err(AgentError::Timeout(limit)) if limit == config.timeout.unwrap())
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // Killed and reaped: the pid no longer names a process
+        let pid = std::fs::read_to_string(dir.path().join("pid")).unwrap();
+        let alive = std::process::Command::new("kill")
+            .args(["-0", pid.trim()])
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(!alive.success());
+
+        // A process that finishes in time is unaffected
+        let output = ProcessSpawner::spawn(Path::new("sh"), &["-c", "echo quick"], &config)
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, "quick");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn timeout_kills_what_the_process_started() {
+        let dir = tempfile::tempdir().unwrap();
+        let config =
+            AgentConfig::new(dir.path().to_path_buf()).with_timeout(Duration::from_millis(300));
+
+        let result = ProcessSpawner::spawn(
+            Path::new("sh"),
+            &["-c", "sleep 30 & echo $! > child; wait"],
+            &config,
+        )
+        .await;
+        assert!(matches!(result, Err(AgentError::Timeout(_))));
+
+        // Gone, or a zombie waiting for whoever adopted it to reap it
+        let pid = std::fs::read_to_string(dir.path().join("child")).unwrap();
+        let running = || {
+            std::fs::read_to_string(format!("/proc/{}/stat", pid.trim()))
+                .is_ok_and(|stat| !stat.rsplit(')').next().unwrap_or("").starts_with(" Z"))
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while running() && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!running());
+    }
+
+    #[tokio::test]
+    async fn invalid_utf8_output_is_replaced_and_flagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AgentConfig::new(dir.path().to_path_buf());
+        let output = ProcessSpawner::spawn(
+            Path::new("sh"),
+            &[
+                "-c",
+                r"printf 'ok\r\nbin: \377\376 end\n'; printf 'err \200\n' >&2",
+            ],
+            &config,
+        )
+        .await
+        .unwrap();
+
+        assert!(output.success());
+        assert!(output.output_was_lossy);
+        assert_eq!(output.stdout, "ok\nbin: \u{FFFD}\u{FFFD} end");
+        assert_eq!(output.stderr, "err \u{FFFD}");
+
+        let output = ProcessSpawner::spawn(Path::new("sh"), &["-c", "echo clean"], &config)
+            .await
+            .unwrap();
+        assert!(!output.output_was_lossy);
+        assert_eq!(output.stdout, "clean");
     }
 }
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-use crate::{AgentOutput, OutputCallback};
+use crate::{AgentOutput, OutputCallback, OutputCallbacks};
 
 /// Errors that can occur during agent execution
 #[derive(Error, Debug)]
@@ -35,6 +35,12 @@ pub struct AgentConfig {
     pub env_vars: HashMap<String, String>,
     /// Model to use (if agent supports it)
     pub model: Option<String>,
+    /// Cap on response length in tokens (if agent supports it)
+    pub max_output_tokens: Option<u32>,
+    /// Id of an earlier conversation to resume instead of starting a new
+    /// one, as reported in [`AgentOutput::session_id`] (if agent supports
+    /// it; see [`Agent::supports_continuation`])
+    pub resume_session: Option<String>,
 }
 
 impl Default for AgentConfig {
@@ -44,6 +50,8 @@ impl Default for AgentConfig {
             timeout: None,
             env_vars: HashMap::new(),
             model: None,
+            max_output_tokens: None,
+            resume_session: None,
         }
     }
 }
@@ -66,6 +74,20 @@ impl AgentConfig {
         self
     }
 
+    /// Ask the agent to cap its response length. Agents without a matching
+    /// option ignore it.
+    pub fn with_max_output_tokens(mut self, tokens: u32) -> Self {
+        self.max_output_tokens = Some(tokens);
+        self
+    }
+
+    /// Resume the conversation with id `session`. Agents that cannot ignore
+    /// it.
+    pub fn with_resume_session(mut self, session: impl Into<String>) -> Self {
+        self.resume_session = Some(session.into());
+        self
+    }
+
     pub fn with_env(mut self, key: String, value: String) -> Self {
         self.env_vars.insert(key, value);
         self
@@ -79,6 +101,10 @@ pub enum AgentType {
     ClaudeGateway,
     OpenCode,
     Cursor,
+    /// A user-provided shell script; see [`crate::CommandAgent`]
+    Command,
+    /// Any CLI run from a command template; see [`crate::CustomAgent`]
+    Custom,
 }
 
 impl std::fmt::Display for AgentType {
@@ -88,6 +114,8 @@ impl std::fmt::Display for AgentType {
             AgentType::ClaudeGateway => write!(f, "claude-gateway"),
             AgentType::OpenCode => write!(f, "opencode"),
             AgentType::Cursor => write!(f, "cursor"),
+            AgentType::Command => write!(f, "command"),
+            AgentType::Custom => write!(f, "custom"),
         }
     }
 }
@@ -101,11 +129,24 @@ impl std::str::FromStr for AgentType {
             "claude-gateway" | "claudegateway" => Ok(AgentType::ClaudeGateway),
             "opencode" | "open-code" => Ok(AgentType::OpenCode),
             "cursor" => Ok(AgentType::Cursor),
+            "command" => Ok(AgentType::Command),
+            "custom" => Ok(AgentType::Custom),
             _ => Err(format!("Unknown agent type: {}", s)),
         }
     }
 }
 
+/// How an agent run ended, as judged by [`Agent::classify_exit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecStatus {
+    /// The agent did its work
+    Ok,
+    /// The agent declined the prompt; running it again will not help
+    Refused,
+    /// The agent failed (crash, API or auth error); worth retrying
+    Error,
+}
+
 /// The core abstraction for coding agents
 #[async_trait]
 pub trait Agent: Send + Sync {
@@ -126,13 +167,58 @@ pub trait Agent: Send + Sync {
         prompt: &str,
         config: &AgentConfig,
         on_output: Option<OutputCallback>,
+    ) -> Result<AgentOutput, AgentError> {
+        self.execute_with_callbacks(prompt, config, OutputCallbacks::lines(on_output))
+            .await
+    }
+
+    /// Execute a task, streaming output by whole line and/or by partial
+    /// chunk as the agent prints it
+    async fn execute_with_callbacks(
+        &self,
+        prompt: &str,
+        config: &AgentConfig,
+        callbacks: OutputCallbacks,
     ) -> Result<AgentOutput, AgentError>;
 
-    /// Check if the agent CLI is available on the system
-    async fn is_available(&self) -> bool;
+    /// Check if the agent CLI is available on the system.
+    ///
+    /// Results are cached briefly; see [`crate::availability`].
+    async fn is_available(&self) -> bool {
+        self.check_available(false).await
+    }
+
+    /// Check availability, re-probing the binary when `force` is set
+    /// instead of using a cached result.
+    async fn check_available(&self, force: bool) -> bool;
 
     /// Get the path to the agent binary
     fn binary_path(&self) -> &Path;
+
+    /// Judge how a run ended from its exit code and what the agent reported.
+    ///
+    /// The default treats any non-zero exit as an error. Agents that exit 0
+    /// on failure, or that report refusals, override this.
+    fn classify_exit(&self, output: &AgentOutput) -> ExecStatus {
+        if output.exit_code == 0 {
+            ExecStatus::Ok
+        } else {
+            ExecStatus::Error
+        }
+    }
+
+    /// Whether the agent prints its changes as a unified diff instead of
+    /// editing files. The loop runner then applies the diff itself.
+    fn produces_patches(&self) -> bool {
+        false
+    }
+
+    /// Whether the agent reports [`AgentOutput::session_id`] and picks that
+    /// conversation up again when [`AgentConfig::resume_session`] is set, so
+    /// later iterations keep what it learned in earlier ones.
+    fn supports_continuation(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
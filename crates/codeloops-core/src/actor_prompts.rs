@@ -0,0 +1,174 @@
+//! Prompt templates for the actor.
+//!
+//! The actor is told what role it plays in the loop, and from the second
+//! iteration on it gets the critic's feedback as its own section. The
+//! template can be replaced with `actor_prompt_file` in the config; see
+//! [`ActorPrompts::with_template`] for the placeholders.
+
+/// Template used unless the config names another.
+pub const DEFAULT_ACTOR_TEMPLATE: &str = r#"You are an autonomous coding agent working in a git repository. Make the changes the task asks for directly in the working tree, then check your work by building the project and running its tests.
+
+A critic reviews your changes after each turn. It either accepts them or sends feedback, which you will then be asked to address.
+
+## Task
+{task}
+{feedback}"#;
+
+/// Placeholder replaced with the original task prompt. Required.
+pub const TASK_PLACEHOLDER: &str = "{task}";
+/// Placeholder replaced with the feedback section, empty when there is none.
+pub const FEEDBACK_PLACEHOLDER: &str = "{feedback}";
+/// Placeholder replaced with the 1-based iteration number.
+pub const ITERATION_PLACEHOLDER: &str = "{iteration}";
+
+/// Builds the actor's prompt for each iteration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActorPrompts {
+    template: String,
+}
+
+impl Default for ActorPrompts {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_ACTOR_TEMPLATE.to_string(),
+        }
+    }
+}
+
+impl ActorPrompts {
+    /// Use `template` instead of [`DEFAULT_ACTOR_TEMPLATE`].
+    ///
+    /// `{task}` is replaced with the task prompt, `{feedback}` with the
+    /// critic's feedback section (empty on the first iteration) and
+    /// `{iteration}` with the iteration number. Fails when `{task}` is
+    /// missing, since the actor would never see the task.
+    pub fn with_template(template: String) -> Result<Self, String> {
+        if !template.contains(TASK_PLACEHOLDER) {
+            return Err(format!(
+                "actor prompt template has no {} placeholder",
+                TASK_PLACEHOLDER
+            ));
+        }
+        Ok(Self { template })
+    }
+
+    /// Build the prompt for `iteration` (0-indexed), with the feedback the
+    /// actor should address, if any. Feedback on the first iteration was
+    /// seeded (`--seed-feedback`) rather than written about earlier work.
+    pub fn build(&self, task: &str, feedback: Option<&str>, iteration: usize) -> String {
+        let feedback = match feedback {
+            Some(feedback) if iteration == 0 => seeded_feedback_section(feedback),
+            Some(feedback) => feedback_section(feedback),
+            None => String::new(),
+        };
+        let iteration = (iteration + 1).to_string();
+        fill(
+            &self.template,
+            &[
+                (TASK_PLACEHOLDER, task),
+                (FEEDBACK_PLACEHOLDER, &feedback),
+                (ITERATION_PLACEHOLDER, &iteration),
+            ],
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+/// Replace placeholders in one pass, so a task or feedback that happens to
+/// contain `{task}` is inserted as written.
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match values.iter().find(|(name, _)| rest.starts_with(name)) {
+            Some((name, value)) => {
+                out.push_str(value);
+                rest = &rest[name.len()..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The critic's feedback, set apart from the task and followed by how to
+/// use it.
+fn feedback_section(feedback: &str) -> String {
+    format!(
+        r#"
+## Critic Feedback
+The critic reviewed your previous work and asked for more:
+
+{}
+
+Address every point above before anything else. Keep the parts of your previous work the critic did not object to; build on them rather than starting over."#,
+        feedback.trim()
+    )
+}
+
+/// Feedback given before the run started, when there is no previous work
+/// for it to be about.
+fn seeded_feedback_section(feedback: &str) -> String {
+    format!(
+        r#"
+## Critic Feedback
+Feedback given before you started, to take into account from the outset:
+
+{}
+
+Address every point above as you work on the task."#,
+        feedback.trim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_the_task_and_adds_feedback_after_it() {
+        let prompts = ActorPrompts::default();
+
+        let first = prompts.build("Add input validation", None, 0);
+        assert!(first.starts_with("You are an autonomous coding agent"));
+        assert!(first.ends_with("## Task\nAdd input validation"));
+
+        let later = prompts.build("Add input validation", Some("Password checks missing\n"), 1);
+        let task = later.find("## Task").unwrap();
+        let feedback = later.find("## Critic Feedback").unwrap();
+        assert!(task < feedback);
+        assert!(later.contains("asked for more:\n\nPassword checks missing\n\nAddress"));
+    }
+
+    #[test]
+    fn seeded_feedback_does_not_mention_previous_work() {
+        let prompts = ActorPrompts::default();
+        let first = prompts.build("Add input validation", Some("Reuse the validator\n"), 0);
+        assert!(first.contains("## Critic Feedback\nFeedback given before you started"));
+        assert!(first.contains("outset:\n\nReuse the validator\n\nAddress"));
+        assert!(!first.contains("previous work"));
+    }
+
+    #[test]
+    fn custom_template_needs_a_task_placeholder() {
+        let prompts =
+            ActorPrompts::with_template("Turn {iteration}: {task}\n{feedback}".to_string())
+                .unwrap();
+        assert_eq!(
+            prompts.build("Fix {feedback}", None, 2),
+            "Turn 3: Fix {feedback}"
+        );
+        assert!(prompts
+            .build("Fix it", Some("Use {iteration}"), 0)
+            .contains("Use {iteration}"));
+
+        assert!(ActorPrompts::with_template("No placeholders".to_string()).is_err());
+    }
+}
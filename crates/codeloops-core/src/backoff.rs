@@ -0,0 +1,84 @@
+//! How long to wait before retrying a failed agent run.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Longest wait before a retry, however many attempts came before.
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Base wait before retrying a failed agent run.
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Exponential backoff with full jitter: the wait before retry `n` is
+/// random between zero and `base * 2^(n - 1)`, capped at
+/// [`MAX_RETRY_DELAY`]. Runs that failed together, such as several loops
+/// hitting the same rate limit, then retry at different times.
+#[derive(Debug)]
+pub struct RetryBackoff {
+    base: Duration,
+    rng: Mutex<fastrand::Rng>,
+}
+
+impl RetryBackoff {
+    pub fn new(base: Duration) -> Self {
+        Self {
+            base,
+            rng: Mutex::new(fastrand::Rng::new()),
+        }
+    }
+
+    /// Draw the waits from a generator seeded with `seed`, so the same
+    /// seed gives the same waits.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(fastrand::Rng::with_seed(seed)),
+            ..self
+        }
+    }
+
+    /// The wait before retry `attempt`, counted from 1.
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(31) as u32;
+        let ceiling = self
+            .base
+            .saturating_mul(1 << doublings)
+            .min(MAX_RETRY_DELAY.max(self.base));
+        let nanos = u64::try_from(ceiling.as_nanos()).unwrap_or(u64::MAX);
+        let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        Duration::from_nanos(rng.u64(0..=nanos))
+    }
+}
+
+impl Default for RetryBackoff {
+    fn default() -> Self {
+        Self::new(DEFAULT_RETRY_DELAY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_are_jittered_below_a_doubling_cap() {
+        let backoff = RetryBackoff::new(Duration::from_secs(1)).with_seed(7);
+        for attempt in 1..=10 {
+            let ceiling = Duration::from_secs(1 << (attempt - 1)).min(MAX_RETRY_DELAY);
+            assert!(backoff.delay(attempt) <= ceiling);
+        }
+        let delays: Vec<_> = (0..5).map(|_| backoff.delay(3)).collect();
+        assert!(delays.windows(2).any(|pair| pair[0] != pair[1]));
+
+        assert_eq!(RetryBackoff::new(Duration::ZERO).delay(4), Duration::ZERO);
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_delays() {
+        let delays = |seed| {
+            let backoff = RetryBackoff::new(Duration::from_secs(5)).with_seed(seed);
+            (1..=4).map(|n| backoff.delay(n)).collect::<Vec<_>>()
+        };
+        assert_eq!(delays(42), delays(42));
+        assert_ne!(delays(42), delays(43));
+    }
+}
@@ -1,5 +1,8 @@
 use chrono::{DateTime, Utc};
+
+use crate::ActorPrompts;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
@@ -20,6 +23,38 @@ pub struct LoopContext {
     pub max_iterations: Option<usize>,
     /// Last feedback from critic (for next actor iteration)
     pub last_feedback: Option<String>,
+    /// Whether to remind the actor of files changed in earlier iterations
+    pub include_cumulative_diff: bool,
+    /// Files touched across all iterations so far, in first-seen order
+    pub files_touched: Vec<String>,
+    /// Whether the verify command passed at session start (None = no verify command)
+    pub verify_baseline: Option<bool>,
+    /// Coverage percentage at session start (None = no coverage command or no
+    /// percentage in its output)
+    pub coverage_baseline: Option<f64>,
+    /// Estimated USD spent so far, summed over iterations that reported a cost
+    pub spent_usd: f64,
+    /// Files the prompt was assembled from, in order (empty for an inline prompt)
+    pub prompt_files: Vec<PathBuf>,
+    /// Guidance treated as critic feedback before the first iteration
+    pub seed_feedback: Option<String>,
+    /// Template the actor's prompt is built from
+    pub actor_prompts: ActorPrompts,
+    /// Reference material shown to both actor and critic, in order
+    pub context_files: Vec<ContextFile>,
+    /// Cap on the feedback characters put in the actor's prompt (None = no cap)
+    pub max_feedback_chars: Option<usize>,
+    /// Conversation the actor resumes in the next iteration, for agents
+    /// that support it (None = start a new one)
+    pub actor_session: Option<String>,
+}
+
+/// A file given with `--context-file`: project conventions or other
+/// material both roles should honor, kept apart from the task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextFile {
+    pub path: PathBuf,
+    pub content: String,
 }
 
 /// Record of a single iteration
@@ -34,6 +69,25 @@ pub struct IterationRecord {
     pub git_files_changed: usize,
     pub critic_output: String,
     pub critic_decision: String,
+    /// Confidence the critic reported for this iteration, if any
+    #[serde(default)]
+    pub critic_confidence: Option<f64>,
+    /// Whether the verify command passed after this iteration (None = not run)
+    #[serde(default)]
+    pub verify_passed: Option<bool>,
+    /// Coverage percentage after this iteration (None = not measured)
+    #[serde(default)]
+    pub coverage_percent: Option<f64>,
+    /// Change in coverage against the session baseline, in percentage points
+    #[serde(default)]
+    pub coverage_delta: Option<f64>,
+    /// Estimated cost of the actor and critic runs in USD (None = neither
+    /// agent reported one)
+    #[serde(default)]
+    pub estimated_cost_usd: Option<f64>,
+    /// Whether the working tree was reset after this iteration at the critic's request
+    #[serde(default)]
+    pub reset_applied: bool,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -47,6 +101,17 @@ impl LoopContext {
             started_at: Instant::now(),
             max_iterations: None,
             last_feedback: None,
+            include_cumulative_diff: false,
+            files_touched: Vec::new(),
+            verify_baseline: None,
+            coverage_baseline: None,
+            spent_usd: 0.0,
+            prompt_files: Vec::new(),
+            seed_feedback: None,
+            actor_prompts: ActorPrompts::default(),
+            context_files: Vec::new(),
+            max_feedback_chars: None,
+            actor_session: None,
         }
     }
 
@@ -55,14 +120,69 @@ impl LoopContext {
         self
     }
 
+    pub fn with_cumulative_diff(mut self, enabled: bool) -> Self {
+        self.include_cumulative_diff = enabled;
+        self
+    }
+
+    pub fn with_prompt_files(mut self, files: Vec<PathBuf>) -> Self {
+        self.prompt_files = files;
+        self
+    }
+
+    /// Start as if the critic had already answered `Continue` with
+    /// `feedback`, so the first actor prompt asks to address it.
+    pub fn with_seed_feedback(mut self, feedback: Option<String>) -> Self {
+        self.last_feedback = feedback.clone();
+        self.seed_feedback = feedback;
+        self
+    }
+
+    pub fn with_actor_prompts(mut self, prompts: ActorPrompts) -> Self {
+        self.actor_prompts = prompts;
+        self
+    }
+
+    pub fn with_context_files(mut self, files: Vec<ContextFile>) -> Self {
+        self.context_files = files;
+        self
+    }
+
+    /// Shorten feedback longer than `max` characters in the actor's prompt,
+    /// keeping its head and tail. The full feedback is still recorded.
+    pub fn with_max_feedback_chars(mut self, max: Option<usize>) -> Self {
+        self.max_feedback_chars = max;
+        self
+    }
+
     pub fn increment_iteration(&mut self) {
         self.iteration += 1;
     }
 
     pub fn push_record(&mut self, record: IterationRecord) {
+        self.spent_usd += record.estimated_cost_usd.unwrap_or(0.0);
         self.history.push(record);
     }
 
+    /// Average cost of the iterations that reported one; `None` until one has.
+    pub fn average_iteration_cost(&self) -> Option<f64> {
+        let costed = self
+            .history
+            .iter()
+            .filter(|record| record.estimated_cost_usd.is_some())
+            .count();
+        (costed > 0).then(|| self.spent_usd / costed as f64)
+    }
+
+    /// Merge files changed in the latest iteration into the cumulative set.
+    pub fn record_files_touched(&mut self, files: impl IntoIterator<Item = String>) {
+        for file in files {
+            if !self.files_touched.contains(&file) {
+                self.files_touched.push(file);
+            }
+        }
+    }
+
     pub fn set_feedback(&mut self, feedback: String) {
         self.last_feedback = Some(feedback);
     }
@@ -78,16 +198,277 @@ impl LoopContext {
         }
     }
 
+    /// Whether the current iteration is the last one an actor-only run makes:
+    /// the only pass without a cap, otherwise the pass that reaches it.
+    pub fn is_final_actor_only_pass(&self) -> bool {
+        match self.max_iterations {
+            Some(max) => self.iteration + 1 >= max,
+            None => true,
+        }
+    }
+
+    /// Whether the upcoming iteration is the first to reach `percent` of
+    /// the iteration cap. Always false without a cap or when `percent` is 0.
+    pub fn reaches_warning_threshold(&self, percent: u8) -> bool {
+        match self.max_iterations {
+            Some(max) if percent > 0 && max > 1 => {
+                let threshold = (max * percent.min(100) as usize).div_ceil(100).max(1);
+                self.iteration + 1 == threshold
+            }
+            _ => false,
+        }
+    }
+
     /// Get the prompt for the current iteration
-    /// First iteration uses original prompt, subsequent use feedback
+    /// First iteration uses original prompt (plus any seed feedback),
+    /// subsequent use feedback
     pub fn current_prompt(&self) -> String {
-        if self.iteration == 0 {
-            self.prompt.clone()
-        } else if let Some(ref feedback) = self.last_feedback {
-            codeloops_critic::CriticPrompts::build_continuation_prompt(&self.prompt, feedback)
-        } else {
-            // Fallback to original prompt if no feedback
-            self.prompt.clone()
+        let feedback =
+            self.last_feedback
+                .as_deref()
+                .map(|feedback| match self.max_feedback_chars {
+                    Some(max) => truncate_middle(feedback, max),
+                    None => Cow::Borrowed(feedback),
+                });
+        let prompt = self
+            .actor_prompts
+            .build(&self.prompt, feedback.as_deref(), self.iteration);
+
+        let prompt = match self.cumulative_diff_summary() {
+            Some(summary) => format!("{}\n\n{}", summary, prompt),
+            None => prompt,
+        };
+        match self.reference_section() {
+            Some(reference) => format!("{}\n\n{}", reference, prompt),
+            None => prompt,
+        }
+    }
+
+    /// The context files as one section that goes before the actor's and
+    /// the critic's prompts. `None` when there are no context files.
+    pub fn reference_section(&self) -> Option<String> {
+        if self.context_files.is_empty() {
+            return None;
         }
+        let mut section = String::from(
+            "## Reference Material\n\
+             The files below are reference, not the task. Follow them wherever they apply; \
+             the task comes after this section.\n",
+        );
+        for file in &self.context_files {
+            let name = file
+                .path
+                .strip_prefix(&self.working_dir)
+                .unwrap_or(&file.path);
+            section.push_str(&format!(
+                "\n### {}\n{}\n",
+                name.display(),
+                file.content.trim_end()
+            ));
+        }
+        section.push_str("\n---");
+        Some(section)
+    }
+
+    /// Compact reminder of files changed in earlier iterations.
+    ///
+    /// Only produced after the first iteration when `include_cumulative_diff`
+    /// is enabled and at least one file has been touched.
+    fn cumulative_diff_summary(&self) -> Option<String> {
+        if !self.include_cumulative_diff || self.iteration == 0 || self.files_touched.is_empty() {
+            return None;
+        }
+        let mut summary = String::from(
+            "## Changes From Previous Iterations\nYou have already modified these files; build on that work rather than redoing it:\n",
+        );
+        for file in &self.files_touched {
+            summary.push_str("- ");
+            summary.push_str(file);
+            summary.push('\n');
+        }
+        Some(summary.trim_end().to_string())
+    }
+}
+
+/// `text` cut down to `max` characters by dropping the middle, which is
+/// replaced with a marker saying how much was left out. Feedback tends to
+/// open with the verdict and close with next steps, so both ends are kept.
+pub(crate) fn truncate_middle(text: &str, max: usize) -> Cow<'_, str> {
+    let len = text.chars().count();
+    if len <= max {
+        return Cow::Borrowed(text);
+    }
+    let head_len = max.div_ceil(2);
+    let tail_len = max - head_len;
+    let head: String = text.chars().take(head_len).collect();
+    let tail: String = text.chars().skip(len - tail_len).collect();
+    Cow::Owned(format!(
+        "{}\n\n[... {} characters omitted ...]\n\n{}",
+        head,
+        len - max,
+        tail
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cumulative_diff_off_by_default() {
+        let mut ctx = LoopContext::new("Task".to_string(), PathBuf::from("/tmp"));
+        ctx.record_files_touched(vec!["src/lib.rs".to_string()]);
+        ctx.increment_iteration();
+        ctx.set_feedback("Fix tests".to_string());
+        assert!(!ctx.current_prompt().contains("src/lib.rs"));
+    }
+
+    #[test]
+    fn cumulative_diff_prepended_after_first_iteration() {
+        let mut ctx =
+            LoopContext::new("Task".to_string(), PathBuf::from("/tmp")).with_cumulative_diff(true);
+        ctx.record_files_touched(vec!["src/lib.rs".to_string()]);
+        assert!(ctx.current_prompt().ends_with("## Task\nTask"));
+
+        ctx.increment_iteration();
+        ctx.set_feedback("Fix tests".to_string());
+        ctx.record_files_touched(vec!["src/lib.rs".to_string(), "tests/a.rs".to_string()]);
+        let prompt = ctx.current_prompt();
+        assert!(prompt.starts_with("## Changes From Previous Iterations"));
+        assert_eq!(prompt.matches("- src/lib.rs").count(), 1);
+        assert!(prompt.contains("- tests/a.rs"));
+        assert!(prompt.contains("Fix tests"));
+    }
+
+    #[test]
+    fn long_feedback_keeps_head_and_tail() {
+        let feedback = format!("Verdict: {}Next: fix tests", "x".repeat(500));
+        let mut ctx = LoopContext::new("Task".to_string(), PathBuf::from("/tmp"))
+            .with_max_feedback_chars(Some(40));
+        ctx.increment_iteration();
+        ctx.set_feedback(feedback.clone());
+        let prompt = ctx.current_prompt();
+        assert!(prompt.contains("Verdict: xxxxxxxxxxx\n\n[... 484 characters omitted ...]"));
+        assert!(prompt.contains("xxxxxNext: fix tests"));
+        assert_eq!(ctx.last_feedback.as_deref(), Some(feedback.as_str()));
+
+        assert_eq!(truncate_middle("short", 40), "short");
+        assert_eq!(
+            truncate_middle("héllo wörld", 4),
+            "hé\n\n[... 7 characters omitted ...]\n\nld"
+        );
+    }
+
+    #[test]
+    fn context_files_come_first_as_reference() {
+        let ctx =
+            LoopContext::new("Task".to_string(), PathBuf::from("/work")).with_context_files(vec![
+                ContextFile {
+                    path: PathBuf::from("/work/CONVENTIONS.md"),
+                    content: "Use snake_case.\n".to_string(),
+                },
+                ContextFile {
+                    path: PathBuf::from("/shared/style.md"),
+                    content: "No unwrap.".to_string(),
+                },
+            ]);
+        let reference = ctx.reference_section().unwrap();
+        assert!(reference
+            .starts_with("## Reference Material\nThe files below are reference, not the task."));
+        assert!(reference.contains("\n### CONVENTIONS.md\nUse snake_case.\n"));
+        assert!(reference.contains("\n### /shared/style.md\nNo unwrap.\n"));
+
+        let prompt = ctx.current_prompt();
+        assert!(prompt.starts_with(&reference));
+        assert!(prompt.ends_with("## Task\nTask"));
+
+        let ctx = LoopContext::new("Task".to_string(), PathBuf::from("/work"));
+        assert_eq!(ctx.reference_section(), None);
+    }
+
+    #[test]
+    fn seed_feedback_shapes_the_first_prompt() {
+        let mut ctx = LoopContext::new("Task".to_string(), PathBuf::from("/tmp"))
+            .with_seed_feedback(Some("Use the existing retry helper".to_string()));
+        let prompt = ctx.current_prompt();
+        assert!(prompt.contains("## Task\nTask"));
+        assert!(prompt.contains("## Critic Feedback"));
+        assert!(prompt.contains("Use the existing retry helper"));
+
+        // Later critic feedback replaces it
+        ctx.increment_iteration();
+        ctx.set_feedback("Fix tests".to_string());
+        let prompt = ctx.current_prompt();
+        assert!(prompt.contains("Fix tests"));
+        assert!(!prompt.contains("retry helper"));
+    }
+
+    #[test]
+    fn actor_only_pass_count() {
+        let ctx = LoopContext::new("Task".to_string(), PathBuf::from("/tmp"));
+        assert!(ctx.is_final_actor_only_pass());
+
+        let mut ctx =
+            LoopContext::new("Task".to_string(), PathBuf::from("/tmp")).with_max_iterations(3);
+        assert!(!ctx.is_final_actor_only_pass());
+        ctx.increment_iteration();
+        ctx.increment_iteration();
+        assert!(ctx.is_final_actor_only_pass());
+    }
+
+    #[test]
+    fn warning_threshold_fires_once() {
+        let mut ctx =
+            LoopContext::new("Task".to_string(), PathBuf::from("/tmp")).with_max_iterations(10);
+        let mut fired = Vec::new();
+        while ctx.should_continue() {
+            if ctx.reaches_warning_threshold(80) {
+                fired.push(ctx.iteration + 1);
+            }
+            ctx.increment_iteration();
+        }
+        assert_eq!(fired, vec![8]);
+
+        let ctx = LoopContext::new("Task".to_string(), PathBuf::from("/tmp"));
+        assert!(!ctx.reaches_warning_threshold(80));
+        let ctx =
+            LoopContext::new("Task".to_string(), PathBuf::from("/tmp")).with_max_iterations(1);
+        assert!(!ctx.reaches_warning_threshold(80));
+        let ctx =
+            LoopContext::new("Task".to_string(), PathBuf::from("/tmp")).with_max_iterations(3);
+        assert!(!ctx.reaches_warning_threshold(0));
+    }
+
+    fn record(estimated_cost_usd: Option<f64>) -> IterationRecord {
+        IterationRecord {
+            iteration_number: 0,
+            actor_output: String::new(),
+            actor_stderr: String::new(),
+            actor_exit_code: 0,
+            actor_duration_secs: 0.0,
+            git_diff: String::new(),
+            git_files_changed: 0,
+            critic_output: String::new(),
+            critic_decision: String::new(),
+            critic_confidence: None,
+            verify_passed: None,
+            coverage_percent: None,
+            coverage_delta: None,
+            estimated_cost_usd,
+            reset_applied: false,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn spending_is_summed_over_costed_iterations() {
+        let mut ctx = LoopContext::new("Task".to_string(), PathBuf::from("/tmp"));
+        ctx.push_record(record(None));
+        assert_eq!(ctx.average_iteration_cost(), None);
+
+        ctx.push_record(record(Some(0.5)));
+        ctx.push_record(record(Some(0.25)));
+        assert_eq!(ctx.spent_usd, 0.75);
+        assert_eq!(ctx.average_iteration_cost(), Some(0.375));
     }
 }
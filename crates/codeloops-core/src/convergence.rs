@@ -0,0 +1,78 @@
+//! Stop loops whose critic confidence has stopped rising.
+//!
+//! A loop can oscillate between attempts without getting closer to done. When
+//! enabled, the runner compares the critic's confidence over the last few
+//! iterations against the best it reached before them and gives up with
+//! [`LoopOutcome::NoProgress`](crate::LoopOutcome::NoProgress) when it has not
+//! gained enough.
+
+/// Default minimum gain in confidence (0.0-1.0) required across the window.
+pub const DEFAULT_MIN_CONFIDENCE_GAIN: f64 = 0.05;
+
+/// How many iterations must pass without confidence rising by `min_gain`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvergenceCheck {
+    /// Number of recent iterations (K) that must show an improvement
+    pub window: usize,
+    /// Minimum rise over the best earlier confidence
+    pub min_gain: f64,
+}
+
+impl ConvergenceCheck {
+    pub fn new(window: usize, min_gain: f64) -> Self {
+        Self { window, min_gain }
+    }
+
+    /// Whether the loop has stalled, given the critic's confidence per
+    /// iteration in order. Iterations without a confidence are skipped.
+    ///
+    /// Stalled means the best of the last `window` confidences is less than
+    /// `min_gain` above the best confidence recorded before them. Needs at
+    /// least `window + 1` confidences; a window of 0 never stalls.
+    pub fn is_stalled(&self, confidences: &[Option<f64>]) -> bool {
+        let recorded: Vec<f64> = confidences.iter().flatten().copied().collect();
+        if self.window == 0 || recorded.len() <= self.window {
+            return false;
+        }
+        let (earlier, recent) = recorded.split_at(recorded.len() - self.window);
+        let best_earlier = earlier.iter().copied().fold(f64::MIN, f64::max);
+        let best_recent = recent.iter().copied().fold(f64::MIN, f64::max);
+        best_recent - best_earlier < self.min_gain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn some(values: &[f64]) -> Vec<Option<f64>> {
+        values.iter().copied().map(Some).collect()
+    }
+
+    #[test]
+    fn rising_confidence_is_not_stalled() {
+        let check = ConvergenceCheck::new(2, 0.05);
+        assert!(!check.is_stalled(&some(&[0.4, 0.5, 0.6, 0.7])));
+        // A single jump inside the window is enough
+        assert!(!check.is_stalled(&some(&[0.4, 0.5, 0.5, 0.6])));
+    }
+
+    #[test]
+    fn flat_or_oscillating_confidence_stalls() {
+        let check = ConvergenceCheck::new(3, 0.05);
+        assert!(check.is_stalled(&some(&[0.6, 0.6, 0.62, 0.6])));
+        assert!(check.is_stalled(&some(&[0.3, 0.7, 0.4, 0.7, 0.5])));
+        // Gains smaller than the minimum do not count
+        assert!(check.is_stalled(&some(&[0.5, 0.51, 0.52, 0.53])));
+    }
+
+    #[test]
+    fn needs_a_full_window_of_recorded_confidences() {
+        let check = ConvergenceCheck::new(3, 0.05);
+        assert!(!check.is_stalled(&some(&[0.5, 0.5, 0.5])));
+        // Iterations without a confidence are skipped, not counted as zero
+        assert!(!check.is_stalled(&[Some(0.5), None, Some(0.5), None, Some(0.5)]));
+        assert!(check.is_stalled(&[Some(0.5), None, Some(0.5), Some(0.5), None, Some(0.5)]));
+        assert!(!ConvergenceCheck::new(0, 0.05).is_stalled(&some(&[0.5, 0.5])));
+    }
+}
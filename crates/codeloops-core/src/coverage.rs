@@ -0,0 +1,177 @@
+//! Coverage command support.
+//!
+//! A coverage command (e.g. `cargo tarpaulin`) is run at session start to
+//! record a baseline percentage and again after each actor iteration. The
+//! change against the baseline is shown to the critic, which can reject
+//! iterations that lower coverage.
+
+use std::path::Path;
+
+use crate::verify;
+
+/// Run `command` and read a coverage percentage from its output.
+///
+/// `None` when the output has no recognisable percentage. The exit code is
+/// ignored: many tools exit non-zero when a test fails but still report
+/// coverage.
+pub async fn measure_coverage(command: &str, working_dir: &Path) -> Option<f64> {
+    let result = verify::run_verify(command, working_dir).await;
+    parse_coverage(&result.output_tail)
+}
+
+/// Change from `baseline` to `current` in percentage points, when both are
+/// known.
+pub fn coverage_delta(current: Option<f64>, baseline: Option<f64>) -> Option<f64> {
+    Some(current? - baseline?)
+}
+
+/// Read the total coverage percentage from a coverage tool's output.
+///
+/// Understands a bare number (`87.5` or `87.5%`), `TOTAL` rows (pytest-cov,
+/// llvm-cov), Istanbul's `All files` row, lcov's `lines......:` summary, and
+/// otherwise takes the first percentage on the last line that has one
+/// (tarpaulin, `go test -cover`).
+pub fn parse_coverage(output: &str) -> Option<f64> {
+    let output = output.trim();
+    if let Some(value) = parse_number(output.trim_end_matches('%')) {
+        return in_range(value);
+    }
+
+    let lines: Vec<&str> = output.lines().map(str::trim).collect();
+    let summary = |prefix: &str| {
+        lines
+            .iter()
+            .rev()
+            .find(|line| line.starts_with(prefix))
+            .copied()
+    };
+
+    if let Some(line) = summary("TOTAL") {
+        return percentages(line)
+            .last()
+            .copied()
+            .or_else(|| numbers(line).last().copied())
+            .and_then(in_range);
+    }
+    if let Some(line) = summary("All files") {
+        // Columns are % Stmts | % Branch | % Funcs | % Lines
+        return numbers(line).last().copied().and_then(in_range);
+    }
+    if let Some(line) = lines
+        .iter()
+        .rev()
+        .find(|line| line.starts_with("lines") && line.contains(':'))
+    {
+        return percentages(line).first().copied().and_then(in_range);
+    }
+
+    lines
+        .iter()
+        .rev()
+        .find_map(|line| percentages(line).first().copied())
+        .and_then(in_range)
+}
+
+fn in_range(value: f64) -> Option<f64> {
+    (0.0..=100.0).contains(&value).then_some(value)
+}
+
+fn parse_number(s: &str) -> Option<f64> {
+    let s = s.trim();
+    if s.is_empty() || !s.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Every number in `line`, in order.
+fn numbers(line: &str) -> Vec<f64> {
+    line.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .filter_map(parse_number)
+        .collect()
+}
+
+/// Every number in `line` directly followed by `%`, in order.
+fn percentages(line: &str) -> Vec<f64> {
+    line.match_indices('%')
+        .filter_map(|(end, _)| {
+            let start = line[..end]
+                .rfind(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .map_or(0, |i| i + 1);
+            parse_number(&line[start..end])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bare_numbers() {
+        assert_eq!(parse_coverage("87.5\n"), Some(87.5));
+        assert_eq!(parse_coverage("  92%  "), Some(92.0));
+        assert_eq!(parse_coverage("250"), None);
+        assert_eq!(parse_coverage("no coverage data"), None);
+    }
+
+    #[test]
+    fn parses_tarpaulin() {
+        let output = "\
+|| Tested/Total Lines:
+|| src/lib.rs: 12/14 +0.00%
+||
+85.71% coverage, 12/14 lines covered, +0.00% change in coverage";
+        assert_eq!(parse_coverage(output), Some(85.71));
+    }
+
+    #[test]
+    fn parses_pytest_cov() {
+        let output = "\
+Name              Stmts   Miss  Cover
+-------------------------------------
+app/__init__.py       4      0   100%
+app/views.py         40     12    70%
+-------------------------------------
+TOTAL                44     12    73%";
+        assert_eq!(parse_coverage(output), Some(73.0));
+    }
+
+    #[test]
+    fn parses_llvm_cov_total() {
+        let output = "\
+Filename   Regions  Missed Regions  Cover  Functions  Missed Functions  Executed  Lines  Missed Lines  Cover
+src/lib.rs      20               2 90.00%          5                 0   100.00%     50             4 92.00%
+TOTAL           20               2 90.00%          5                 0   100.00%     50             4 92.00%";
+        assert_eq!(parse_coverage(output), Some(92.0));
+    }
+
+    #[test]
+    fn parses_istanbul_table() {
+        let output = "\
+----------|---------|----------|---------|---------|
+File      | % Stmts | % Branch | % Funcs | % Lines |
+----------|---------|----------|---------|---------|
+All files |   81.25 |     62.5 |     100 |   80.65 |
+ index.js |   81.25 |     62.5 |     100 |   80.65 |
+----------|---------|----------|---------|---------|";
+        assert_eq!(parse_coverage(output), Some(80.65));
+    }
+
+    #[test]
+    fn parses_lcov_summary() {
+        let output = "\
+Summary coverage rate:
+  lines......: 85.2% (123 of 144 lines)
+  functions..: 90.0% (18 of 20 functions)
+  branches...: no data found";
+        assert_eq!(parse_coverage(output), Some(85.2));
+    }
+
+    #[test]
+    fn parses_go_cover() {
+        let output = "\
+ok  \texample.com/app\t0.012s\tcoverage: 75.0% of statements";
+        assert_eq!(parse_coverage(output), Some(75.0));
+    }
+}
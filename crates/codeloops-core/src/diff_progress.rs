@@ -0,0 +1,86 @@
+//! Stop loops whose changes have stopped changing.
+//!
+//! The critic can keep asking for another pass while the actor leaves the
+//! working tree exactly as it was. When enabled, the runner hashes the
+//! cumulative diff after every iteration and gives up with
+//! [`LoopOutcome::NoProgress`](crate::LoopOutcome::NoProgress) once it has
+//! stayed the same for too many iterations in a row.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Counts iterations in a row that left the cumulative diff unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffProgress {
+    /// Unchanged iterations in a row that stop the loop
+    max_unchanged: usize,
+    /// Hash of the diff seen last; `None` before the first
+    last_hash: Option<u64>,
+    unchanged: usize,
+}
+
+impl DiffProgress {
+    pub fn new(max_unchanged: usize) -> Self {
+        Self {
+            max_unchanged,
+            last_hash: None,
+            unchanged: 0,
+        }
+    }
+
+    /// Record the cumulative diff after an iteration (or before the first
+    /// one). Returns how many iterations in a row, ending with this one, left
+    /// the diff as the one before found it.
+    pub fn observe(&mut self, diff: &str) -> usize {
+        let mut hasher = DefaultHasher::new();
+        diff.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.last_hash == Some(hash) {
+            self.unchanged += 1;
+        } else {
+            self.unchanged = 0;
+        }
+        self.last_hash = Some(hash);
+        self.unchanged
+    }
+
+    /// Whether the last `max_unchanged` iterations all left the diff alone.
+    /// A limit of 0 never stalls.
+    pub fn is_stalled(&self) -> bool {
+        self.max_unchanged > 0 && self.unchanged >= self.max_unchanged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stalls_after(max_unchanged: usize, diffs: &[&str]) -> Option<usize> {
+        let mut progress = DiffProgress::new(max_unchanged);
+        diffs.iter().position(|diff| {
+            progress.observe(diff);
+            progress.is_stalled()
+        })
+    }
+
+    #[test]
+    fn repeated_diffs_stall() {
+        assert_eq!(stalls_after(2, &["", "+a", "+a", "+a"]), Some(3));
+        // The baseline counts: an iteration that changes nothing is no progress
+        assert_eq!(stalls_after(1, &["", ""]), Some(1));
+        assert_eq!(
+            stalls_after(3, &["+a", "+a", "+b", "+b", "+b", "+b"]),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn any_change_resets_the_count() {
+        assert_eq!(stalls_after(2, &["", "+a", "+a", "+b", "+b", "+a"]), None);
+        // Going back to an earlier diff is still a change
+        assert_eq!(stalls_after(1, &["+a", "+b", "+a", "+b"]), None);
+        // Whitespace changes count
+        assert_eq!(stalls_after(1, &["+a", "+a "]), None);
+        assert_eq!(stalls_after(0, &["+a", "+a", "+a"]), None);
+    }
+}
@@ -11,6 +11,18 @@ pub enum LoopError {
     #[error("Critic evaluation error: {0}")]
     CriticError(#[from] codeloops_critic::evaluator::EvaluationError),
 
+    #[error(
+        "Another codeloops run holds {} ({holder}). Wait for it to finish, or pass --force to start anyway",
+        path.display()
+    )]
+    Locked {
+        path: std::path::PathBuf,
+        holder: String,
+    },
+
+    #[error("Actor refused the task: {0}")]
+    ActorRefused(String),
+
     #[error("Loop was interrupted")]
     Interrupted,
 
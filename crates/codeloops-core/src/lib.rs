@@ -20,6 +20,7 @@
 //! - [`LoopContext`] - Shared state across iterations
 //! - [`IterationRecord`] - Record of a single iteration
 //! - [`LoopOutcome`] - Terminal states (Success, Failed, etc.)
+//! - [`PromptDraft`] - Sections parsed from a prompt.md
 //!
 //! ## Usage
 //!
@@ -29,8 +30,8 @@
 //! use std::path::PathBuf;
 //!
 //! // Create agents
-//! let actor = create_agent(AgentType::ClaudeCode);
-//! let critic = create_agent(AgentType::ClaudeCode);
+//! let actor = create_agent(AgentType::ClaudeCode)?;
+//! let critic = create_agent(AgentType::ClaudeCode)?;
 //!
 //! // Create the loop runner
 //! let runner = LoopRunner::new(actor, critic, working_dir, logger, writer);
@@ -60,16 +61,38 @@
 //! The loop terminates when:
 //! - Critic returns DONE (success)
 //! - Max iterations reached
+//! - Critic confidence stops rising (when a convergence check is set)
+//! - The changes stay the same for too many iterations (when a limit is set)
 //! - User interrupts (Ctrl+C)
 //! - Unrecoverable error occurs
 
+mod actor_prompts;
+mod backoff;
 mod context;
+mod convergence;
+mod coverage;
+mod diff_progress;
 mod error;
+mod lock;
 mod loop_runner;
 mod outcome;
+mod prompt_draft;
+mod review;
+mod verify;
 pub mod watcher;
 
-pub use context::{IterationRecord, LoopContext};
+pub use actor_prompts::{ActorPrompts, DEFAULT_ACTOR_TEMPLATE};
+pub use backoff::{RetryBackoff, DEFAULT_RETRY_DELAY, MAX_RETRY_DELAY};
+pub use context::{ContextFile, IterationRecord, LoopContext};
+pub use convergence::{ConvergenceCheck, DEFAULT_MIN_CONFIDENCE_GAIN};
+pub use coverage::parse_coverage;
 pub use error::LoopError;
-pub use loop_runner::LoopRunner;
+pub use lock::RunLock;
+pub use loop_runner::{
+    CriticBackend, LoopRunner, DEFAULT_AGENT_RETRIES, DEFAULT_IDLE_WARNING_SECS,
+    DEFAULT_ITERATION_WARNING_PERCENT, RULES_CRITIC_NAME, SKIPPED_DECISION,
+};
 pub use outcome::LoopOutcome;
+pub use prompt_draft::PromptDraft;
+pub use review::{IterationReviewer, ReviewVerdict};
+pub use verify::VerifyResult;
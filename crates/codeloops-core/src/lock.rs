@@ -0,0 +1,210 @@
+//! Advisory lock preventing concurrent runs in the same working directory.
+//!
+//! Two loops mutating the same tree produce interleaved edits and garbled
+//! diffs. The first run creates a lock file for its working directory;
+//! later runs refuse to start until it is gone (or they are forced). The
+//! lock is released when the [`RunLock`] guard drops, which covers normal
+//! exit, errors, and panics. A run killed outright leaves its file behind,
+//! so a lock whose process is no longer alive is taken over.
+//!
+//! Lock files live in a directory of their own (by default
+//! `<data dir>/locks`), named after a hash of the canonical working
+//! directory, so they never appear in the tree's diff or get committed.
+
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::LoopError;
+
+/// Held for the duration of a run; removes the lock file on drop.
+#[derive(Debug)]
+pub struct RunLock {
+    path: PathBuf,
+}
+
+impl RunLock {
+    /// Acquire the lock for `working_dir`, keeping the lock file in
+    /// `lock_dir`.
+    ///
+    /// With `force`, an existing lock file is taken over instead of refused.
+    pub fn acquire(lock_dir: &Path, working_dir: &Path, force: bool) -> Result<Self, LoopError> {
+        let working_dir = working_dir
+            .canonicalize()
+            .unwrap_or_else(|_| working_dir.to_path_buf());
+        let path = lock_path(lock_dir, &working_dir);
+        let failed = |e: std::io::Error| {
+            LoopError::ConfigError(format!(
+                "Failed to create lock file {}: {}",
+                path.display(),
+                e
+            ))
+        };
+        std::fs::create_dir_all(lock_dir).map_err(failed)?;
+
+        let mut took_over_stale = false;
+        loop {
+            let mut options = OpenOptions::new();
+            options.write(true);
+            if force {
+                options.create(true).truncate(true);
+            } else {
+                options.create_new(true);
+            }
+
+            match options.open(&path) {
+                Ok(mut file) => {
+                    let _ = writeln!(
+                        file,
+                        "pid={}\nstarted_at={}\nworking_dir={}",
+                        std::process::id(),
+                        chrono::Utc::now().to_rfc3339(),
+                        working_dir.display()
+                    );
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let holder = std::fs::read_to_string(&path).unwrap_or_default();
+                    if !took_over_stale && !holder_alive(&holder) {
+                        // The holder died without releasing the lock
+                        tracing::warn!(lock = %path.display(), "Taking over a stale run lock");
+                        let _ = std::fs::remove_file(&path);
+                        took_over_stale = true;
+                        continue;
+                    }
+                    return Err(LoopError::Locked {
+                        path,
+                        holder: holder.split_whitespace().collect::<Vec<_>>().join(", "),
+                    });
+                }
+                Err(e) => return Err(failed(e)),
+            }
+        }
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// The lock file for `working_dir`, which should already be canonical.
+fn lock_path(lock_dir: &Path, working_dir: &Path) -> PathBuf {
+    let hash = codeloops_db::content_hash(&working_dir.to_string_lossy());
+    let name = working_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    lock_dir.join(format!("{}-{}.lock", name, hash))
+}
+
+/// Whether the process recorded in a lock file's contents is still running.
+/// A lock without a readable PID is assumed held.
+fn holder_alive(holder: &str) -> bool {
+    let Some(pid) = holder
+        .lines()
+        .find_map(|line| line.strip_prefix("pid="))
+        .and_then(|pid| pid.trim().parse::<u32>().ok())
+    else {
+        return true;
+    };
+    process_alive(pid)
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    use nix::errno::Errno;
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    let Ok(pid) = i32::try_from(pid) else {
+        return true;
+    };
+    // Signal 0 checks the process exists without disturbing it; EPERM
+    // means it exists but belongs to someone else
+    !matches!(kill(Pid::from_raw(pid), None), Err(Errno::ESRCH))
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock_file(locks: &Path, working_dir: &Path) -> PathBuf {
+        lock_path(locks, &working_dir.canonicalize().unwrap())
+    }
+
+    #[test]
+    fn second_acquire_is_refused_until_released() {
+        let dir = tempfile::tempdir().unwrap();
+        let locks = tempfile::tempdir().unwrap();
+        let lock = RunLock::acquire(locks.path(), dir.path(), false).unwrap();
+        assert!(lock_file(locks.path(), dir.path()).exists());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        let err = RunLock::acquire(locks.path(), dir.path(), false).unwrap_err();
+        assert!(matches!(err, LoopError::Locked { .. }));
+        assert!(err.to_string().contains("pid="));
+
+        drop(lock);
+        assert!(!lock_file(locks.path(), dir.path()).exists());
+        assert!(RunLock::acquire(locks.path(), dir.path(), false).is_ok());
+    }
+
+    #[test]
+    fn lock_is_per_canonical_working_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let locks = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+
+        let _lock = RunLock::acquire(locks.path(), dir.path(), false).unwrap();
+        let same = dir.path().join("sub").join("..");
+        assert!(RunLock::acquire(locks.path(), &same, false).is_err());
+        assert!(RunLock::acquire(locks.path(), other.path(), false).is_ok());
+    }
+
+    #[test]
+    fn force_takes_over_existing_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let locks = tempfile::tempdir().unwrap();
+        let path = lock_file(locks.path(), dir.path());
+        std::fs::write(&path, format!("pid={}", std::process::id())).unwrap();
+        assert!(RunLock::acquire(locks.path(), dir.path(), false).is_err());
+
+        let lock = RunLock::acquire(locks.path(), dir.path(), true).unwrap();
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn lock_of_a_dead_process_is_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let locks = tempfile::tempdir().unwrap();
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead = child.id();
+        child.wait().unwrap();
+        std::fs::write(lock_file(locks.path(), dir.path()), format!("pid={}", dead)).unwrap();
+
+        assert!(RunLock::acquire(locks.path(), dir.path(), false).is_ok());
+    }
+
+    #[test]
+    fn released_on_panic() {
+        let dir = tempfile::tempdir().unwrap();
+        let locks = tempfile::tempdir().unwrap();
+        let (path, lock_dir) = (dir.path().to_path_buf(), locks.path().to_path_buf());
+        let result = std::panic::catch_unwind(move || {
+            let _lock = RunLock::acquire(&lock_dir, &path, false).unwrap();
+            panic!("boom");
+        });
+        assert!(result.is_err());
+        assert!(!lock_file(locks.path(), dir.path()).exists());
+    }
+}
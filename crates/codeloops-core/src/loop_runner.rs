@@ -1,23 +1,83 @@
 use chrono::Utc;
-use std::io::Write as IoWrite;
-use std::path::PathBuf;
+use std::io::{Seek, Write as IoWrite};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
 use tracing::{debug, info, warn};
 
-use codeloops_agent::{Agent, AgentConfig, OutputCallback, OutputType};
-use codeloops_critic::{CriticDecision, CriticEvaluationInput, CriticEvaluator};
-use codeloops_db::{Database, SessionEnd, SessionStart};
-use codeloops_git::DiffCapture;
+use codeloops_agent::{
+    text, Agent, AgentConfig, AgentOutput, ExecStatus, IdleMonitor, OutputCallback, OutputType,
+};
+use codeloops_critic::{
+    CoverageChange, CriticDecision, CriticEvaluationInput, CriticEvaluator, DiffScopeNote,
+    EvaluationError, Rubric, RuleConfig, RuleCritic, RuleInput, RuleVerify, ScopeAdherence,
+    VerifyRegression,
+};
+use codeloops_db::{Database, SessionEnd, SessionOutcome, SessionStart};
+use codeloops_git::{
+    apply_patch, diff_files, extract_patch, DiffCapture, DiffScope, ScopedDiff, Snapshot,
+};
 use codeloops_logging::{AgentRole, LogEvent, Logger, StreamType};
 
 use crate::watcher;
 
-use crate::context::IterationRecord;
+use crate::backoff::RetryBackoff;
+use crate::context::{truncate_middle, IterationRecord};
+use crate::convergence::ConvergenceCheck;
+use crate::coverage::{self, coverage_delta};
+use crate::diff_progress::DiffProgress;
 use crate::error::LoopError;
+use crate::lock::RunLock;
 use crate::outcome::LoopOutcome;
+use crate::prompt_draft::PromptDraft;
+use crate::review::{IterationReviewer, ReviewVerdict};
+use crate::verify::{self, VerifyResult};
 use crate::LoopContext;
 
+/// Maximum bytes of raw critic response stored per iteration.
+const MAX_CRITIC_RAW_LEN: usize = 20_000;
+
+/// Maximum characters of actor prompt stored per iteration. Longer prompts
+/// lose their middle; the task opens them and the feedback closes them.
+const MAX_STORED_ACTOR_PROMPT_CHARS: usize = 100_000;
+
+/// Characters of critic feedback logged with each decision.
+const RATIONALE_PREVIEW_CHARS: usize = 200;
+
+/// Decision recorded for iterations that ran without a critic.
+pub const SKIPPED_DECISION: &str = "skipped";
+
+/// Feedback given to the actor between passes of an actor-only run.
+const ACTOR_ONLY_FEEDBACK: &str = "No reviewer is checking this run. Re-read the original task, \
+     review your changes against it, and improve them further: finish anything incomplete, \
+     fix anything incorrect, and add missing tests.";
+
+/// Seconds without agent output before the loop warns the agent may be stuck.
+pub const DEFAULT_IDLE_WARNING_SECS: u64 = 30;
+
+/// How often a paused loop checks whether it was resumed.
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Share of the iteration cap at which the loop warns it is running out.
+pub const DEFAULT_ITERATION_WARNING_PERCENT: u8 = 80;
+
+/// Times a failed agent run is retried before the loop moves on.
+pub const DEFAULT_AGENT_RETRIES: usize = 0;
+
+/// How each iteration is reviewed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CriticBackend {
+    /// Run the critic agent
+    #[default]
+    Agent,
+    /// Apply fixed rules to the verify result and diff; no agent is called
+    Rules(RuleConfig),
+}
+
+/// Agent name recorded for sessions reviewed by [`CriticBackend::Rules`].
+pub const RULES_CRITIC_NAME: &str = "rules";
+
 type TeeFile = Arc<StdMutex<std::fs::File>>;
 type TeeFiles = (TeeFile, TeeFile);
 
@@ -30,8 +90,37 @@ pub struct LoopRunner<'a> {
     db: Option<Arc<Database>>,
     session_id: Option<String>,
     interrupted: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
     actor_model: Option<String>,
     critic_model: Option<String>,
+    sessions_dir: PathBuf,
+    lock_dir: PathBuf,
+    verify_command: Option<String>,
+    coverage_command: Option<String>,
+    apply_patches: bool,
+    fail_on_regression: bool,
+    force_lock: bool,
+    allow_critic_reset: bool,
+    stream_to_disk: bool,
+    max_output_tokens: Option<u32>,
+    iteration_warning_percent: u8,
+    skip_critic: bool,
+    critic_backend: CriticBackend,
+    rubric: Option<Rubric>,
+    critic_diff_scope: Option<DiffScope>,
+    idle_warning: Option<Duration>,
+    agent_timeout: Option<Duration>,
+    agent_retries: usize,
+    retry_backoff: RetryBackoff,
+    convergence: Option<ConvergenceCheck>,
+    diff_progress: Option<DiffProgress>,
+    max_cost_usd: Option<f64>,
+    reviewer: Option<Arc<dyn IterationReviewer>>,
+    /// Whether the "no cost data" warning for `max_cost_usd` was logged
+    cost_warning_logged: bool,
+    /// The working tree before the first iteration, which a reset restores
+    /// and the scope check compares against
+    baseline: Option<Snapshot>,
 }
 
 impl<'a> LoopRunner<'a> {
@@ -52,21 +141,349 @@ impl<'a> LoopRunner<'a> {
             db,
             session_id: None,
             interrupted: Arc::new(AtomicBool::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
             actor_model,
             critic_model,
+            sessions_dir: codeloops_db::paths::sessions_dir(None),
+            lock_dir: codeloops_db::paths::locks_dir(),
+            verify_command: None,
+            coverage_command: None,
+            apply_patches: false,
+            fail_on_regression: false,
+            force_lock: false,
+            allow_critic_reset: false,
+            stream_to_disk: false,
+            max_output_tokens: None,
+            iteration_warning_percent: DEFAULT_ITERATION_WARNING_PERCENT,
+            skip_critic: false,
+            critic_backend: CriticBackend::Agent,
+            rubric: None,
+            critic_diff_scope: None,
+            idle_warning: Some(Duration::from_secs(DEFAULT_IDLE_WARNING_SECS)),
+            agent_timeout: None,
+            agent_retries: DEFAULT_AGENT_RETRIES,
+            retry_backoff: RetryBackoff::default(),
+            convergence: None,
+            diff_progress: None,
+            max_cost_usd: None,
+            reviewer: None,
+            cost_warning_logged: false,
+            baseline: None,
+        }
+    }
+
+    /// Write per-session live output under `dir` instead of the default location.
+    pub fn with_sessions_dir(mut self, dir: PathBuf) -> Self {
+        self.sessions_dir = dir;
+        self
+    }
+
+    /// Keep the run lock file under `dir` instead of the default location.
+    pub fn with_lock_dir(mut self, dir: PathBuf) -> Self {
+        self.lock_dir = dir;
+        self
+    }
+
+    /// Run `command` at session start and after each actor iteration.
+    pub fn with_verify_command(mut self, command: Option<String>) -> Self {
+        self.verify_command = command;
+        self
+    }
+
+    /// Run `command` at session start and after each actor iteration, and
+    /// show the critic how the coverage percentage it prints has changed.
+    pub fn with_coverage_command(mut self, command: Option<String>) -> Self {
+        self.coverage_command = command;
+        self
+    }
+
+    /// Treat a verify command that passed at baseline but fails after an
+    /// iteration as a blocker: the critic is told about the regression and
+    /// the iteration cannot be approved until it is fixed.
+    pub fn with_fail_on_regression(mut self, enabled: bool) -> Self {
+        self.fail_on_regression = enabled;
+        self
+    }
+
+    /// Take over the working directory lock even if another run holds it.
+    pub fn with_force_lock(mut self, force: bool) -> Self {
+        self.force_lock = force;
+        self
+    }
+
+    /// Let a critic `reset` decision discard the working tree changes.
+    ///
+    /// When disabled, a reset decision is treated as a plain continue.
+    pub fn with_allow_critic_reset(mut self, allow: bool) -> Self {
+        self.allow_critic_reset = allow;
+        self
+    }
+
+    /// Sync the actor's streamed stdout to disk after every line, so the
+    /// output of an iteration cut short survives a system crash too; see
+    /// [`codeloops_db::paths::read_actor_stdout`].
+    pub fn with_stream_to_disk(mut self, enabled: bool) -> Self {
+        self.stream_to_disk = enabled;
+        self
+    }
+
+    /// Ask both agents to cap their response length.
+    pub fn with_max_output_tokens(mut self, tokens: Option<u32>) -> Self {
+        self.max_output_tokens = tokens;
+        self
+    }
+
+    /// Warn once the loop reaches `percent` of `--max-iterations`; 0 disables.
+    pub fn with_iteration_warning_percent(mut self, percent: u8) -> Self {
+        self.iteration_warning_percent = percent;
+        self
+    }
+
+    /// Run the actor alone: one pass, or `--max-iterations` passes with a
+    /// fixed "improve further" prompt in between. Iterations record
+    /// [`SKIPPED_DECISION`] as the critic decision.
+    pub fn with_skip_critic(mut self, skip: bool) -> Self {
+        self.skip_critic = skip;
+        self
+    }
+
+    /// Choose how iterations are reviewed. [`CriticBackend::Rules`] never
+    /// calls the critic agent.
+    pub fn with_critic_backend(mut self, backend: CriticBackend) -> Self {
+        self.critic_backend = backend;
+        self
+    }
+
+    /// Have the critic agent check `rubric` on every iteration. A `done`
+    /// stands only when every must-pass criterion is reported met.
+    pub fn with_rubric(mut self, rubric: Option<Rubric>) -> Self {
+        self.rubric = rubric;
+        self
+    }
+
+    /// Show the critic only the files of each diff within `scope`. The full
+    /// diff is still recorded for the session.
+    pub fn with_critic_diff_scope(mut self, scope: Option<DiffScope>) -> Self {
+        self.critic_diff_scope = scope;
+        self
+    }
+
+    /// Warn when an agent streams no output for `after`; `None` disables.
+    pub fn with_idle_warning(mut self, after: Option<Duration>) -> Self {
+        self.idle_warning = after;
+        self
+    }
+
+    /// Kill an actor or critic run that takes longer than `limit`, failing
+    /// the loop; `None` (the default) waits as long as it takes.
+    pub fn with_agent_timeout(mut self, limit: Option<Duration>) -> Self {
+        self.agent_timeout = limit;
+        self
+    }
+
+    /// Run the actor or critic again, up to `retries` times, when a run
+    /// fails (crash, API or auth error). Refusals are never retried.
+    pub fn with_agent_retries(mut self, retries: usize) -> Self {
+        self.agent_retries = retries;
+        self
+    }
+
+    /// How long to wait before each retry of a failed agent run.
+    pub fn with_retry_backoff(mut self, backoff: RetryBackoff) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Stop with [`LoopOutcome::NoProgress`] when critic confidence stops
+    /// rising; `None` (the default) never stops early.
+    pub fn with_convergence_check(mut self, check: Option<ConvergenceCheck>) -> Self {
+        self.convergence = check;
+        self
+    }
+
+    /// Stop with [`LoopOutcome::NoProgress`] when `max` iterations in a row
+    /// leave the cumulative diff unchanged; `None` or 0 (the default) never
+    /// stops early.
+    pub fn with_max_unchanged_iterations(mut self, max: Option<usize>) -> Self {
+        self.diff_progress = max.filter(|&max| max > 0).map(DiffProgress::new);
+        self
+    }
+
+    /// Stop with [`LoopOutcome::CostBudgetExceeded`] before an iteration that
+    /// would likely push estimated spending past `usd`. Ignored, with a
+    /// warning, when the agents report no costs.
+    pub fn with_max_cost(mut self, usd: Option<f64>) -> Self {
+        self.max_cost_usd = usd;
+        self
+    }
+
+    /// Have `reviewer` rule on every reviewed iteration before the critic's
+    /// decision takes effect. Iterations of an actor-only run are not shown.
+    pub fn with_reviewer(mut self, reviewer: Option<Arc<dyn IterationReviewer>>) -> Self {
+        self.reviewer = reviewer;
+        self
+    }
+
+    /// Wrap `callback` so a silent agent logs [`LogEvent::AgentIdle`]. The
+    /// monitor must be kept alive while the agent runs.
+    fn watch_idle(
+        &self,
+        iteration: usize,
+        role: AgentRole,
+        callback: OutputCallback,
+    ) -> (OutputCallback, Option<IdleMonitor>) {
+        let Some(threshold) = self.idle_warning else {
+            return (callback, None);
+        };
+        let logger = self.logger.clone();
+        let (callback, monitor) = IdleMonitor::watch(threshold, Some(callback), move |idle| {
+            logger.log(&LogEvent::AgentIdle {
+                iteration,
+                role,
+                idle_secs: idle.as_secs(),
+            });
+        });
+        (callback, Some(monitor))
+    }
+
+    /// Run the verify command (if any) and log the result.
+    async fn run_verify(
+        &self,
+        working_dir: &Path,
+        iteration: Option<usize>,
+        baseline: Option<bool>,
+    ) -> Option<VerifyResult> {
+        let command = self.verify_command.as_deref()?;
+        debug!(?iteration, command, "Running verify command");
+        let result = verify::run_verify(command, working_dir).await;
+        self.logger.log(&LogEvent::VerifyCompleted {
+            iteration,
+            passed: result.passed,
+            exit_code: result.exit_code,
+            regression: iteration.is_some() && result.is_regression(baseline),
+        });
+        Some(result)
+    }
+
+    /// Apply the diff in the actor's output to the working tree, even when
+    /// the actor doesn't declare [`Agent::produces_patches`].
+    pub fn with_apply_patches(mut self, apply: bool) -> Self {
+        self.apply_patches = apply;
+        self
+    }
+
+    /// Apply the diff a patch-mode actor printed and record whether it
+    /// applied. Does nothing when patch mode is off. A patch that doesn't
+    /// apply leaves the working tree untouched, so the critic sees no
+    /// changes for the iteration.
+    fn apply_actor_patch(&self, working_dir: &Path, iteration: usize, output: &str) {
+        if !self.apply_patches && !self.actor.produces_patches() {
+            return;
+        }
+        let Some(patch) = extract_patch(output) else {
+            warn!(iteration, "Actor output has no diff to apply");
+            return;
+        };
+
+        let applied = match apply_patch(working_dir, &patch) {
+            Ok(summary) => {
+                self.logger.log(&LogEvent::PatchApplied {
+                    iteration,
+                    files_changed: summary.files_changed,
+                });
+                true
+            }
+            Err(e) => {
+                self.logger.log(&LogEvent::PatchRejected {
+                    iteration,
+                    error: e.to_string(),
+                });
+                false
+            }
+        };
+        if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
+            if let Err(e) = db
+                .sessions()
+                .set_patch_applied(session_id, iteration, applied)
+            {
+                warn!(error = %e, "Failed to write patch result to database");
+            }
         }
     }
 
+    /// Run the coverage command (if any) and log the percentage and its
+    /// change against `baseline`. Returns `None` when there is no command or
+    /// its output has no percentage.
+    async fn run_coverage(
+        &self,
+        working_dir: &Path,
+        iteration: Option<usize>,
+        baseline: Option<f64>,
+    ) -> Option<f64> {
+        let command = self.coverage_command.as_deref()?;
+        debug!(?iteration, command, "Running coverage command");
+        let percent = coverage::measure_coverage(command, working_dir).await;
+        if percent.is_none() {
+            warn!(command, "Coverage command printed no coverage percentage");
+        }
+        self.logger.log(&LogEvent::CoverageMeasured {
+            iteration,
+            percent,
+            delta: iteration.and(coverage_delta(percent, baseline)),
+        });
+        percent
+    }
+
     /// Get the session ID (available after run starts).
     pub fn session_id(&self) -> Option<&str> {
         self.session_id.as_deref()
     }
 
+    /// Paths the run has changed in `working_dir`, relative to the
+    /// repository root: those that differ from the tree it started from.
+    /// Without a starting snapshot, the files `git_diff` touches.
+    pub fn files_changed(&self, working_dir: &Path, git_diff: &str) -> Vec<String> {
+        match self.baseline {
+            Some(ref baseline) => self
+                .diff_capture
+                .files_changed_since(working_dir, baseline)
+                .unwrap_or_else(|e| {
+                    warn!(error = %e, "Failed to list files changed since the run started");
+                    diff_files(git_diff)
+                }),
+            None => diff_files(git_diff),
+        }
+    }
+
     /// Get a handle to signal interruption
     pub fn interrupt_handle(&self) -> Arc<AtomicBool> {
         self.interrupted.clone()
     }
 
+    /// Flag that holds the loop before its next iteration while set.
+    pub fn pause_handle(&self) -> Arc<AtomicBool> {
+        self.paused.clone()
+    }
+
+    /// Wait while the loop is paused. Returns early on interruption.
+    async fn wait_while_paused(&self, next_iteration: usize) {
+        if !self.paused.load(Ordering::SeqCst) {
+            return;
+        }
+        info!(next_iteration, "Loop paused");
+        self.logger.log(&LogEvent::LoopPaused {
+            iteration: next_iteration,
+        });
+        while self.paused.load(Ordering::SeqCst) && !self.interrupted.load(Ordering::SeqCst) {
+            tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+        }
+        if !self.interrupted.load(Ordering::SeqCst) {
+            self.logger.log(&LogEvent::LoopResumed {
+                iteration: next_iteration,
+            });
+        }
+    }
+
     /// Create an output callback for streaming agent output
     fn create_output_callback(&self, iteration: usize, role: AgentRole) -> OutputCallback {
         let logger = self.logger.clone();
@@ -93,6 +510,7 @@ impl<'a> LoopRunner<'a> {
         stderr_file: TeeFile,
     ) -> OutputCallback {
         let logger = self.logger.clone();
+        let sync_stdout = self.stream_to_disk && role == AgentRole::Actor;
         Arc::new(move |line: &str, output_type: OutputType| {
             let stream = match output_type {
                 OutputType::Stdout => StreamType::Stdout,
@@ -111,6 +529,9 @@ impl<'a> LoopRunner<'a> {
                     if let Ok(mut f) = stdout_file.lock() {
                         let _ = writeln!(f, "{}", line);
                         let _ = f.flush();
+                        if sync_stdout {
+                            let _ = f.sync_data();
+                        }
                     }
                 }
                 OutputType::Stderr => {
@@ -123,24 +544,29 @@ impl<'a> LoopRunner<'a> {
         })
     }
 
-    /// Get the output directory for a session's temp files.
-    fn output_dir(session_id: &str) -> PathBuf {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("codeloops")
-            .join("output")
-            .join(session_id)
-    }
-
     /// Create temp files for tee output, returning (stdout_file, stderr_file).
-    fn create_tee_files(session_id: &str, iteration: usize, phase: &str) -> Option<TeeFiles> {
-        let dir = Self::output_dir(session_id);
+    fn create_tee_files(
+        sessions_dir: &Path,
+        session_id: &str,
+        iteration: usize,
+        phase: &str,
+    ) -> Option<TeeFiles> {
+        let dir = codeloops_db::paths::session_output_dir(sessions_dir, session_id);
         if std::fs::create_dir_all(&dir).is_err() {
             return None;
         }
 
-        let stdout_path = dir.join(format!("iter_{}_{}.stdout", iteration, phase));
-        let stderr_path = dir.join(format!("iter_{}_{}.stderr", iteration, phase));
+        let path = |stream| {
+            codeloops_db::paths::live_output_path(
+                sessions_dir,
+                session_id,
+                iteration,
+                phase,
+                stream,
+            )
+        };
+        let stdout_path = path("stdout");
+        let stderr_path = path("stderr");
 
         let stdout = std::fs::File::create(stdout_path).ok()?;
         let stderr = std::fs::File::create(stderr_path).ok()?;
@@ -153,6 +579,9 @@ impl<'a> LoopRunner<'a> {
 
     /// Run the actor-critic loop until completion
     pub async fn run(&mut self, mut context: LoopContext) -> Result<LoopOutcome, LoopError> {
+        // Held until this function returns (or unwinds)
+        let _lock = RunLock::acquire(&self.lock_dir, &context.working_dir, self.force_lock)?;
+
         self.logger.log(&LogEvent::LoopStarted {
             prompt: context.prompt.clone(),
             working_dir: context.working_dir.clone(),
@@ -164,10 +593,32 @@ impl<'a> LoopRunner<'a> {
                 prompt: context.prompt.clone(),
                 working_dir: context.working_dir.clone(),
                 actor_agent: self.actor.name().to_string(),
-                critic_agent: self.critic.name().to_string(),
+                critic_agent: match self.critic_backend {
+                    CriticBackend::Agent => self.critic.name().to_string(),
+                    CriticBackend::Rules(_) => RULES_CRITIC_NAME.to_string(),
+                },
                 actor_model: self.actor_model.clone(),
-                critic_model: self.critic_model.clone(),
+                critic_model: match self.critic_backend {
+                    CriticBackend::Agent => self.critic_model.clone(),
+                    CriticBackend::Rules(_) => None,
+                },
                 max_iterations: context.max_iterations,
+                prompt_files: context
+                    .prompt_files
+                    .iter()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect(),
+                branch: self
+                    .diff_capture
+                    .capture_status(&context.working_dir)
+                    .ok()
+                    .and_then(|status| status.branch),
+                seed_feedback: context.seed_feedback.clone(),
+                context_files: context
+                    .context_files
+                    .iter()
+                    .map(|file| file.path.to_string_lossy().to_string())
+                    .collect(),
             };
             match db.sessions().create(&start) {
                 Ok(id) => {
@@ -190,14 +641,54 @@ impl<'a> LoopRunner<'a> {
             critic_config = critic_config.with_model(model.clone());
         }
 
+        if let Some(tokens) = self.max_output_tokens {
+            actor_config = actor_config.with_max_output_tokens(tokens);
+            critic_config = critic_config.with_max_output_tokens(tokens);
+        }
+
+        if let Some(limit) = self.agent_timeout {
+            actor_config = actor_config.with_timeout(limit);
+            critic_config = critic_config.with_timeout(limit);
+        }
+
+        self.baseline = self
+            .diff_capture
+            .snapshot(&context.working_dir)
+            .map_err(|e| warn!(error = %e, "Failed to snapshot working tree"))
+            .ok();
+
+        context.verify_baseline = self
+            .run_verify(&context.working_dir, None, None)
+            .await
+            .map(|r| r.passed);
+        if let (Some(ref db), Some(ref session_id), Some(passed)) =
+            (&self.db, &self.session_id, context.verify_baseline)
+        {
+            if let Err(e) = db.sessions().set_verify_baseline(session_id, passed) {
+                warn!(error = %e, "Failed to write verify baseline to database");
+            }
+        }
+        context.coverage_baseline = self.run_coverage(&context.working_dir, None, None).await;
+        if let Some(ref mut progress) = self.diff_progress {
+            // The first iteration is compared with the tree it started from
+            progress.observe(
+                &self
+                    .diff_capture
+                    .capture_diff(&context.working_dir)
+                    .unwrap_or_default(),
+            );
+        }
+
         loop {
+            self.wait_while_paused(context.iteration + 1).await;
+
             // Check for interruption
             if self.interrupted.load(Ordering::SeqCst) {
                 info!("Loop interrupted by user");
                 let duration = context.total_duration();
                 let outcome =
                     LoopOutcome::interrupted(context.iteration, context.history, duration);
-                self.write_session_end(&outcome);
+                self.finish_run(&context.working_dir, &outcome);
                 return Ok(outcome);
             }
 
@@ -212,20 +703,55 @@ impl<'a> LoopRunner<'a> {
                     context.history,
                     duration,
                 );
-                self.write_session_end(&outcome);
+                self.finish_run(&context.working_dir, &outcome);
+                return Ok(outcome);
+            }
+
+            if self.over_budget(&context) {
+                let duration = context.total_duration();
+                let outcome = LoopOutcome::cost_budget_exceeded(
+                    context.iteration,
+                    context.spent_usd,
+                    context.history,
+                    duration,
+                );
+                self.finish_run(&context.working_dir, &outcome);
                 return Ok(outcome);
             }
 
+            if context.reaches_warning_threshold(self.iteration_warning_percent) {
+                if let Some(max_iterations) = context.max_iterations {
+                    self.logger.log(&LogEvent::ApproachingMaxIterations {
+                        iteration: context.iteration + 1,
+                        max_iterations,
+                    });
+                }
+            }
+
             // Run one iteration
             match self
                 .run_iteration(&mut context, &actor_config, &critic_config)
                 .await
             {
                 Ok(Some(outcome)) => {
-                    self.write_session_end(&outcome);
+                    self.finish_run(&context.working_dir, &outcome);
                     return Ok(outcome);
                 }
                 Ok(None) => {
+                    let reason = self
+                        .stalled_reason(&context)
+                        .or_else(|| self.unchanged_reason(&context));
+                    if let Some(reason) = reason {
+                        let duration = context.total_duration();
+                        let outcome = LoopOutcome::no_progress(
+                            context.iteration + 1,
+                            reason,
+                            context.history,
+                            duration,
+                        );
+                        self.finish_run(&context.working_dir, &outcome);
+                        return Ok(outcome);
+                    }
                     // Continue to next iteration
                     context.increment_iteration();
                 }
@@ -238,13 +764,90 @@ impl<'a> LoopRunner<'a> {
                         context.history,
                         duration,
                     );
-                    self.write_session_end(&outcome);
+                    self.finish_run(&context.working_dir, &outcome);
                     return Ok(outcome);
                 }
             }
         }
     }
 
+    /// Why the loop should stop for lack of progress, if the convergence
+    /// check says it has stalled. Logs [`LogEvent::NoProgress`].
+    fn stalled_reason(&self, context: &LoopContext) -> Option<String> {
+        let check = self.convergence?;
+        let confidences: Vec<Option<f64>> = context
+            .history
+            .iter()
+            .map(|record| record.critic_confidence)
+            .collect();
+        if !check.is_stalled(&confidences) {
+            return None;
+        }
+        let best_confidence = confidences.iter().flatten().copied().fold(0.0, f64::max);
+        self.logger.log(&LogEvent::NoProgress {
+            iterations: context.iteration + 1,
+            window: check.window,
+            best_confidence,
+        });
+        Some(format!(
+            "Critic confidence did not rise by {:.0} points in the last {} iterations \
+             (best {:.0}%)",
+            check.min_gain * 100.0,
+            check.window,
+            best_confidence * 100.0
+        ))
+    }
+
+    /// Why the loop should stop for lack of progress, if the last iterations
+    /// all left the cumulative diff as they found it. Logs
+    /// [`LogEvent::ChangesStalled`].
+    fn unchanged_reason(&mut self, context: &LoopContext) -> Option<String> {
+        let progress = self.diff_progress.as_mut()?;
+        let record = context.history.last()?;
+        let unchanged = progress.observe(&record.git_diff);
+        if !progress.is_stalled() {
+            return None;
+        }
+        self.logger.log(&LogEvent::ChangesStalled {
+            iterations: context.iteration + 1,
+            unchanged,
+        });
+        Some(format!(
+            "The changes in the working tree stayed the same for the last {} iterations",
+            unchanged
+        ))
+    }
+
+    /// Whether the next iteration, costing about as much as the average one
+    /// so far, would take spending past the budget. Logs
+    /// [`LogEvent::CostBudgetExceeded`], or [`LogEvent::CostBudgetIgnored`]
+    /// once when no iteration has reported a cost.
+    fn over_budget(&mut self, context: &LoopContext) -> bool {
+        let Some(max_cost_usd) = self.max_cost_usd else {
+            return false;
+        };
+        if context.history.is_empty() {
+            return false;
+        }
+        let Some(average) = context.average_iteration_cost() else {
+            if !self.cost_warning_logged {
+                self.cost_warning_logged = true;
+                self.logger
+                    .log(&LogEvent::CostBudgetIgnored { max_cost_usd });
+            }
+            return false;
+        };
+        if context.spent_usd + average <= max_cost_usd {
+            return false;
+        }
+        self.logger.log(&LogEvent::CostBudgetExceeded {
+            iterations: context.iteration,
+            spent_usd: context.spent_usd,
+            max_cost_usd,
+        });
+        true
+    }
+
     /// Run a single iteration of the actor-critic loop
     /// Returns Some(outcome) if loop should terminate, None to continue
     async fn run_iteration(
@@ -265,6 +868,15 @@ impl<'a> LoopRunner<'a> {
 
         // Get the prompt for this iteration
         let actor_prompt = context.current_prompt();
+        if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
+            if let Err(e) = db.sessions().set_actor_prompt(
+                session_id,
+                iteration,
+                &truncate_middle(&actor_prompt, MAX_STORED_ACTOR_PROMPT_CHARS),
+            ) {
+                warn!(error = %e, "Failed to write actor_prompt to database");
+            }
+        }
 
         self.logger.log(&LogEvent::ActorStarted {
             iteration,
@@ -272,17 +884,20 @@ impl<'a> LoopRunner<'a> {
         });
 
         // Create output callback — tee to files if we have a session
-        let actor_callback = if let Some(ref session_id) = self.session_id {
-            if let Some((stdout_file, stderr_file)) =
-                Self::create_tee_files(session_id, iteration, "actor")
-            {
-                self.create_tee_callback(iteration, AgentRole::Actor, stdout_file, stderr_file)
-            } else {
-                self.create_output_callback(iteration, AgentRole::Actor)
-            }
-        } else {
-            self.create_output_callback(iteration, AgentRole::Actor)
+        let actor_tee = self.session_id.as_deref().and_then(|session_id| {
+            Self::create_tee_files(&self.sessions_dir, session_id, iteration, "actor")
+        });
+        let actor_callback = match actor_tee {
+            Some((ref stdout_file, ref stderr_file)) => self.create_tee_callback(
+                iteration,
+                AgentRole::Actor,
+                stdout_file.clone(),
+                stderr_file.clone(),
+            ),
+            None => self.create_output_callback(iteration, AgentRole::Actor),
         };
+        let (actor_callback, actor_idle) =
+            self.watch_idle(iteration, AgentRole::Actor, actor_callback);
 
         // Start file watcher for real-time file change detection.
         // The watcher uses a tokio mpsc channel so we can consume events from
@@ -314,11 +929,44 @@ impl<'a> LoopRunner<'a> {
         };
 
         // Run actor with streaming output
-        debug!(iteration, "Running actor");
-        let actor_output = self
-            .actor
-            .execute_with_callback(&actor_prompt, actor_config, Some(actor_callback))
-            .await?;
+        let resumed_config;
+        let actor_config = match context.actor_session.as_deref() {
+            Some(session) if self.actor.supports_continuation() => {
+                resumed_config = actor_config.clone().with_resume_session(session);
+                &resumed_config
+            }
+            _ => actor_config,
+        };
+        debug!(
+            iteration,
+            resume_session = actor_config.resume_session.as_deref(),
+            "Running actor"
+        );
+        let mut attempt = 0;
+        let mut retried_cost = None;
+        let actor_output = loop {
+            let output = self
+                .actor
+                .execute_with_callback(&actor_prompt, actor_config, Some(actor_callback.clone()))
+                .await?;
+            if !self.should_retry(attempt, self.actor.classify_exit(&output)) {
+                break output;
+            }
+            attempt += 1;
+            if !self
+                .before_retry(iteration, AgentRole::Actor, attempt)
+                .await
+            {
+                break output;
+            }
+            retried_cost = add_cost(retried_cost, output.estimated_cost_usd);
+            clear_tee_files(actor_tee.as_ref());
+        };
+        let actor_cost = add_cost(actor_output.estimated_cost_usd, retried_cost);
+        drop(actor_idle);
+        if actor_output.session_id.is_some() {
+            context.actor_session = actor_output.session_id.clone();
+        }
 
         // Stop file watcher by dropping the handle (closes the sender)
         drop(watcher_handle);
@@ -351,8 +999,34 @@ impl<'a> LoopRunner<'a> {
             ) {
                 warn!(error = %e, "Failed to write complete_actor to database");
             }
+            if actor_output.output_was_lossy {
+                if let Err(e) = db.sessions().set_actor_output_lossy(session_id, iteration) {
+                    warn!(error = %e, "Failed to write actor_output_lossy to database");
+                }
+            }
+        }
+        if actor_output.output_was_lossy {
+            warn!(
+                iteration,
+                "Actor output had bytes that were not UTF-8; they were replaced"
+            );
+        }
+
+        // A refusal will repeat on every retry, so stop here. Errors that
+        // outlast the retries go on to the critic, which suggests how to
+        // recover in the next iteration.
+        if self.actor.classify_exit(&actor_output) == ExecStatus::Refused {
+            let reply = actor_output.stdout.trim();
+            let reply = if reply.is_empty() {
+                actor_output.stderr.trim()
+            } else {
+                reply
+            };
+            return Err(LoopError::ActorRefused(reply.to_string()));
         }
 
+        self.apply_actor_patch(&context.working_dir, iteration, &actor_output.stdout);
+
         // Capture git diff
         let git_diff = self
             .diff_capture
@@ -367,6 +1041,13 @@ impl<'a> LoopRunner<'a> {
             .capture_summary(&context.working_dir)
             .unwrap_or_default();
 
+        if context.include_cumulative_diff {
+            // Only what the run changed; edits already in the tree when it
+            // started are the user's, not earlier iterations'
+            let touched = self.files_changed(&context.working_dir, &git_diff);
+            context.record_files_touched(touched);
+        }
+
         self.logger.log(&LogEvent::GitDiffCaptured {
             iteration,
             files_changed: diff_summary.files_changed,
@@ -381,72 +1062,180 @@ impl<'a> LoopRunner<'a> {
                 iteration,
                 &git_diff,
                 diff_summary.files_changed,
+                &diff_files(&git_diff),
             ) {
                 warn!(error = %e, "Failed to write complete_diff to database");
             }
         }
+        let scope_adherence = self.check_scope_adherence(context, iteration, &git_diff);
 
-        // --- Phase: critic_started ---
-        if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
-            if let Err(e) = db.sessions().start_critic(session_id, iteration) {
-                warn!(error = %e, "Failed to write start_critic to database");
+        let verify_result = self
+            .run_verify(
+                &context.working_dir,
+                Some(iteration),
+                context.verify_baseline,
+            )
+            .await;
+        if let (Some(ref db), Some(ref session_id), Some(result)) =
+            (&self.db, &self.session_id, verify_result.as_ref())
+        {
+            if let Err(e) = db
+                .sessions()
+                .set_verify_passed(session_id, iteration, result.passed)
+            {
+                warn!(error = %e, "Failed to write verify result to database");
             }
         }
+        let coverage_percent = self
+            .run_coverage(
+                &context.working_dir,
+                Some(iteration),
+                context.coverage_baseline,
+            )
+            .await;
+        let coverage_delta = coverage_delta(coverage_percent, context.coverage_baseline);
+        if let (Some(ref db), Some(ref session_id), Some(percent)) =
+            (&self.db, &self.session_id, coverage_percent)
+        {
+            if let Err(e) =
+                db.sessions()
+                    .set_coverage(session_id, iteration, percent, coverage_delta)
+            {
+                warn!(error = %e, "Failed to write coverage to database");
+            }
+        }
+        if self.skip_critic {
+            let record = IterationRecord {
+                iteration_number: iteration,
+                actor_output: actor_output.stdout.clone(),
+                actor_stderr: actor_output.stderr.clone(),
+                actor_exit_code: actor_output.exit_code,
+                actor_duration_secs: actor_output.duration.as_secs_f64(),
+                git_diff,
+                git_files_changed: diff_summary.files_changed,
+                critic_output: String::new(),
+                critic_decision: SKIPPED_DECISION.to_string(),
+                critic_confidence: None,
+                verify_passed: verify_result.map(|r| r.passed),
+                coverage_percent,
+                coverage_delta,
+                estimated_cost_usd: actor_cost,
+                reset_applied: false,
+                timestamp: Utc::now(),
+            };
+            return Ok(self.finish_actor_only_pass(context, record));
+        }
 
-        // Run critic with streaming output
-        self.logger.log(&LogEvent::CriticStarted { iteration });
+        let regressed = verify_result
+            .as_ref()
+            .filter(|r| self.fail_on_regression && r.is_regression(context.verify_baseline))
+            .zip(self.verify_command.as_deref());
+        let regression = regressed.map(|(result, command)| VerifyRegression {
+            command,
+            exit_code: result.exit_code,
+            output: &result.output_tail,
+        });
+        if regression.is_some() {
+            warn!(
+                iteration,
+                "Verify command regressed; flagging it to the critic"
+            );
+        }
 
-        let critic_callback = if let Some(ref session_id) = self.session_id {
-            if let Some((stdout_file, stderr_file)) =
-                Self::create_tee_files(session_id, iteration, "critic")
-            {
-                self.create_tee_callback(iteration, AgentRole::Critic, stdout_file, stderr_file)
-            } else {
-                self.create_output_callback(iteration, AgentRole::Critic)
+        let (critic_raw, decision, critic_cost) = match self.critic_backend {
+            CriticBackend::Agent => {
+                let coverage = coverage_percent.map(|current| CoverageChange {
+                    baseline: context.coverage_baseline,
+                    current,
+                });
+                let review = CriticReview {
+                    iteration,
+                    actor_output: &actor_output,
+                    git_diff: &git_diff,
+                    coverage,
+                    scope_adherence: scope_adherence.as_ref(),
+                    regression,
+                };
+                self.run_critic(context, review, critic_config).await?
+            }
+            CriticBackend::Rules(config) => {
+                // A failing verify command already stops the rules critic
+                // from approving
+                let verify = verify_result
+                    .as_ref()
+                    .zip(self.verify_command.as_deref())
+                    .map(|(result, command)| RuleVerify {
+                        command,
+                        passed: result.passed,
+                        exit_code: result.exit_code,
+                        output: &result.output_tail,
+                    });
+                self.run_rules_critic(iteration, config, &git_diff, verify)
             }
-        } else {
-            self.create_output_callback(iteration, AgentRole::Critic)
         };
-
-        let evaluator = CriticEvaluator::new(self.critic);
-        let evaluation_input = CriticEvaluationInput {
-            original_task: &context.prompt,
-            actor_stdout: &actor_output.stdout,
-            actor_stderr: &actor_output.stderr,
-            git_diff: &git_diff,
-            iteration,
+        // The regression is a blocker, so approval waits until it is fixed
+        let decision = match (regressed, decision) {
+            (Some((result, command)), CriticDecision::Done { .. }) => {
+                warn!(
+                    iteration,
+                    "Critic approved despite the verify regression; continuing"
+                );
+                CriticDecision::Continue {
+                    feedback: verify::regression_feedback(command, result),
+                    remaining_issues: vec![format!("`{}` regressed", command)],
+                    checklist: vec![],
+                    rubric: vec![],
+                }
+            }
+            (_, decision) => decision,
         };
-        let decision = evaluator
-            .evaluate_with_callback(evaluation_input, critic_config, Some(critic_callback))
-            .await?;
+        let critic_confidence = CriticDecision::parse_confidence(&critic_raw);
 
+        self.logger.log(&LogEvent::CriticDecision {
+            iteration,
+            decision: decision.kind().to_string(),
+            confidence: critic_confidence,
+            rationale_preview: rationale_preview(&decision_feedback(&decision)),
+        });
         self.logger.log(&LogEvent::CriticCompleted {
             iteration,
             decision: decision.short_description(),
         });
 
-        // --- Phase: critic_completed ---
-        let feedback = match &decision {
-            CriticDecision::Continue { feedback, .. } => Some(feedback.clone()),
-            CriticDecision::Error {
-                error_description,
-                recovery_suggestion,
-            } => Some(format!(
-                "Error encountered: {}\n\nRecovery suggestion: {}",
-                error_description, recovery_suggestion
-            )),
-            CriticDecision::Done { summary, .. } => Some(summary.clone()),
-        };
+        // From here on the decision is the one that takes effect, which a
+        // reviewer may have overruled
+        let decision = self.review_iteration(iteration, &git_diff, decision).await;
+        let feedback = Some(decision_feedback(&decision));
 
+        // --- Phase: critic_completed ---
         if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
             if let Err(e) = db.sessions().complete_critic(
                 session_id,
                 iteration,
-                &decision.short_description(),
+                decision.kind(),
                 feedback.as_deref(),
+                critic_confidence,
             ) {
                 warn!(error = %e, "Failed to write complete_critic to database");
             }
+            let checklist = decision.checklist();
+            if !checklist.is_empty() {
+                if let Err(e) = db
+                    .sessions()
+                    .set_critic_checklist(session_id, iteration, checklist)
+                {
+                    warn!(error = %e, "Failed to write critic_checklist to database");
+                }
+            }
+        }
+
+        let reset_applied = decision.is_reset() && self.apply_reset(context);
+        if reset_applied {
+            if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
+                if let Err(e) = db.sessions().set_reset_applied(session_id, iteration) {
+                    warn!(error = %e, "Failed to write reset_applied to database");
+                }
+            }
         }
 
         // Record this iteration in the in-memory context
@@ -458,8 +1247,14 @@ impl<'a> LoopRunner<'a> {
             actor_duration_secs: actor_output.duration.as_secs_f64(),
             git_diff: git_diff.clone(),
             git_files_changed: diff_summary.files_changed,
-            critic_output: String::new(),
+            critic_output: critic_raw,
             critic_decision: decision.short_description(),
+            critic_confidence,
+            verify_passed: verify_result.map(|r| r.passed),
+            coverage_percent,
+            coverage_delta,
+            estimated_cost_usd: add_cost(actor_cost, critic_cost),
+            reset_applied,
             timestamp: Utc::now(),
         };
         context.push_record(record.clone());
@@ -469,6 +1264,7 @@ impl<'a> LoopRunner<'a> {
             CriticDecision::Done {
                 summary,
                 confidence,
+                ..
             } => {
                 self.logger.log(&LogEvent::LoopCompleted {
                     iterations: iteration + 1,
@@ -487,6 +1283,7 @@ impl<'a> LoopRunner<'a> {
             CriticDecision::Continue {
                 feedback,
                 remaining_issues,
+                ..
             } => {
                 info!(
                     iteration = iteration + 1,
@@ -513,13 +1310,418 @@ impl<'a> LoopRunner<'a> {
                 context.set_feedback(feedback);
                 Ok(None)
             }
+            CriticDecision::Reset { feedback } => {
+                let feedback = if reset_applied {
+                    format!(
+                        "RESET: The critic judged the previous approach to be wrong and all \
+                         changes since the first iteration were discarded. Start again from \
+                         the working tree as it was then.\n\n{}",
+                        feedback
+                    )
+                } else {
+                    feedback
+                };
+                context.set_feedback(feedback);
+                Ok(None)
+            }
         }
     }
 
+    /// Ask the reviewer, if any, to rule on the critic's `decision` and
+    /// return the decision that takes effect. Skipped once the run was
+    /// interrupted, since it stops after this iteration anyway.
+    async fn review_iteration(
+        &self,
+        iteration: usize,
+        git_diff: &str,
+        decision: CriticDecision,
+    ) -> CriticDecision {
+        let Some(reviewer) = self.reviewer.clone() else {
+            return decision;
+        };
+        if self.interrupted.load(Ordering::SeqCst) {
+            return decision;
+        }
+
+        let diff = git_diff.to_string();
+        let critic_decision = decision.clone();
+        let verdict = match tokio::task::spawn_blocking(move || {
+            reviewer.review(iteration, &diff, &critic_decision)
+        })
+        .await
+        {
+            Ok(verdict) => verdict,
+            Err(e) => {
+                warn!(error = %e, "Iteration review failed; keeping the critic's decision");
+                ReviewVerdict::Accept
+            }
+        };
+
+        self.logger.log(&LogEvent::IterationReviewed {
+            iteration,
+            verdict: verdict.as_str().to_string(),
+        });
+        if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
+            if let Err(e) = db
+                .sessions()
+                .set_human_review(session_id, iteration, verdict.as_str())
+            {
+                warn!(error = %e, "Failed to write human_review to database");
+            }
+        }
+        verdict.apply(decision)
+    }
+
+    /// Record an iteration that ran without the critic and decide whether the
+    /// actor-only run is finished.
+    fn finish_actor_only_pass(
+        &self,
+        context: &mut LoopContext,
+        record: IterationRecord,
+    ) -> Option<LoopOutcome> {
+        let iteration = record.iteration_number;
+        let finished = context.is_final_actor_only_pass();
+        let feedback = (!finished).then_some(ACTOR_ONLY_FEEDBACK);
+
+        self.logger.log(&LogEvent::CriticCompleted {
+            iteration,
+            decision: SKIPPED_DECISION.to_string(),
+        });
+
+        if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
+            if let Err(e) = db.sessions().complete_critic(
+                session_id,
+                iteration,
+                SKIPPED_DECISION,
+                feedback,
+                None,
+            ) {
+                warn!(error = %e, "Failed to write complete_critic to database");
+            }
+        }
+
+        context.push_record(record);
+
+        match feedback {
+            Some(feedback) => {
+                context.set_feedback(feedback.to_string());
+                None
+            }
+            None => {
+                let passes = iteration + 1;
+                let summary = format!(
+                    "Actor-only run finished after {} pass{} (critic skipped)",
+                    passes,
+                    if passes == 1 { "" } else { "es" }
+                );
+                self.logger.log(&LogEvent::LoopCompleted {
+                    iterations: passes,
+                    summary: summary.clone(),
+                    duration_secs: context.total_duration().as_secs_f64(),
+                });
+                Some(LoopOutcome::success(
+                    passes,
+                    summary,
+                    0.0,
+                    context.history.clone(),
+                    context.total_duration(),
+                ))
+            }
+        }
+    }
+
+    /// Whether an agent run that ended with `status` after `attempt`
+    /// retries should run again.
+    fn should_retry(&self, attempt: usize, status: ExecStatus) -> bool {
+        status == ExecStatus::Error
+            && attempt < self.agent_retries
+            && !self.interrupted.load(Ordering::SeqCst)
+    }
+
+    /// Log the `attempt`th retry of a failed agent run and wait before it,
+    /// for a random time that grows with each failure. Returns `false`
+    /// as soon as the loop is interrupted, instead of waiting it out.
+    async fn before_retry(&self, iteration: usize, role: AgentRole, attempt: usize) -> bool {
+        warn!(
+            iteration,
+            role = role.as_str(),
+            attempt,
+            "Agent run failed; retrying"
+        );
+        self.logger.log(&LogEvent::AgentRetrying {
+            iteration,
+            role,
+            attempt,
+            retries: self.agent_retries,
+        });
+        let deadline = tokio::time::Instant::now() + self.retry_backoff.delay(attempt);
+        while !self.interrupted.load(Ordering::SeqCst) {
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return true;
+            }
+            tokio::time::sleep((deadline - now).min(PAUSE_POLL_INTERVAL)).await;
+        }
+        false
+    }
+
+    /// Discard the actor's changes after a critic reset decision, putting
+    /// the working tree back as it was before the first iteration. Changes
+    /// the user had made before the run are kept.
+    ///
+    /// Returns whether the reset happened; it is skipped (with a warning)
+    /// unless resets were explicitly allowed.
+    fn apply_reset(&self, context: &mut LoopContext) -> bool {
+        if !self.allow_critic_reset {
+            warn!("Critic requested a reset but resets are not allowed; continuing instead");
+            return false;
+        }
+        let Some(ref baseline) = self.baseline else {
+            warn!(
+                "Critic requested a reset but the starting tree was not saved; continuing instead"
+            );
+            return false;
+        };
+        match self.diff_capture.restore(&context.working_dir, baseline) {
+            Ok(()) => {
+                info!("Reset working tree at critic's request");
+                context.files_touched.clear();
+                // The actor's conversation holds the approach being dropped
+                context.actor_session = None;
+                true
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to reset working tree");
+                false
+            }
+        }
+    }
+
+    /// Run the critic on this iteration's output, returning its raw response,
+    /// parsed decision and reported cost.
+    async fn run_critic(
+        &self,
+        context: &LoopContext,
+        review: CriticReview<'_>,
+        critic_config: &AgentConfig,
+    ) -> Result<(String, CriticDecision, Option<f64>), LoopError> {
+        let CriticReview {
+            iteration,
+            actor_output,
+            git_diff,
+            coverage,
+            scope_adherence,
+            regression,
+        } = review;
+        // --- Phase: critic_started ---
+        if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
+            if let Err(e) = db.sessions().start_critic(session_id, iteration) {
+                warn!(error = %e, "Failed to write start_critic to database");
+            }
+        }
+
+        // Run critic with streaming output
+        self.logger.log(&LogEvent::CriticStarted { iteration });
+
+        let critic_tee = self.session_id.as_deref().and_then(|session_id| {
+            Self::create_tee_files(&self.sessions_dir, session_id, iteration, "critic")
+        });
+        let critic_callback = match critic_tee {
+            Some((ref stdout_file, ref stderr_file)) => self.create_tee_callback(
+                iteration,
+                AgentRole::Critic,
+                stdout_file.clone(),
+                stderr_file.clone(),
+            ),
+            None => self.create_output_callback(iteration, AgentRole::Critic),
+        };
+
+        let (critic_callback, _critic_idle) =
+            self.watch_idle(iteration, AgentRole::Critic, critic_callback);
+
+        let acceptance_criteria = PromptDraft::from_markdown(&context.prompt).acceptance_criteria;
+        let evaluator = CriticEvaluator::new(self.critic);
+        let reference = context.reference_section();
+        let scoped = self.scope_critic_diff(iteration, git_diff);
+        let evaluation_input = CriticEvaluationInput {
+            original_task: &context.prompt,
+            actor_stdout: &actor_output.stdout,
+            actor_stderr: &actor_output.stderr,
+            git_diff: scoped.as_ref().map_or(git_diff, |s| &s.diff),
+            iteration,
+            allow_reset: self.allow_critic_reset,
+            acceptance_criteria: &acceptance_criteria,
+            coverage,
+            reference: reference.as_deref(),
+            rubric: self.rubric.as_ref(),
+            diff_scope: self.critic_diff_scope.as_ref().zip(scoped.as_ref()).map(
+                |(scope, scoped)| DiffScopeNote {
+                    patterns: scope.patterns(),
+                    omitted: &scoped.omitted,
+                },
+            ),
+            scope_adherence,
+            regression,
+        };
+        let mut attempt = 0;
+        let mut retried_cost = None;
+        let critic_output = loop {
+            let result = evaluator
+                .run_with_callback(
+                    evaluation_input,
+                    critic_config,
+                    Some(critic_callback.clone()),
+                )
+                .await;
+            if let Err(EvaluationError::AgentFailure {
+                estimated_cost_usd, ..
+            }) = result
+            {
+                if self.should_retry(attempt, ExecStatus::Error) {
+                    attempt += 1;
+                    if self
+                        .before_retry(iteration, AgentRole::Critic, attempt)
+                        .await
+                    {
+                        retried_cost = add_cost(retried_cost, estimated_cost_usd);
+                        clear_tee_files(critic_tee.as_ref());
+                        continue;
+                    }
+                }
+            }
+            break result?;
+        };
+        let critic_raw = critic_output.stdout;
+
+        // Store the raw response before parsing so parse failures stay debuggable
+        if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
+            if let Err(e) = db.sessions().set_critic_raw(
+                session_id,
+                iteration,
+                text::head(&critic_raw, MAX_CRITIC_RAW_LEN),
+            ) {
+                warn!(error = %e, "Failed to write critic_raw to database");
+            }
+        }
+
+        let mut decision =
+            CriticDecision::parse(&critic_raw).map_err(EvaluationError::ParseError)?;
+        if let Some(ref rubric) = self.rubric {
+            let results = rubric.results(decision.rubric());
+            if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
+                if let Err(e) = db
+                    .sessions()
+                    .set_critic_rubric(session_id, iteration, &results)
+                {
+                    warn!(error = %e, "Failed to write critic_rubric to database");
+                }
+            }
+            if decision.is_done() {
+                decision = rubric.enforce(decision);
+                if decision.is_continue() {
+                    warn!(
+                        iteration,
+                        "Critic decided done with must-pass rubric criteria unmet"
+                    );
+                }
+            }
+        }
+        Ok((
+            critic_raw,
+            decision,
+            add_cost(critic_output.estimated_cost_usd, retried_cost),
+        ))
+    }
+
+    /// Review this iteration with the rule-based critic. The raw response is
+    /// empty and there is no cost since no agent ran.
+    fn run_rules_critic(
+        &self,
+        iteration: usize,
+        config: RuleConfig,
+        git_diff: &str,
+        verify: Option<RuleVerify<'_>>,
+    ) -> (String, CriticDecision, Option<f64>) {
+        if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
+            if let Err(e) = db.sessions().start_critic(session_id, iteration) {
+                warn!(error = %e, "Failed to write start_critic to database");
+            }
+        }
+        self.logger.log(&LogEvent::CriticStarted { iteration });
+
+        let scoped = self.scope_critic_diff(iteration, git_diff);
+        let git_diff = scoped.as_ref().map_or(git_diff, |s| &s.diff);
+        let decision = RuleCritic::new(config).evaluate(&RuleInput { git_diff, verify });
+        (String::new(), decision, None)
+    }
+
+    /// Compare the files changed since the first iteration with the prompt's
+    /// files to modify, and store the result. `None` when the prompt lists
+    /// none. Without a baseline, the files `git_diff` touched are used, which
+    /// include changes made before the run.
+    fn check_scope_adherence(
+        &self,
+        context: &LoopContext,
+        iteration: usize,
+        git_diff: &str,
+    ) -> Option<ScopeAdherence> {
+        let intended = PromptDraft::from_markdown(&context.prompt).files_to_modify;
+        if intended.is_empty() {
+            return None;
+        }
+        let touched = self.files_changed(&context.working_dir, git_diff);
+        let adherence = ScopeAdherence::check(&intended, &touched);
+        debug!(
+            iteration,
+            unexpected = adherence.unexpected.len(),
+            untouched = adherence.untouched.len(),
+            "Checked changed files against the files to modify"
+        );
+        if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
+            if let Err(e) = db
+                .sessions()
+                .set_scope_adherence(session_id, iteration, &adherence)
+            {
+                warn!(error = %e, "Failed to write scope_adherence to database");
+            }
+        }
+        Some(adherence)
+    }
+
+    /// The part of `git_diff` the critic reviews, when a diff scope is set.
+    fn scope_critic_diff(&self, iteration: usize, git_diff: &str) -> Option<ScopedDiff> {
+        let scoped = self.critic_diff_scope.as_ref()?.apply(git_diff);
+        debug!(
+            iteration,
+            omitted = scoped.omitted.len(),
+            "Narrowed critic diff to its scope"
+        );
+        Some(scoped)
+    }
+
+    /// Log how much the run changed and write the session end. Changes
+    /// already in the tree when the run started are not counted.
+    fn finish_run(&self, working_dir: &Path, outcome: &LoopOutcome) {
+        let summary = match self.baseline {
+            Some(ref baseline) => self.diff_capture.summary_since(working_dir, baseline),
+            None => self.diff_capture.capture_summary(working_dir),
+        };
+        match summary {
+            Ok(summary) => self.logger.log(&LogEvent::RunSummary {
+                files_changed: summary.files_changed,
+                insertions: summary.insertions,
+                deletions: summary.deletions,
+                iterations: outcome.iterations(),
+            }),
+            Err(e) => warn!(error = %e, "Failed to capture the run's diff summary"),
+        }
+        self.write_session_end(outcome);
+    }
+
     /// Write the session end to the database.
     fn write_session_end(&self, outcome: &LoopOutcome) {
         if let (Some(ref db), Some(ref session_id)) = (&self.db, &self.session_id) {
-            let (outcome_str, iterations, summary, confidence, duration_secs) = match outcome {
+            let (session_outcome, iterations, summary, confidence, duration_secs) = match outcome {
                 LoopOutcome::Success {
                     iterations,
                     summary,
@@ -527,10 +1729,11 @@ impl<'a> LoopRunner<'a> {
                     total_duration_secs,
                     ..
                 } => (
-                    "success",
+                    SessionOutcome::Success,
                     *iterations,
                     Some(summary.clone()),
-                    Some(*confidence),
+                    // No critic means no confidence score to record
+                    (!self.skip_critic).then_some(*confidence),
                     *total_duration_secs,
                 ),
                 LoopOutcome::MaxIterationsReached {
@@ -538,9 +1741,37 @@ impl<'a> LoopRunner<'a> {
                     total_duration_secs,
                     ..
                 } => (
-                    "max_iterations_reached",
+                    SessionOutcome::MaxIterationsReached,
+                    *iterations,
+                    None,
+                    None,
+                    *total_duration_secs,
+                ),
+                LoopOutcome::NoProgress {
+                    iterations,
+                    reason,
+                    total_duration_secs,
+                    ..
+                } => (
+                    SessionOutcome::NoProgress,
                     *iterations,
+                    Some(reason.clone()),
                     None,
+                    *total_duration_secs,
+                ),
+                LoopOutcome::CostBudgetExceeded {
+                    iterations,
+                    spent_usd,
+                    total_duration_secs,
+                    ..
+                } => (
+                    SessionOutcome::CostBudgetExceeded,
+                    *iterations,
+                    Some(format!(
+                        "Stopped at an estimated ${:.2} of the ${:.2} budget",
+                        spent_usd,
+                        self.max_cost_usd.unwrap_or_default()
+                    )),
                     None,
                     *total_duration_secs,
                 ),
@@ -549,7 +1780,7 @@ impl<'a> LoopRunner<'a> {
                     total_duration_secs,
                     ..
                 } => (
-                    "user_interrupted",
+                    SessionOutcome::Interrupted,
                     *iterations,
                     None,
                     None,
@@ -561,7 +1792,7 @@ impl<'a> LoopRunner<'a> {
                     total_duration_secs,
                     ..
                 } => (
-                    "failed",
+                    SessionOutcome::Failed,
                     *iterations,
                     Some(error.clone()),
                     None,
@@ -569,7 +1800,7 @@ impl<'a> LoopRunner<'a> {
                 ),
             };
             let end = SessionEnd {
-                outcome: outcome_str.to_string(),
+                outcome: session_outcome,
                 iterations,
                 summary,
                 confidence,
@@ -581,3 +1812,547 @@ impl<'a> LoopRunner<'a> {
         }
     }
 }
+
+/// What the critic reviews on one iteration.
+struct CriticReview<'a> {
+    iteration: usize,
+    actor_output: &'a AgentOutput,
+    git_diff: &'a str,
+    coverage: Option<CoverageChange>,
+    scope_adherence: Option<&'a ScopeAdherence>,
+    regression: Option<VerifyRegression<'a>>,
+}
+
+/// The text a decision passes on: feedback, summary or error description.
+fn decision_feedback(decision: &CriticDecision) -> String {
+    match decision {
+        CriticDecision::Continue { feedback, .. } => feedback.clone(),
+        CriticDecision::Error {
+            error_description,
+            recovery_suggestion,
+        } => format!(
+            "Error encountered: {}\n\nRecovery suggestion: {}",
+            error_description, recovery_suggestion
+        ),
+        CriticDecision::Done { summary, .. } => summary.clone(),
+        CriticDecision::Reset { feedback } => feedback.clone(),
+    }
+}
+
+/// The sum of two costs; `None` only when neither is known.
+fn add_cost(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0)),
+    }
+}
+
+/// Empty an agent's tee files before it runs again, so they only hold the
+/// latest attempt's output.
+fn clear_tee_files(files: Option<&TeeFiles>) {
+    for file in files
+        .into_iter()
+        .flat_map(|(stdout, stderr)| [stdout, stderr])
+    {
+        if let Ok(mut file) = file.lock() {
+            let _ = file.set_len(0);
+            let _ = file.rewind();
+        }
+    }
+}
+
+/// `text` on one line, cut to [`RATIONALE_PREVIEW_CHARS`] characters.
+fn rationale_preview(text: &str) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= RATIONALE_PREVIEW_CHARS {
+        return line;
+    }
+    let cut: String = line.chars().take(RATIONALE_PREVIEW_CHARS).collect();
+    format!("{}...", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use codeloops_agent::{AgentError, AgentType, OutputCallbacks};
+    use codeloops_logging::LogFormat;
+
+    /// An agent that prints `reply` and reports `cost_usd` on every run,
+    /// first writing each of `edits` (a path and its contents). The first
+    /// `failures` runs exit with an error instead. With `sessions`, each run
+    /// reports a session id (`session-<run>`), and the session each run was
+    /// asked to resume is kept in `resumed`.
+    struct ScriptedAgent {
+        reply: String,
+        cost_usd: Option<f64>,
+        edits: Vec<(&'static str, &'static str)>,
+        failures: std::sync::atomic::AtomicUsize,
+        runs: std::sync::atomic::AtomicUsize,
+        sessions: bool,
+        resumed: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    impl ScriptedAgent {
+        fn new(reply: &str, cost_usd: Option<f64>) -> Self {
+            Self {
+                reply: reply.to_string(),
+                cost_usd,
+                edits: Vec::new(),
+                failures: Default::default(),
+                runs: Default::default(),
+                sessions: false,
+                resumed: Default::default(),
+            }
+        }
+
+        fn with_sessions(mut self) -> Self {
+            self.sessions = true;
+            self
+        }
+
+        fn failing(self, times: usize) -> Self {
+            self.failures.store(times, Ordering::SeqCst);
+            self
+        }
+
+        fn runs(&self) -> usize {
+            self.runs.load(Ordering::SeqCst)
+        }
+
+        fn editing(mut self, path: &'static str, contents: &'static str) -> Self {
+            self.edits.push((path, contents));
+            self
+        }
+    }
+
+    #[async_trait]
+    impl Agent for ScriptedAgent {
+        fn name(&self) -> &str {
+            "Scripted"
+        }
+
+        fn agent_type(&self) -> AgentType {
+            AgentType::ClaudeCode
+        }
+
+        fn binary_path(&self) -> &Path {
+            Path::new("scripted")
+        }
+
+        fn supports_continuation(&self) -> bool {
+            self.sessions
+        }
+
+        async fn check_available(&self, _force: bool) -> bool {
+            true
+        }
+
+        async fn execute_with_callbacks(
+            &self,
+            _prompt: &str,
+            config: &AgentConfig,
+            _callbacks: OutputCallbacks,
+        ) -> Result<AgentOutput, AgentError> {
+            let run = self.runs.fetch_add(1, Ordering::SeqCst);
+            self.resumed
+                .lock()
+                .unwrap()
+                .push(config.resume_session.clone());
+            let mut output = if run < self.failures.load(Ordering::SeqCst) {
+                AgentOutput::new(
+                    String::new(),
+                    "API Error: 529 Overloaded".to_string(),
+                    1,
+                    Duration::ZERO,
+                )
+            } else {
+                for (path, contents) in &self.edits {
+                    std::fs::write(config.working_dir.join(path), contents).unwrap();
+                }
+                AgentOutput::new(self.reply.clone(), String::new(), 0, Duration::ZERO)
+            };
+            if self.sessions && output.exit_code == 0 {
+                output = output.with_session_id(format!("session-{}", run));
+            }
+            Ok(match self.cost_usd {
+                Some(usd) => output.with_estimated_cost(usd),
+                None => output,
+            })
+        }
+    }
+
+    const CONTINUE: &str =
+        "<decision>\n{\"type\": \"continue\", \"feedback\": \"Keep going\"}\n</decision>";
+
+    #[tokio::test]
+    async fn max_cost_stops_the_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        let actor = ScriptedAgent::new("Edited the code", Some(0.4));
+        let critic = ScriptedAgent::new(CONTINUE, Some(0.1));
+        let mut runner = LoopRunner::new(
+            &actor,
+            &critic,
+            DiffCapture::new(),
+            Arc::new(Logger::new(LogFormat::Compact)),
+            None,
+            None,
+            None,
+        )
+        .with_max_cost(Some(1.2));
+
+        let context =
+            LoopContext::new("Do it".to_string(), dir.path().to_path_buf()).with_max_iterations(10);
+        match runner.run(context).await.unwrap() {
+            LoopOutcome::CostBudgetExceeded {
+                iterations,
+                spent_usd,
+                ..
+            } => {
+                // A third iteration would take spending to 1.5
+                assert_eq!(iterations, 2);
+                assert!((spent_usd - 1.0).abs() < 1e-9);
+            }
+            other => panic!("expected the budget to stop the loop, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_agent_runs_are_retried() {
+        let dir = tempfile::tempdir().unwrap();
+        let actor = ScriptedAgent::new("Edited the code", Some(0.5)).failing(2);
+        let critic = ScriptedAgent::new(
+            "<decision>\n{\"type\": \"done\", \"summary\": \"Finished\"}\n</decision>",
+            Some(0.1),
+        )
+        .failing(1);
+        let mut runner = LoopRunner::new(
+            &actor,
+            &critic,
+            DiffCapture::new(),
+            Arc::new(Logger::new(LogFormat::Compact)),
+            None,
+            None,
+            None,
+        )
+        .with_agent_retries(2)
+        .with_retry_backoff(RetryBackoff::new(Duration::ZERO));
+
+        let context = LoopContext::new("Do it".to_string(), dir.path().to_path_buf());
+        let outcome = runner.run(context).await.unwrap();
+        let LoopOutcome::Success { history, .. } = outcome else {
+            panic!("expected success, got {outcome:?}");
+        };
+        assert_eq!(actor.runs(), 3);
+        assert_eq!(critic.runs(), 2);
+        // Failed attempts count toward the spend
+        let cost = history[0].estimated_cost_usd.unwrap();
+        assert!((cost - 1.7).abs() < 1e-9, "cost was {cost}");
+
+        // Out of retries, the critic's failure ends the run
+        let actor = ScriptedAgent::new("Edited the code", None);
+        let critic = ScriptedAgent::new(CONTINUE, None).failing(5);
+        let mut runner = LoopRunner::new(
+            &actor,
+            &critic,
+            DiffCapture::new(),
+            Arc::new(Logger::new(LogFormat::Compact)),
+            None,
+            None,
+            None,
+        )
+        .with_agent_retries(1)
+        .with_retry_backoff(RetryBackoff::new(Duration::ZERO));
+
+        let context = LoopContext::new("Do it".to_string(), dir.path().to_path_buf());
+        let outcome = runner.run(context).await.unwrap();
+        assert!(matches!(outcome, LoopOutcome::Failed { .. }));
+        assert_eq!(critic.runs(), 2);
+    }
+
+    #[tokio::test]
+    async fn an_interrupt_cuts_the_retry_wait_short() {
+        let dir = tempfile::tempdir().unwrap();
+        let actor = ScriptedAgent::new("Edited the code", None).failing(1);
+        let critic = ScriptedAgent::new(CONTINUE, None);
+        let mut runner = LoopRunner::new(
+            &actor,
+            &critic,
+            DiffCapture::new(),
+            Arc::new(Logger::new(LogFormat::Compact)),
+            None,
+            None,
+            None,
+        )
+        .with_agent_retries(1)
+        .with_retry_backoff(RetryBackoff::new(Duration::from_secs(60)).with_seed(1));
+        let interrupted = runner.interrupt_handle();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            interrupted.store(true, Ordering::SeqCst);
+        });
+
+        let started = std::time::Instant::now();
+        let context = LoopContext::new("Do it".to_string(), dir.path().to_path_buf());
+        let outcome = runner.run(context).await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(matches!(outcome, LoopOutcome::UserInterrupted { .. }));
+        assert_eq!(actor.runs(), 1);
+    }
+
+    #[test]
+    fn cleared_tee_files_hold_only_the_next_attempt() {
+        let dir = tempfile::tempdir().unwrap();
+        let open = |name| {
+            Arc::new(StdMutex::new(
+                std::fs::File::create(dir.path().join(name)).unwrap(),
+            ))
+        };
+        let files = (open("stdout"), open("stderr"));
+        writeln!(files.0.lock().unwrap(), "first attempt").unwrap();
+        clear_tee_files(Some(&files));
+        writeln!(files.0.lock().unwrap(), "second").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("stdout")).unwrap(),
+            "second\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_results_are_stored_with_the_session() {
+        let dir = git_repo();
+        let sessions = tempfile::tempdir().unwrap();
+        let actor = ScriptedAgent::new("Broke it", None).editing("broken", "yes\n");
+        let critic = ScriptedAgent::new(CONTINUE, None);
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let mut runner = LoopRunner::new(
+            &actor,
+            &critic,
+            DiffCapture::new(),
+            Arc::new(Logger::new(LogFormat::Compact)),
+            Some(db.clone()),
+            None,
+            None,
+        )
+        .with_sessions_dir(sessions.path().to_path_buf())
+        .with_verify_command(Some("test ! -e broken".to_string()));
+
+        let context =
+            LoopContext::new("Do it".to_string(), dir.path().to_path_buf()).with_max_iterations(1);
+        let LoopOutcome::MaxIterationsReached { history, .. } = runner.run(context).await.unwrap()
+        else {
+            panic!("expected the loop to run out of iterations");
+        };
+        assert_eq!(history[0].verify_passed, Some(false));
+
+        let session_id = runner.session_id().unwrap().to_string();
+        let session = db.sessions().get(&session_id).unwrap().unwrap();
+        assert_eq!(session.verify_baseline, Some(true));
+        assert_eq!(session.iterations[0].verify_passed, Some(false));
+    }
+
+    #[tokio::test]
+    async fn regression_is_reviewed_but_blocks_done() {
+        let dir = git_repo();
+        let sessions = tempfile::tempdir().unwrap();
+        let actor = ScriptedAgent::new("Broke it", None).editing("broken", "yes\n");
+        let critic = ScriptedAgent::new(
+            "<decision>\n{\"type\": \"done\", \"summary\": \"Finished\"}\n</decision>",
+            None,
+        );
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let mut runner = LoopRunner::new(
+            &actor,
+            &critic,
+            DiffCapture::new(),
+            Arc::new(Logger::new(LogFormat::Compact)),
+            Some(db.clone()),
+            None,
+            None,
+        )
+        .with_sessions_dir(sessions.path().to_path_buf())
+        .with_verify_command(Some("test ! -e broken".to_string()))
+        .with_fail_on_regression(true);
+
+        let context =
+            LoopContext::new("Do it".to_string(), dir.path().to_path_buf()).with_max_iterations(1);
+        let LoopOutcome::MaxIterationsReached { history, .. } = runner.run(context).await.unwrap()
+        else {
+            panic!("expected the regression to keep the loop going");
+        };
+        assert_eq!(critic.runs(), 1);
+        assert!(history[0].critic_decision.starts_with("CONTINUE"));
+        assert!(history[0].critic_output.contains("Finished"));
+
+        let session_id = runner.session_id().unwrap().to_string();
+        let session = db.sessions().get(&session_id).unwrap().unwrap();
+        let feedback = session.iterations[0].feedback.as_deref().unwrap();
+        assert!(
+            feedback.starts_with("REGRESSION: `test ! -e broken`"),
+            "{}",
+            feedback
+        );
+    }
+
+    #[tokio::test]
+    async fn actor_resumes_its_own_session() {
+        let dir = tempfile::tempdir().unwrap();
+        // A critic of the same kind runs in between; its session is not the
+        // one resumed
+        let actor = ScriptedAgent::new("Edited the code", None).with_sessions();
+        let critic = ScriptedAgent::new(CONTINUE, None).with_sessions();
+        let mut runner = LoopRunner::new(
+            &actor,
+            &critic,
+            DiffCapture::new(),
+            Arc::new(Logger::new(LogFormat::Compact)),
+            None,
+            None,
+            None,
+        );
+
+        let context =
+            LoopContext::new("Do it".to_string(), dir.path().to_path_buf()).with_max_iterations(3);
+        runner.run(context).await.unwrap();
+        assert_eq!(
+            *actor.resumed.lock().unwrap(),
+            vec![
+                None,
+                Some("session-0".to_string()),
+                Some("session-1".to_string())
+            ]
+        );
+    }
+
+    /// A repository with one commit of `file.txt`.
+    fn git_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q"]);
+        std::fs::write(dir.path().join("file.txt"), "committed\n").unwrap();
+        git(&["add", "file.txt"]);
+        git(&["commit", "-q", "-m", "init"]);
+        dir
+    }
+
+    #[tokio::test]
+    async fn reset_keeps_changes_made_before_the_run() {
+        let dir = git_repo();
+        let sessions = tempfile::tempdir().unwrap();
+        let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).ok();
+        std::fs::write(dir.path().join("file.txt"), "user edit\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "user notes\n").unwrap();
+
+        let actor = ScriptedAgent::new("Rewrote it", None)
+            .editing("file.txt", "actor edit\n")
+            .editing("new.txt", "actor file\n");
+        let critic = ScriptedAgent::new(
+            "<decision>\n{\"type\": \"reset\", \"feedback\": \"Wrong approach\"}\n</decision>",
+            None,
+        );
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let mut runner = LoopRunner::new(
+            &actor,
+            &critic,
+            DiffCapture::new(),
+            Arc::new(Logger::new(LogFormat::Compact)),
+            Some(db.clone()),
+            None,
+            None,
+        )
+        .with_sessions_dir(sessions.path().to_path_buf())
+        .with_allow_critic_reset(true);
+
+        let context =
+            LoopContext::new("Do it".to_string(), dir.path().to_path_buf()).with_max_iterations(1);
+        let LoopOutcome::MaxIterationsReached { history, .. } = runner.run(context).await.unwrap()
+        else {
+            panic!("expected the loop to run out of iterations");
+        };
+
+        assert!(history[0].reset_applied);
+        assert_eq!(read("file.txt").as_deref(), Some("user edit\n"));
+        assert_eq!(read("notes.txt").as_deref(), Some("user notes\n"));
+        assert_eq!(read("new.txt"), None);
+
+        let session_id = runner.session_id().unwrap().to_string();
+        let session = db.sessions().get(&session_id).unwrap().unwrap();
+        assert!(session.iterations[0].reset_applied);
+    }
+
+    #[tokio::test]
+    async fn scope_check_ignores_changes_made_before_the_run() {
+        let dir = git_repo();
+        std::fs::write(dir.path().join("file.txt"), "user edit\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "user notes\n").unwrap();
+
+        let actor = ScriptedAgent::new("Added it", None).editing("new.txt", "actor file\n");
+        let critic = ScriptedAgent::new(CONTINUE, None);
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let mut runner = LoopRunner::new(
+            &actor,
+            &critic,
+            DiffCapture::new(),
+            Arc::new(Logger::new(LogFormat::Compact)),
+            Some(db.clone()),
+            None,
+            None,
+        );
+
+        let prompt = "Add a file\n\n## Files to Modify\n- new.txt\n- src/\n";
+        let context =
+            LoopContext::new(prompt.to_string(), dir.path().to_path_buf()).with_max_iterations(1);
+        runner.run(context).await.unwrap();
+
+        let session_id = runner.session_id().unwrap().to_string();
+        let session = db.sessions().get(&session_id).unwrap().unwrap();
+        let adherence = session.iterations[0].scope_adherence.clone().unwrap();
+        assert!(adherence.unexpected.is_empty(), "{:?}", adherence);
+        assert_eq!(adherence.untouched, ["src/"]);
+    }
+
+    #[tokio::test]
+    async fn files_touched_ignores_changes_made_before_the_run() {
+        let dir = git_repo();
+        std::fs::write(dir.path().join("file.txt"), "user edit\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "user notes\n").unwrap();
+
+        let actor = ScriptedAgent::new("Added it", None).editing("new.txt", "actor file\n");
+        let critic = ScriptedAgent::new(CONTINUE, None);
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let mut runner = LoopRunner::new(
+            &actor,
+            &critic,
+            DiffCapture::new(),
+            Arc::new(Logger::new(LogFormat::Compact)),
+            Some(db.clone()),
+            None,
+            None,
+        );
+
+        let context = LoopContext::new("Add a file".to_string(), dir.path().to_path_buf())
+            .with_max_iterations(2)
+            .with_cumulative_diff(true);
+        runner.run(context).await.unwrap();
+
+        let session_id = runner.session_id().unwrap().to_string();
+        let session = db.sessions().get(&session_id).unwrap().unwrap();
+        let prompt = session.iterations[1].actor_prompt.clone().unwrap();
+        let (_, touched) = prompt
+            .split_once("## Changes From Previous Iterations")
+            .unwrap();
+        let touched = touched.split("\n## ").next().unwrap();
+        assert!(touched.contains("- new.txt"), "{}", touched);
+        assert!(!touched.contains("file.txt"), "{}", touched);
+        assert!(!touched.contains("notes.txt"), "{}", touched);
+    }
+}
@@ -23,6 +23,22 @@ pub enum LoopOutcome {
         history: Vec<IterationRecord>,
         total_duration_secs: f64,
     },
+    /// Critic confidence stopped rising (see [`ConvergenceCheck`](crate::ConvergenceCheck))
+    NoProgress {
+        iterations: usize,
+        reason: String,
+        #[serde(skip)]
+        history: Vec<IterationRecord>,
+        total_duration_secs: f64,
+    },
+    /// The next iteration would likely have pushed spending past `--max-cost`
+    CostBudgetExceeded {
+        iterations: usize,
+        spent_usd: f64,
+        #[serde(skip)]
+        history: Vec<IterationRecord>,
+        total_duration_secs: f64,
+    },
     /// User requested stop (e.g., Ctrl+C)
     UserInterrupted {
         iterations: usize,
@@ -69,6 +85,34 @@ impl LoopOutcome {
         }
     }
 
+    pub fn no_progress(
+        iterations: usize,
+        reason: String,
+        history: Vec<IterationRecord>,
+        duration: Duration,
+    ) -> Self {
+        Self::NoProgress {
+            iterations,
+            reason,
+            history,
+            total_duration_secs: duration.as_secs_f64(),
+        }
+    }
+
+    pub fn cost_budget_exceeded(
+        iterations: usize,
+        spent_usd: f64,
+        history: Vec<IterationRecord>,
+        duration: Duration,
+    ) -> Self {
+        Self::CostBudgetExceeded {
+            iterations,
+            spent_usd,
+            history,
+            total_duration_secs: duration.as_secs_f64(),
+        }
+    }
+
     pub fn interrupted(
         iterations: usize,
         history: Vec<IterationRecord>,
@@ -99,11 +143,54 @@ impl LoopOutcome {
         match self {
             Self::Success { iterations, .. } => *iterations,
             Self::MaxIterationsReached { iterations, .. } => *iterations,
+            Self::NoProgress { iterations, .. } => *iterations,
+            Self::CostBudgetExceeded { iterations, .. } => *iterations,
             Self::UserInterrupted { iterations, .. } => *iterations,
             Self::Failed { iterations, .. } => *iterations,
         }
     }
 
+    /// The `status` tag the outcome serializes with, e.g. `"success"`.
+    pub fn status(&self) -> &'static str {
+        match self {
+            Self::Success { .. } => "success",
+            Self::MaxIterationsReached { .. } => "max_iterations_reached",
+            Self::NoProgress { .. } => "no_progress",
+            Self::CostBudgetExceeded { .. } => "cost_budget_exceeded",
+            Self::UserInterrupted { .. } => "user_interrupted",
+            Self::Failed { .. } => "failed",
+        }
+    }
+
+    pub fn total_duration_secs(&self) -> f64 {
+        match self {
+            Self::Success {
+                total_duration_secs,
+                ..
+            }
+            | Self::MaxIterationsReached {
+                total_duration_secs,
+                ..
+            }
+            | Self::NoProgress {
+                total_duration_secs,
+                ..
+            }
+            | Self::CostBudgetExceeded {
+                total_duration_secs,
+                ..
+            }
+            | Self::UserInterrupted {
+                total_duration_secs,
+                ..
+            }
+            | Self::Failed {
+                total_duration_secs,
+                ..
+            } => *total_duration_secs,
+        }
+    }
+
     pub fn is_success(&self) -> bool {
         matches!(self, Self::Success { .. })
     }
@@ -111,7 +198,9 @@ impl LoopOutcome {
     pub fn exit_code(&self) -> i32 {
         match self {
             Self::Success { .. } => 0,
-            Self::MaxIterationsReached { .. } => 1,
+            Self::MaxIterationsReached { .. }
+            | Self::NoProgress { .. }
+            | Self::CostBudgetExceeded { .. } => 1,
             Self::UserInterrupted { .. } => 130,
             Self::Failed { .. } => 2,
         }
@@ -0,0 +1,406 @@
+//! Structured view of a prompt.md file.
+//!
+//! Parses the markdown produced by the prompt builder (or written by hand)
+//! into its well-known sections so they can be scored, linted, and fed to
+//! the critic individually.
+
+use serde::{Deserialize, Serialize};
+
+/// A prompt.md broken into its well-known sections.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptDraft {
+    /// First `#` heading
+    pub title: Option<String>,
+    /// Problem statement / goal prose
+    pub goal: Option<String>,
+    /// In-scope requirements
+    pub requirements: Vec<String>,
+    /// Explicit exclusions
+    pub non_goals: Vec<String>,
+    pub acceptance_criteria: Vec<String>,
+    pub edge_cases: Vec<String>,
+    /// Verification / testing plan prose
+    pub testing_strategy: Option<String>,
+    /// Paths listed as the files to modify, from a section, sub-heading or
+    /// list item naming them (e.g. "Key files to modify" under Codebase
+    /// Context)
+    #[serde(default)]
+    pub files_to_modify: Vec<String>,
+    /// Sections that did not map onto a known field, as `(heading, body)`
+    pub other_sections: Vec<(String, String)>,
+}
+
+/// Which field a section heading maps onto.
+enum SectionKind {
+    Goal,
+    Requirements,
+    NonGoals,
+    AcceptanceCriteria,
+    EdgeCases,
+    TestingStrategy,
+    FilesToModify,
+    Other,
+}
+
+impl SectionKind {
+    fn from_heading(heading: &str) -> Self {
+        let h = heading.to_lowercase();
+        // Order matters: "non-goals" must be checked before "goals".
+        if names_files_to_modify(&h) {
+            Self::FilesToModify
+        } else if h.contains("codebase") {
+            Self::Other
+        } else if h.contains("non-goal") || h.contains("non goal") || h.contains("out of scope") {
+            Self::NonGoals
+        } else if h.contains("acceptance") || h.contains("definition of done") {
+            Self::AcceptanceCriteria
+        } else if h.contains("edge case") || h.contains("error handling") {
+            Self::EdgeCases
+        } else if h.contains("verification")
+            || h.contains("testing")
+            || h.contains("test plan")
+            || h.contains("tests")
+        {
+            Self::TestingStrategy
+        } else if h.contains("requirement") || h.contains("in scope") || h.contains("goals") {
+            Self::Requirements
+        } else if h.contains("goal")
+            || h.contains("problem")
+            || h.contains("context")
+            || h.contains("overview")
+            || h.contains("summary")
+            || h.contains("objective")
+        {
+            Self::Goal
+        } else {
+            Self::Other
+        }
+    }
+}
+
+impl PromptDraft {
+    /// Parse a prompt.md into its sections.
+    ///
+    /// The first `#` heading becomes the title. Each `##` heading starts a new
+    /// section; deeper headings stay part of the enclosing section's body.
+    /// Bullet and numbered list items are extracted for list-valued sections.
+    pub fn from_markdown(markdown: &str) -> Self {
+        let mut draft = PromptDraft::default();
+        let mut current: Option<(String, String)> = None;
+
+        for line in markdown.lines() {
+            let trimmed = line.trim_start();
+            if let Some(heading) = trimmed.strip_prefix("## ") {
+                if let Some((h, body)) = current.take() {
+                    draft.push_section(&h, &body);
+                }
+                current = Some((heading.trim().to_string(), String::new()));
+            } else if let (Some(title), true) = (trimmed.strip_prefix("# "), draft.title.is_none())
+            {
+                draft.title = Some(title.trim().to_string());
+            } else if let Some((_, ref mut body)) = current {
+                body.push_str(line);
+                body.push('\n');
+            }
+        }
+        if let Some((h, body)) = current.take() {
+            draft.push_section(&h, &body);
+        }
+
+        draft
+    }
+
+    fn push_section(&mut self, heading: &str, body: &str) {
+        let text = body.trim();
+        if text.is_empty() {
+            return;
+        }
+        let kind = SectionKind::from_heading(heading);
+        if !matches!(kind, SectionKind::FilesToModify) {
+            push_unique(&mut self.files_to_modify, files_in_body(text));
+        }
+        match kind {
+            SectionKind::Goal => append_prose(&mut self.goal, text),
+            SectionKind::Requirements => self.requirements.extend(list_items(text)),
+            SectionKind::NonGoals => self.non_goals.extend(list_items(text)),
+            SectionKind::AcceptanceCriteria => self.acceptance_criteria.extend(list_items(text)),
+            SectionKind::EdgeCases => self.edge_cases.extend(list_items(text)),
+            SectionKind::TestingStrategy => append_prose(&mut self.testing_strategy, text),
+            SectionKind::FilesToModify => push_unique(
+                &mut self.files_to_modify,
+                list_items(text)
+                    .iter()
+                    .flat_map(|item| paths_in(item))
+                    .collect(),
+            ),
+            SectionKind::Other => self
+                .other_sections
+                .push((heading.to_string(), text.to_string())),
+        }
+    }
+
+    /// Rough completeness score from 0 to 100.
+    ///
+    /// Acceptance criteria and requirements carry the most weight since the
+    /// critic judges against them; testing strategy and edge cases earn
+    /// partial credit.
+    pub fn completion_percentage(&self) -> u8 {
+        let weighted = [
+            (self.title.is_some(), 10),
+            (self.goal.is_some(), 15),
+            (!self.requirements.is_empty(), 25),
+            (!self.acceptance_criteria.is_empty(), 30),
+            (self.testing_strategy.is_some(), 10),
+            (!self.edge_cases.is_empty(), 10),
+        ];
+        weighted
+            .iter()
+            .filter(|(filled, _)| *filled)
+            .map(|(_, weight)| weight)
+            .sum()
+    }
+}
+
+fn append_prose(field: &mut Option<String>, text: &str) {
+    match field {
+        Some(existing) => {
+            existing.push_str("\n\n");
+            existing.push_str(text);
+        }
+        None => *field = Some(text.to_string()),
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, items: Vec<String>) {
+    for item in items {
+        if !list.contains(&item) {
+            list.push(item);
+        }
+    }
+}
+
+fn names_files_to_modify(text: &str) -> bool {
+    let text = text.to_lowercase();
+    text.contains("files to modify") || text.contains("files to change")
+}
+
+/// Paths listed under a `###` heading or a list item that names the files
+/// to modify, within a section about something else. A list item opens a
+/// list of its more deeply indented children; paths after its colon count
+/// too.
+fn files_in_body(body: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    // Some(None): under a heading; Some(Some(indent)): under a list item
+    let mut open: Option<Option<usize>> = None;
+    for line in body.lines() {
+        let indent = line.len() - line.trim_start().len();
+        if let Some(heading) = line.trim().strip_prefix('#') {
+            open = names_files_to_modify(heading).then_some(None);
+            continue;
+        }
+        let Some(item) = strip_list_marker(line) else {
+            continue;
+        };
+        if matches!(open, Some(Some(parent)) if indent <= parent) {
+            open = None;
+        }
+        if open.is_some() {
+            files.extend(paths_in(&item));
+        } else if names_files_to_modify(&item) {
+            let after_colon = item.split_once(':').map_or("", |(_, rest)| rest);
+            files.extend(paths_in(after_colon));
+            open = Some(Some(indent));
+        }
+    }
+    files
+}
+
+/// File paths mentioned in a list item. Backtick spans are preferred; without
+/// any, words that look like paths are taken. `:line` suffixes are dropped.
+fn paths_in(text: &str) -> Vec<String> {
+    let quoted: Vec<&str> = text.split('`').skip(1).step_by(2).collect();
+    let (candidates, strict) = if quoted.is_empty() {
+        (text.split_whitespace().collect::<Vec<_>>(), true)
+    } else {
+        (quoted, false)
+    };
+    candidates
+        .into_iter()
+        .filter_map(|word| {
+            let word = word
+                .trim_matches(|c: char| matches!(c, '*' | ',' | ';' | '(' | ')' | '"' | '\''))
+                .trim_end_matches('.');
+            let path = match word.rsplit_once(':') {
+                Some((path, line)) if line.chars().all(|c| c.is_ascii_digit() || c == '-') => path,
+                _ => word,
+            };
+            let path = path.strip_prefix("./").unwrap_or(path);
+            looks_like_path(path, strict).then(|| path.to_string())
+        })
+        .collect()
+}
+
+/// Whether `word` could be a repository path. `strict` applies to bare
+/// words in prose, where "e.g." and the like must not count.
+fn looks_like_path(word: &str, strict: bool) -> bool {
+    if word.is_empty() || word.contains(char::is_whitespace) || word.contains("://") {
+        return false;
+    }
+    if !word
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '/' | '.' | '_' | '-' | '[' | ']' | '@'))
+    {
+        return false;
+    }
+    let has_extension = word
+        .rsplit_once('.')
+        .is_some_and(|(stem, ext)| !ext.is_empty() && (!strict || stem.len() > 1));
+    word.contains('/') || has_extension
+}
+
+/// Extract list items from a section body.
+///
+/// Falls back to treating each non-empty paragraph line as an item when the
+/// section has no bullets, so prose-style sections still count.
+fn list_items(text: &str) -> Vec<String> {
+    let items: Vec<String> = text.lines().filter_map(strip_list_marker).collect();
+    if !items.is_empty() {
+        return items;
+    }
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
+fn strip_list_marker(line: &str) -> Option<String> {
+    let line = line.trim_start();
+    let rest = if let Some(rest) = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+    {
+        rest
+    } else {
+        let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return None;
+        }
+        line[digits..]
+            .strip_prefix(". ")
+            .or_else(|| line[digits..].strip_prefix(") "))?
+    };
+    // Drop task-list checkboxes.
+    let rest = rest
+        .strip_prefix("[ ] ")
+        .or_else(|| rest.strip_prefix("[x] "))
+        .or_else(|| rest.strip_prefix("[X] "))
+        .unwrap_or(rest)
+        .trim();
+    (!rest.is_empty()).then(|| rest.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL: &str = r#"# Add rate limiting
+
+## Context / Problem
+The API has no rate limiting and gets hammered by bots.
+
+## Goals (in scope)
+- Limit requests per IP
+- Return 429 when exceeded
+
+## Non-goals (out of scope)
+- Per-user quotas
+
+## Edge Cases & Error Handling
+- Clock skew between nodes
+
+## Acceptance Criteria
+1. Requests over the limit get 429
+2. [ ] Limit is configurable
+
+## Verification Plan
+Add integration tests hitting the endpoint in a loop.
+
+## Open Questions
+Which store backs the counters?
+"#;
+
+    #[test]
+    fn parses_known_sections() {
+        let draft = PromptDraft::from_markdown(FULL);
+        assert_eq!(draft.title.as_deref(), Some("Add rate limiting"));
+        assert!(draft.goal.as_deref().unwrap().contains("no rate limiting"));
+        assert_eq!(
+            draft.requirements,
+            vec!["Limit requests per IP", "Return 429 when exceeded"]
+        );
+        assert_eq!(draft.non_goals, vec!["Per-user quotas"]);
+        assert_eq!(draft.edge_cases, vec!["Clock skew between nodes"]);
+        assert_eq!(
+            draft.acceptance_criteria,
+            vec!["Requests over the limit get 429", "Limit is configurable"]
+        );
+        assert!(draft.testing_strategy.is_some());
+        assert_eq!(draft.other_sections.len(), 1);
+        assert_eq!(draft.other_sections[0].0, "Open Questions");
+    }
+
+    #[test]
+    fn completion_percentage_weights_sections() {
+        assert_eq!(PromptDraft::default().completion_percentage(), 0);
+        assert_eq!(
+            PromptDraft::from_markdown(FULL).completion_percentage(),
+            100
+        );
+        let partial = PromptDraft::from_markdown("# Title\n\n## Goal\nDo the thing.\n");
+        assert_eq!(partial.completion_percentage(), 25);
+        let core = PromptDraft::from_markdown(
+            "## Requirements\n- Cache reads\n\n## Acceptance Criteria\n- Hits logged\n",
+        );
+        assert_eq!(core.completion_percentage(), 55);
+    }
+
+    #[test]
+    fn collects_files_to_modify() {
+        let draft = PromptDraft::from_markdown(
+            "## Files to Modify\n- `src/api/routes.rs` (add the route)\n- src/models/user.rs:42\n",
+        );
+        assert_eq!(
+            draft.files_to_modify,
+            vec!["src/api/routes.rs", "src/models/user.rs"]
+        );
+
+        // As the interviewer writes it: nested under Codebase Context
+        let draft = PromptDraft::from_markdown(
+            "## Codebase Context\n\
+             - Key files to modify:\n  \
+               - `src/limits.rs`\n  \
+               - ./src/api/mod.rs, e.g. the router\n\
+             - Existing patterns to follow: `src/auth.rs:10`\n\n\
+             ### Files to change\n\
+             1. `Cargo.toml`\n\
+             2. `src/limits.rs`\n",
+        );
+        assert_eq!(
+            draft.files_to_modify,
+            vec!["src/limits.rs", "src/api/mod.rs", "Cargo.toml"]
+        );
+        assert_eq!(draft.other_sections.len(), 1);
+
+        let draft = PromptDraft::from_markdown(FULL);
+        assert!(draft.files_to_modify.is_empty());
+    }
+
+    #[test]
+    fn plain_prompt_has_no_sections() {
+        let draft = PromptDraft::from_markdown("Fix the bug in login.rs");
+        assert_eq!(draft, PromptDraft::default());
+    }
+}
@@ -0,0 +1,113 @@
+//! A person reviewing each iteration before its decision takes effect.
+//!
+//! With a reviewer set, the loop shows it every iteration's diff and the
+//! critic's decision. The reviewer can let the decision stand, reject it and
+//! have the actor go again, or replace the critic's feedback with their own.
+
+use codeloops_critic::CriticDecision;
+
+/// Feedback for the actor when a person rejects an iteration. The critic's
+/// feedback, if it gave any, follows.
+const REJECTED_FEEDBACK: &str = "A human reviewer rejected the changes from this iteration. \
+     Re-read the original task and rework them.";
+
+/// How a person ruled on one iteration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewVerdict {
+    /// Let the critic's decision stand
+    Accept,
+    /// Overrule the critic and run another iteration
+    Reject,
+    /// Run another iteration with this feedback instead of the critic's
+    Feedback(String),
+}
+
+impl ReviewVerdict {
+    /// Name recorded in the session for the verdict.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewVerdict::Accept => "accepted",
+            ReviewVerdict::Reject => "rejected",
+            ReviewVerdict::Feedback(_) => "feedback",
+        }
+    }
+
+    /// The decision that takes effect given the critic's `decision`.
+    pub fn apply(&self, decision: CriticDecision) -> CriticDecision {
+        let feedback = match self {
+            ReviewVerdict::Accept => return decision,
+            ReviewVerdict::Reject => match critic_feedback(&decision) {
+                Some(feedback) => format!("{}\n\n{}", REJECTED_FEEDBACK, feedback),
+                None => REJECTED_FEEDBACK.to_string(),
+            },
+            ReviewVerdict::Feedback(feedback) => feedback.clone(),
+        };
+        CriticDecision::Continue {
+            feedback,
+            remaining_issues: vec![],
+            checklist: decision.checklist().to_vec(),
+            rubric: decision.rubric().to_vec(),
+        }
+    }
+}
+
+/// What the critic asked the actor to do, for decisions that ask anything.
+fn critic_feedback(decision: &CriticDecision) -> Option<&str> {
+    match decision {
+        CriticDecision::Continue { feedback, .. } | CriticDecision::Reset { feedback } => {
+            Some(feedback.as_str())
+        }
+        CriticDecision::Error {
+            recovery_suggestion,
+            ..
+        } => Some(recovery_suggestion.as_str()),
+        CriticDecision::Done { .. } => None,
+    }
+    .filter(|feedback| !feedback.trim().is_empty())
+}
+
+/// Asks a person to rule on each iteration. Called from a blocking task, so
+/// implementations may wait on the terminal.
+pub trait IterationReviewer: Send + Sync {
+    fn review(&self, iteration: usize, git_diff: &str, decision: &CriticDecision) -> ReviewVerdict;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn done() -> CriticDecision {
+        CriticDecision::Done {
+            summary: "Fixed".to_string(),
+            confidence: 0.9,
+            checklist: vec![],
+            rubric: vec![],
+        }
+    }
+
+    fn feedback_of(decision: CriticDecision) -> String {
+        match decision {
+            CriticDecision::Continue { feedback, .. } => feedback,
+            other => panic!("expected continue, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn verdicts_override_the_critic() {
+        assert_eq!(ReviewVerdict::Accept.apply(done()), done());
+        assert_eq!(
+            feedback_of(ReviewVerdict::Reject.apply(done())),
+            REJECTED_FEEDBACK
+        );
+        let reset = CriticDecision::Reset {
+            feedback: "Use the parser instead".to_string(),
+        };
+        assert_eq!(
+            feedback_of(ReviewVerdict::Reject.apply(reset)),
+            format!("{REJECTED_FEEDBACK}\n\nUse the parser instead")
+        );
+        let verdict = ReviewVerdict::Feedback("Keep the old flag name".to_string());
+        assert_eq!(feedback_of(verdict.apply(done())), "Keep the old flag name");
+        assert_eq!(verdict.as_str(), "feedback");
+    }
+}
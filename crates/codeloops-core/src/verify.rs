@@ -0,0 +1,100 @@
+//! Verify command support.
+//!
+//! A verify command (e.g. `cargo test`) is run at session start to establish
+//! a baseline and again after each actor iteration. A command that passed at
+//! baseline but fails after an iteration is a regression.
+
+use std::path::Path;
+
+use codeloops_agent::text::tail;
+use tokio::process::Command;
+
+/// Maximum bytes of verify output kept for feedback.
+const MAX_OUTPUT_TAIL: usize = 4_000;
+
+/// Outcome of a single verify command run.
+#[derive(Debug, Clone)]
+pub struct VerifyResult {
+    pub passed: bool,
+    pub exit_code: i32,
+    /// Tail of combined stdout/stderr
+    pub output_tail: String,
+}
+
+impl VerifyResult {
+    /// Whether this result is a regression relative to `baseline`.
+    pub fn is_regression(&self, baseline: Option<bool>) -> bool {
+        baseline == Some(true) && !self.passed
+    }
+}
+
+/// Run `command` through the shell in `working_dir`.
+///
+/// Failure to spawn the shell is reported as a failed run with exit code -1
+/// rather than an error, so a broken verify command never aborts the loop.
+pub async fn run_verify(command: &str, working_dir: &Path) -> VerifyResult {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(working_dir)
+        .output()
+        .await;
+
+    match output {
+        Ok(output) => {
+            let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+            combined.push_str(&String::from_utf8_lossy(&output.stderr));
+            VerifyResult {
+                passed: output.status.success(),
+                exit_code: output.status.code().unwrap_or(-1),
+                output_tail: tail(&combined, MAX_OUTPUT_TAIL).to_string(),
+            }
+        }
+        Err(e) => VerifyResult {
+            passed: false,
+            exit_code: -1,
+            output_tail: format!("failed to run verify command: {}", e),
+        },
+    }
+}
+
+/// Feedback handed to the actor when an iteration regresses the verify command.
+pub fn regression_feedback(command: &str, result: &VerifyResult) -> String {
+    format!(
+        "REGRESSION: `{}` passed before this session but fails after your last changes \
+         (exit {}). Fix the regression before continuing with the task.\n\nOutput:\n{}",
+        command,
+        result.exit_code,
+        result.output_tail.trim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn run_verify_reports_exit_status() {
+        let dir = std::env::temp_dir();
+        let ok = run_verify("echo fine", &dir).await;
+        assert!(ok.passed);
+        assert_eq!(ok.output_tail.trim(), "fine");
+
+        let bad = run_verify("echo broken >&2; exit 3", &dir).await;
+        assert!(!bad.passed);
+        assert_eq!(bad.exit_code, 3);
+        assert!(bad.output_tail.contains("broken"));
+    }
+
+    #[test]
+    fn regression_requires_passing_baseline() {
+        let failed = VerifyResult {
+            passed: false,
+            exit_code: 1,
+            output_tail: String::new(),
+        };
+        assert!(failed.is_regression(Some(true)));
+        assert!(!failed.is_regression(Some(false)));
+        assert!(!failed.is_regression(None));
+    }
+}
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::debug;
 
+use crate::ChecklistItem;
+
 /// The critic's decision after evaluating actor output
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
@@ -13,6 +15,12 @@ pub enum CriticDecision {
         /// Confidence level (0.0 - 1.0)
         #[serde(default = "default_confidence")]
         confidence: f64,
+        /// Status of each acceptance criterion, when a checklist was given
+        #[serde(default)]
+        checklist: Vec<ChecklistItem>,
+        /// Status of each rubric criterion, when a rubric was given
+        #[serde(default)]
+        rubric: Vec<ChecklistItem>,
     },
     /// Task needs more work, continue with feedback
     Continue {
@@ -21,6 +29,12 @@ pub enum CriticDecision {
         /// What aspects still need work
         #[serde(default)]
         remaining_issues: Vec<String>,
+        /// Status of each acceptance criterion, when a checklist was given
+        #[serde(default)]
+        checklist: Vec<ChecklistItem>,
+        /// Status of each rubric criterion, when a rubric was given
+        #[serde(default)]
+        rubric: Vec<ChecklistItem>,
     },
     /// Actor encountered an error that needs addressing
     Error {
@@ -30,6 +44,11 @@ pub enum CriticDecision {
         #[serde(default)]
         recovery_suggestion: String,
     },
+    /// The approach is wrong; discard the working tree changes and start over
+    Reset {
+        /// Guidance for the fresh attempt
+        feedback: String,
+    },
 }
 
 fn default_confidence() -> f64 {
@@ -78,6 +97,23 @@ impl CriticDecision {
         Self::parse_simple_markers(critic_output)
     }
 
+    /// Read the `confidence` field from the decision block, whatever the
+    /// decision type.
+    ///
+    /// Returns `None` when there is no decision block, the field is missing,
+    /// or it is outside 0.0 - 1.0. Unlike [`CriticDecision::Done`], no
+    /// default is filled in.
+    pub fn parse_confidence(critic_output: &str) -> Option<f64> {
+        let start = critic_output.find("<decision>")?;
+        let end = critic_output.find("</decision>")?;
+        let json_str = critic_output.get(start + 10..end)?.trim();
+        let value: serde_json::Value = serde_json::from_str(json_str).ok()?;
+        value
+            .get("confidence")?
+            .as_f64()
+            .filter(|c| (0.0..=1.0).contains(c))
+    }
+
     fn parse_decision_block(output: &str) -> Result<Option<Self>, DecisionParseError> {
         let decision_start = output.find("<decision>");
         let decision_end = output.find("</decision>");
@@ -125,6 +161,8 @@ impl CriticDecision {
                 Ok(CriticDecision::Done {
                     summary: "Task marked as complete by critic".into(),
                     confidence: 0.8,
+                    checklist: vec![],
+                    rubric: vec![],
                 })
             }
             (false, true) => {
@@ -132,6 +170,8 @@ impl CriticDecision {
                 Ok(CriticDecision::Continue {
                     feedback: Self::extract_feedback(output),
                     remaining_issues: vec![],
+                    checklist: vec![],
+                    rubric: vec![],
                 })
             }
             (true, true) => Err(DecisionParseError::AmbiguousDecision),
@@ -175,6 +215,39 @@ impl CriticDecision {
         matches!(self, CriticDecision::Error { .. })
     }
 
+    pub fn is_reset(&self) -> bool {
+        matches!(self, CriticDecision::Reset { .. })
+    }
+
+    /// Per-criterion status reported with a `done` or `continue` decision.
+    pub fn checklist(&self) -> &[ChecklistItem] {
+        match self {
+            CriticDecision::Done { checklist, .. } | CriticDecision::Continue { checklist, .. } => {
+                checklist
+            }
+            CriticDecision::Error { .. } | CriticDecision::Reset { .. } => &[],
+        }
+    }
+
+    /// Per-criterion rubric status reported with a `done` or `continue`
+    /// decision.
+    pub fn rubric(&self) -> &[ChecklistItem] {
+        match self {
+            CriticDecision::Done { rubric, .. } | CriticDecision::Continue { rubric, .. } => rubric,
+            CriticDecision::Error { .. } | CriticDecision::Reset { .. } => &[],
+        }
+    }
+
+    /// The decision type in upper case, e.g. `DONE`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CriticDecision::Done { .. } => "DONE",
+            CriticDecision::Continue { .. } => "CONTINUE",
+            CriticDecision::Error { .. } => "ERROR",
+            CriticDecision::Reset { .. } => "RESET",
+        }
+    }
+
     /// Get a short description of the decision for logging
     pub fn short_description(&self) -> String {
         match self {
@@ -191,6 +264,7 @@ impl CriticDecision {
                 }
             }
             CriticDecision::Error { .. } => "ERROR".to_string(),
+            CriticDecision::Reset { .. } => "RESET".to_string(),
         }
     }
 }
@@ -214,6 +288,7 @@ The task has been completed successfully.
         if let CriticDecision::Done {
             summary,
             confidence,
+            ..
         } = decision
         {
             assert_eq!(summary, "Implemented the feature");
@@ -236,6 +311,7 @@ The implementation is incomplete.
         if let CriticDecision::Continue {
             feedback,
             remaining_issues,
+            ..
         } = decision
         {
             assert_eq!(feedback, "Need to add error handling");
@@ -257,10 +333,95 @@ The implementation is incomplete.
         assert!(decision.is_continue());
     }
 
+    #[test]
+    fn test_parse_reset_decision() {
+        let output = r#"
+The approach is fundamentally wrong.
+
+<decision>
+{"type": "reset", "feedback": "Use the existing cache module instead"}
+</decision>
+"#;
+
+        let decision = CriticDecision::parse(output).unwrap();
+        assert!(decision.is_reset());
+        assert_eq!(decision.short_description(), "RESET");
+    }
+
     #[test]
     fn test_parse_no_decision() {
         let output = "This output has no clear decision markers.";
         let result = CriticDecision::parse(output);
         assert!(matches!(result, Err(DecisionParseError::NoDecisionFound)));
     }
+
+    #[test]
+    fn test_parse_confidence_any_decision_type() {
+        let output = r#"
+<decision>
+{"type": "continue", "feedback": "Add tests", "confidence": 0.6}
+</decision>
+"#;
+        assert!(CriticDecision::parse(output).unwrap().is_continue());
+        assert_eq!(CriticDecision::parse_confidence(output), Some(0.6));
+
+        let without = r#"<decision>{"type": "done", "summary": "ok"}</decision>"#;
+        assert_eq!(CriticDecision::parse_confidence(without), None);
+
+        let out_of_range =
+            r#"<decision>{"type": "continue", "feedback": "x", "confidence": 7}</decision>"#;
+        assert_eq!(CriticDecision::parse_confidence(out_of_range), None);
+        assert_eq!(CriticDecision::parse_confidence("TASK COMPLETE"), None);
+    }
+
+    #[test]
+    fn test_parse_checklist() {
+        let output = r#"
+<decision>
+{"type": "continue", "feedback": "Log misses", "checklist": [
+  {"item": "Hits are logged", "met": true},
+  {"item": "Misses are logged", "met": false}
+]}
+</decision>
+"#;
+        let decision = CriticDecision::parse(output).unwrap();
+        assert_eq!(
+            decision.checklist(),
+            [
+                ChecklistItem {
+                    item: "Hits are logged".to_string(),
+                    met: true
+                },
+                ChecklistItem {
+                    item: "Misses are logged".to_string(),
+                    met: false
+                },
+            ]
+        );
+
+        let without = r#"<decision>{"type": "done", "summary": "ok"}</decision>"#;
+        assert!(CriticDecision::parse(without)
+            .unwrap()
+            .checklist()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_rubric() {
+        let output = r#"
+<decision>
+{"type": "done", "summary": "ok", "checklist": [{"item": "Hits are logged", "met": true}],
+ "rubric": [{"item": "Public functions have doc comments", "met": false}]}
+</decision>
+"#;
+        let decision = CriticDecision::parse(output).unwrap();
+        assert_eq!(decision.checklist().len(), 1);
+        assert_eq!(
+            decision.rubric(),
+            [ChecklistItem {
+                item: "Public functions have doc comments".to_string(),
+                met: false
+            }]
+        );
+    }
 }
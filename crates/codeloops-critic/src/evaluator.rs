@@ -1,7 +1,14 @@
-use codeloops_agent::{Agent, AgentConfig, OutputCallback};
+use codeloops_agent::text::tail;
+use codeloops_agent::{Agent, AgentConfig, AgentOutput, ExecStatus, OutputCallback};
 use tracing::{debug, info};
 
-use crate::{CriticDecision, CriticPrompts, DecisionParseError};
+use crate::{
+    CoverageChange, CriticDecision, CriticPrompts, DecisionParseError, DiffScopeNote, Rubric,
+    ScopeAdherence, VerifyRegression,
+};
+
+/// Bytes of critic stderr kept in [`EvaluationError::AgentFailure`].
+const STDERR_TAIL_LEN: usize = 2000;
 
 /// Inputs required to evaluate the critic decision.
 #[derive(Clone, Copy)]
@@ -11,6 +18,24 @@ pub struct CriticEvaluationInput<'a> {
     pub actor_stderr: &'a str,
     pub git_diff: &'a str,
     pub iteration: usize,
+    /// Offer the critic the `reset` decision
+    pub allow_reset: bool,
+    /// Acceptance criteria the critic must mark as met or unmet
+    pub acceptance_criteria: &'a [String],
+    /// Test coverage after the iteration, when a coverage command is set
+    pub coverage: Option<CoverageChange>,
+    /// Reference material shared with the actor, placed before the prompt
+    pub reference: Option<&'a str>,
+    /// Project-wide criteria the critic must mark as met or unmet
+    pub rubric: Option<&'a Rubric>,
+    /// Set when `git_diff` was narrowed to part of the changes
+    pub diff_scope: Option<DiffScopeNote<'a>>,
+    /// How the changed files compare with the prompt's files to modify,
+    /// when it lists any
+    pub scope_adherence: Option<&'a ScopeAdherence>,
+    /// Set when the verify command passed at session start and fails after
+    /// this iteration; the critic is told not to decide `done`
+    pub regression: Option<VerifyRegression<'a>>,
 }
 
 /// Evaluator that runs the critic agent
@@ -39,13 +64,23 @@ impl<'a> CriticEvaluator<'a> {
         config: &AgentConfig,
         on_output: Option<OutputCallback>,
     ) -> Result<CriticDecision, EvaluationError> {
-        let prompt = CriticPrompts::build_evaluation_prompt(
-            input.original_task,
-            input.actor_stdout,
-            input.actor_stderr,
-            input.git_diff,
-            input.iteration,
-        );
+        let output = self.run_with_callback(input, config, on_output).await?;
+        CriticDecision::parse(&output.stdout).map_err(EvaluationError::ParseError)
+    }
+
+    /// Run the critic agent and return its output without parsing it. The raw
+    /// response is in `stdout`.
+    ///
+    /// Callers that need to keep the response around (e.g. to debug parse
+    /// failures) or its cost run this and then call [`CriticDecision::parse`]
+    /// themselves.
+    pub async fn run_with_callback(
+        &self,
+        input: CriticEvaluationInput<'_>,
+        config: &AgentConfig,
+        on_output: Option<OutputCallback>,
+    ) -> Result<AgentOutput, EvaluationError> {
+        let prompt = CriticPrompts::build_evaluation_prompt(&input);
 
         debug!(
             prompt_len = prompt.len(),
@@ -65,14 +100,17 @@ impl<'a> CriticEvaluator<'a> {
             "Critic completed"
         );
 
-        if output.exit_code != 0 {
-            return Err(EvaluationError::AgentError(format!(
-                "Critic exited with code {}",
-                output.exit_code
-            )));
+        match self.agent.classify_exit(&output) {
+            ExecStatus::Ok => {}
+            ExecStatus::Refused => {
+                return Err(EvaluationError::AgentRefused(
+                    output.stdout.trim().to_string(),
+                ))
+            }
+            ExecStatus::Error => return Err(EvaluationError::failure(&output)),
         }
 
-        CriticDecision::parse(&output.stdout).map_err(EvaluationError::ParseError)
+        Ok(output)
     }
 }
 
@@ -81,6 +119,83 @@ pub enum EvaluationError {
     #[error("Agent execution error: {0}")]
     AgentError(String),
 
+    #[error("Critic exited with code {exit_code}: {stderr_tail}")]
+    AgentFailure {
+        exit_code: i32,
+        stderr_tail: String,
+        /// What the failed run cost, when the agent reported it
+        estimated_cost_usd: Option<f64>,
+    },
+
+    #[error("Critic refused to review: {0}")]
+    AgentRefused(String),
+
     #[error("Failed to parse critic decision: {0}")]
     ParseError(#[from] DecisionParseError),
 }
+
+impl EvaluationError {
+    /// Build an [`EvaluationError::AgentFailure`] from a non-zero exit.
+    ///
+    /// Keeps the last [`STDERR_TAIL_LEN`] bytes of stderr, falling back to
+    /// stdout when the agent wrote its error there instead.
+    pub fn failure(output: &AgentOutput) -> Self {
+        let stream = if output.stderr.trim().is_empty() {
+            &output.stdout
+        } else {
+            &output.stderr
+        };
+        let stderr_tail = match tail(stream.trim(), STDERR_TAIL_LEN) {
+            "" => "(no output)".to_string(),
+            tail => tail.to_string(),
+        };
+        EvaluationError::AgentFailure {
+            exit_code: output.exit_code,
+            stderr_tail,
+            estimated_cost_usd: output.estimated_cost_usd,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn output(stdout: &str, stderr: &str, exit_code: i32) -> AgentOutput {
+        AgentOutput::new(
+            stdout.to_string(),
+            stderr.to_string(),
+            exit_code,
+            Duration::ZERO,
+        )
+    }
+
+    #[test]
+    fn failure_keeps_exit_code_and_stderr_tail() {
+        let stderr = format!("{}\nError: invalid API key\n", "x".repeat(5000));
+        let err = EvaluationError::failure(&output("", &stderr, 1));
+        match &err {
+            EvaluationError::AgentFailure {
+                exit_code,
+                stderr_tail,
+                ..
+            } => {
+                assert_eq!(*exit_code, 1);
+                assert!(stderr_tail.len() <= STDERR_TAIL_LEN);
+                assert!(stderr_tail.ends_with("Error: invalid API key"));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(err.to_string().starts_with("Critic exited with code 1: "));
+    }
+
+    #[test]
+    fn failure_falls_back_to_stdout() {
+        let err = EvaluationError::failure(&output("rate limited", "  \n", 2));
+        assert_eq!(err.to_string(), "Critic exited with code 2: rate limited");
+
+        let err = EvaluationError::failure(&output("", "", 137));
+        assert_eq!(err.to_string(), "Critic exited with code 137: (no output)");
+    }
+}
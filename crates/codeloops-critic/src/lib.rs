@@ -11,16 +11,21 @@
 //! - Original task prompt
 //! - Actor's output (stdout)
 //! - Git diff of changes
+//! - Change in test coverage, when a coverage command is set
+//! - A [`VerifyRegression`], when the verify command broke during the iteration
+//! - The project's [`Rubric`], when one is configured
 //! - Iteration history
 //!
 //! And returns a decision:
 //! - [`CriticDecision::Done`] - Task complete
 //! - [`CriticDecision::Continue`] - More work needed, with feedback
 //! - [`CriticDecision::Error`] - Error occurred, with recovery suggestion
+//! - [`CriticDecision::Reset`] - Approach is wrong; discard changes and start over
 //!
 //! ## Key Types
 //!
 //! - [`CriticEvaluator`] - Runs evaluation using an agent
+//! - [`RuleCritic`] - Decides from the verify command and diff, without an agent
 //! - [`CriticDecision`] - Parsed decision from critic response
 //! - [`CriticEvaluationInput`] - Input data for evaluation
 //!
@@ -31,7 +36,7 @@
 //! use codeloops_agent::{create_agent, AgentType};
 //!
 //! // Create evaluator with a critic agent
-//! let agent = create_agent(AgentType::ClaudeCode);
+//! let agent = create_agent(AgentType::ClaudeCode)?;
 //! let evaluator = CriticEvaluator::new(agent);
 //!
 //! // Prepare evaluation input
@@ -74,7 +79,12 @@
 mod decision;
 pub mod evaluator;
 mod prompts;
+mod rubric;
+mod rules;
 
+pub use codeloops_db::{ChecklistItem, RubricResult, ScopeAdherence};
 pub use decision::{CriticDecision, DecisionParseError};
 pub use evaluator::{CriticEvaluationInput, CriticEvaluator, EvaluationError};
-pub use prompts::CriticPrompts;
+pub use prompts::{CoverageChange, CriticPrompts, DiffScopeNote, VerifyRegression};
+pub use rubric::{Rubric, RubricCriterion};
+pub use rules::{RuleConfig, RuleCritic, RuleInput, RuleVerify};
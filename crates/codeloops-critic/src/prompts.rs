@@ -1,16 +1,56 @@
+/// Extra decision option offered when the loop allows the critic to reset.
+const RESET_OPTION: &str = r#"
+**If the approach is FUNDAMENTALLY WRONG and building on it would make things worse:**
+<decision>
+{"type": "reset", "feedback": "Why the approach failed and what to do instead"}
+</decision>
+This discards ALL uncommitted changes in the working tree before the next attempt. Use it only when starting over is clearly cheaper than fixing.
+"#;
+
+use codeloops_agent::text;
+
+use crate::{CriticEvaluationInput, Rubric, ScopeAdherence};
+
+/// Coverage percentage after the iteration under review, and at session start.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoverageChange {
+    /// `None` when the baseline run printed no percentage
+    pub baseline: Option<f64>,
+    pub current: f64,
+}
+
+impl CoverageChange {
+    /// Change in percentage points, when the baseline is known.
+    pub fn delta(&self) -> Option<f64> {
+        self.baseline.map(|baseline| self.current - baseline)
+    }
+}
+
+/// How the diff shown to the critic was narrowed with `--critic-diff-scope`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffScopeNote<'a> {
+    pub patterns: &'a [String],
+    /// Changed files left out of the diff
+    pub omitted: &'a [String],
+}
+
+/// A verify command that passed at session start and fails after the
+/// iteration under review.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VerifyRegression<'a> {
+    pub command: &'a str,
+    pub exit_code: i32,
+    /// The end of the command's output
+    pub output: &'a str,
+}
+
 /// Prompt templates for the critic
 pub struct CriticPrompts;
 
 impl CriticPrompts {
     /// Build the critic evaluation prompt
-    pub fn build_evaluation_prompt(
-        original_task: &str,
-        actor_stdout: &str,
-        actor_stderr: &str,
-        git_diff: &str,
-        iteration: usize,
-    ) -> String {
-        format!(
+    pub fn build_evaluation_prompt(input: &CriticEvaluationInput<'_>) -> String {
+        let prompt = format!(
             r#"You are a rigorous code review critic with FULL SHELL ACCESS. Your job is to verify whether a coding task has been completed correctly.
 
 ## Original Task
@@ -33,7 +73,7 @@ impl CriticPrompts {
 
 ## Context
 This is iteration {iteration} of the actor-critic loop.
-
+{regression}{scope}{files}{checklist}{rubric}{coverage}
 ---
 
 ## YOUR CRITICAL RESPONSIBILITY
@@ -103,41 +143,194 @@ Then, end your response with a decision block:
 
 **If MORE WORK is needed (verification failed or requirements incomplete):**
 <decision>
-{{"type": "continue", "feedback": "Specific, actionable feedback including:\n- What verification failed\n- What requirements are unmet\n- What code quality issues need addressing", "remaining_issues": ["issue1", "issue2"]}}
+{{"type": "continue", "feedback": "Specific, actionable feedback including:\n- What verification failed\n- What requirements are unmet\n- What code quality issues need addressing", "remaining_issues": ["issue1", "issue2"], "confidence": 0.7}}
 </decision>
 
+For `continue`, `confidence` is optional: how sure you are (0.0 - 1.0) that the feedback identifies what is left to do.
+
 **If a blocking ERROR was encountered:**
 <decision>
 {{"type": "error", "error_description": "What went wrong", "recovery_suggestion": "How to fix it"}}
 </decision>
-
+{reset_option}
 ---
 
 Remember: You have FULL SHELL ACCESS. Use it to verify the work before approving."#,
-            task = original_task,
-            stdout = truncate_output(actor_stdout, 10000),
-            stderr = truncate_output(actor_stderr, 2000),
-            diff = truncate_output(git_diff, 20000),
-            iteration = iteration + 1,
-        )
+            task = input.original_task,
+            stdout = truncate_output(input.actor_stdout, 10000),
+            stderr = truncate_output(input.actor_stderr, 2000),
+            diff = truncate_output(input.git_diff, 20000),
+            iteration = input.iteration + 1,
+            reset_option = if input.allow_reset { RESET_OPTION } else { "" },
+            checklist = checklist_section(input.acceptance_criteria),
+            rubric = input.rubric.map(rubric_section).unwrap_or_default(),
+            coverage = input.coverage.map(coverage_section).unwrap_or_default(),
+            regression = input.regression.map(regression_section).unwrap_or_default(),
+            scope = input.diff_scope.map(scope_section).unwrap_or_default(),
+            files = input
+                .scope_adherence
+                .map(adherence_section)
+                .unwrap_or_default(),
+        );
+        match input.reference {
+            Some(reference) => format!("{}\n\n{}", reference, prompt),
+            None => prompt,
+        }
     }
+}
 
-    /// Build prompt for subsequent iterations (includes previous feedback)
-    pub fn build_continuation_prompt(original_task: &str, previous_feedback: &str) -> String {
-        format!(
-            r#"Continue working on this task:
+/// Definition-of-done section listing the prompt's acceptance criteria.
+/// Empty when the prompt has none.
+fn checklist_section(acceptance_criteria: &[String]) -> String {
+    if acceptance_criteria.is_empty() {
+        return String::new();
+    }
+    let items: String = acceptance_criteria
+        .iter()
+        .enumerate()
+        .map(|(i, item)| format!("{}. {}\n", i + 1, item))
+        .collect();
+    format!(
+        r#"
+## Definition of Done
+The task's acceptance criteria:
+
+{items}
+Check every item. Only decide `done` if all of them are met. In your `done` or `continue` decision, include a `checklist` field with one entry per item, in this order, using the item text verbatim:
+`"checklist": [{{"item": "...", "met": true}}, {{"item": "...", "met": false}}]`
+"#
+    )
+}
 
-## Original Task
-{task}
+/// Review rubric section. Must-pass criteria block `done`; the others are
+/// reported but advisory.
+fn rubric_section(rubric: &Rubric) -> String {
+    let items: String = rubric
+        .criteria()
+        .iter()
+        .enumerate()
+        .map(|(i, criterion)| {
+            let tag = if criterion.must_pass {
+                "MUST"
+            } else {
+                "SHOULD"
+            };
+            format!("{}. [{}] {}\n", i + 1, tag, criterion.text)
+        })
+        .collect();
+    format!(
+        r#"
+## Review Rubric
+The project's rubric applies to every change, whatever the task:
+
+{items}
+Check every criterion. Only decide `done` if all MUST criteria are met; SHOULD criteria are advisory, so mention misses in your summary or feedback. In your `done` or `continue` decision, include a `rubric` field with one entry per criterion, in this order, using the criterion text verbatim (without the tag):
+`"rubric": [{{"item": "...", "met": true}}, {{"item": "...", "met": false}}]`
+"#
+    )
+}
 
-## Previous Attempt Feedback
-{feedback}
+/// Test coverage section. Asks the critic to reject a drop against the
+/// session baseline.
+fn coverage_section(coverage: CoverageChange) -> String {
+    let Some(delta) = coverage.delta() else {
+        return format!(
+            "\n## Test Coverage\nCoverage is {:.1}% after this iteration.\n",
+            coverage.current
+        );
+    };
+    let mut section = format!(
+        "\n## Test Coverage\nCoverage was {:.1}% at the start of the session and is {:.1}% \
+         after this iteration ({:+.1} points).\n",
+        coverage.baseline.unwrap_or_default(),
+        coverage.current,
+        delta
+    );
+    if delta < 0.0 {
+        section.push_str(
+            "Coverage DROPPED. Unless the task calls for it (e.g. removing tested code), do not \
+             decide `done`: decide `continue` and ask for tests covering the changed code.\n",
+        );
+    }
+    section
+}
 
-Please address the feedback and complete the remaining work. Focus specifically on the issues mentioned in the feedback."#,
-            task = original_task,
-            feedback = previous_feedback,
-        )
+/// Verify regression section. The regression blocks `done` whatever else
+/// the review finds.
+fn regression_section(regression: VerifyRegression<'_>) -> String {
+    format!(
+        "\n## Verify Regression (BLOCKER)\n`{}` passed at the start of the session but fails \
+         after this iteration (exit {}). Do not decide `done`: review the rest of the work as \
+         usual, then decide `continue` and make fixing the regression the first item of your \
+         feedback.\n\nOutput:\n```\n{}\n```\n",
+        regression.command,
+        regression.exit_code,
+        regression.output.trim()
+    )
+}
+
+/// Most files named in a list of files; the rest are counted.
+const MAX_FILES_LISTED: usize = 20;
+
+/// Tells the critic the diff covers only part of the changes, and which
+/// files it leaves out.
+fn scope_section(scope: DiffScopeNote<'_>) -> String {
+    let patterns: Vec<String> = scope.patterns.iter().map(|p| format!("`{}`", p)).collect();
+    let mut section = format!(
+        "\n## Review Scope\nThe diff above only shows files matching {}. Judge the work on \
+         those files; changes elsewhere are incidental to this task and should not block `done`.\n",
+        patterns.join(", ")
+    );
+    if !scope.omitted.is_empty() {
+        section.push_str(&format!(
+            "Changed files left out of the diff ({}):\n",
+            scope.omitted.len()
+        ));
+        for file in scope.omitted.iter().take(MAX_FILES_LISTED) {
+            section.push_str(&format!("- {}\n", file));
+        }
+        if scope.omitted.len() > MAX_FILES_LISTED {
+            section.push_str(&format!(
+                "- ...and {} more\n",
+                scope.omitted.len() - MAX_FILES_LISTED
+            ));
+        }
     }
+    section
+}
+
+/// Compares the changed files with the prompt's files to modify. Empty
+/// when they match.
+fn adherence_section(adherence: &ScopeAdherence) -> String {
+    if adherence.is_clean() {
+        return String::new();
+    }
+    let mut section = String::from(
+        "\n## Files to Modify\nThe task lists the files to modify, and the changes do not match \
+         it.\n",
+    );
+    let mut list = |heading: &str, files: &[String]| {
+        if files.is_empty() {
+            return;
+        }
+        section.push_str(&format!("{} ({}):\n", heading, files.len()));
+        for file in files.iter().take(MAX_FILES_LISTED) {
+            section.push_str(&format!("- {}\n", file));
+        }
+        if files.len() > MAX_FILES_LISTED {
+            section.push_str(&format!(
+                "- ...and {} more\n",
+                files.len() - MAX_FILES_LISTED
+            ));
+        }
+    };
+    list("Changed but not listed", &adherence.unexpected);
+    list("Listed but not changed", &adherence.untouched);
+    section.push_str(
+        "Check whether the unlisted changes are needed for the task (tests, docs and build files \
+         often are) or are scope creep to undo, and whether the unchanged files still need work.\n",
+    );
+    section
 }
 
 fn truncate_output(output: &str, max_len: usize) -> &str {
@@ -145,10 +338,156 @@ fn truncate_output(output: &str, max_len: usize) -> &str {
         output
     } else {
         // Try to truncate at a line boundary
-        if let Some(pos) = output[..max_len].rfind('\n') {
-            &output[..pos]
-        } else {
-            &output[..max_len]
+        let head = text::head(output, max_len);
+        match head.rfind('\n') {
+            Some(pos) => &head[..pos],
+            None => head,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_output_at_a_line_or_char_boundary() {
+        assert_eq!(truncate_output("one\ntwo\nthree", 9), "one\ntwo");
+        assert_eq!(truncate_output("héllo", 2), "h");
+    }
+
+    fn input(acceptance_criteria: &[String]) -> CriticEvaluationInput<'_> {
+        CriticEvaluationInput {
+            original_task: "task",
+            actor_stdout: "",
+            actor_stderr: "",
+            git_diff: "",
+            iteration: 0,
+            allow_reset: false,
+            acceptance_criteria,
+            coverage: None,
+            reference: None,
+            rubric: None,
+            diff_scope: None,
+            scope_adherence: None,
+            regression: None,
+        }
+    }
+
+    #[test]
+    fn reference_goes_before_the_prompt() {
+        let mut with_reference = input(&[]);
+        with_reference.reference = Some("## Reference Material\nUse snake_case.\n\n---");
+        let prompt = CriticPrompts::build_evaluation_prompt(&with_reference);
+        assert!(prompt.starts_with("## Reference Material\nUse snake_case.\n\n---\n\nYou are"));
+
+        let prompt = CriticPrompts::build_evaluation_prompt(&input(&[]));
+        assert!(prompt.starts_with("You are"));
+    }
+
+    #[test]
+    fn checklist_section_lists_criteria() {
+        let criteria = vec!["Hits are logged".to_string(), "No new warnings".to_string()];
+        let prompt = CriticPrompts::build_evaluation_prompt(&input(&criteria));
+        assert!(prompt.contains("## Definition of Done"));
+        assert!(prompt.contains("1. Hits are logged\n2. No new warnings\n"));
+        assert!(prompt.contains(r#""checklist": [{"item""#));
+
+        let prompt = CriticPrompts::build_evaluation_prompt(&input(&[]));
+        assert!(!prompt.contains("## Definition of Done"));
+    }
+
+    #[test]
+    fn scope_section_names_omitted_files() {
+        let patterns = vec!["src/api/**".to_string()];
+        let omitted = vec!["README.md".to_string()];
+        let mut scoped = input(&[]);
+        scoped.diff_scope = Some(DiffScopeNote {
+            patterns: &patterns,
+            omitted: &omitted,
+        });
+        let prompt = CriticPrompts::build_evaluation_prompt(&scoped);
+        assert!(prompt
+            .contains("## Review Scope\nThe diff above only shows files matching `src/api/**`."));
+        assert!(prompt.contains("left out of the diff (1):\n- README.md\n"));
+
+        let prompt = CriticPrompts::build_evaluation_prompt(&input(&[]));
+        assert!(!prompt.contains("## Review Scope"));
+    }
+
+    #[test]
+    fn adherence_section_lists_mismatched_files() {
+        let adherence = ScopeAdherence {
+            unexpected: vec!["src/cache.rs".to_string()],
+            untouched: vec![],
+        };
+        let mut checked = input(&[]);
+        checked.scope_adherence = Some(&adherence);
+        let prompt = CriticPrompts::build_evaluation_prompt(&checked);
+        assert!(prompt.contains("## Files to Modify"));
+        assert!(prompt.contains("Changed but not listed (1):\n- src/cache.rs\n"));
+        assert!(!prompt.contains("Listed but not changed"));
+
+        let clean = ScopeAdherence::default();
+        checked.scope_adherence = Some(&clean);
+        let prompt = CriticPrompts::build_evaluation_prompt(&checked);
+        assert!(!prompt.contains("## Files to Modify"));
+    }
+
+    #[test]
+    fn rubric_section_tags_must_pass_criteria() {
+        let rubric = Rubric::parse("## Must\n- Doc comments\n## Should\n- Unit tests\n").unwrap();
+        let mut with_rubric = input(&[]);
+        with_rubric.rubric = Some(&rubric);
+        let prompt = CriticPrompts::build_evaluation_prompt(&with_rubric);
+        assert!(prompt.contains("## Review Rubric"));
+        assert!(prompt.contains("1. [MUST] Doc comments\n2. [SHOULD] Unit tests\n"));
+        assert!(prompt.contains(r#""rubric": [{"item""#));
+
+        let prompt = CriticPrompts::build_evaluation_prompt(&input(&[]));
+        assert!(!prompt.contains("## Review Rubric"));
+    }
+
+    #[test]
+    fn coverage_section_flags_drops() {
+        let prompt = CriticPrompts::build_evaluation_prompt(&input(&[]));
+        assert!(!prompt.contains("## Test Coverage"));
+
+        let mut dropped = input(&[]);
+        dropped.coverage = Some(CoverageChange {
+            baseline: Some(82.5),
+            current: 80.0,
+        });
+        let prompt = CriticPrompts::build_evaluation_prompt(&dropped);
+        assert!(prompt.contains("is 80.0% after this iteration (-2.5 points)"));
+        assert!(prompt.contains("Coverage DROPPED"));
+
+        let mut rose = input(&[]);
+        rose.coverage = Some(CoverageChange {
+            baseline: Some(80.0),
+            current: 81.0,
+        });
+        let prompt = CriticPrompts::build_evaluation_prompt(&rose);
+        assert!(prompt.contains("(+1.0 points)"));
+        assert!(!prompt.contains("DROPPED"));
+    }
+
+    #[test]
+    fn regression_section_blocks_done() {
+        let prompt = CriticPrompts::build_evaluation_prompt(&input(&[]));
+        assert!(!prompt.contains("## Verify Regression"));
+
+        let mut regressed = input(&[]);
+        regressed.regression = Some(VerifyRegression {
+            command: "cargo test",
+            exit_code: 101,
+            output: "test parser::empty ... FAILED\n",
+        });
+        let prompt = CriticPrompts::build_evaluation_prompt(&regressed);
+        assert!(prompt.contains(
+            "## Verify Regression (BLOCKER)\n`cargo test` passed at the start of the session but \
+             fails after this iteration (exit 101). Do not decide `done`"
+        ));
+        assert!(prompt.contains("```\ntest parser::empty ... FAILED\n```"));
+    }
+}
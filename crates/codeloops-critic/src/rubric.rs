@@ -0,0 +1,266 @@
+//! Project-wide review rubric.
+//!
+//! A rubric file lists criteria the critic checks on every iteration, on top
+//! of the task's own acceptance criteria, so reviews stay consistent across
+//! prompts. It is plain Markdown: each list item is one criterion. Items
+//! under a heading that starts with "Should" or mentions "optional" are
+//! advisory; every other item must pass before a `done` decision stands.
+//!
+//! ```markdown
+//! ## Must
+//! - Public functions have doc comments
+//! - No `unwrap()` outside tests
+//!
+//! ## Should
+//! - New modules come with unit tests
+//! ```
+
+use crate::{ChecklistItem, CriticDecision, RubricResult};
+
+/// One rubric criterion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RubricCriterion {
+    pub text: String,
+    /// Whether `done` requires the criterion to be met
+    pub must_pass: bool,
+}
+
+/// Criteria read from a rubric file, in file order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rubric {
+    criteria: Vec<RubricCriterion>,
+}
+
+impl Rubric {
+    /// Read the criteria from a rubric file's Markdown. Fails when it has no
+    /// list items, since the critic would have nothing to check.
+    pub fn parse(markdown: &str) -> Result<Self, String> {
+        let mut criteria = Vec::new();
+        let mut must_pass = true;
+        for line in markdown.lines() {
+            let line = line.trim();
+            if let Some(heading) = line.strip_prefix('#') {
+                let heading = heading.trim_start_matches('#').trim().to_lowercase();
+                must_pass = !(heading.starts_with("should") || heading.contains("optional"));
+            } else if let Some(text) = list_item(line) {
+                criteria.push(RubricCriterion {
+                    text: text.to_string(),
+                    must_pass,
+                });
+            }
+        }
+        if criteria.is_empty() {
+            return Err("rubric has no criteria; list each one as a Markdown list item".into());
+        }
+        Ok(Self { criteria })
+    }
+
+    pub fn criteria(&self) -> &[RubricCriterion] {
+        &self.criteria
+    }
+
+    /// Pair each criterion with the status the critic reported for it,
+    /// matched by text. Criteria the critic left out have no status.
+    pub fn results(&self, reported: &[ChecklistItem]) -> Vec<RubricResult> {
+        self.criteria
+            .iter()
+            .map(|criterion| RubricResult {
+                criterion: criterion.text.clone(),
+                must_pass: criterion.must_pass,
+                met: reported
+                    .iter()
+                    .find(|item| same_text(&item.item, &criterion.text))
+                    .map(|item| item.met),
+            })
+            .collect()
+    }
+
+    /// Turn a `done` into a `continue` when a must-pass criterion was not
+    /// reported as met. Other decisions are returned unchanged.
+    pub fn enforce(&self, decision: CriticDecision) -> CriticDecision {
+        let CriticDecision::Done {
+            summary,
+            confidence,
+            checklist,
+            rubric,
+        } = decision
+        else {
+            return decision;
+        };
+
+        let blocking: Vec<RubricResult> = self
+            .results(&rubric)
+            .into_iter()
+            .filter(|result| result.must_pass && result.met != Some(true))
+            .collect();
+        if blocking.is_empty() {
+            return CriticDecision::Done {
+                summary,
+                confidence,
+                checklist,
+                rubric,
+            };
+        }
+
+        let list: String = blocking
+            .iter()
+            .map(|result| match result.met {
+                Some(_) => format!("- {}\n", result.criterion),
+                None => format!("- {} (not checked)\n", result.criterion),
+            })
+            .collect();
+        CriticDecision::Continue {
+            feedback: format!(
+                "The critic approved the work, but these must-pass rubric criteria are not met:\n\
+                 {list}\nMeet them before anything else.\n\nCritic summary: {summary}"
+            ),
+            remaining_issues: blocking.into_iter().map(|r| r.criterion).collect(),
+            checklist,
+            rubric,
+        }
+    }
+}
+
+/// The text of a `-`, `*`, `+` or numbered list item.
+fn list_item(line: &str) -> Option<&str> {
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+        .or_else(|| {
+            let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+            let rest = line[digits..]
+                .strip_prefix(". ")
+                .or_else(|| line[digits..].strip_prefix(") "));
+            rest.filter(|_| digits > 0)
+        })?;
+    let rest = rest.trim();
+    (!rest.is_empty()).then_some(rest)
+}
+
+fn same_text(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUBRIC: &str = "\
+# Team rubric
+
+Reviewed on every change.
+
+## Must
+- Public functions have doc comments
+1. No `unwrap()` outside tests
+
+## Should (optional)
+* New modules come with unit tests
+";
+
+    fn reported(items: &[(&str, bool)]) -> Vec<ChecklistItem> {
+        items
+            .iter()
+            .map(|(item, met)| ChecklistItem {
+                item: item.to_string(),
+                met: *met,
+            })
+            .collect()
+    }
+
+    fn done(rubric: Vec<ChecklistItem>) -> CriticDecision {
+        CriticDecision::Done {
+            summary: "Looks good".to_string(),
+            confidence: 0.9,
+            checklist: vec![],
+            rubric,
+        }
+    }
+
+    #[test]
+    fn parses_criteria_by_section() {
+        let rubric = Rubric::parse(RUBRIC).unwrap();
+        let criteria: Vec<(&str, bool)> = rubric
+            .criteria()
+            .iter()
+            .map(|c| (c.text.as_str(), c.must_pass))
+            .collect();
+        assert_eq!(
+            criteria,
+            [
+                ("Public functions have doc comments", true),
+                ("No `unwrap()` outside tests", true),
+                ("New modules come with unit tests", false),
+            ]
+        );
+
+        assert!(Rubric::parse("# Rubric\n\nJust prose.\n").is_err());
+    }
+
+    #[test]
+    fn matches_reported_results_by_text() {
+        let rubric = Rubric::parse(RUBRIC).unwrap();
+        let results = rubric.results(&reported(&[
+            ("public functions have doc comments ", true),
+            ("New modules come with unit tests", false),
+        ]));
+        let met: Vec<Option<bool>> = results.iter().map(|r| r.met).collect();
+        assert_eq!(met, [Some(true), None, Some(false)]);
+        assert_eq!(
+            serde_json::to_value(&results[0]).unwrap(),
+            serde_json::json!({
+                "item": "Public functions have doc comments",
+                "mustPass": true,
+                "met": true
+            })
+        );
+    }
+
+    #[test]
+    fn done_needs_every_must_pass_criterion() {
+        let rubric = Rubric::parse(RUBRIC).unwrap();
+
+        // Advisory criteria do not block
+        let all_met = done(reported(&[
+            ("Public functions have doc comments", true),
+            ("No `unwrap()` outside tests", true),
+            ("New modules come with unit tests", false),
+        ]));
+        assert_eq!(rubric.enforce(all_met.clone()), all_met);
+
+        let blocked = rubric.enforce(done(reported(&[(
+            "Public functions have doc comments",
+            false,
+        )])));
+        let CriticDecision::Continue {
+            feedback,
+            remaining_issues,
+            rubric: results,
+            ..
+        } = blocked
+        else {
+            panic!("expected continue, got {:?}", blocked);
+        };
+        assert_eq!(
+            remaining_issues,
+            [
+                "Public functions have doc comments",
+                "No `unwrap()` outside tests"
+            ]
+        );
+        assert!(feedback.contains(
+            "- Public functions have doc comments\n- No `unwrap()` outside tests (not checked)\n"
+        ));
+        assert!(feedback.ends_with("Critic summary: Looks good"));
+        assert_eq!(results.len(), 1);
+
+        let cont = CriticDecision::Continue {
+            feedback: "More".to_string(),
+            remaining_issues: vec![],
+            checklist: vec![],
+            rubric: vec![],
+        };
+        assert_eq!(rubric.enforce(cont.clone()), cont);
+    }
+}
@@ -0,0 +1,198 @@
+//! Rule-based critic.
+//!
+//! Decides without calling an agent: the iteration is approved when the
+//! verify command passed and the working tree has changes, otherwise the
+//! actor gets a templated message saying which rule failed. This is free,
+//! deterministic, and a baseline to compare agent critics against.
+
+use codeloops_agent::text::tail;
+
+use crate::CriticDecision;
+
+/// Bytes of verify output quoted back to the actor.
+const OUTPUT_TAIL_LEN: usize = 2_000;
+
+/// Which rules the rule-based critic applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleConfig {
+    /// Refuse to approve an iteration that left no diff
+    pub require_diff: bool,
+}
+
+impl Default for RuleConfig {
+    fn default() -> Self {
+        Self { require_diff: true }
+    }
+}
+
+/// Result of the verify command for the iteration under review.
+#[derive(Debug, Clone, Copy)]
+pub struct RuleVerify<'a> {
+    pub command: &'a str,
+    pub passed: bool,
+    pub exit_code: i32,
+    /// Tail of the command's combined output
+    pub output: &'a str,
+}
+
+/// Inputs to the rule-based critic.
+#[derive(Debug, Clone, Copy)]
+pub struct RuleInput<'a> {
+    pub git_diff: &'a str,
+    /// `None` when no verify command is configured; only the diff rule applies
+    pub verify: Option<RuleVerify<'a>>,
+}
+
+/// Critic that applies [`RuleConfig`] instead of running an agent.
+pub struct RuleCritic {
+    config: RuleConfig,
+}
+
+impl RuleCritic {
+    pub fn new(config: RuleConfig) -> Self {
+        Self { config }
+    }
+
+    /// Decide on an iteration. Never errors and never asks for a reset.
+    pub fn evaluate(&self, input: &RuleInput<'_>) -> CriticDecision {
+        let mut feedback = Vec::new();
+        let mut remaining_issues = Vec::new();
+
+        if let Some(verify) = input.verify.filter(|v| !v.passed) {
+            feedback.push(format!(
+                "The verify command `{}` failed with exit code {}. Fix the failures it \
+                 reports.\n\nOutput (tail):\n```\n{}\n```",
+                verify.command,
+                verify.exit_code,
+                tail(verify.output.trim(), OUTPUT_TAIL_LEN)
+            ));
+            remaining_issues.push(format!("`{}` fails", verify.command));
+        }
+
+        if self.config.require_diff && input.git_diff.trim().is_empty() {
+            feedback.push(
+                "No changes were made to the working tree. Make the changes the task asks for."
+                    .to_string(),
+            );
+            remaining_issues.push("no changes in the working tree".to_string());
+        }
+
+        if feedback.is_empty() {
+            let summary = match input.verify {
+                Some(verify) => format!("`{}` passed", verify.command),
+                None => "The working tree has changes".to_string(),
+            };
+            return CriticDecision::Done {
+                summary,
+                confidence: 1.0,
+                checklist: vec![],
+                rubric: vec![],
+            };
+        }
+
+        CriticDecision::Continue {
+            feedback: feedback.join("\n\n"),
+            remaining_issues,
+            checklist: vec![],
+            rubric: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs\n+fn fixed() {}\n";
+
+    fn verify(passed: bool) -> RuleVerify<'static> {
+        RuleVerify {
+            command: "cargo test",
+            passed,
+            exit_code: if passed { 0 } else { 101 },
+            output: "test result: FAILED. 1 failed",
+        }
+    }
+
+    #[test]
+    fn approves_passing_verify_with_changes() {
+        let critic = RuleCritic::new(RuleConfig::default());
+        let decision = critic.evaluate(&RuleInput {
+            git_diff: DIFF,
+            verify: Some(verify(true)),
+        });
+        match decision {
+            CriticDecision::Done {
+                summary,
+                confidence,
+                ..
+            } => {
+                assert_eq!(summary, "`cargo test` passed");
+                assert_eq!(confidence, 1.0);
+            }
+            other => panic!("unexpected decision: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn continues_when_verify_fails() {
+        let critic = RuleCritic::new(RuleConfig::default());
+        let decision = critic.evaluate(&RuleInput {
+            git_diff: DIFF,
+            verify: Some(verify(false)),
+        });
+        match decision {
+            CriticDecision::Continue {
+                feedback,
+                remaining_issues,
+                ..
+            } => {
+                assert!(feedback.contains("`cargo test` failed with exit code 101"));
+                assert!(feedback.contains("1 failed"));
+                assert_eq!(remaining_issues, vec!["`cargo test` fails"]);
+            }
+            other => panic!("unexpected decision: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn empty_diff_blocks_approval_unless_allowed() {
+        let input = RuleInput {
+            git_diff: "  \n",
+            verify: Some(verify(true)),
+        };
+        let decision = RuleCritic::new(RuleConfig::default()).evaluate(&input);
+        match decision {
+            CriticDecision::Continue {
+                remaining_issues, ..
+            } => assert_eq!(remaining_issues, vec!["no changes in the working tree"]),
+            other => panic!("unexpected decision: {other:?}"),
+        }
+
+        let lenient = RuleCritic::new(RuleConfig {
+            require_diff: false,
+        });
+        assert!(lenient.evaluate(&input).is_done());
+    }
+
+    #[test]
+    fn reports_every_failed_rule() {
+        let decision = RuleCritic::new(RuleConfig::default()).evaluate(&RuleInput {
+            git_diff: "",
+            verify: Some(verify(false)),
+        });
+        match decision {
+            CriticDecision::Continue {
+                remaining_issues, ..
+            } => assert_eq!(remaining_issues.len(), 2),
+            other => panic!("unexpected decision: {other:?}"),
+        }
+
+        // Without a verify command only the diff rule applies
+        let decision = RuleCritic::new(RuleConfig::default()).evaluate(&RuleInput {
+            git_diff: DIFF,
+            verify: None,
+        });
+        assert!(decision.is_done());
+    }
+}
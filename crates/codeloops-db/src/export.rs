@@ -0,0 +1,487 @@
+//! Export of stored sessions as standalone HTML reports.
+//!
+//! The report is a single document with inline CSS and no scripts or
+//! external assets, so it can be mailed or attached as-is. Every value from
+//! the session is HTML-escaped.
+
+use std::fmt::Write;
+
+use crate::sessions::{decision_kind, Iteration, Session, Sessions};
+use crate::SessionOutcome;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; background: #0b0b10; color: #e4e4e7; margin: 0; padding: 2rem; line-height: 1.5; }
+main { max-width: 960px; margin: 0 auto; }
+h1 { font-size: 1.25rem; margin: 0 0 1rem; }
+h2 { font-size: 0.75rem; text-transform: uppercase; letter-spacing: 0.08em; color: #a1a1aa; margin: 2rem 0 0.75rem; }
+h3 { font-size: 0.95rem; margin: 0; }
+pre { white-space: pre-wrap; word-break: break-word; margin: 0; font-family: ui-monospace, SFMono-Regular, Menlo, monospace; font-size: 0.8rem; }
+.card { background: #111118; border: 1px solid #27272a; border-radius: 0.5rem; padding: 1rem; margin-bottom: 1rem; }
+.card-head { display: flex; align-items: center; gap: 0.75rem; flex-wrap: wrap; margin-bottom: 0.75rem; }
+.meta { display: grid; grid-template-columns: max-content 1fr; gap: 0.25rem 1rem; font-size: 0.85rem; }
+.meta dt { color: #a1a1aa; }
+.meta dd { margin: 0; }
+.muted { color: #a1a1aa; font-size: 0.8rem; }
+.badge { display: inline-block; padding: 0.1rem 0.5rem; border-radius: 9999px; font-size: 0.75rem; font-weight: 600; }
+.good { background: rgba(34, 197, 94, 0.15); color: #4ade80; }
+.warn { background: rgba(245, 158, 11, 0.15); color: #fbbf24; }
+.bad { background: rgba(239, 68, 68, 0.15); color: #f87171; }
+.info { background: rgba(34, 211, 238, 0.15); color: #22d3ee; }
+.checklist { list-style: none; padding: 0; margin: 0.5rem 0 0; font-size: 0.85rem; }
+details { margin-top: 0.75rem; }
+summary { cursor: pointer; color: #a1a1aa; font-size: 0.8rem; }
+.diff { background: #0b0b10; border: 1px solid #27272a; border-radius: 0.375rem; padding: 0.5rem; margin-top: 0.5rem; overflow-x: auto; }
+.diff span { display: block; }
+.add { color: #4ade80; background: rgba(34, 197, 94, 0.08); }
+.del { color: #f87171; background: rgba(239, 68, 68, 0.08); }
+.hunk { color: #22d3ee; }
+.file { color: #e4e4e7; font-weight: 600; }
+"#;
+
+impl Sessions<'_> {
+    /// Render a session as a standalone HTML report; `None` if it does not
+    /// exist.
+    pub fn export_html(&self, id: &str) -> Result<Option<String>, rusqlite::Error> {
+        Ok(self.get(id)?.as_ref().map(session_html))
+    }
+}
+
+/// Render `session` as a standalone HTML document.
+pub fn session_html(session: &Session) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>codeloops session {}</title>\n<style>{}</style>\n</head>\n<body>\n<main>\n",
+        escape(&session.id),
+        STYLE
+    );
+
+    let _ = writeln!(html, "<h1>codeloops session report</h1>");
+    let _ = writeln!(html, "<section class=\"card\">");
+    let _ = writeln!(
+        html,
+        "<div class=\"card-head\">{}</div>",
+        outcome_badge(session)
+    );
+    let _ = writeln!(html, "<dl class=\"meta\">");
+    meta_row(&mut html, "Session", &session.id);
+    meta_row(
+        &mut html,
+        "Started",
+        &session
+            .started_at
+            .format("%Y-%m-%d %H:%M:%S UTC")
+            .to_string(),
+    );
+    meta_row(
+        &mut html,
+        "Working dir",
+        &session.working_dir.display().to_string(),
+    );
+    if let Some(ref branch) = session.branch {
+        meta_row(&mut html, "Branch", branch);
+    }
+    meta_row(
+        &mut html,
+        "Actor",
+        &agent_label(&session.actor_agent, &session.actor_model),
+    );
+    meta_row(
+        &mut html,
+        "Critic",
+        &agent_label(&session.critic_agent, &session.critic_model),
+    );
+    if let Some(iterations) = session.iteration_count {
+        meta_row(&mut html, "Iterations", &iterations.to_string());
+    }
+    if let Some(duration) = session.duration_secs {
+        meta_row(&mut html, "Duration", &format!("{:.1}s", duration));
+    }
+    if let Some(confidence) = session.confidence {
+        meta_row(
+            &mut html,
+            "Confidence",
+            &format!("{:.0}%", confidence * 100.0),
+        );
+    }
+    if !session.prompt_files.is_empty() {
+        meta_row(&mut html, "Prompt files", &session.prompt_files.join(", "));
+    }
+    if !session.context_files.is_empty() {
+        meta_row(
+            &mut html,
+            "Context files",
+            &session.context_files.join(", "),
+        );
+    }
+    let _ = writeln!(html, "</dl>");
+    if let Some(ref summary) = session.summary {
+        let _ = writeln!(html, "<h2>Summary</h2>\n<pre>{}</pre>", escape(summary));
+    }
+    let _ = writeln!(html, "</section>");
+
+    let _ = writeln!(
+        html,
+        "<h2>Prompt</h2>\n<section class=\"card\"><pre>{}</pre></section>",
+        escape(&session.prompt)
+    );
+    if let Some(ref feedback) = session.seed_feedback {
+        let _ = writeln!(
+            html,
+            "<h2>Seed Feedback</h2>\n<section class=\"card\"><pre>{}</pre></section>",
+            escape(feedback)
+        );
+    }
+    if let Some(ref notes) = session.notes {
+        let _ = writeln!(
+            html,
+            "<h2>Notes</h2>\n<section class=\"card\"><pre>{}</pre></section>",
+            escape(notes)
+        );
+    }
+
+    if !session.iterations.is_empty() {
+        let _ = writeln!(html, "<h2>Iterations ({})</h2>", session.iterations.len());
+        for iteration in &session.iterations {
+            iteration_card(&mut html, iteration);
+        }
+    }
+
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+fn iteration_card(html: &mut String, iteration: &Iteration) {
+    let _ = writeln!(html, "<section class=\"card\">");
+    let _ = write!(
+        html,
+        "<div class=\"card-head\"><h3>Iteration {}</h3>{}",
+        iteration.iteration_number,
+        decision_badge(
+            iteration
+                .legacy_critic_decision
+                .as_deref()
+                .or(iteration.critic_decision.as_deref())
+        )
+    );
+    let mut details = Vec::new();
+    if let Some(confidence) = iteration.critic_confidence {
+        details.push(format!("confidence {:.0}%", confidence * 100.0));
+    }
+    if let Some(secs) = iteration.actor_duration_secs {
+        details.push(format!("actor {:.1}s", secs));
+    }
+    if let Some(code) = iteration.actor_exit_code.filter(|code| *code != 0) {
+        details.push(format!("exit code {}", code));
+    }
+    details.push(format!(
+        "{} files changed",
+        iteration.git_files_changed.unwrap_or(0)
+    ));
+    match iteration.patch_applied {
+        Some(true) => details.push("patch applied".to_string()),
+        Some(false) => details.push("patch did not apply".to_string()),
+        None => {}
+    }
+    if let Some(ref review) = iteration.human_review {
+        details.push(format!("reviewer {}", review));
+    }
+    match iteration.verify_passed {
+        Some(true) => details.push("verify passed".to_string()),
+        Some(false) => details.push("verify failed".to_string()),
+        None => {}
+    }
+    if let Some(percent) = iteration.coverage_percent {
+        details.push(match iteration.coverage_delta {
+            Some(delta) => format!("coverage {:.1}% ({:+.1})", percent, delta),
+            None => format!("coverage {:.1}%", percent),
+        });
+    }
+    let _ = writeln!(
+        html,
+        "<span class=\"muted\">{}</span></div>",
+        escape(&details.join(" · "))
+    );
+
+    if let Some(feedback) = iteration.feedback.as_deref().filter(|f| !f.is_empty()) {
+        let _ = writeln!(html, "<pre>{}</pre>", escape(feedback));
+    }
+
+    if !iteration.critic_checklist.is_empty() {
+        let _ = writeln!(html, "<ul class=\"checklist\">");
+        for item in &iteration.critic_checklist {
+            let (mark, class) = if item.met {
+                ("✓", "add")
+            } else {
+                ("✗", "del")
+            };
+            let _ = writeln!(
+                html,
+                "<li><span class=\"{}\">{}</span> {}</li>",
+                class,
+                mark,
+                escape(&item.item)
+            );
+        }
+        let _ = writeln!(html, "</ul>");
+    }
+
+    if !iteration.critic_rubric.is_empty() {
+        let _ = writeln!(html, "<ul class=\"checklist\">");
+        for result in &iteration.critic_rubric {
+            let (mark, class) = match result.met {
+                Some(true) => ("✓", "add"),
+                Some(false) => ("✗", "del"),
+                None => ("?", "muted"),
+            };
+            let tag = if result.must_pass { "must" } else { "should" };
+            let _ = writeln!(
+                html,
+                "<li><span class=\"{}\">{}</span> {} <span class=\"muted\">(rubric, {})</span></li>",
+                class,
+                mark,
+                escape(&result.criterion),
+                tag
+            );
+        }
+        let _ = writeln!(html, "</ul>");
+    }
+
+    if let Some(adherence) = &iteration.scope_adherence {
+        let mut files: Vec<String> = Vec::new();
+        files.extend(
+            adherence
+                .unexpected
+                .iter()
+                .map(|f| format!("{} (not listed)", f)),
+        );
+        files.extend(
+            adherence
+                .untouched
+                .iter()
+                .map(|f| format!("{} (not changed)", f)),
+        );
+        if !files.is_empty() {
+            let _ = writeln!(html, "<ul class=\"checklist\">");
+            for file in files {
+                let _ = writeln!(
+                    html,
+                    "<li><span class=\"muted\">scope</span> {}</li>",
+                    escape(&file)
+                );
+            }
+            let _ = writeln!(html, "</ul>");
+        }
+    }
+
+    if let Some(diff) = iteration.git_diff.as_deref().filter(|d| !d.is_empty()) {
+        let _ = writeln!(
+            html,
+            "<details open><summary>Diff</summary>\n<pre class=\"diff\">{}</pre></details>",
+            highlight_diff(diff)
+        );
+    }
+
+    if let Some(prompt) = iteration.actor_prompt.as_deref().filter(|p| !p.is_empty()) {
+        let _ = writeln!(
+            html,
+            "<details><summary>Actor prompt</summary>\n<pre>{}</pre></details>",
+            escape(prompt)
+        );
+    }
+
+    if let Some(output) = iteration.actor_output.as_deref().filter(|o| !o.is_empty()) {
+        let _ = writeln!(
+            html,
+            "<details><summary>Actor output</summary>\n<pre>{}</pre></details>",
+            escape(output)
+        );
+    }
+    let _ = writeln!(html, "</section>");
+}
+
+/// Wrap each diff line in a span classed by its kind.
+fn highlight_diff(diff: &str) -> String {
+    let mut out = String::with_capacity(diff.len() * 2);
+    for line in diff.lines() {
+        let class =
+            if line.starts_with("diff --git") || line.starts_with("+++") || line.starts_with("---")
+            {
+                "file"
+            } else if line.starts_with("@@") {
+                "hunk"
+            } else if line.starts_with('+') {
+                "add"
+            } else if line.starts_with('-') {
+                "del"
+            } else {
+                ""
+            };
+        if class.is_empty() {
+            let _ = write!(out, "<span>{}</span>", escape(line));
+        } else {
+            let _ = write!(out, "<span class=\"{}\">{}</span>", class, escape(line));
+        }
+    }
+    out
+}
+
+fn outcome_badge(session: &Session) -> String {
+    let (class, label) = match session.outcome {
+        Some(SessionOutcome::Success) => ("good", "success"),
+        Some(SessionOutcome::Failed) => ("bad", "failed"),
+        Some(outcome) => ("warn", outcome.as_str()),
+        None => ("info", "active"),
+    };
+    format!("<span class=\"badge {}\">{}</span>", class, label)
+}
+
+fn decision_badge(decision: Option<&str>) -> String {
+    let decision = decision.unwrap_or("pending");
+    let class = match decision_kind(decision) {
+        "DONE" => "good",
+        "CONTINUE" => "warn",
+        "ERROR" => "bad",
+        _ => "info",
+    };
+    format!(
+        "<span class=\"badge {}\">{}</span>",
+        class,
+        escape(decision)
+    )
+}
+
+fn meta_row(html: &mut String, label: &str, value: &str) {
+    let _ = writeln!(html, "<dt>{}</dt><dd>{}</dd>", label, escape(value));
+}
+
+fn agent_label(agent: &str, model: &Option<String>) -> String {
+    match model {
+        Some(model) => format!("{} ({})", agent, model),
+        None => agent.to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChecklistItem, Database, RubricResult, ScopeAdherence, SessionEnd, SessionStart};
+
+    #[test]
+    fn renders_a_self_contained_escaped_report() {
+        // As older builds stored it
+        let decision = "DONE (confidence: 90%)".to_string();
+        let db = Database::open_in_memory().unwrap();
+        let id = db
+            .sessions()
+            .create(&SessionStart {
+                prompt: "Fix <script>alert(1)</script> & friends".to_string(),
+                working_dir: std::path::PathBuf::from("/project"),
+                actor_agent: "Claude Code".to_string(),
+                critic_agent: "Claude Code".to_string(),
+                actor_model: Some("sonnet".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        db.sessions().start_iteration(&id, 0).unwrap();
+        db.sessions()
+            .complete_diff(
+                &id,
+                0,
+                "diff --git a/x b/x\n@@ -1 +1 @@\n-old\n+new <b>\n",
+                1,
+                &["x".to_string()],
+            )
+            .unwrap();
+        db.sessions()
+            .complete_critic(&id, 0, &decision, Some("Looks good"), Some(0.9))
+            .unwrap();
+        db.sessions()
+            .set_critic_checklist(
+                &id,
+                0,
+                &[ChecklistItem {
+                    item: "tests pass".to_string(),
+                    met: true,
+                }],
+            )
+            .unwrap();
+        db.sessions()
+            .set_critic_rubric(
+                &id,
+                0,
+                &[RubricResult {
+                    criterion: "doc comments".to_string(),
+                    must_pass: true,
+                    met: None,
+                }],
+            )
+            .unwrap();
+        db.sessions()
+            .set_scope_adherence(
+                &id,
+                0,
+                &ScopeAdherence {
+                    unexpected: vec!["y".to_string()],
+                    untouched: vec!["src/<z>.rs".to_string()],
+                },
+            )
+            .unwrap();
+        db.sessions()
+            .end(
+                &id,
+                &SessionEnd {
+                    outcome: SessionOutcome::Success,
+                    iterations: 1,
+                    summary: Some("Fixed".to_string()),
+                    confidence: Some(0.9),
+                    duration_secs: 12.0,
+                },
+            )
+            .unwrap();
+        db.sessions()
+            .set_notes(&id, Some("Merged after <review>"))
+            .unwrap();
+
+        let html = db.sessions().export_html(&id).unwrap().unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Fix &lt;script&gt;alert(1)&lt;/script&gt; &amp; friends"));
+        assert!(!html.contains("<script"));
+        assert!(html.contains("<span class=\"del\">-old</span>"));
+        assert!(html.contains("<span class=\"add\">+new &lt;b&gt;</span>"));
+        assert!(html.contains("<span class=\"hunk\">@@ -1 +1 @@</span>"));
+        assert!(html.contains("<span class=\"badge good\">success</span>"));
+        assert!(html.contains("<span class=\"badge good\">DONE (confidence: 90%)</span>"));
+        assert!(html.contains("tests pass"));
+        assert!(html.contains(
+            "<span class=\"muted\">?</span> doc comments <span class=\"muted\">(rubric, must)</span>"
+        ));
+        assert!(html.contains("<span class=\"muted\">scope</span> y (not listed)"));
+        assert!(html.contains("src/&lt;z&gt;.rs (not changed)"));
+        assert!(html.contains("Claude Code (sonnet)"));
+        assert!(html.contains(
+            "<h2>Notes</h2>\n<section class=\"card\"><pre>Merged after &lt;review&gt;</pre>"
+        ));
+        // No external assets
+        assert!(!html.contains("<link") && !html.contains("src="));
+
+        assert!(db.sessions().export_html("missing").unwrap().is_none());
+    }
+}
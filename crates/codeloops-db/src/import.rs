@@ -0,0 +1,312 @@
+//! Import of JSONL session files.
+//!
+//! Before the SQLite store, each session was an NDJSON file with one
+//! `session_start` line, zero or more `iteration` lines and an optional
+//! `session_end` line (see the session format reference). Imported sessions
+//! get an ID derived from their prompt, working directory and start time, so
+//! importing the same session twice is a no-op whatever the file is called.
+//! Gzipped files (`.jsonl.gz`) are read transparently.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use rusqlite::{params, OptionalExtension, Transaction, TransactionBehavior};
+use serde::Deserialize;
+
+use crate::outcome::ParseOutcomeError;
+use crate::prompts::content_hash;
+use crate::sessions::{decision_kind, legacy_confidence, Iteration, SessionEnd, Sessions};
+
+/// One line of a JSONL session file.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SessionLine {
+    SessionStart {
+        timestamp: DateTime<Utc>,
+        prompt: String,
+        working_dir: PathBuf,
+        actor_agent: String,
+        critic_agent: String,
+        actor_model: Option<String>,
+        critic_model: Option<String>,
+        max_iterations: Option<usize>,
+    },
+    Iteration {
+        iteration_number: usize,
+        actor_output: String,
+        actor_stderr: String,
+        actor_exit_code: i32,
+        actor_duration_secs: f64,
+        git_diff: String,
+        git_files_changed: usize,
+        critic_decision: String,
+        feedback: Option<String>,
+        timestamp: DateTime<Utc>,
+    },
+    SessionEnd {
+        outcome: String,
+        iterations: usize,
+        summary: Option<String>,
+        confidence: Option<f64>,
+        duration_secs: f64,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+/// What happened to one imported file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportResult {
+    /// The session was inserted under `id`. `bad_lines` counts lines that
+    /// could not be parsed and were left out.
+    Imported { id: String, bad_lines: usize },
+    /// Nothing was inserted, for the given reason.
+    Skipped(String),
+}
+
+/// Error importing a session file.
+#[derive(Debug)]
+pub enum ImportError {
+    Io(std::io::Error),
+    Database(rusqlite::Error),
+    Outcome(ParseOutcomeError),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(e) => write!(f, "failed to read session file: {}", e),
+            ImportError::Database(e) => write!(f, "failed to write session: {}", e),
+            ImportError::Outcome(e) => write!(f, "invalid session_end: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<std::io::Error> for ImportError {
+    fn from(e: std::io::Error) -> Self {
+        ImportError::Io(e)
+    }
+}
+
+impl From<rusqlite::Error> for ImportError {
+    fn from(e: rusqlite::Error) -> Self {
+        ImportError::Database(e)
+    }
+}
+
+impl From<ParseOutcomeError> for ImportError {
+    fn from(e: ParseOutcomeError) -> Self {
+        ImportError::Outcome(e)
+    }
+}
+
+/// Stable ID for a session with this prompt, working directory and start
+/// time: the start time plus a hash of all three, the same shape as the old
+/// `<timestamp>_<hash>.jsonl` file names. Live runs keep random UUIDs.
+pub fn content_session_id(prompt: &str, working_dir: &Path, started_at: DateTime<Utc>) -> String {
+    let key = format!(
+        "{}\0{}\0{}",
+        prompt,
+        working_dir.to_string_lossy(),
+        started_at.to_rfc3339()
+    );
+    format!(
+        "{}_{}",
+        started_at.format("%Y-%m-%dT%H-%M-%SZ"),
+        &content_hash(&key)[..12]
+    )
+}
+
+/// ID earlier imports gave a session, from its start time and prompt alone.
+/// Checked, together with the stored working directory, so sessions imported
+/// before the working directory was part of the ID are still recognized.
+fn legacy_session_id(started_at: DateTime<Utc>, prompt: &str) -> String {
+    format!(
+        "{}_{}",
+        started_at.format("%Y-%m-%dT%H-%M-%SZ"),
+        &content_hash(prompt)[..6]
+    )
+}
+
+/// Whether `path` names a session file the importer reads: `.jsonl` or
+/// gzipped `.jsonl.gz`.
+pub fn is_session_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    name.ends_with(".jsonl") || name.ends_with(".jsonl.gz")
+}
+
+impl Sessions<'_> {
+    /// Import one JSONL session file, gzipped if its name ends in `.gz`.
+    ///
+    /// The session and its iterations are written in one transaction. Files
+    /// without a `session_start` line, or whose [`content_session_id`] (or
+    /// the ID an earlier import gave it) already exists, are skipped.
+    pub fn import_jsonl(&self, path: &Path) -> Result<ImportResult, ImportError> {
+        let file = File::open(path)?;
+        let reader: Box<dyn BufRead> = if path.extension().and_then(|e| e.to_str()) == Some("gz") {
+            Box::new(BufReader::new(GzDecoder::new(file)))
+        } else {
+            Box::new(BufReader::new(file))
+        };
+
+        let mut start = None;
+        let mut iterations = Vec::new();
+        let mut end = None;
+        let mut bad_lines = 0;
+
+        for line in reader.lines() {
+            let line = line?;
+            // Files written on Windows may carry CRLF endings and a BOM
+            let line = line.trim_end_matches('\r').trim_start_matches('\u{feff}');
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<SessionLine>(line) {
+                Ok(parsed @ SessionLine::SessionStart { .. }) => start = Some(parsed),
+                Ok(parsed @ SessionLine::Iteration { .. }) => iterations.push(parsed),
+                Ok(parsed @ SessionLine::SessionEnd { .. }) => end = Some(parsed),
+                Err(_) => bad_lines += 1,
+            }
+        }
+
+        let Some(SessionLine::SessionStart {
+            timestamp: started_at,
+            prompt,
+            working_dir,
+            actor_agent,
+            critic_agent,
+            actor_model,
+            critic_model,
+            max_iterations,
+        }) = start
+        else {
+            return Ok(ImportResult::Skipped("no session_start found".to_string()));
+        };
+
+        let id = content_session_id(&prompt, &working_dir, started_at);
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+
+        let existing: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT id FROM sessions WHERE id = ?1 OR (id = ?2 AND working_dir = ?3)",
+                params![
+                    id,
+                    legacy_session_id(started_at, &prompt),
+                    working_dir.to_string_lossy()
+                ],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(existing) = existing {
+            return Ok(ImportResult::Skipped(format!(
+                "{} already exists",
+                existing
+            )));
+        }
+
+        self.conn.execute(
+            r#"
+            INSERT INTO sessions (
+                id, prompt, working_dir, actor_agent, critic_agent,
+                actor_model, critic_model, max_iterations, started_at, prompt_bytes
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+            params![
+                id,
+                prompt,
+                working_dir.to_string_lossy().to_string(),
+                actor_agent,
+                critic_agent,
+                actor_model,
+                critic_model,
+                max_iterations.map(|n| n as i64),
+                started_at.to_rfc3339(),
+                prompt.len() as i64,
+            ],
+        )?;
+
+        for line in iterations {
+            if let SessionLine::Iteration {
+                iteration_number,
+                actor_output,
+                actor_stderr,
+                actor_exit_code,
+                actor_duration_secs,
+                git_diff,
+                git_files_changed,
+                critic_decision,
+                feedback,
+                timestamp,
+            } = line
+            {
+                let kind = decision_kind(&critic_decision).to_string();
+                let iteration = Iteration {
+                    iteration_number,
+                    phase: "critic_completed".to_string(),
+                    actor_output: Some(actor_output),
+                    actor_stderr: Some(actor_stderr),
+                    actor_exit_code: Some(actor_exit_code),
+                    actor_duration_secs: Some(actor_duration_secs),
+                    diff_bytes: Some(git_diff.len()),
+                    git_diff: Some(git_diff),
+                    git_files_changed: Some(git_files_changed),
+                    critic_confidence: legacy_confidence(&critic_decision),
+                    legacy_critic_decision: (kind != critic_decision).then_some(critic_decision),
+                    critic_decision: Some(kind),
+                    feedback,
+                    critic_raw: None,
+                    critic_checklist: Vec::new(),
+                    critic_rubric: Vec::new(),
+                    scope_adherence: None,
+                    human_review: None,
+                    actor_prompt: None,
+                    actor_output_lossy: false,
+                    reset_applied: false,
+                    verify_passed: None,
+                    files: Vec::new(),
+                    coverage_percent: None,
+                    coverage_delta: None,
+                    patch_applied: None,
+                    timestamp,
+                };
+                self.add_iteration(&id, &iteration)?;
+            }
+        }
+
+        if let Some(SessionLine::SessionEnd {
+            outcome,
+            iterations,
+            summary,
+            confidence,
+            duration_secs,
+            timestamp,
+        }) = end
+        {
+            let end = SessionEnd {
+                outcome: outcome.parse()?,
+                iterations,
+                summary,
+                confidence,
+                duration_secs,
+            };
+            self.end(&id, &end)?;
+            self.conn.execute(
+                "UPDATE sessions SET ended_at = ?1 WHERE id = ?2",
+                params![timestamp.to_rfc3339(), id],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(ImportResult::Imported { id, bad_lines })
+    }
+}
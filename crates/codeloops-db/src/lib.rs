@@ -3,20 +3,55 @@
 //! Provides a unified `Database` struct that owns the SQLite connection
 //! and provides access to domain-specific stores.
 
+mod export;
+mod import;
+mod outcome;
+pub mod paths;
 mod projects;
 mod prompts;
+mod query;
+mod review;
 mod sessions;
+mod time_zone;
 
+pub use export::session_html;
+pub use import::{content_session_id, is_session_file, ImportError, ImportResult};
+pub use outcome::{ParseOutcomeError, SessionOutcome};
 pub use projects::{NewProject, ProjectConfigOverrides, ProjectRecord, ProjectUpdate, Projects};
-pub use prompts::{PromptFilter, PromptRecord, Prompts};
+pub use prompts::{content_hash, DuplicateGroup, PromptFilter, PromptRecord, Prompts};
+pub use query::QueryResult;
+pub use review::{ChecklistItem, RubricResult, ScopeAdherence};
 pub use sessions::{
-    AgenticMetrics, DayCount, Iteration, ProjectStats, Session, SessionEnd, SessionFilter,
-    SessionStart, SessionStats, SessionSummary, Sessions,
+    decision_kind, AgenticMetrics, DayCount, FileTouches, Iteration, ProjectStats, Session,
+    SessionEnd, SessionFilter, SessionStart, SessionStats, SessionSummary, Sessions,
+    SESSION_FORMAT_VERSION,
 };
+pub use time_zone::{ParseTimeZoneError, StatsTimeZone};
 
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Transaction, TransactionBehavior};
 use std::path::PathBuf;
 use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long opening a database waits for another connection's write lock,
+/// such as a second process running the same migrations.
+const MIGRATION_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A single schema migration step.
+///
+/// Migrations run inside a transaction; a failing step rolls back and leaves
+/// the recorded schema version untouched.
+pub type Migration = fn(&Connection) -> Result<(), rusqlite::Error>;
+
+/// Identifies the state of the database's contents, for caching values
+/// derived from them. See [`Database::change_marker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangeMarker {
+    /// Moves when another connection (e.g. a running loop) commits
+    data_version: i64,
+    /// Moves when this connection writes
+    total_changes: u64,
+}
 
 /// The main database struct that owns the SQLite connection.
 pub struct Database {
@@ -26,7 +61,7 @@ pub struct Database {
 impl Database {
     /// Open or create a database at the default location.
     ///
-    /// The default location is `~/.local/share/codeloops/codeloops.db`.
+    /// The default location is `codeloops.db` in [`paths::data_dir`].
     pub fn open() -> Result<Self, rusqlite::Error> {
         let db_path = Self::default_path();
 
@@ -41,12 +76,71 @@ impl Database {
     /// Open or create a database at a specific path.
     pub fn open_at(path: &std::path::Path) -> Result<Self, rusqlite::Error> {
         let conn = Connection::open(path)?;
+        // Another process may be migrating the same file; wait for it
+        conn.busy_timeout(MIGRATION_LOCK_TIMEOUT)?;
         Self::init_schema(&conn)?;
         Ok(Self {
             conn: Mutex::new(conn),
         })
     }
 
+    /// Open an existing database at `path` without ever writing to it.
+    ///
+    /// No migrations run, so this works where [`Database::open_at`] cannot
+    /// write (a read-only filesystem, a database file owned by another
+    /// user). Every write through it fails. A database last migrated by an
+    /// older build is refused, since the queries expect the current schema.
+    pub fn open_read_only(path: &std::path::Path) -> Result<Self, rusqlite::Error> {
+        let conn = Self::connect_read_only(path)?;
+        // A database from before schema versioning has no version table
+        let version = Self::current_version(&conn).unwrap_or(0);
+        if version < Self::SCHEMA_VERSION {
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
+                Some(format!(
+                    "database schema is v{}, this build needs v{}; open it once with \
+                     write access to migrate it",
+                    version,
+                    Self::SCHEMA_VERSION
+                )),
+            ));
+        }
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// The schema version of the existing database at `path`, read without
+    /// migrating it or writing to it. A database from before schema
+    /// versioning is v0.
+    pub fn stored_schema_version(path: &std::path::Path) -> Result<u32, rusqlite::Error> {
+        let conn = Self::connect_read_only(path)?;
+        Ok(Self::current_version(&conn).unwrap_or(0))
+    }
+
+    /// Verify the existing database at `path` accepts writes by taking (and
+    /// releasing) a write lock. Nothing is created or migrated.
+    pub fn check_writable_at(path: &std::path::Path) -> Result<(), rusqlite::Error> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        // A running loop may hold the lock for a moment
+        conn.busy_timeout(MIGRATION_LOCK_TIMEOUT)?;
+        conn.execute_batch("BEGIN IMMEDIATE; ROLLBACK;")
+    }
+
+    fn connect_read_only(path: &std::path::Path) -> Result<Connection, rusqlite::Error> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        // Opening is lazy; read the header now so a missing or corrupt
+        // file fails here rather than on the first query
+        conn.query_row("PRAGMA user_version", [], |row| row.get::<_, u32>(0))?;
+        Ok(conn)
+    }
+
     /// Open an in-memory database (useful for testing).
     pub fn open_in_memory() -> Result<Self, rusqlite::Error> {
         let conn = Connection::open_in_memory()?;
@@ -58,10 +152,7 @@ impl Database {
 
     /// Get the default database path.
     pub fn default_path() -> PathBuf {
-        dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("codeloops")
-            .join("codeloops.db")
+        paths::data_dir().join("codeloops.db")
     }
 
     /// Access the prompts store.
@@ -82,8 +173,123 @@ impl Database {
         Projects::new(conn)
     }
 
-    /// Initialize the database schema.
+    /// A marker that differs from every earlier one once anything is
+    /// written, by this connection or another. Compare markers to tell
+    /// whether a cached value is stale; reading one costs no table scan.
+    pub fn change_marker(&self) -> Result<ChangeMarker, rusqlite::Error> {
+        let conn = self.conn.lock().expect("Database lock poisoned");
+        Ok(ChangeMarker {
+            data_version: conn.query_row("PRAGMA data_version", [], |row| row.get(0))?,
+            total_changes: conn.total_changes(),
+        })
+    }
+
+    /// Run a read-only `SELECT` against the database.
+    ///
+    /// Statements that could modify data are refused with
+    /// [`rusqlite::Error::InvalidQuery`].
+    pub fn query_readonly(&self, sql: &str) -> Result<QueryResult, rusqlite::Error> {
+        let conn = self.conn.lock().expect("Database lock poisoned");
+        query::run_readonly(&conn, sql)
+    }
+
+    /// Ordered list of schema migrations.
+    ///
+    /// Migration `i` upgrades the schema to version `i + 1`. Append new steps
+    /// to the end; never reorder or edit a step that has shipped.
+    const MIGRATIONS: &'static [Migration] = &[
+        Self::migrate_v1_base_schema,
+        Self::migrate_v2_critic_raw,
+        Self::migrate_v3_prompt_content_hash,
+        Self::migrate_v4_normalize_outcomes,
+        Self::migrate_v5_byte_sizes,
+        Self::migrate_v6_critic_confidence,
+        Self::migrate_v7_critic_checklist,
+        Self::migrate_v8_total_files_changed,
+        Self::migrate_v9_prompt_files,
+        Self::migrate_v10_coverage,
+        Self::migrate_v11_patch_applied,
+        Self::migrate_v12_branch,
+        Self::migrate_v13_seed_feedback,
+        Self::migrate_v14_iteration_files,
+        Self::migrate_v15_context_files,
+        Self::migrate_v16_critic_rubric,
+        Self::migrate_v17_session_notes,
+        Self::migrate_v18_scope_adherence,
+        Self::migrate_v19_human_review,
+        Self::migrate_v20_actor_prompt,
+        Self::migrate_v21_reset_applied,
+        Self::migrate_v22_decision_kind,
+        Self::migrate_v23_verify,
+        Self::migrate_v24_actor_output_lossy,
+    ];
+
+    /// The schema version this build expects after all migrations are applied.
+    pub const SCHEMA_VERSION: u32 = Self::MIGRATIONS.len() as u32;
+
+    /// The schema migrations, in order.
+    pub fn migrations() -> &'static [Migration] {
+        Self::MIGRATIONS
+    }
+
+    /// Same as [`Self::SCHEMA_VERSION`].
+    pub fn latest_schema_version() -> u32 {
+        Self::SCHEMA_VERSION
+    }
+
+    /// The schema version currently recorded in the database.
+    pub fn schema_version(&self) -> Result<u32, rusqlite::Error> {
+        let conn = self.conn.lock().expect("Database lock poisoned");
+        Self::current_version(&conn)
+    }
+
+    /// Initialize the database schema, applying any pending migrations.
     fn init_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
+                applied_at TEXT NOT NULL
+            );
+            "#,
+        )?;
+
+        for (idx, migration) in Self::migrations().iter().enumerate() {
+            let version = idx as u32 + 1;
+            // Take the write lock before reading the version, so a process
+            // opening the same file at the same time cannot apply this step
+            // too; it waits, then sees the step done
+            let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+            if version <= Self::current_version(&tx)? {
+                continue;
+            }
+            migration(&tx)?;
+            tx.execute(
+                "INSERT INTO schema_version (version, applied_at) VALUES (?1, ?2)",
+                rusqlite::params![version, chrono::Utc::now().to_rfc3339()],
+            )?;
+            tx.commit()?;
+        }
+
+        Ok(())
+    }
+
+    /// Read the highest applied schema version (0 for a fresh or pre-versioned DB).
+    fn current_version(conn: &Connection) -> Result<u32, rusqlite::Error> {
+        let version: Option<u32> = conn
+            .query_row("SELECT MAX(version) FROM schema_version", [], |row| {
+                row.get(0)
+            })
+            .optional()?
+            .flatten();
+        Ok(version.unwrap_or(0))
+    }
+
+    /// v1: the base schema.
+    ///
+    /// Uses `IF NOT EXISTS` so databases created before schema versioning was
+    /// introduced are adopted as-is, then upgraded in place.
+    fn migrate_v1_base_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
         conn.execute_batch(
             r#"
             CREATE TABLE IF NOT EXISTS prompts (
@@ -172,6 +378,203 @@ impl Database {
         Ok(())
     }
 
+    /// v2: store the critic's raw response alongside the parsed decision.
+    fn migrate_v2_critic_raw(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE iterations ADD COLUMN critic_raw TEXT;")
+    }
+
+    /// v3: content hash on prompts for duplicate detection, backfilled for
+    /// existing rows.
+    fn migrate_v3_prompt_content_hash(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE prompts ADD COLUMN content_hash TEXT;
+            CREATE INDEX IF NOT EXISTS idx_prompts_content_hash ON prompts(project_name, content_hash);
+            "#,
+        )?;
+
+        let mut stmt = conn.prepare("SELECT id, content FROM prompts WHERE content IS NOT NULL")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (id, content) in rows {
+            conn.execute(
+                "UPDATE prompts SET content_hash = ?1 WHERE id = ?2",
+                rusqlite::params![content_hash(&content), id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// v4: rewrite the legacy `user_interrupted` outcome to `interrupted` so
+    /// outcome filters match every interrupted session.
+    fn migrate_v4_normalize_outcomes(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "UPDATE sessions SET outcome = ?1 WHERE outcome = 'user_interrupted'",
+            [SessionOutcome::Interrupted],
+        )?;
+        Ok(())
+    }
+
+    /// v5: record prompt and diff sizes for capacity analytics, backfilling
+    /// existing rows.
+    fn migrate_v5_byte_sizes(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE sessions ADD COLUMN prompt_bytes INTEGER;
+            ALTER TABLE iterations ADD COLUMN diff_bytes INTEGER;
+            UPDATE sessions SET prompt_bytes = LENGTH(CAST(prompt AS BLOB));
+            UPDATE iterations SET diff_bytes = LENGTH(CAST(git_diff AS BLOB))
+                WHERE git_diff IS NOT NULL;
+            "#,
+        )
+    }
+
+    /// v6: per-iteration critic confidence, for any decision type. Existing
+    /// rows stay NULL.
+    fn migrate_v6_critic_confidence(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE iterations ADD COLUMN critic_confidence REAL;")
+    }
+
+    /// v7: per-iteration acceptance criteria status as a JSON array.
+    fn migrate_v7_critic_checklist(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE iterations ADD COLUMN critic_checklist TEXT;")
+    }
+
+    /// v8: files changed summed across iterations, stored on the session when
+    /// it ends so list queries need no join. Backfills ended sessions.
+    fn migrate_v8_total_files_changed(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE sessions ADD COLUMN total_files_changed INTEGER;
+            UPDATE sessions SET total_files_changed = (
+                SELECT COALESCE(SUM(git_files_changed), 0)
+                FROM iterations i WHERE i.session_id = sessions.id
+            )
+            WHERE ended_at IS NOT NULL;
+            "#,
+        )
+    }
+
+    /// v9: the prompt files a session's prompt was assembled from.
+    fn migrate_v9_prompt_files(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE sessions ADD COLUMN prompt_files TEXT;")
+    }
+
+    /// v10: coverage percentage after each iteration and its change against
+    /// the session baseline.
+    fn migrate_v10_coverage(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE iterations ADD COLUMN coverage_percent REAL;
+            ALTER TABLE iterations ADD COLUMN coverage_delta REAL;
+            "#,
+        )
+    }
+
+    /// v11: whether the diff in a patch-mode actor's output applied.
+    fn migrate_v11_patch_applied(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE iterations ADD COLUMN patch_applied INTEGER;")
+    }
+
+    /// v12: the git branch checked out when a session started.
+    fn migrate_v12_branch(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE sessions ADD COLUMN branch TEXT;")
+    }
+
+    /// v13: feedback given to the actor before the first iteration.
+    fn migrate_v13_seed_feedback(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE sessions ADD COLUMN seed_feedback TEXT;")
+    }
+
+    /// v14: paths of the files each iteration's diff touched, as JSON.
+    fn migrate_v14_iteration_files(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE iterations ADD COLUMN files_changed TEXT;")
+    }
+
+    /// v15: reference files given to actor and critic, as JSON.
+    fn migrate_v15_context_files(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE sessions ADD COLUMN context_files TEXT;")
+    }
+
+    /// v16: the critic's per-criterion rubric results, as JSON.
+    fn migrate_v16_critic_rubric(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE iterations ADD COLUMN critic_rubric TEXT;")
+    }
+
+    /// v17: free-form notes added to a session after the fact.
+    fn migrate_v17_session_notes(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE sessions ADD COLUMN notes TEXT;")
+    }
+
+    /// v18: how an iteration's changed files compare with the prompt's
+    /// files to modify, as JSON.
+    fn migrate_v18_scope_adherence(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE iterations ADD COLUMN scope_adherence TEXT;")
+    }
+
+    /// v19: how a person ruled on an iteration in an interactive run.
+    fn migrate_v19_human_review(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE iterations ADD COLUMN human_review TEXT;")
+    }
+
+    /// v20: the prompt the actor was given, with instructions, context
+    /// files and feedback around the task.
+    fn migrate_v20_actor_prompt(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("ALTER TABLE iterations ADD COLUMN actor_prompt TEXT;")
+    }
+
+    /// v21: whether a critic reset discarded an iteration's changes.
+    fn migrate_v21_reset_applied(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch(
+            "ALTER TABLE iterations ADD COLUMN reset_applied INTEGER NOT NULL DEFAULT 0;",
+        )
+    }
+
+    /// v22: store the bare decision kind. The description older builds
+    /// wrote after it (`DONE (confidence: 90%)`, `CONTINUE (3 issues)`) moves
+    /// to `legacy_critic_decision`, and a confidence it carries fills
+    /// `critic_confidence` when that is empty.
+    fn migrate_v22_decision_kind(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE iterations ADD COLUMN legacy_critic_decision TEXT;
+
+            UPDATE iterations
+            SET critic_confidence = CAST(
+                substr(critic_decision, 19, instr(critic_decision, '%)') - 19) AS REAL
+            ) / 100.0
+            WHERE critic_confidence IS NULL
+              AND critic_decision LIKE 'DONE (confidence: %\%)' ESCAPE '\';
+
+            UPDATE iterations
+            SET legacy_critic_decision = critic_decision,
+                critic_decision = substr(critic_decision, 1, instr(critic_decision, ' (') - 1)
+            WHERE instr(critic_decision, ' (') > 0;
+            "#,
+        )
+    }
+
+    /// v23: whether the verify command passed at session start and after
+    /// each iteration.
+    fn migrate_v23_verify(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE sessions ADD COLUMN verify_baseline INTEGER;
+            ALTER TABLE iterations ADD COLUMN verify_passed INTEGER;
+            "#,
+        )
+    }
+
+    /// v24: whether the actor's output had bytes that were not UTF-8.
+    fn migrate_v24_actor_output_lossy(conn: &Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch(
+            "ALTER TABLE iterations ADD COLUMN actor_output_lossy INTEGER NOT NULL DEFAULT 0;",
+        )
+    }
+
     /// Migrate the iterations table to add the `phase` column and make fields nullable.
     ///
     /// Old schema had all iteration fields as NOT NULL and no phase column.
@@ -630,6 +1033,7 @@ mod tests {
             actor_model: Some("claude-3-sonnet".to_string()),
             critic_model: Some("claude-3-haiku".to_string()),
             max_iterations: Some(5),
+            ..Default::default()
         };
 
         let id = db.sessions().create(&start).unwrap();
@@ -643,6 +1047,78 @@ mod tests {
         assert!(session.outcome.is_none());
     }
 
+    #[test]
+    fn test_open_read_only_reads_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("codeloops.db");
+        assert!(Database::open_read_only(&path).is_err());
+
+        let start = SessionStart {
+            prompt: "Fix the bug in login".to_string(),
+            working_dir: std::path::PathBuf::from("/home/user/project"),
+            actor_agent: "Claude Code".to_string(),
+            critic_agent: "Claude Code".to_string(),
+            ..Default::default()
+        };
+        let id = Database::open_at(&path)
+            .unwrap()
+            .sessions()
+            .create(&start)
+            .unwrap();
+
+        let db = Database::open_read_only(&path).unwrap();
+        assert_eq!(
+            db.sessions().get(&id).unwrap().unwrap().prompt,
+            "Fix the bug in login"
+        );
+        assert_eq!(
+            db.sessions().list(&SessionFilter::default()).unwrap().len(),
+            1
+        );
+        assert!(db.sessions().create(&start).is_err());
+    }
+
+    #[test]
+    fn test_open_read_only_refuses_an_old_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("codeloops.db");
+        Database::open_at(&path).unwrap();
+        // As last written by a build that knew one fewer migration
+        Connection::open(&path)
+            .unwrap()
+            .execute(
+                "DELETE FROM schema_version WHERE version = ?1",
+                [Database::SCHEMA_VERSION],
+            )
+            .unwrap();
+
+        let err = Database::open_read_only(&path).err().unwrap();
+        assert!(err
+            .to_string()
+            .contains(&format!("this build needs v{}", Database::SCHEMA_VERSION)));
+        assert_eq!(
+            Database::stored_schema_version(&path).unwrap(),
+            Database::SCHEMA_VERSION - 1
+        );
+        Database::check_writable_at(&path).unwrap();
+        // Neither of those migrated it
+        assert!(Database::open_read_only(&path).is_err());
+
+        // From before schema versioning
+        let path = dir.path().join("unversioned.db");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch("CREATE TABLE sessions (id TEXT PRIMARY KEY, prompt TEXT NOT NULL);")
+            .unwrap();
+        assert!(Database::open_read_only(&path).is_err());
+        assert_eq!(Database::stored_schema_version(&path).unwrap(), 0);
+
+        let missing = dir.path().join("missing.db");
+        assert!(Database::stored_schema_version(&missing).is_err());
+        assert!(Database::check_writable_at(&missing).is_err());
+        assert!(!missing.exists());
+    }
+
     #[test]
     fn test_session_add_iteration() {
         let db = Database::open_in_memory().unwrap();
@@ -653,9 +1129,7 @@ mod tests {
             working_dir: std::path::PathBuf::from("/home/user/project"),
             actor_agent: "Actor".to_string(),
             critic_agent: "Critic".to_string(),
-            actor_model: None,
-            critic_model: None,
-            max_iterations: None,
+            ..Default::default()
         };
 
         let id = db.sessions().create(&start).unwrap();
@@ -670,7 +1144,33 @@ mod tests {
             git_diff: Some("diff --git a/file.rs".to_string()),
             git_files_changed: Some(2),
             critic_decision: Some("CONTINUE".to_string()),
+            legacy_critic_decision: None,
             feedback: Some("Please also fix tests".to_string()),
+            critic_raw: Some("CONTINUE: Please also fix tests".to_string()),
+            critic_confidence: Some(0.4),
+            critic_checklist: vec![ChecklistItem {
+                item: "Tests pass".to_string(),
+                met: false,
+            }],
+            critic_rubric: vec![RubricResult {
+                criterion: "Doc comments".to_string(),
+                must_pass: true,
+                met: None,
+            }],
+            scope_adherence: Some(ScopeAdherence {
+                unexpected: vec!["tests/file.rs".to_string()],
+                untouched: Vec::new(),
+            }),
+            human_review: Some("feedback".to_string()),
+            actor_prompt: Some("## Task\nFix bug".to_string()),
+            actor_output_lossy: true,
+            reset_applied: true,
+            verify_passed: Some(false),
+            files: vec!["file.rs".to_string(), "tests/file.rs".to_string()],
+            diff_bytes: None,
+            coverage_percent: None,
+            coverage_delta: None,
+            patch_applied: None,
             timestamp: now,
         };
 
@@ -687,6 +1187,133 @@ mod tests {
             session.iterations[0].feedback,
             Some("Please also fix tests".to_string())
         );
+        assert_eq!(
+            session.iterations[0].critic_raw,
+            Some("CONTINUE: Please also fix tests".to_string())
+        );
+        assert_eq!(session.iterations[0].critic_confidence, Some(0.4));
+        assert_eq!(
+            session.iterations[0].files,
+            vec!["file.rs".to_string(), "tests/file.rs".to_string()]
+        );
+        assert_eq!(
+            session.iterations[0].critic_checklist,
+            vec![ChecklistItem {
+                item: "Tests pass".to_string(),
+                met: false,
+            }]
+        );
+        assert_eq!(
+            session.iterations[0].critic_rubric,
+            vec![RubricResult {
+                criterion: "Doc comments".to_string(),
+                must_pass: true,
+                met: None,
+            }]
+        );
+        assert_eq!(
+            session.iterations[0].scope_adherence,
+            Some(ScopeAdherence {
+                unexpected: vec!["tests/file.rs".to_string()],
+                untouched: Vec::new(),
+            })
+        );
+        assert_eq!(
+            session.iterations[0].human_review,
+            Some("feedback".to_string())
+        );
+        assert_eq!(
+            session.iterations[0].actor_prompt.as_deref(),
+            Some("## Task\nFix bug")
+        );
+        assert!(session.iterations[0].actor_output_lossy);
+        assert!(session.iterations[0].reset_applied);
+        assert_eq!(session.iterations[0].verify_passed, Some(false));
+    }
+
+    #[test]
+    fn test_complete_critic_confidence() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db
+            .sessions()
+            .create(&SessionStart {
+                prompt: "Test task".to_string(),
+                working_dir: std::path::PathBuf::from("/project"),
+                actor_agent: "Actor".to_string(),
+                critic_agent: "Critic".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        for (iteration, confidence) in [(0, Some(0.5)), (1, None)] {
+            db.sessions().start_iteration(&id, iteration).unwrap();
+            db.sessions()
+                .complete_critic(&id, iteration, "CONTINUE", Some("more"), confidence)
+                .unwrap();
+        }
+
+        let session = db.sessions().get(&id).unwrap().unwrap();
+        let confidences: Vec<_> = session
+            .iterations
+            .iter()
+            .map(|i| i.critic_confidence)
+            .collect();
+        assert_eq!(confidences, vec![Some(0.5), None]);
+    }
+
+    #[test]
+    fn test_session_critic_raw_without_decision() {
+        let db = Database::open_in_memory().unwrap();
+
+        let start = SessionStart {
+            prompt: "Test task".to_string(),
+            working_dir: std::path::PathBuf::from("/project"),
+            actor_agent: "Actor".to_string(),
+            critic_agent: "Critic".to_string(),
+            ..Default::default()
+        };
+
+        let id = db.sessions().create(&start).unwrap();
+        db.sessions().start_iteration(&id, 0).unwrap();
+        db.sessions().start_critic(&id, 0).unwrap();
+        db.sessions()
+            .set_critic_raw(&id, 0, "I think it looks fine?")
+            .unwrap();
+
+        let session = db.sessions().get(&id).unwrap().unwrap();
+        let iter = &session.iterations[0];
+        assert_eq!(iter.phase, "critic_started");
+        assert_eq!(iter.critic_decision, None);
+        assert_eq!(iter.critic_raw.as_deref(), Some("I think it looks fine?"));
+    }
+
+    #[test]
+    fn test_session_actor_output_lossy() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db
+            .sessions()
+            .create(&SessionStart {
+                prompt: "Test task".to_string(),
+                working_dir: std::path::PathBuf::from("/project"),
+                actor_agent: "Actor".to_string(),
+                critic_agent: "Critic".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        db.sessions().start_iteration(&id, 0).unwrap();
+        db.sessions().start_iteration(&id, 1).unwrap();
+        db.sessions()
+            .complete_actor(&id, 0, "binary \u{FFFD} dump", "", 0, 1.0)
+            .unwrap();
+        db.sessions().set_actor_output_lossy(&id, 0).unwrap();
+
+        let session = db.sessions().get(&id).unwrap().unwrap();
+        assert!(session.iterations[0].actor_output_lossy);
+        assert_eq!(
+            session.iterations[0].actor_output.as_deref(),
+            Some("binary \u{FFFD} dump")
+        );
+        assert!(!session.iterations[1].actor_output_lossy);
     }
 
     #[test]
@@ -698,15 +1325,13 @@ mod tests {
             working_dir: std::path::PathBuf::from("/project"),
             actor_agent: "Actor".to_string(),
             critic_agent: "Critic".to_string(),
-            actor_model: None,
-            critic_model: None,
-            max_iterations: None,
+            ..Default::default()
         };
 
         let id = db.sessions().create(&start).unwrap();
 
         let end = SessionEnd {
-            outcome: "success".to_string(),
+            outcome: SessionOutcome::Success,
             iterations: 2,
             summary: Some("Fixed the bug successfully".to_string()),
             confidence: Some(0.95),
@@ -716,7 +1341,7 @@ mod tests {
         db.sessions().end(&id, &end).unwrap();
 
         let session = db.sessions().get(&id).unwrap().unwrap();
-        assert_eq!(session.outcome, Some("success".to_string()));
+        assert_eq!(session.outcome, Some(SessionOutcome::Success));
         assert_eq!(session.iteration_count, Some(2));
         assert_eq!(
             session.summary,
@@ -736,9 +1361,7 @@ mod tests {
             working_dir: std::path::PathBuf::from("/project-a"),
             actor_agent: "Actor".to_string(),
             critic_agent: "Critic".to_string(),
-            actor_model: None,
-            critic_model: None,
-            max_iterations: None,
+            ..Default::default()
         };
 
         let start2 = SessionStart {
@@ -746,9 +1369,7 @@ mod tests {
             working_dir: std::path::PathBuf::from("/project-b"),
             actor_agent: "Actor".to_string(),
             critic_agent: "Critic".to_string(),
-            actor_model: None,
-            critic_model: None,
-            max_iterations: None,
+            ..Default::default()
         };
 
         let id1 = db.sessions().create(&start1).unwrap();
@@ -759,10 +1380,10 @@ mod tests {
             .end(
                 &id1,
                 &SessionEnd {
-                    outcome: "success".to_string(),
+                    outcome: SessionOutcome::Success,
                     iterations: 1,
                     summary: None,
-                    confidence: None,
+                    confidence: Some(0.9),
                     duration_secs: 10.0,
                 },
             )
@@ -776,13 +1397,32 @@ mod tests {
         let successful = db
             .sessions()
             .list(&SessionFilter {
-                outcome: Some("success".to_string()),
+                outcome: Some(SessionOutcome::Success),
                 ..Default::default()
             })
             .unwrap();
         assert_eq!(successful.len(), 1);
         assert_eq!(successful[0].id, id1);
 
+        // Filter by confidence; sessions without one are excluded
+        let confident = db
+            .sessions()
+            .list(&SessionFilter {
+                min_confidence: Some(0.5),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(confident.len(), 1);
+        assert_eq!(confident[0].id, id1);
+        let unsure = db
+            .sessions()
+            .list(&SessionFilter {
+                max_confidence: Some(0.5),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(unsure.is_empty());
+
         // Search by prompt
         let searched = db
             .sessions()
@@ -804,9 +1444,7 @@ mod tests {
             working_dir: std::path::PathBuf::from("/project"),
             actor_agent: "Actor".to_string(),
             critic_agent: "Critic".to_string(),
-            actor_model: None,
-            critic_model: None,
-            max_iterations: None,
+            ..Default::default()
         };
 
         let id1 = db.sessions().create(&start).unwrap();
@@ -817,7 +1455,7 @@ mod tests {
             .end(
                 &id1,
                 &SessionEnd {
-                    outcome: "success".to_string(),
+                    outcome: SessionOutcome::Success,
                     iterations: 1,
                     summary: None,
                     confidence: None,
@@ -831,6 +1469,23 @@ mod tests {
         assert_eq!(active[0], id2);
     }
 
+    #[test]
+    fn test_session_latest_id() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.sessions().latest_id().unwrap(), None);
+
+        let start = SessionStart {
+            prompt: "Task".to_string(),
+            working_dir: std::path::PathBuf::from("/project"),
+            actor_agent: "Actor".to_string(),
+            critic_agent: "Critic".to_string(),
+            ..Default::default()
+        };
+        db.sessions().create(&start).unwrap();
+        let id2 = db.sessions().create(&start).unwrap();
+        assert_eq!(db.sessions().latest_id().unwrap(), Some(id2));
+    }
+
     #[test]
     fn test_session_delete_cascades_iterations() {
         let db = Database::open_in_memory().unwrap();
@@ -841,9 +1496,7 @@ mod tests {
             working_dir: std::path::PathBuf::from("/project"),
             actor_agent: "Actor".to_string(),
             critic_agent: "Critic".to_string(),
-            actor_model: None,
-            critic_model: None,
-            max_iterations: None,
+            ..Default::default()
         };
 
         let id = db.sessions().create(&start).unwrap();
@@ -858,7 +1511,23 @@ mod tests {
             git_diff: Some("".to_string()),
             git_files_changed: Some(0),
             critic_decision: Some("DONE".to_string()),
+            legacy_critic_decision: None,
             feedback: None,
+            critic_raw: None,
+            critic_confidence: None,
+            critic_checklist: vec![],
+            critic_rubric: Vec::new(),
+            scope_adherence: None,
+            human_review: None,
+            actor_prompt: None,
+            actor_output_lossy: false,
+            reset_applied: false,
+            verify_passed: None,
+            files: Vec::new(),
+            diff_bytes: None,
+            coverage_percent: None,
+            coverage_delta: None,
+            patch_applied: None,
             timestamp: now,
         };
 
@@ -886,9 +1555,7 @@ mod tests {
             working_dir: std::path::PathBuf::from("/project"),
             actor_agent: "Actor".to_string(),
             critic_agent: "Critic".to_string(),
-            actor_model: None,
-            critic_model: None,
-            max_iterations: None,
+            ..Default::default()
         };
 
         let id1 = db.sessions().create(&start).unwrap();
@@ -899,7 +1566,7 @@ mod tests {
             .end(
                 &id1,
                 &SessionEnd {
-                    outcome: "success".to_string(),
+                    outcome: SessionOutcome::Success,
                     iterations: 2,
                     summary: None,
                     confidence: None,
@@ -912,7 +1579,7 @@ mod tests {
             .end(
                 &id2,
                 &SessionEnd {
-                    outcome: "success".to_string(),
+                    outcome: SessionOutcome::Success,
                     iterations: 1,
                     summary: None,
                     confidence: None,
@@ -925,7 +1592,7 @@ mod tests {
             .end(
                 &id3,
                 &SessionEnd {
-                    outcome: "failed".to_string(),
+                    outcome: SessionOutcome::Failed,
                     iterations: 3,
                     summary: None,
                     confidence: None,
@@ -934,10 +1601,944 @@ mod tests {
             )
             .unwrap();
 
-        let stats = db.sessions().stats(&SessionFilter::default()).unwrap();
+        let stats = db
+            .sessions()
+            .stats(&SessionFilter::default(), StatsTimeZone::Utc)
+            .unwrap();
         assert_eq!(stats.total_sessions, 3);
         assert!((stats.success_rate - 2.0 / 3.0).abs() < 0.001);
         assert!((stats.avg_iterations - 2.0).abs() < 0.001);
         assert!((stats.avg_duration_secs - 60.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_byte_sizes_recorded() {
+        let db = Database::open_in_memory().unwrap();
+        let start = SessionStart {
+            prompt: "héllo".to_string(),
+            working_dir: std::path::PathBuf::from("/project"),
+            actor_agent: "Actor".to_string(),
+            critic_agent: "Critic".to_string(),
+            ..Default::default()
+        };
+        let id = db.sessions().create(&start).unwrap();
+        for (n, diff) in [(0, "small"), (1, "a larger diff")] {
+            db.sessions().start_iteration(&id, n).unwrap();
+            db.sessions().complete_diff(&id, n, diff, 1, &[]).unwrap();
+        }
+
+        let summary = &db.sessions().list(&SessionFilter::default()).unwrap()[0];
+        assert_eq!(summary.prompt_bytes, Some(6));
+        assert_eq!(summary.max_diff_bytes, Some(13));
+
+        let session = db.sessions().get(&id).unwrap().unwrap();
+        assert_eq!(session.iterations[0].diff_bytes, Some(5));
+
+        let metrics = db
+            .sessions()
+            .agentic_metrics(&SessionFilter::default(), StatsTimeZone::Utc, None)
+            .unwrap();
+        assert!((metrics.avg_prompt_bytes - 6.0).abs() < 0.001);
+        assert_eq!(metrics.max_diff_bytes, 13);
+    }
+
+    #[test]
+    fn test_total_files_changed_stored_at_end() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db
+            .sessions()
+            .create(&SessionStart {
+                prompt: "p".to_string(),
+                working_dir: std::path::PathBuf::from("/project"),
+                actor_agent: "Actor".to_string(),
+                critic_agent: "Critic".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        for (n, files) in [(0, 3), (1, 2)] {
+            db.sessions().start_iteration(&id, n).unwrap();
+            db.sessions()
+                .complete_diff(&id, n, "diff", files, &[])
+                .unwrap();
+        }
+        // Not stored until the session ends
+        let summary = &db.sessions().list(&SessionFilter::default()).unwrap()[0];
+        assert_eq!(summary.total_files_changed, None);
+
+        db.sessions()
+            .end(
+                &id,
+                &SessionEnd {
+                    outcome: SessionOutcome::Success,
+                    iterations: 2,
+                    summary: None,
+                    confidence: None,
+                    duration_secs: 1.0,
+                },
+            )
+            .unwrap();
+        let summary = &db.sessions().list(&SessionFilter::default()).unwrap()[0];
+        assert_eq!(summary.total_files_changed, Some(5));
+    }
+
+    #[test]
+    fn test_most_touched_files() {
+        let db = Database::open_in_memory().unwrap();
+        let paths = |files: &[&str]| files.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+        for iterations in [
+            vec![paths(&["src/lib.rs", "README.md"]), paths(&["src/lib.rs"])],
+            vec![paths(&["src/lib.rs"])],
+        ] {
+            let id = db
+                .sessions()
+                .create(&SessionStart {
+                    prompt: "p".to_string(),
+                    working_dir: std::path::PathBuf::from("/work/app"),
+                    actor_agent: "Actor".to_string(),
+                    critic_agent: "Critic".to_string(),
+                    ..Default::default()
+                })
+                .unwrap();
+            for (n, files) in iterations.iter().enumerate() {
+                db.sessions().start_iteration(&id, n).unwrap();
+                db.sessions()
+                    .complete_diff(&id, n, "diff", files.len(), files)
+                    .unwrap();
+            }
+        }
+
+        let metrics = db
+            .sessions()
+            .agentic_metrics(&SessionFilter::default(), StatsTimeZone::Utc, None)
+            .unwrap();
+        let touched: Vec<_> = metrics
+            .most_touched_files
+            .iter()
+            .map(|f| {
+                (
+                    f.project.as_str(),
+                    f.path.as_str(),
+                    f.iterations,
+                    f.sessions,
+                )
+            })
+            .collect();
+        assert_eq!(
+            touched,
+            vec![("app", "src/lib.rs", 3, 2), ("app", "README.md", 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_prompt_files_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        let start = |prompt_files: Vec<String>| SessionStart {
+            prompt: "p".to_string(),
+            working_dir: std::path::PathBuf::from("/project"),
+            actor_agent: "Actor".to_string(),
+            critic_agent: "Critic".to_string(),
+            prompt_files,
+            ..Default::default()
+        };
+        let files = vec![
+            "/project/context.md".to_string(),
+            "/project/task.md".to_string(),
+        ];
+        let id = db.sessions().create(&start(files.clone())).unwrap();
+        let session = db.sessions().get(&id).unwrap().unwrap();
+        assert_eq!(session.prompt_files, files);
+
+        let id = db.sessions().create(&start(Vec::new())).unwrap();
+        let session = db.sessions().get(&id).unwrap().unwrap();
+        assert!(session.prompt_files.is_empty());
+    }
+
+    #[test]
+    fn test_session_notes() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db
+            .sessions()
+            .create(&SessionStart {
+                prompt: "p".to_string(),
+                working_dir: std::path::PathBuf::from("/project"),
+                actor_agent: "Actor".to_string(),
+                critic_agent: "Critic".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        let notes = |db: &Database| db.sessions().get(&id).unwrap().unwrap().notes;
+        assert_eq!(notes(&db), None);
+
+        assert!(db
+            .sessions()
+            .set_notes(&id, Some("Reverted the config change by hand\n"))
+            .unwrap());
+        assert_eq!(
+            notes(&db).as_deref(),
+            Some("Reverted the config change by hand")
+        );
+
+        // Blank text clears the notes
+        assert!(db.sessions().set_notes(&id, Some("  ")).unwrap());
+        assert_eq!(notes(&db), None);
+
+        assert!(!db.sessions().set_notes("missing", Some("x")).unwrap());
+    }
+
+    #[test]
+    fn test_interrupted_sessions_count_as_waste() {
+        let db = Database::open_in_memory().unwrap();
+        let start = SessionStart {
+            prompt: "Task".to_string(),
+            working_dir: std::path::PathBuf::from("/project"),
+            actor_agent: "Actor".to_string(),
+            critic_agent: "Critic".to_string(),
+            ..Default::default()
+        };
+        let done = db.sessions().create(&start).unwrap();
+        let stopped = db.sessions().create(&start).unwrap();
+        for (id, outcome) in [
+            (&done, SessionOutcome::Success),
+            (&stopped, SessionOutcome::Interrupted),
+        ] {
+            db.sessions()
+                .end(
+                    id,
+                    &SessionEnd {
+                        outcome,
+                        iterations: 1,
+                        summary: None,
+                        confidence: None,
+                        duration_secs: 10.0,
+                    },
+                )
+                .unwrap();
+        }
+
+        let metrics = db
+            .sessions()
+            .agentic_metrics(&SessionFilter::default(), StatsTimeZone::Utc, None)
+            .unwrap();
+        assert!((metrics.waste_rate - 0.5).abs() < 0.001);
+
+        let interrupted = db
+            .sessions()
+            .list(&SessionFilter {
+                outcome: Some(SessionOutcome::Interrupted),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].outcome, Some(SessionOutcome::Interrupted));
+    }
+
+    #[test]
+    fn test_metrics_by_project_branch() {
+        let db = Database::open_in_memory().unwrap();
+        let start = |dir: &str, branch: Option<&str>| SessionStart {
+            prompt: "Task".to_string(),
+            working_dir: std::path::PathBuf::from(dir),
+            actor_agent: "Actor".to_string(),
+            critic_agent: "Critic".to_string(),
+            branch: branch.map(str::to_string),
+            ..Default::default()
+        };
+        let runs = [
+            ("/app", Some("main"), SessionOutcome::Success),
+            ("/app", Some("main"), SessionOutcome::Success),
+            ("/app", Some("experimental"), SessionOutcome::Failed),
+            ("/app", Some("experimental"), SessionOutcome::Success),
+            ("/app", Some("experimental"), SessionOutcome::Failed),
+            ("/lib", None, SessionOutcome::Success),
+        ];
+        for (dir, branch, outcome) in runs {
+            let id = db.sessions().create(&start(dir, branch)).unwrap();
+            db.sessions()
+                .end(
+                    &id,
+                    &SessionEnd {
+                        outcome,
+                        iterations: 1,
+                        summary: None,
+                        confidence: None,
+                        duration_secs: 1.0,
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                db.sessions().get(&id).unwrap().unwrap().branch.as_deref(),
+                branch
+            );
+        }
+
+        let metrics = db
+            .sessions()
+            .agentic_metrics(&SessionFilter::default(), StatsTimeZone::Utc, None)
+            .unwrap();
+        let groups: Vec<(&str, Option<&str>, usize, usize)> = metrics
+            .by_project_branch
+            .iter()
+            .map(|g| {
+                (
+                    g.project.as_str(),
+                    g.branch.as_deref(),
+                    g.total,
+                    g.successes,
+                )
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("app", Some("experimental"), 3, 1),
+                ("app", Some("main"), 2, 2),
+                ("lib", None, 1, 1),
+            ]
+        );
+        assert!((metrics.by_project_branch[1].success_rate - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_agentic_metrics_limited_to_recent_sessions() {
+        let db = Database::open_in_memory().unwrap();
+        let start = SessionStart {
+            prompt: "p".to_string(),
+            working_dir: std::path::PathBuf::from("/app"),
+            actor_agent: "Actor".to_string(),
+            critic_agent: "Critic".to_string(),
+            ..Default::default()
+        };
+        // An older approved session, then a newer one the critic rejected
+        for (started_at, decision) in [("2020-01-01T00:00:00+00:00", "DONE"), ("", "CONTINUE")] {
+            let id = db.sessions().create(&start).unwrap();
+            db.sessions().start_iteration(&id, 0).unwrap();
+            db.sessions()
+                .complete_critic(&id, 0, decision, Some("feedback"), None)
+                .unwrap();
+            if !started_at.is_empty() {
+                db.conn
+                    .lock()
+                    .unwrap()
+                    .execute(
+                        "UPDATE sessions SET started_at = ?1 WHERE id = ?2",
+                        rusqlite::params![started_at, id],
+                    )
+                    .unwrap();
+            }
+        }
+
+        let metrics = |limit| {
+            db.sessions()
+                .agentic_metrics(&SessionFilter::default(), StatsTimeZone::Utc, limit)
+                .unwrap()
+        };
+        let all = metrics(None);
+        assert_eq!((all.total_iterations, all.iteration_sessions), (2, 2));
+        assert!((all.critic_approval_rate - 0.5).abs() < 0.001);
+
+        let recent = metrics(Some(1));
+        assert_eq!(recent.total_sessions, 2);
+        assert_eq!((recent.total_iterations, recent.iteration_sessions), (1, 1));
+        assert_eq!(recent.critic_approval_rate, 0.0);
+        assert_eq!(metrics(Some(10)).iteration_sessions, 2);
+    }
+
+    #[test]
+    fn test_import_jsonl() {
+        let db = Database::open_in_memory().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("2025-01-27T15-30-45Z_a3f2c1.jsonl");
+        std::fs::write(
+            &path,
+            r#"{"type":"session_start","timestamp":"2025-01-27T15:30:45Z","prompt":"Fix the bug","working_dir":"/home/user/proj","actor_agent":"Claude Code","critic_agent":"Claude Code","actor_model":null,"critic_model":null,"max_iterations":5}
+{"type":"iteration","iteration_number":1,"actor_output":"done","actor_stderr":"","actor_exit_code":0,"actor_duration_secs":4.5,"git_diff":"+fix","git_files_changed":1,"critic_decision":"DONE (confidence: 85%)","feedback":null,"timestamp":"2025-01-27T15:31:00Z"}
+not json
+{"type":"session_end","outcome":"user_interrupted","iterations":1,"summary":null,"confidence":null,"duration_secs":15.0,"timestamp":"2025-01-27T15:31:00Z"}
+"#,
+        )
+        .unwrap();
+
+        let id = match db.sessions().import_jsonl(&path).unwrap() {
+            ImportResult::Imported { id, bad_lines } => {
+                assert_eq!(bad_lines, 1);
+                id
+            }
+            other => panic!("unexpected result: {other:?}"),
+        };
+        assert_eq!(
+            id,
+            content_session_id(
+                "Fix the bug",
+                std::path::Path::new("/home/user/proj"),
+                "2025-01-27T15:30:45Z".parse().unwrap()
+            )
+        );
+        assert!(id.starts_with("2025-01-27T15-30-45Z_"));
+
+        let session = db.sessions().get(&id).unwrap().unwrap();
+        assert_eq!(session.prompt, "Fix the bug");
+        assert_eq!(session.outcome, Some(SessionOutcome::Interrupted));
+        assert_eq!(session.started_at.to_rfc3339(), "2025-01-27T15:30:45+00:00");
+        assert_eq!(session.iterations.len(), 1);
+        assert_eq!(session.iterations[0].diff_bytes, Some(4));
+        assert_eq!(
+            session.iterations[0].critic_decision.as_deref(),
+            Some("DONE")
+        );
+        assert_eq!(
+            session.iterations[0].legacy_critic_decision.as_deref(),
+            Some("DONE (confidence: 85%)")
+        );
+        assert_eq!(session.iterations[0].critic_confidence, Some(0.85));
+
+        // Re-importing the same session is skipped
+        assert!(matches!(
+            db.sessions().import_jsonl(&path).unwrap(),
+            ImportResult::Skipped(_)
+        ));
+
+        let empty = dir.path().join("empty.jsonl");
+        std::fs::write(&empty, "").unwrap();
+        assert!(matches!(
+            db.sessions().import_jsonl(&empty).unwrap(),
+            ImportResult::Skipped(_)
+        ));
+    }
+
+    #[test]
+    fn test_import_ids_are_content_derived() {
+        let db = Database::open_in_memory().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, working_dir: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(
+                &path,
+                format!(
+                    r#"{{"type":"session_start","timestamp":"2025-01-27T15:30:45Z","prompt":"Fix the bug","working_dir":"{working_dir}","actor_agent":"Claude Code","critic_agent":"Claude Code","actor_model":null,"critic_model":null,"max_iterations":null}}"#
+                ),
+            )
+            .unwrap();
+            path
+        };
+        let import = |path: &std::path::Path| db.sessions().import_jsonl(path).unwrap();
+
+        let imported_id = |result: ImportResult| match result {
+            ImportResult::Imported { id, .. } => id,
+            other => panic!("unexpected result: {other:?}"),
+        };
+
+        // Same content under another file name is the same session
+        let first = imported_id(import(&write("a.jsonl", "/work/api")));
+        assert!(matches!(
+            import(&write("copy-of-a.jsonl", "/work/api")),
+            ImportResult::Skipped(_)
+        ));
+
+        // Same prompt and start time in another directory is not
+        let other = imported_id(import(&write("b.jsonl", "/work/web")));
+        assert_ne!(first, other);
+
+        // Sessions imported under the old prompt-only ID are recognized
+        db.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE sessions SET id = '2025-01-27T15-30-45Z_' || substr(?1, 1, 6) \
+                 WHERE working_dir = '/work/api'",
+                [content_hash("Fix the bug")],
+            )
+            .unwrap();
+        assert!(matches!(
+            import(&write("a-again.jsonl", "/work/api")),
+            ImportResult::Skipped(_)
+        ));
+        // ... but only in the directory they were recorded in
+        imported_id(import(&write("c.jsonl", "/work/cli")));
+        assert_eq!(
+            db.sessions().list(&SessionFilter::default()).unwrap().len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_import_gzipped_jsonl() {
+        use std::io::Write;
+
+        let db = Database::open_in_memory().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder
+            .write_all(
+                br#"{"type":"session_start","timestamp":"2025-01-27T15:30:45Z","prompt":"Zipped","working_dir":"/home/user/proj","actor_agent":"Claude Code","critic_agent":"Claude Code","actor_model":null,"critic_model":null,"max_iterations":null}
+{"type":"session_end","outcome":"success","iterations":0,"summary":null,"confidence":null,"duration_secs":1.0,"timestamp":"2025-01-27T15:31:00Z"}
+"#,
+            )
+            .unwrap();
+        encoder.finish().unwrap();
+
+        assert!(is_session_file(&path));
+        assert!(!is_session_file(&dir.path().join("notes.gz")));
+        let id = match db.sessions().import_jsonl(&path).unwrap() {
+            ImportResult::Imported { id, bad_lines } => {
+                assert_eq!(bad_lines, 0);
+                id
+            }
+            other => panic!("unexpected result: {other:?}"),
+        };
+        let session = db.sessions().get(&id).unwrap().unwrap();
+        assert_eq!(session.prompt, "Zipped");
+        assert_eq!(session.outcome, Some(SessionOutcome::Success));
+    }
+
+    #[test]
+    fn test_windows_sessions() {
+        let db = Database::open_in_memory().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("windows.jsonl");
+        std::fs::write(
+            &path,
+            "\u{feff}{\"type\":\"session_start\",\"timestamp\":\"2025-01-27T15:30:45Z\",\
+             \"prompt\":\"Fix it\",\"working_dir\":\"C:\\\\Users\\\\me\\\\project\",\
+             \"actor_agent\":\"Claude Code\",\"critic_agent\":\"Claude Code\",\
+             \"actor_model\":null,\"critic_model\":null,\"max_iterations\":null}\r\n\
+             {\"type\":\"session_end\",\"outcome\":\"success\",\"iterations\":0,\
+             \"summary\":null,\"confidence\":null,\"duration_secs\":1.0,\
+             \"timestamp\":\"2025-01-27T15:31:00Z\"}\r\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            db.sessions().import_jsonl(&path).unwrap(),
+            ImportResult::Imported { bad_lines: 0, .. }
+        ));
+
+        db.sessions()
+            .create(&SessionStart {
+                prompt: "Other".to_string(),
+                working_dir: std::path::PathBuf::from(r"D:\work\api"),
+                actor_agent: "Actor".to_string(),
+                critic_agent: "Critic".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(
+            db.sessions().list_projects().unwrap(),
+            vec!["api".to_string(), "project".to_string()]
+        );
+
+        let filter = SessionFilter {
+            project: Some("project".to_string()),
+            ..SessionFilter::default()
+        };
+        let summaries = db.sessions().list(&filter).unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].project, "project");
+        assert_eq!(summaries[0].outcome, Some(SessionOutcome::Success));
+    }
+
+    #[test]
+    fn test_change_marker_moves_on_writes_from_any_connection() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("codeloops.db");
+        let api = Database::open_at(&path).unwrap();
+        let run = Database::open_at(&path).unwrap();
+        let start = |prompt: &str| SessionStart {
+            prompt: prompt.to_string(),
+            working_dir: std::path::PathBuf::from("/project"),
+            actor_agent: "Actor".to_string(),
+            critic_agent: "Critic".to_string(),
+            ..Default::default()
+        };
+
+        let before = api.change_marker().unwrap();
+        api.sessions()
+            .stats(&SessionFilter::default(), StatsTimeZone::Utc)
+            .unwrap();
+        assert_eq!(api.change_marker().unwrap(), before);
+
+        // Written through another connection, as by a running loop
+        let id = run.sessions().create(&start("p")).unwrap();
+        let after_other = api.change_marker().unwrap();
+        assert_ne!(after_other, before);
+
+        api.sessions().set_notes(&id, Some("n")).unwrap();
+        assert_ne!(api.change_marker().unwrap(), after_other);
+    }
+
+    #[test]
+    fn test_fresh_db_at_latest_schema_version() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(
+            db.schema_version().unwrap(),
+            Database::latest_schema_version()
+        );
+    }
+
+    #[test]
+    fn test_concurrent_opens_apply_each_migration_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("codeloops.db");
+
+        let openers: Vec<_> = (0..4)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || Database::open_at(&path).map(|_| ()))
+            })
+            .collect();
+        for opener in openers {
+            opener.join().unwrap().unwrap();
+        }
+
+        let db = Database::open_at(&path).unwrap();
+        let applied: u32 = db
+            .conn
+            .lock()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(applied, Database::latest_schema_version());
+    }
+
+    #[test]
+    fn test_migrates_pre_versioned_db() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("old.db");
+
+        // Fixture: the original schema, with NOT NULL iteration fields, no
+        // `phase` column, and no `schema_version` table.
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                r#"
+                CREATE TABLE sessions (
+                    id TEXT PRIMARY KEY,
+                    prompt TEXT NOT NULL,
+                    working_dir TEXT NOT NULL,
+                    actor_agent TEXT NOT NULL,
+                    critic_agent TEXT NOT NULL,
+                    actor_model TEXT,
+                    critic_model TEXT,
+                    max_iterations INTEGER,
+                    outcome TEXT,
+                    iteration_count INTEGER,
+                    summary TEXT,
+                    confidence REAL,
+                    duration_secs REAL,
+                    started_at TEXT NOT NULL,
+                    ended_at TEXT
+                );
+
+                CREATE TABLE iterations (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                    iteration_number INTEGER NOT NULL,
+                    actor_output TEXT NOT NULL,
+                    actor_stderr TEXT NOT NULL,
+                    actor_exit_code INTEGER NOT NULL,
+                    actor_duration_secs REAL NOT NULL,
+                    git_diff TEXT NOT NULL,
+                    git_files_changed INTEGER NOT NULL,
+                    critic_decision TEXT NOT NULL,
+                    feedback TEXT,
+                    timestamp TEXT NOT NULL,
+                    UNIQUE(session_id, iteration_number)
+                );
+
+                INSERT INTO sessions (id, prompt, working_dir, actor_agent, critic_agent, started_at)
+                VALUES ('old-session', 'Fix the bug', '/tmp/project', 'claude', 'claude',
+                        '2024-01-01T00:00:00+00:00');
+
+                INSERT INTO iterations (
+                    session_id, iteration_number, actor_output, actor_stderr, actor_exit_code,
+                    actor_duration_secs, git_diff, git_files_changed, critic_decision, timestamp
+                )
+                VALUES ('old-session', 0, 'more', '', 0, 1.5, 'diff', 1,
+                        'CONTINUE (3 issues)', '2024-01-01T00:00:01+00:00'),
+                       ('old-session', 1, 'done', '', 0, 1.5, 'diff', 1,
+                        'DONE (confidence: 90%)', '2024-01-01T00:00:02+00:00');
+                "#,
+            )
+            .unwrap();
+        }
+
+        let db = Database::open_at(&path).unwrap();
+        assert_eq!(
+            db.schema_version().unwrap(),
+            Database::latest_schema_version()
+        );
+
+        let session = db.sessions().get("old-session").unwrap().unwrap();
+        assert_eq!(session.iterations.len(), 2);
+        assert_eq!(session.iterations[1].actor_output.as_deref(), Some("done"));
+
+        // The kind is stored bare; what older builds wrote is kept alongside
+        let decisions: Vec<_> = session
+            .iterations
+            .iter()
+            .map(|i| {
+                (
+                    i.critic_decision.as_deref(),
+                    i.legacy_critic_decision.as_deref(),
+                    i.critic_confidence,
+                )
+            })
+            .collect();
+        assert_eq!(
+            decisions,
+            [
+                (Some("CONTINUE"), Some("CONTINUE (3 issues)"), None),
+                (Some("DONE"), Some("DONE (confidence: 90%)"), Some(0.9)),
+            ]
+        );
+
+        // Tables missing from the old schema were created.
+        assert!(db.projects().list().unwrap().is_empty());
+        assert!(db
+            .prompts()
+            .list(&PromptFilter::default())
+            .unwrap()
+            .is_empty());
+
+        // Reopening is a no-op.
+        drop(db);
+        let db = Database::open_at(&path).unwrap();
+        assert_eq!(
+            db.schema_version().unwrap(),
+            Database::latest_schema_version()
+        );
+    }
+
+    #[test]
+    fn test_query_readonly_select() {
+        let db = Database::open_in_memory().unwrap();
+        let start = SessionStart {
+            prompt: "Query me".to_string(),
+            working_dir: PathBuf::from("/tmp"),
+            actor_agent: "claude".to_string(),
+            critic_agent: "claude".to_string(),
+            ..Default::default()
+        };
+        db.sessions().create(&start).unwrap();
+
+        let result = db
+            .query_readonly("SELECT prompt, max_iterations FROM sessions")
+            .unwrap();
+        assert_eq!(result.columns, vec!["prompt", "max_iterations"]);
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0][0], serde_json::json!("Query me"));
+        assert_eq!(result.rows[0][1], serde_json::Value::Null);
+
+        let cte = db
+            .query_readonly("WITH c AS (SELECT COUNT(*) AS n FROM sessions) SELECT n FROM c")
+            .unwrap();
+        assert_eq!(cte.rows[0][0], serde_json::json!(1));
+    }
+
+    #[test]
+    fn test_query_readonly_refuses_writes() {
+        let db = Database::open_in_memory().unwrap();
+        for sql in [
+            "DELETE FROM sessions",
+            "UPDATE sessions SET prompt = 'x'",
+            "DROP TABLE sessions",
+            "  insert into projects (id) values ('x')",
+        ] {
+            assert!(
+                matches!(db.query_readonly(sql), Err(rusqlite::Error::InvalidQuery)),
+                "{} should be refused",
+                sql
+            );
+        }
+
+        // A write hidden behind a CTE is caught by the read-only check.
+        assert!(db
+            .query_readonly("WITH x AS (SELECT 1) DELETE FROM sessions")
+            .is_err());
+
+        // The connection is writable again afterwards.
+        let start = SessionStart {
+            prompt: "After query".to_string(),
+            working_dir: PathBuf::from("/tmp"),
+            actor_agent: "claude".to_string(),
+            critic_agent: "claude".to_string(),
+            ..Default::default()
+        };
+        assert!(db.sessions().create(&start).is_ok());
+    }
+
+    fn prompt(id: &str, project: &str, content: &str, updated_secs_ago: i64) -> PromptRecord {
+        let at = Utc::now() - chrono::Duration::seconds(updated_secs_ago);
+        PromptRecord {
+            id: id.to_string(),
+            title: Some(id.to_string()),
+            work_type: "feature".to_string(),
+            project_path: format!("/path/to/{}", project),
+            project_name: project.to_string(),
+            content: Some(content.to_string()),
+            session_state: "{}".to_string(),
+            created_at: at,
+            updated_at: at,
+        }
+    }
+
+    #[test]
+    fn test_content_hash_ignores_whitespace() {
+        assert_eq!(
+            content_hash("# Task\n\nDo  the thing.\n"),
+            content_hash("# Task\r\nDo the thing.")
+        );
+        assert_ne!(content_hash("# Task"), content_hash("# Other"));
+        assert_eq!(content_hash("").len(), 16);
+    }
+
+    #[test]
+    fn test_save_with_dedup() {
+        let db = Database::open_in_memory().unwrap();
+        db.prompts()
+            .save(&prompt("first", "proj", "# Same\nBody", 60))
+            .unwrap();
+
+        // Without dedup a second record is created.
+        let id = db
+            .prompts()
+            .save_with_dedup(&prompt("second", "proj", "# Same  \nBody", 0), false)
+            .unwrap();
+        assert_eq!(id, "second");
+
+        // With dedup the newest matching record is updated instead.
+        let id = db
+            .prompts()
+            .save_with_dedup(&prompt("third", "proj", "# Same Body", 0), true)
+            .unwrap();
+        assert_eq!(id, "second");
+        assert!(db.prompts().get("third").unwrap().is_none());
+        assert_eq!(
+            db.prompts()
+                .get("second")
+                .unwrap()
+                .unwrap()
+                .title
+                .as_deref(),
+            Some("third")
+        );
+
+        // Other projects are not considered duplicates.
+        let id = db
+            .prompts()
+            .save_with_dedup(&prompt("elsewhere", "other", "# Same Body", 0), true)
+            .unwrap();
+        assert_eq!(id, "elsewhere");
+    }
+
+    #[test]
+    fn test_concurrent_prompt_updates_keep_both_changes() {
+        let db = std::sync::Arc::new(Database::open_in_memory().unwrap());
+        db.prompts().save(&prompt("p", "proj", "# P", 0)).unwrap();
+
+        let writers: Vec<_> = (0..8)
+            .map(|n| {
+                let db = db.clone();
+                std::thread::spawn(move || {
+                    db.prompts()
+                        .update("p", |record| {
+                            std::thread::yield_now();
+                            record.session_state.push_str(&format!("[{}]", n));
+                            Ok::<_, rusqlite::Error>(())
+                        })
+                        .unwrap()
+                })
+            })
+            .collect();
+        for w in writers {
+            assert!(w.join().unwrap().is_some());
+        }
+
+        let state = db.prompts().get("p").unwrap().unwrap().session_state;
+        for n in 0..8 {
+            assert!(state.contains(&format!("[{}]", n)), "lost update {}", n);
+        }
+        assert!(db
+            .prompts()
+            .update("missing", |_| Ok::<_, rusqlite::Error>(()))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_save_checked_rejects_stale_writes() {
+        let db = Database::open_in_memory().unwrap();
+        let original = prompt("p", "proj", "# Original", 60);
+        db.prompts().save(&original).unwrap();
+        let base = db.prompts().get("p").unwrap().unwrap().updated_at;
+
+        // Someone else writes first.
+        db.prompts()
+            .update("p", |r| {
+                r.content = Some("# Theirs".to_string());
+                Ok::<_, rusqlite::Error>(())
+            })
+            .unwrap();
+
+        let mine = PromptRecord {
+            content: Some("# Mine".to_string()),
+            updated_at: Utc::now(),
+            ..original
+        };
+        assert_eq!(
+            db.prompts().save_checked(&mine, Some(base), false).unwrap(),
+            None
+        );
+        assert_eq!(
+            db.prompts().get("p").unwrap().unwrap().content.as_deref(),
+            Some("# Theirs")
+        );
+
+        // Without an expectation the last writer wins.
+        assert_eq!(
+            db.prompts().save_checked(&mine, None, false).unwrap(),
+            Some("p".to_string())
+        );
+        assert_eq!(
+            db.prompts().get("p").unwrap().unwrap().content.as_deref(),
+            Some("# Mine")
+        );
+    }
+
+    #[test]
+    fn test_find_and_merge_duplicates() {
+        let db = Database::open_in_memory().unwrap();
+        db.prompts()
+            .save(&prompt("old", "proj", "# Dup", 120))
+            .unwrap();
+        db.prompts()
+            .save(&prompt("new", "proj", "# Dup", 0))
+            .unwrap();
+        db.prompts()
+            .save(&prompt("child", "proj", "# Child", 0))
+            .unwrap();
+        db.prompts()
+            .save(&prompt("unique", "proj", "# Unique", 0))
+            .unwrap();
+        db.prompts()
+            .set_parent_ids("child", &["old".to_string()])
+            .unwrap();
+
+        let groups = db.prompts().find_duplicates().unwrap();
+        assert_eq!(groups.len(), 1);
+        let ids: Vec<&str> = groups[0].records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["new", "old"]);
+
+        let deleted = db
+            .prompts()
+            .merge("new", &["new".to_string(), "old".to_string()])
+            .unwrap();
+        assert_eq!(deleted, 1);
+        assert!(db.prompts().get("old").unwrap().is_none());
+        assert_eq!(db.prompts().get_parent_ids("child").unwrap(), vec!["new"]);
+        assert!(db.prompts().find_duplicates().unwrap().is_empty());
+    }
 }
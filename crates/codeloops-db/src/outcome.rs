@@ -0,0 +1,145 @@
+//! Typed session outcomes.
+//!
+//! Outcomes are stored as text in the `sessions.outcome` column. Parsing
+//! goes through [`SessionOutcome`] so filters and metrics cannot drift from
+//! what the loop runner writes.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+
+/// How a finished session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionOutcome {
+    Success,
+    Failed,
+    /// Stopped by the user (Ctrl+C). Older builds wrote `user_interrupted`.
+    #[serde(alias = "user_interrupted")]
+    Interrupted,
+    MaxIterationsReached,
+    /// Stopped because critic confidence stopped rising
+    NoProgress,
+    /// Stopped before an iteration that would have exceeded the cost budget
+    CostBudgetExceeded,
+}
+
+impl SessionOutcome {
+    pub const ALL: [SessionOutcome; 6] = [
+        SessionOutcome::Success,
+        SessionOutcome::Failed,
+        SessionOutcome::Interrupted,
+        SessionOutcome::MaxIterationsReached,
+        SessionOutcome::NoProgress,
+        SessionOutcome::CostBudgetExceeded,
+    ];
+
+    /// The canonical string stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionOutcome::Success => "success",
+            SessionOutcome::Failed => "failed",
+            SessionOutcome::Interrupted => "interrupted",
+            SessionOutcome::MaxIterationsReached => "max_iterations_reached",
+            SessionOutcome::NoProgress => "no_progress",
+            SessionOutcome::CostBudgetExceeded => "cost_budget_exceeded",
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, SessionOutcome::Success)
+    }
+
+    /// Outcomes whose iterations produced no accepted result.
+    pub fn is_waste(&self) -> bool {
+        !self.is_success()
+    }
+}
+
+impl fmt::Display for SessionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when a string is not a known outcome.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOutcomeError(pub String);
+
+impl fmt::Display for ParseOutcomeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let valid: Vec<&str> = SessionOutcome::ALL.iter().map(|o| o.as_str()).collect();
+        write!(
+            f,
+            "unknown outcome '{}' (expected one of: {})",
+            self.0,
+            valid.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ParseOutcomeError {}
+
+impl FromStr for SessionOutcome {
+    type Err = ParseOutcomeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "success" => Ok(SessionOutcome::Success),
+            "failed" => Ok(SessionOutcome::Failed),
+            "interrupted" | "user_interrupted" => Ok(SessionOutcome::Interrupted),
+            "max_iterations_reached" => Ok(SessionOutcome::MaxIterationsReached),
+            "no_progress" => Ok(SessionOutcome::NoProgress),
+            "cost_budget_exceeded" => Ok(SessionOutcome::CostBudgetExceeded),
+            other => Err(ParseOutcomeError(other.to_string())),
+        }
+    }
+}
+
+impl ToSql for SessionOutcome {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.as_str()))
+    }
+}
+
+impl FromSql for SessionOutcome {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_str_and_serde() {
+        for outcome in SessionOutcome::ALL {
+            assert_eq!(outcome.as_str().parse::<SessionOutcome>(), Ok(outcome));
+            let json = serde_json::to_string(&outcome).unwrap();
+            assert_eq!(json, format!("\"{}\"", outcome));
+            assert_eq!(
+                serde_json::from_str::<SessionOutcome>(&json).unwrap(),
+                outcome
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_legacy_user_interrupted() {
+        assert_eq!(
+            "user_interrupted".parse::<SessionOutcome>(),
+            Ok(SessionOutcome::Interrupted)
+        );
+        assert_eq!(
+            serde_json::from_str::<SessionOutcome>("\"user_interrupted\"").unwrap(),
+            SessionOutcome::Interrupted
+        );
+        assert!("sucess".parse::<SessionOutcome>().is_err());
+    }
+}
@@ -0,0 +1,192 @@
+//! Filesystem locations for codeloops data.
+//!
+//! Everything lives under a single data root (see [`data_dir`]) so one
+//! environment variable can relocate the database and session output.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the data root.
+pub const DATA_DIR_ENV: &str = "CODELOOPS_DATA_DIR";
+
+/// Environment variable overriding the sessions directory, where runs write
+/// their live output.
+pub const SESSIONS_DIR_ENV: &str = "CODELOOPS_SESSIONS_DIR";
+
+/// Environment variable overriding the directory of pre-SQLite JSONL
+/// session files the migration script imports.
+pub const LEGACY_SESSIONS_DIR_ENV: &str = "CODELOOPS_LEGACY_SESSIONS_DIR";
+
+/// Root directory for all codeloops data.
+///
+/// Precedence: `CODELOOPS_DATA_DIR` > `~/.local/share/codeloops`.
+pub fn data_dir() -> PathBuf {
+    resolve_dir(None, std::env::var_os(DATA_DIR_ENV), || {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("codeloops")
+    })
+}
+
+/// Directory of pre-SQLite JSONL session files, read by the migration script.
+///
+/// Precedence: explicit override > `CODELOOPS_LEGACY_SESSIONS_DIR` >
+/// `<data dir>/sessions`.
+pub fn legacy_sessions_dir(override_dir: Option<&Path>) -> PathBuf {
+    resolve_dir(
+        override_dir,
+        std::env::var_os(LEGACY_SESSIONS_DIR_ENV),
+        || data_dir().join("sessions"),
+    )
+}
+
+/// Resolve the sessions directory.
+///
+/// Precedence: explicit override (e.g. `--sessions-dir`) > `CODELOOPS_SESSIONS_DIR`
+/// > `<data dir>/output`.
+pub fn sessions_dir(override_dir: Option<&Path>) -> PathBuf {
+    resolve_dir(
+        override_dir,
+        std::env::var_os(SESSIONS_DIR_ENV),
+        default_sessions_dir,
+    )
+}
+
+/// `override_dir`, else the value of an environment variable unless it is
+/// empty, else `default`.
+fn resolve_dir(
+    override_dir: Option<&Path>,
+    env: Option<OsString>,
+    default: impl FnOnce() -> PathBuf,
+) -> PathBuf {
+    match (override_dir, env) {
+        (Some(dir), _) => dir.to_path_buf(),
+        (None, Some(dir)) if !dir.is_empty() => PathBuf::from(dir),
+        _ => default(),
+    }
+}
+
+/// The default sessions directory, ignoring `CODELOOPS_SESSIONS_DIR`.
+pub fn default_sessions_dir() -> PathBuf {
+    data_dir().join("output")
+}
+
+/// Directory of run lock files, one per working directory being looped on.
+/// Kept out of the working tree so a lock never shows up in its diff.
+pub fn locks_dir() -> PathBuf {
+    data_dir().join("locks")
+}
+
+/// Directory holding a single session's live output files.
+pub fn session_output_dir(sessions_dir: &Path, session_id: &str) -> PathBuf {
+    sessions_dir.join(session_id)
+}
+
+/// File an agent's output is copied to as it streams, e.g.
+/// `iter_2_actor.stdout`. `phase` is `actor` or `critic`, and `stream` is
+/// `stdout` or `stderr`.
+pub fn live_output_path(
+    sessions_dir: &Path,
+    session_id: &str,
+    iteration: usize,
+    phase: &str,
+    stream: &str,
+) -> PathBuf {
+    session_output_dir(sessions_dir, session_id)
+        .join(format!("iter_{}_{}.{}", iteration, phase, stream))
+}
+
+/// The actor stdout streamed for an iteration, if its file exists. Used to
+/// recover the output of an iteration that died before it was saved.
+pub fn read_actor_stdout(
+    sessions_dir: &Path,
+    session_id: &str,
+    iteration: usize,
+) -> Option<String> {
+    std::fs::read_to_string(live_output_path(
+        sessions_dir,
+        session_id,
+        iteration,
+        "actor",
+        "stdout",
+    ))
+    .ok()
+}
+
+/// List the IDs of sessions that have output in `sessions_dir`.
+pub fn list_session_ids(sessions_dir: &Path) -> std::io::Result<Vec<String>> {
+    let mut ids: Vec<String> = std::fs::read_dir(sessions_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(String::from))
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+/// Project name for a session's working directory: its last path component.
+///
+/// Sessions may have been recorded on another OS, so both `/` and `\` are
+/// treated as separators whatever platform reads them. Trailing separators
+/// are ignored.
+pub fn project_name(working_dir: &str) -> Option<&str> {
+    working_dir
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_name_handles_both_separators() {
+        assert_eq!(project_name("/home/me/project"), Some("project"));
+        assert_eq!(project_name(r"C:\Users\me\project"), Some("project"));
+        assert_eq!(project_name(r"C:\Users\me\project\"), Some("project"));
+        assert_eq!(project_name("C:/Users/me/project/"), Some("project"));
+        assert_eq!(project_name("project"), Some("project"));
+        assert_eq!(project_name("/"), None);
+    }
+
+    #[test]
+    fn override_then_env_then_default() {
+        let flag = PathBuf::from("/mnt/flag");
+        let default = || PathBuf::from("/srv/codeloops/output");
+        let env = || Some(OsString::from("/mnt/env"));
+        assert_eq!(resolve_dir(Some(&flag), env(), default), flag);
+        assert_eq!(resolve_dir(None, env(), default), PathBuf::from("/mnt/env"));
+        assert_eq!(resolve_dir(None, Some(OsString::new()), default), default());
+        assert_eq!(resolve_dir(None, None, default), default());
+    }
+
+    #[test]
+    fn custom_dir_used_for_writing_and_listing() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = sessions_dir(Some(tmp.path()));
+
+        let out = session_output_dir(&dir, "abc-123");
+        std::fs::create_dir_all(&out).unwrap();
+        std::fs::write(out.join("iter_0_actor.stdout"), "hello\n").unwrap();
+
+        assert!(tmp.path().join("abc-123/iter_0_actor.stdout").exists());
+        assert_eq!(list_session_ids(&dir).unwrap(), vec!["abc-123".to_string()]);
+    }
+
+    #[test]
+    fn actor_stdout_round_trip() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        assert!(read_actor_stdout(tmp.path(), "s1", 2).is_none());
+
+        let path = live_output_path(tmp.path(), "s1", 2, "actor", "stdout");
+        assert_eq!(path, tmp.path().join("s1/iter_2_actor.stdout"));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "line one\nline two\n").unwrap();
+        assert_eq!(
+            read_actor_stdout(tmp.path(), "s1", 2).as_deref(),
+            Some("line one\nline two\n")
+        );
+    }
+}
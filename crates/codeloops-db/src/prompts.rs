@@ -1,7 +1,7 @@
 //! Prompts store for persistent prompt storage.
 
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::sync::MutexGuard;
@@ -30,6 +30,31 @@ pub struct PromptFilter {
     pub offset: Option<usize>,
 }
 
+/// Prompts sharing the same project and normalized content.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub project_name: String,
+    pub content_hash: String,
+    /// Most recently updated first; the first record is the one to keep
+    pub records: Vec<PromptRecord>,
+}
+
+/// Stable hash of prompt content, ignoring whitespace differences.
+///
+/// FNV-1a (64-bit) over the whitespace-collapsed text, hex encoded. Stable
+/// across builds, which matters because the value is persisted.
+pub fn content_hash(content: &str) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let normalized = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    let hash = normalized
+        .bytes()
+        .fold(OFFSET, |h, b| (h ^ b as u64).wrapping_mul(PRIME));
+    format!("{:016x}", hash)
+}
+
 /// Prompts store with a borrowed connection.
 pub struct Prompts<'db> {
     conn: MutexGuard<'db, Connection>,
@@ -45,8 +70,8 @@ impl<'db> Prompts<'db> {
     pub fn save(&self, record: &PromptRecord) -> Result<(), rusqlite::Error> {
         self.conn.execute(
             r#"
-            INSERT INTO prompts (id, title, work_type, project_path, project_name, content, session_state, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            INSERT INTO prompts (id, title, work_type, project_path, project_name, content, session_state, created_at, updated_at, content_hash)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
             ON CONFLICT(id) DO UPDATE SET
                 title = excluded.title,
                 work_type = excluded.work_type,
@@ -54,7 +79,8 @@ impl<'db> Prompts<'db> {
                 project_name = excluded.project_name,
                 content = excluded.content,
                 session_state = excluded.session_state,
-                updated_at = excluded.updated_at
+                updated_at = excluded.updated_at,
+                content_hash = excluded.content_hash
             "#,
             params![
                 record.id,
@@ -66,12 +92,156 @@ impl<'db> Prompts<'db> {
                 record.session_state,
                 record.created_at.to_rfc3339(),
                 record.updated_at.to_rfc3339(),
+                record.content.as_deref().map(content_hash),
             ],
         )?;
 
         Ok(())
     }
 
+    /// Save a prompt, optionally folding it into an identical existing prompt.
+    ///
+    /// With `dedup`, if another prompt in the same project has the same
+    /// normalized content, that record is updated (keeping its id and creation
+    /// time) instead of inserting a new one. Returns the id the prompt was
+    /// saved under.
+    pub fn save_with_dedup(
+        &self,
+        record: &PromptRecord,
+        dedup: bool,
+    ) -> Result<String, rusqlite::Error> {
+        if dedup {
+            if let Some(ref content) = record.content {
+                let existing =
+                    self.find_by_content_hash(&record.project_name, &content_hash(content))?;
+                if let Some(existing) = existing.filter(|e| e.id != record.id) {
+                    let merged = PromptRecord {
+                        id: existing.id.clone(),
+                        created_at: existing.created_at,
+                        ..record.clone()
+                    };
+                    self.save(&merged)?;
+                    return Ok(existing.id);
+                }
+            }
+        }
+        self.save(record)?;
+        Ok(record.id.clone())
+    }
+
+    /// Save a prompt unless it changed since the caller last read it.
+    ///
+    /// `expected_updated_at` is the `updated_at` the caller's copy was based
+    /// on. If the stored prompt has a different one, nothing is written and
+    /// `Ok(None)` is returned so the caller can reload and retry. `None`
+    /// skips the check (last writer wins). The check and write share one
+    /// transaction. Otherwise behaves like [`Self::save_with_dedup`].
+    pub fn save_checked(
+        &self,
+        record: &PromptRecord,
+        expected_updated_at: Option<DateTime<Utc>>,
+        dedup: bool,
+    ) -> Result<Option<String>, rusqlite::Error> {
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        if let Some(expected) = expected_updated_at {
+            if let Some(current) = self.get(&record.id)? {
+                if current.updated_at != expected {
+                    return Ok(None);
+                }
+            }
+        }
+        let id = self.save_with_dedup(record, dedup)?;
+        tx.commit()?;
+        Ok(Some(id))
+    }
+
+    /// Read-modify-write a prompt atomically.
+    ///
+    /// Loads the prompt, lets `f` change it, bumps `updated_at` and saves, all
+    /// in one transaction so concurrent updates cannot lose each other's
+    /// changes. Returns `Ok(None)` if the prompt does not exist. An error
+    /// from `f` rolls back without writing.
+    pub fn update<T, E, F>(&self, id: &str, f: F) -> Result<Option<T>, E>
+    where
+        F: FnOnce(&mut PromptRecord) -> Result<T, E>,
+        E: From<rusqlite::Error>,
+    {
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let Some(mut record) = self.get(id)? else {
+            return Ok(None);
+        };
+        let value = f(&mut record)?;
+        record.updated_at = Utc::now();
+        self.save(&record)?;
+        tx.commit()?;
+        Ok(Some(value))
+    }
+
+    /// Find the most recently updated prompt in a project with the given content hash.
+    pub fn find_by_content_hash(
+        &self,
+        project_name: &str,
+        hash: &str,
+    ) -> Result<Option<PromptRecord>, rusqlite::Error> {
+        self.conn
+            .query_row(
+                "SELECT id, title, work_type, project_path, project_name, content, session_state, created_at, updated_at FROM prompts WHERE project_name = ?1 AND content_hash = ?2 ORDER BY updated_at DESC LIMIT 1",
+                params![project_name, hash],
+                Self::row_to_record,
+            )
+            .optional()
+    }
+
+    /// Find groups of prompts with identical content within a project.
+    pub fn find_duplicates(&self) -> Result<Vec<DuplicateGroup>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT project_name, content_hash FROM prompts WHERE content_hash IS NOT NULL GROUP BY project_name, content_hash HAVING COUNT(*) > 1 ORDER BY project_name",
+        )?;
+        let keys = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, work_type, project_path, project_name, content, session_state, created_at, updated_at FROM prompts WHERE project_name = ?1 AND content_hash = ?2 ORDER BY updated_at DESC",
+        )?;
+        let mut groups = Vec::new();
+        for (project_name, content_hash) in keys {
+            let records = stmt
+                .query_map(params![project_name, content_hash], Self::row_to_record)?
+                .collect::<Result<Vec<_>, _>>()?;
+            groups.push(DuplicateGroup {
+                project_name,
+                content_hash,
+                records,
+            });
+        }
+        Ok(groups)
+    }
+
+    /// Fold `duplicate_ids` into `keep_id` and delete them.
+    ///
+    /// Prompts that inherited from a removed duplicate inherit from the kept
+    /// prompt instead. Returns the number of prompts deleted.
+    pub fn merge(&self, keep_id: &str, duplicate_ids: &[String]) -> Result<usize, rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut deleted = 0;
+        for id in duplicate_ids.iter().filter(|id| id.as_str() != keep_id) {
+            tx.execute(
+                "UPDATE OR IGNORE prompt_parents SET parent_id = ?1 WHERE parent_id = ?2 AND child_id != ?1",
+                params![keep_id, id],
+            )?;
+            tx.execute(
+                "DELETE FROM prompt_parents WHERE parent_id = ?1 OR child_id = ?1",
+                params![id],
+            )?;
+            deleted += tx.execute("DELETE FROM prompts WHERE id = ?1", params![id])?;
+        }
+        tx.commit()?;
+        Ok(deleted)
+    }
+
     /// Get a prompt by ID.
     pub fn get(&self, id: &str) -> Result<Option<PromptRecord>, rusqlite::Error> {
         self.conn
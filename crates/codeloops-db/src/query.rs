@@ -0,0 +1,67 @@
+//! Read-only ad-hoc SQL queries for power users.
+
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// Columns and rows returned by an ad-hoc query.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// Run a single read-only statement.
+///
+/// Anything other than a `SELECT` (or a `WITH ... SELECT`) is rejected with
+/// [`rusqlite::Error::InvalidQuery`] before it runs. As a second line of
+/// defence the statement executes with `PRAGMA query_only` enabled.
+pub(crate) fn run_readonly(conn: &Connection, sql: &str) -> Result<QueryResult, rusqlite::Error> {
+    if !is_select(sql) {
+        return Err(rusqlite::Error::InvalidQuery);
+    }
+
+    conn.pragma_update(None, "query_only", true)?;
+    let result = collect_rows(conn, sql);
+    conn.pragma_update(None, "query_only", false)?;
+    result
+}
+
+fn collect_rows(conn: &Connection, sql: &str) -> Result<QueryResult, rusqlite::Error> {
+    let mut stmt = conn.prepare(sql)?;
+    if !stmt.readonly() {
+        return Err(rusqlite::Error::InvalidQuery);
+    }
+
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let column_count = columns.len();
+    let rows = stmt
+        .query_map([], |row| {
+            (0..column_count)
+                .map(|i| row.get_ref(i).map(to_json))
+                .collect::<Result<Vec<_>, _>>()
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(QueryResult { columns, rows })
+}
+
+fn is_select(sql: &str) -> bool {
+    let first = sql
+        .trim_start()
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .next()
+        .unwrap_or("")
+        .to_ascii_uppercase();
+    first == "SELECT" || first == "WITH"
+}
+
+fn to_json(value: ValueRef<'_>) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned().into(),
+        ValueRef::Blob(b) => format!("<{} bytes>", b.len()).into(),
+    }
+}
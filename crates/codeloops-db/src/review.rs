@@ -0,0 +1,102 @@
+//! What a critic reported about an iteration, in the shape sessions store.
+//!
+//! The critic produces these and the sessions store persists them, so they
+//! live here rather than in `codeloops-critic`, keeping the database layer
+//! free of the agent runtime.
+//!
+//! [`ScopeAdherence`] compares the files an iteration changed with the files
+//! the prompt said to modify. Prompts from the interviewer list the files to
+//! modify. Changes outside them are often scope creep, and listed files left
+//! alone are often unfinished work, so both are pointed out to the critic.
+
+use serde::{Deserialize, Serialize};
+
+/// One acceptance criterion (or rubric criterion) and whether the critic
+/// judged it met.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub item: String,
+    pub met: bool,
+}
+
+/// How the critic judged one rubric criterion on an iteration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RubricResult {
+    /// Stored as `item`, like the acceptance criteria in a checklist
+    #[serde(rename = "item")]
+    pub criterion: String,
+    /// Whether `done` requires the criterion to be met
+    pub must_pass: bool,
+    /// `None` when the critic did not report the criterion
+    pub met: Option<bool>,
+}
+
+/// How the files a diff touched compare with the files the prompt listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeAdherence {
+    /// Changed files the prompt did not list, in diff order
+    pub unexpected: Vec<String>,
+    /// Listed files (or directories) with no changes, in prompt order
+    pub untouched: Vec<String>,
+}
+
+impl ScopeAdherence {
+    /// Compare the `touched` paths against the `intended` ones. An intended
+    /// path covers itself and, for a directory, everything under it.
+    pub fn check(intended: &[String], touched: &[String]) -> Self {
+        Self {
+            unexpected: touched
+                .iter()
+                .filter(|path| !intended.iter().any(|i| covers(i, path)))
+                .cloned()
+                .collect(),
+            untouched: intended
+                .iter()
+                .filter(|i| !touched.iter().any(|path| covers(i, path)))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Whether the diff changed exactly the listed files.
+    pub fn is_clean(&self) -> bool {
+        self.unexpected.is_empty() && self.untouched.is_empty()
+    }
+}
+
+fn covers(intended: &str, path: &str) -> bool {
+    let intended = intended.trim_end_matches('/');
+    path == intended
+        || path
+            .strip_prefix(intended)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_unexpected_and_untouched_files() {
+        let intended = paths(&["src/limits.rs", "src/api/", "Cargo.toml"]);
+        let touched = paths(&[
+            "src/limits.rs",
+            "src/api/mod.rs",
+            "src/apis.rs",
+            "README.md",
+        ]);
+        let adherence = ScopeAdherence::check(&intended, &touched);
+        assert_eq!(adherence.unexpected, ["src/apis.rs", "README.md"]);
+        assert_eq!(adherence.untouched, ["Cargo.toml"]);
+        assert!(!adherence.is_clean());
+
+        let adherence = ScopeAdherence::check(&intended[..2], &touched[..2]);
+        assert!(adherence.is_clean());
+    }
+}
@@ -8,8 +8,13 @@ use std::path::PathBuf;
 use std::sync::MutexGuard;
 use uuid::Uuid;
 
+use crate::outcome::SessionOutcome;
+use crate::paths;
+use crate::review::{ChecklistItem, RubricResult, ScopeAdherence};
+use crate::time_zone::StatsTimeZone;
+
 /// Data provided when creating a new session.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SessionStart {
     pub prompt: String,
     pub working_dir: PathBuf,
@@ -18,6 +23,18 @@ pub struct SessionStart {
     pub actor_model: Option<String>,
     pub critic_model: Option<String>,
     pub max_iterations: Option<usize>,
+    /// Files the prompt was read from, in order; empty for an inline prompt
+    pub prompt_files: Vec<String>,
+    /// Git branch checked out in the working directory, if any
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Guidance given with `--seed-feedback`, handed to the actor as critic
+    /// feedback before the first iteration
+    #[serde(default)]
+    pub seed_feedback: Option<String>,
+    /// Files given with `--context-file`, in order
+    #[serde(default)]
+    pub context_files: Vec<String>,
 }
 
 /// Data for a single iteration.
@@ -36,20 +53,93 @@ pub struct Iteration {
     pub git_diff: Option<String>,
     pub git_files_changed: Option<usize>,
     pub critic_decision: Option<String>,
+    /// The decision as older builds stored it, with a description after
+    /// the kind (`CONTINUE (3 issues)`); `None` for newer iterations
+    #[serde(default)]
+    pub legacy_critic_decision: Option<String>,
     pub feedback: Option<String>,
+    /// Raw critic response text, kept even when the decision failed to parse.
+    pub critic_raw: Option<String>,
+    /// Confidence the critic gave with its decision, if it gave one
+    #[serde(default)]
+    pub critic_confidence: Option<f64>,
+    /// Acceptance criteria status the critic reported, empty if none
+    #[serde(default)]
+    pub critic_checklist: Vec<ChecklistItem>,
+    /// Rubric criteria status, one entry per criterion, empty without a rubric
+    #[serde(default)]
+    pub critic_rubric: Vec<RubricResult>,
+    /// Changed files against the prompt's files to modify, when it lists any
+    #[serde(default)]
+    pub scope_adherence: Option<ScopeAdherence>,
+    /// How a person ruled on the critic's call in an interactive run:
+    /// `accepted`, `rejected` or `feedback`. The decision and feedback
+    /// stored are the ones that took effect.
+    #[serde(default)]
+    pub human_review: Option<String>,
+    /// The prompt the actor was given, with everything around the task;
+    /// very long prompts are stored without their middle
+    #[serde(default)]
+    pub actor_prompt: Option<String>,
+    /// Whether the actor's output had bytes that were not UTF-8; they are
+    /// stored as U+FFFD
+    #[serde(default)]
+    pub actor_output_lossy: bool,
+    /// Whether a critic reset discarded the iteration's changes afterwards
+    #[serde(default)]
+    pub reset_applied: bool,
+    /// Whether the verify command passed after the iteration, when one ran
+    #[serde(default)]
+    pub verify_passed: Option<bool>,
+    /// Paths of the files `git_diff` touched, in diff order
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Size of `git_diff` in bytes, computed when the diff is written.
+    #[serde(default)]
+    pub diff_bytes: Option<usize>,
+    /// Coverage percentage after the iteration, when a coverage command ran
+    #[serde(default)]
+    pub coverage_percent: Option<f64>,
+    /// Change in coverage against the session baseline, in percentage points
+    #[serde(default)]
+    pub coverage_delta: Option<f64>,
+    /// Whether the diff the actor printed applied, for patch-mode actors
+    #[serde(default)]
+    pub patch_applied: Option<bool>,
     pub timestamp: DateTime<Utc>,
 }
 
+/// The kind of a stored critic decision (`DONE`, `CONTINUE`, `ERROR` or
+/// `RESET`). Older builds stored a description after it, as in
+/// `DONE (confidence: 90%)`.
+pub fn decision_kind(decision: &str) -> &str {
+    decision.split_whitespace().next().unwrap_or(decision)
+}
+
+/// The confidence in a decision description older builds stored, as in
+/// `DONE (confidence: 90%)`.
+pub(crate) fn legacy_confidence(decision: &str) -> Option<f64> {
+    let percent = decision
+        .strip_prefix("DONE (confidence: ")?
+        .strip_suffix("%)")?;
+    percent.parse::<f64>().ok().map(|p| p / 100.0)
+}
+
 /// Data provided when ending a session.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionEnd {
-    pub outcome: String,
+    pub outcome: SessionOutcome,
     pub iterations: usize,
     pub summary: Option<String>,
     pub confidence: Option<f64>,
     pub duration_secs: f64,
 }
 
+/// Version of the JSON shape of [`Session`], as printed by `sessions show
+/// --json`, `sessions export --format json` and the API. Adding a field does
+/// not change it; renaming or removing one does.
+pub const SESSION_FORMAT_VERSION: u32 = 1;
+
 /// A fully loaded session record.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -62,13 +152,32 @@ pub struct Session {
     pub actor_model: Option<String>,
     pub critic_model: Option<String>,
     pub max_iterations: Option<usize>,
-    pub outcome: Option<String>,
+    pub outcome: Option<SessionOutcome>,
     pub iteration_count: Option<usize>,
     pub summary: Option<String>,
     pub confidence: Option<f64>,
     pub duration_secs: Option<f64>,
     pub started_at: DateTime<Utc>,
     pub ended_at: Option<DateTime<Utc>>,
+    /// Files the prompt was read from, in order; empty for an inline prompt
+    #[serde(default)]
+    pub prompt_files: Vec<String>,
+    /// Git branch checked out when the session started
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Feedback the actor started from, given with `--seed-feedback`
+    #[serde(default)]
+    pub seed_feedback: Option<String>,
+    /// Reference files shown to actor and critic, given with `--context-file`
+    #[serde(default)]
+    pub context_files: Vec<String>,
+    /// Notes added after the run with `sessions note` or the web UI
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Whether the verify command passed before the first iteration, when
+    /// one was given
+    #[serde(default)]
+    pub verify_baseline: Option<bool>,
     pub iterations: Vec<Iteration>,
 }
 
@@ -81,22 +190,36 @@ pub struct SessionSummary {
     pub prompt_preview: String,
     pub working_dir: PathBuf,
     pub project: String,
-    pub outcome: Option<String>,
+    pub outcome: Option<SessionOutcome>,
     pub iterations: usize,
     pub duration_secs: Option<f64>,
     pub confidence: Option<f64>,
     pub actor_agent: String,
     pub critic_agent: String,
+    /// Size of the prompt in bytes
+    pub prompt_bytes: Option<usize>,
+    /// Largest single-iteration diff in bytes
+    pub max_diff_bytes: Option<usize>,
+    /// Files changed summed across iterations; set when the session ends
+    #[serde(default)]
+    pub total_files_changed: Option<usize>,
+    /// Git branch checked out when the session started
+    #[serde(default)]
+    pub branch: Option<String>,
 }
 
 /// Filter parameters for listing sessions.
 #[derive(Debug, Default, Clone)]
 pub struct SessionFilter {
-    pub outcome: Option<String>,
+    pub outcome: Option<SessionOutcome>,
     pub after: Option<DateTime<Utc>>,
     pub before: Option<DateTime<Utc>>,
     pub search: Option<String>,
     pub project: Option<String>,
+    /// Inclusive confidence bounds (0.0-1.0). Sessions without a confidence
+    /// are excluded when either bound is set.
+    pub min_confidence: Option<f64>,
+    pub max_confidence: Option<f64>,
 }
 
 /// Aggregate statistics.
@@ -127,6 +250,19 @@ pub struct ProjectStats {
     pub success_rate: f64,
 }
 
+/// Statistics for one branch of a project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBranchStats {
+    pub project: String,
+    /// `None` for sessions recorded without a branch (detached HEAD, not a
+    /// git repository, or recorded before branches were stored)
+    pub branch: Option<String>,
+    pub total: usize,
+    pub successes: usize,
+    pub success_rate: f64,
+}
+
 /// Efficacy metrics for agentic software development (DORA-inspired).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -143,19 +279,44 @@ pub struct AgenticMetrics {
     /// % failed/interrupted/max_iter
     pub waste_rate: f64,
     pub total_iterations: usize,
+    /// Sessions whose iterations `total_iterations`, the critic rates and
+    /// `most_touched_files` come from; the most recent ones when limited
+    #[serde(default)]
+    pub iteration_sessions: usize,
     /// % iterations where critic approved
     pub critic_approval_rate: f64,
     /// Mean feedback chars for rejections
     pub avg_feedback_length: f64,
     /// % rejections where next iteration improved
     pub improvement_rate: f64,
+    /// Mean prompt size in bytes
+    pub avg_prompt_bytes: f64,
+    /// Largest single-iteration diff in bytes
+    pub max_diff_bytes: usize,
+    /// Files changed in the most iterations, most first
+    #[serde(default)]
+    pub most_touched_files: Vec<FileTouches>,
     pub sessions_over_time: Vec<DayCount>,
     pub by_project: Vec<ProjectStats>,
+    /// Like `by_project`, split further by git branch
+    pub by_project_branch: Vec<ProjectBranchStats>,
+}
+
+/// How often one file was changed across sessions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTouches {
+    pub project: String,
+    pub path: String,
+    /// Iterations whose diff touched the file
+    pub iterations: usize,
+    /// Sessions with at least one such iteration
+    pub sessions: usize,
 }
 
 /// Sessions store with a borrowed connection.
 pub struct Sessions<'db> {
-    conn: MutexGuard<'db, Connection>,
+    pub(crate) conn: MutexGuard<'db, Connection>,
 }
 
 impl<'db> Sessions<'db> {
@@ -173,9 +334,10 @@ impl<'db> Sessions<'db> {
             r#"
             INSERT INTO sessions (
                 id, prompt, working_dir, actor_agent, critic_agent,
-                actor_model, critic_model, max_iterations, started_at
+                actor_model, critic_model, max_iterations, started_at, prompt_bytes,
+                prompt_files, branch, seed_feedback, context_files
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
             "#,
             params![
                 id,
@@ -187,6 +349,11 @@ impl<'db> Sessions<'db> {
                 start.critic_model,
                 start.max_iterations.map(|n| n as i64),
                 now.to_rfc3339(),
+                start.prompt.len() as i64,
+                string_list_json(&start.prompt_files),
+                start.branch,
+                start.seed_feedback,
+                string_list_json(&start.context_files),
             ],
         )?;
 
@@ -210,6 +377,23 @@ impl<'db> Sessions<'db> {
         Ok(())
     }
 
+    /// Store the prompt the actor was given for an iteration.
+    pub fn set_actor_prompt(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+        prompt: &str,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET actor_prompt = ?1
+            WHERE session_id = ?2 AND iteration_number = ?3
+            "#,
+            params![prompt, session_id, iteration_number as i64],
+        )?;
+        Ok(())
+    }
+
     /// Update with actor results when actor completes.
     pub fn complete_actor(
         &self,
@@ -242,25 +426,30 @@ impl<'db> Sessions<'db> {
         Ok(())
     }
 
-    /// Update with git diff data.
+    /// Update with git diff data. `files` are the paths the diff touched.
     pub fn complete_diff(
         &self,
         session_id: &str,
         iteration_number: usize,
         diff: &str,
         files_changed: usize,
+        files: &[String],
     ) -> Result<(), rusqlite::Error> {
         self.conn.execute(
             r#"
             UPDATE iterations SET
                 phase = 'diff_captured',
                 git_diff = ?1,
-                git_files_changed = ?2
-            WHERE session_id = ?3 AND iteration_number = ?4
+                git_files_changed = ?2,
+                diff_bytes = ?3,
+                files_changed = ?4
+            WHERE session_id = ?5 AND iteration_number = ?6
             "#,
             params![
                 diff,
                 files_changed as i64,
+                diff.len() as i64,
+                string_list_json(files),
                 session_id,
                 iteration_number as i64
             ],
@@ -291,16 +480,219 @@ impl<'db> Sessions<'db> {
         iteration_number: usize,
         decision: &str,
         feedback: Option<&str>,
+        confidence: Option<f64>,
     ) -> Result<(), rusqlite::Error> {
         self.conn.execute(
             r#"
             UPDATE iterations SET
                 phase = 'critic_completed',
                 critic_decision = ?1,
-                feedback = ?2
+                feedback = ?2,
+                critic_confidence = ?3
+            WHERE session_id = ?4 AND iteration_number = ?5
+            "#,
+            params![
+                decision,
+                feedback,
+                confidence,
+                session_id,
+                iteration_number as i64
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Store the critic's raw response text.
+    ///
+    /// Written independently of `complete_critic` so the response survives
+    /// decision parse failures.
+    pub fn set_critic_raw(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+        raw: &str,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET critic_raw = ?1
+            WHERE session_id = ?2 AND iteration_number = ?3
+            "#,
+            params![raw, session_id, iteration_number as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Store the acceptance criteria status from the critic's decision.
+    pub fn set_critic_checklist(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+        checklist: &[ChecklistItem],
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET critic_checklist = ?1
+            WHERE session_id = ?2 AND iteration_number = ?3
+            "#,
+            params![
+                checklist_json(checklist),
+                session_id,
+                iteration_number as i64
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Store the rubric status from the critic's decision.
+    pub fn set_critic_rubric(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+        rubric: &[RubricResult],
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET critic_rubric = ?1
+            WHERE session_id = ?2 AND iteration_number = ?3
+            "#,
+            params![checklist_json(rubric), session_id, iteration_number as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Store how the iteration's changed files compare with the prompt's
+    /// files to modify.
+    pub fn set_scope_adherence(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+        adherence: &ScopeAdherence,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET scope_adherence = ?1
+            WHERE session_id = ?2 AND iteration_number = ?3
+            "#,
+            params![
+                serde_json::to_string(adherence).ok(),
+                session_id,
+                iteration_number as i64
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Record how a person ruled on the critic's call for an iteration.
+    pub fn set_human_review(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+        review: &str,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET human_review = ?1
+            WHERE session_id = ?2 AND iteration_number = ?3
+            "#,
+            params![review, session_id, iteration_number as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Store the coverage measured after an iteration. `delta` is `None`
+    /// when the session has no baseline percentage.
+    pub fn set_coverage(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+        percent: f64,
+        delta: Option<f64>,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET coverage_percent = ?1, coverage_delta = ?2
             WHERE session_id = ?3 AND iteration_number = ?4
             "#,
-            params![decision, feedback, session_id, iteration_number as i64],
+            params![percent, delta, session_id, iteration_number as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Store whether the verify command passed before the first iteration.
+    pub fn set_verify_baseline(
+        &self,
+        session_id: &str,
+        passed: bool,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "UPDATE sessions SET verify_baseline = ?1 WHERE id = ?2",
+            params![passed, session_id],
+        )?;
+        Ok(())
+    }
+
+    /// Store whether the verify command passed after an iteration.
+    pub fn set_verify_passed(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+        passed: bool,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET verify_passed = ?1
+            WHERE session_id = ?2 AND iteration_number = ?3
+            "#,
+            params![passed, session_id, iteration_number as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Record that an iteration's actor output was not valid UTF-8.
+    pub fn set_actor_output_lossy(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET actor_output_lossy = 1
+            WHERE session_id = ?1 AND iteration_number = ?2
+            "#,
+            params![session_id, iteration_number as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Record that a critic reset discarded an iteration's changes.
+    pub fn set_reset_applied(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET reset_applied = 1
+            WHERE session_id = ?1 AND iteration_number = ?2
+            "#,
+            params![session_id, iteration_number as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Record whether the diff in a patch-mode actor's output applied.
+    pub fn set_patch_applied(
+        &self,
+        session_id: &str,
+        iteration_number: usize,
+        applied: bool,
+    ) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            r#"
+            UPDATE iterations SET patch_applied = ?1
+            WHERE session_id = ?2 AND iteration_number = ?3
+            "#,
+            params![applied, session_id, iteration_number as i64],
         )?;
         Ok(())
     }
@@ -312,9 +704,16 @@ impl<'db> Sessions<'db> {
             INSERT INTO iterations (
                 session_id, iteration_number, phase, actor_output, actor_stderr,
                 actor_exit_code, actor_duration_secs, git_diff, git_files_changed,
-                critic_decision, feedback, timestamp
+                critic_decision, feedback, critic_raw, timestamp, diff_bytes,
+                critic_confidence, critic_checklist, coverage_percent, coverage_delta,
+                patch_applied, files_changed, critic_rubric, scope_adherence, human_review,
+                actor_prompt, reset_applied, verify_passed, legacy_critic_decision,
+                actor_output_lossy
+            )
+            VALUES (
+                ?1, ?2, 'critic_completed', ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15,
+                ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27
             )
-            VALUES (?1, ?2, 'critic_completed', ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
             "#,
             params![
                 session_id,
@@ -327,13 +726,34 @@ impl<'db> Sessions<'db> {
                 iter.git_files_changed.map(|n| n as i64),
                 iter.critic_decision,
                 iter.feedback,
+                iter.critic_raw,
                 iter.timestamp.to_rfc3339(),
+                iter.git_diff.as_ref().map(|d| d.len() as i64),
+                iter.critic_confidence,
+                checklist_json(&iter.critic_checklist),
+                iter.coverage_percent,
+                iter.coverage_delta,
+                iter.patch_applied,
+                string_list_json(&iter.files),
+                checklist_json(&iter.critic_rubric),
+                iter.scope_adherence
+                    .as_ref()
+                    .and_then(|a| serde_json::to_string(a).ok()),
+                iter.human_review,
+                iter.actor_prompt,
+                iter.reset_applied,
+                iter.verify_passed,
+                iter.legacy_critic_decision,
+                iter.actor_output_lossy,
             ],
         )?;
         Ok(())
     }
 
     /// End a session with final outcome data.
+    ///
+    /// Also stores the files changed summed across its iterations, for list
+    /// views.
     pub fn end(&self, session_id: &str, end: &SessionEnd) -> Result<(), rusqlite::Error> {
         let now = Utc::now();
 
@@ -345,7 +765,11 @@ impl<'db> Sessions<'db> {
                 summary = ?3,
                 confidence = ?4,
                 duration_secs = ?5,
-                ended_at = ?6
+                ended_at = ?6,
+                total_files_changed = (
+                    SELECT COALESCE(SUM(git_files_changed), 0)
+                    FROM iterations WHERE session_id = ?7
+                )
             WHERE id = ?7
             "#,
             params![
@@ -371,7 +795,8 @@ impl<'db> Sessions<'db> {
                 SELECT id, prompt, working_dir, actor_agent, critic_agent,
                        actor_model, critic_model, max_iterations, outcome,
                        iteration_count, summary, confidence, duration_secs,
-                       started_at, ended_at
+                       started_at, ended_at, prompt_files, branch, seed_feedback,
+                       context_files, notes, verify_baseline
                 FROM sessions WHERE id = ?1
                 "#,
                 params![id],
@@ -393,7 +818,10 @@ impl<'db> Sessions<'db> {
         let mut sql = String::from(
             r#"
             SELECT id, prompt, working_dir, actor_agent, critic_agent,
-                   outcome, iteration_count, duration_secs, confidence, started_at
+                   outcome, iteration_count, duration_secs, confidence, started_at,
+                   prompt_bytes,
+                   (SELECT MAX(diff_bytes) FROM iterations i WHERE i.session_id = sessions.id),
+                   total_files_changed, branch
             FROM sessions WHERE 1=1
             "#,
         );
@@ -401,7 +829,7 @@ impl<'db> Sessions<'db> {
 
         if let Some(ref outcome) = filter.outcome {
             sql.push_str(" AND outcome = ?");
-            param_values.push(Box::new(outcome.clone()));
+            param_values.push(Box::new(*outcome));
         }
 
         if let Some(after) = filter.after {
@@ -420,9 +848,21 @@ impl<'db> Sessions<'db> {
         }
 
         if let Some(ref project) = filter.project {
-            // Project is derived from working_dir, we filter on path
-            sql.push_str(" AND working_dir LIKE ?");
+            // Project is derived from working_dir, we filter on path. Match
+            // either separator so sessions recorded on Windows are included.
+            sql.push_str(" AND (working_dir LIKE ? OR working_dir LIKE ?)");
             param_values.push(Box::new(format!("%/{}", project)));
+            param_values.push(Box::new(format!("%\\{}", project)));
+        }
+
+        if let Some(min) = filter.min_confidence {
+            sql.push_str(" AND confidence >= ?");
+            param_values.push(Box::new(min));
+        }
+
+        if let Some(max) = filter.max_confidence {
+            sql.push_str(" AND confidence <= ?");
+            param_values.push(Box::new(max));
         }
 
         sql.push_str(" ORDER BY started_at DESC");
@@ -456,8 +896,13 @@ impl<'db> Sessions<'db> {
         Ok(Some(diffs.join("\n")))
     }
 
-    /// Compute aggregate statistics over sessions matching the filter.
-    pub fn stats(&self, filter: &SessionFilter) -> Result<SessionStats, rusqlite::Error> {
+    /// Compute aggregate statistics over sessions matching the filter, with
+    /// daily counts by calendar day in `time_zone`.
+    pub fn stats(
+        &self,
+        filter: &SessionFilter,
+        time_zone: StatsTimeZone,
+    ) -> Result<SessionStats, rusqlite::Error> {
         let summaries = self.list(filter)?;
         let total_sessions = summaries.len();
 
@@ -474,7 +919,7 @@ impl<'db> Sessions<'db> {
 
         let success_count = summaries
             .iter()
-            .filter(|s| s.outcome.as_deref() == Some("success"))
+            .filter(|s| s.outcome.is_some_and(|o| o.is_success()))
             .count();
         let success_rate = success_count as f64 / total_sessions as f64;
 
@@ -488,7 +933,7 @@ impl<'db> Sessions<'db> {
             durations.iter().sum::<f64>() / durations.len() as f64
         };
 
-        let sessions_over_time = Self::compute_sessions_over_time(&summaries);
+        let sessions_over_time = Self::compute_sessions_over_time(&summaries, time_zone);
         let by_project = Self::compute_by_project(&summaries);
 
         Ok(SessionStats {
@@ -501,10 +946,19 @@ impl<'db> Sessions<'db> {
         })
     }
 
-    /// Compute agentic efficacy metrics (DORA-inspired).
+    /// Compute agentic efficacy metrics (DORA-inspired), with daily counts
+    /// by calendar day in `time_zone`.
+    ///
+    /// Metrics from iterations (the critic rates, `total_iterations` and
+    /// `most_touched_files`) need every iteration loaded, which dominates the
+    /// cost. `recent_sessions_limit` computes them over only the most recent
+    /// sessions matching `filter`, trading accuracy over the full history
+    /// for speed. Session-level metrics always cover every match.
     pub fn agentic_metrics(
         &self,
         filter: &SessionFilter,
+        time_zone: StatsTimeZone,
+        recent_sessions_limit: Option<usize>,
     ) -> Result<AgenticMetrics, rusqlite::Error> {
         let summaries = self.list(filter)?;
         let total_sessions = summaries.len();
@@ -519,17 +973,22 @@ impl<'db> Sessions<'db> {
                 avg_cycle_time_secs: 0.0,
                 waste_rate: 0.0,
                 total_iterations: 0,
+                iteration_sessions: 0,
                 critic_approval_rate: 0.0,
                 avg_feedback_length: 0.0,
                 improvement_rate: 0.0,
+                avg_prompt_bytes: 0.0,
+                max_diff_bytes: 0,
+                most_touched_files: Vec::new(),
                 sessions_over_time: Vec::new(),
                 by_project: Vec::new(),
+                by_project_branch: Vec::new(),
             });
         }
 
         let successful_summaries: Vec<_> = summaries
             .iter()
-            .filter(|s| s.outcome.as_deref() == Some("success"))
+            .filter(|s| s.outcome.is_some_and(|o| o.is_success()))
             .collect();
         let successful_sessions = successful_summaries.len();
         let success_rate = successful_sessions as f64 / total_sessions as f64;
@@ -564,24 +1023,43 @@ impl<'db> Sessions<'db> {
             successful_durations.iter().sum::<f64>() / successful_durations.len() as f64
         };
 
-        let waste_outcomes = ["failed", "interrupted", "max_iterations_reached"];
         let waste_count = summaries
             .iter()
-            .filter(|s| {
-                s.outcome
-                    .as_deref()
-                    .map(|o| waste_outcomes.contains(&o))
-                    .unwrap_or(false)
-            })
+            .filter(|s| s.outcome.is_some_and(|o| o.is_waste()))
             .count();
         let waste_rate = waste_count as f64 / total_sessions as f64;
 
-        // Critic metrics: load iterations for all sessions
+        let prompt_sizes: Vec<f64> = summaries
+            .iter()
+            .filter_map(|s| s.prompt_bytes)
+            .map(|b| b as f64)
+            .collect();
+        let avg_prompt_bytes = if prompt_sizes.is_empty() {
+            0.0
+        } else {
+            prompt_sizes.iter().sum::<f64>() / prompt_sizes.len() as f64
+        };
+        let max_diff_bytes = summaries
+            .iter()
+            .filter_map(|s| s.max_diff_bytes)
+            .max()
+            .unwrap_or(0);
+
+        // Critic and file metrics cover the most recent sessions (summaries
+        // are newest first), all of them by default
+        let recent = &summaries
+            [..recent_sessions_limit.map_or(total_sessions, |limit| limit.min(total_sessions))];
+        let mut iterations = Vec::with_capacity(recent.len());
+        for summary in recent {
+            iterations.push(self.get_iterations(&summary.id)?);
+        }
         let (total_iterations, critic_approval_rate, avg_feedback_length, improvement_rate) =
-            self.compute_critic_metrics(&summaries)?;
+            Self::compute_critic_metrics(&iterations);
+        let most_touched_files = self.compute_most_touched_files(recent)?;
 
-        let sessions_over_time = Self::compute_sessions_over_time(&summaries);
+        let sessions_over_time = Self::compute_sessions_over_time(&summaries, time_zone);
         let by_project = Self::compute_by_project(&summaries);
+        let by_project_branch = Self::compute_by_project_branch(&summaries);
 
         Ok(AgenticMetrics {
             total_sessions,
@@ -592,14 +1070,30 @@ impl<'db> Sessions<'db> {
             avg_cycle_time_secs,
             waste_rate,
             total_iterations,
+            iteration_sessions: recent.len(),
             critic_approval_rate,
             avg_feedback_length,
             improvement_rate,
+            avg_prompt_bytes,
+            max_diff_bytes,
+            most_touched_files,
             sessions_over_time,
             by_project,
+            by_project_branch,
         })
     }
 
+    /// ID of the most recently started session, if any.
+    pub fn latest_id(&self) -> Result<Option<String>, rusqlite::Error> {
+        self.conn
+            .query_row(
+                "SELECT id FROM sessions ORDER BY started_at DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
     /// Return IDs of active sessions (those without an end).
     pub fn active_sessions(&self) -> Result<Vec<String>, rusqlite::Error> {
         let mut stmt = self
@@ -615,6 +1109,17 @@ impl<'db> Sessions<'db> {
         Ok(ids)
     }
 
+    /// Replace a session's notes; blank text clears them. Returns whether
+    /// the session exists.
+    pub fn set_notes(&self, id: &str, notes: Option<&str>) -> Result<bool, rusqlite::Error> {
+        let notes = notes.map(str::trim).filter(|n| !n.is_empty());
+        let rows_affected = self.conn.execute(
+            "UPDATE sessions SET notes = ?2 WHERE id = ?1",
+            params![id, notes],
+        )?;
+        Ok(rows_affected > 0)
+    }
+
     /// Delete a session by ID (cascades to iterations).
     pub fn delete(&self, id: &str) -> Result<bool, rusqlite::Error> {
         let rows_affected = self
@@ -625,29 +1130,19 @@ impl<'db> Sessions<'db> {
 
     /// Get all unique project names.
     pub fn list_projects(&self) -> Result<Vec<String>, rusqlite::Error> {
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT DISTINCT
-                CASE
-                    WHEN working_dir LIKE '%/%' THEN
-                        SUBSTR(working_dir, LENGTH(working_dir) - LENGTH(REPLACE(working_dir, '/', '')) + 1)
-                    ELSE working_dir
-                END as project
-            FROM sessions
-            ORDER BY project
-            "#,
-        )?;
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT working_dir FROM sessions")?;
         let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
 
         let mut projects = Vec::new();
         for row in rows {
-            let project = row?;
-            // Remove leading slash if present
-            let project = project.trim_start_matches('/').to_string();
-            if !project.is_empty() && !projects.contains(&project) {
-                projects.push(project);
+            if let Some(project) = paths::project_name(&row?) {
+                projects.push(project.to_string());
             }
         }
+        projects.sort();
+        projects.dedup();
 
         Ok(projects)
     }
@@ -659,7 +1154,10 @@ impl<'db> Sessions<'db> {
             r#"
             SELECT iteration_number, phase, actor_output, actor_stderr, actor_exit_code,
                    actor_duration_secs, git_diff, git_files_changed, critic_decision,
-                   feedback, timestamp
+                   feedback, timestamp, critic_raw, diff_bytes, critic_confidence,
+                   critic_checklist, coverage_percent, coverage_delta, patch_applied,
+                   files_changed, critic_rubric, scope_adherence, human_review, actor_prompt,
+                   reset_applied, verify_passed, legacy_critic_decision, actor_output_lossy
             FROM iterations
             WHERE session_id = ?1
             ORDER BY iteration_number
@@ -678,7 +1176,34 @@ impl<'db> Sessions<'db> {
                 git_diff: row.get(6)?,
                 git_files_changed: row.get::<_, Option<i64>>(7)?.map(|n| n as usize),
                 critic_decision: row.get(8)?,
+                legacy_critic_decision: row.get(25)?,
                 feedback: row.get(9)?,
+                critic_raw: row.get(11)?,
+                diff_bytes: row.get::<_, Option<i64>>(12)?.map(|n| n as usize),
+                critic_confidence: row.get(13)?,
+                critic_checklist: row
+                    .get::<_, Option<String>>(14)?
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+                coverage_percent: row.get(15)?,
+                coverage_delta: row.get(16)?,
+                patch_applied: row.get(17)?,
+                critic_rubric: row
+                    .get::<_, Option<String>>(19)?
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+                scope_adherence: row
+                    .get::<_, Option<String>>(20)?
+                    .and_then(|json| serde_json::from_str(&json).ok()),
+                human_review: row.get(21)?,
+                actor_prompt: row.get(22)?,
+                actor_output_lossy: row.get(26)?,
+                reset_applied: row.get(23)?,
+                verify_passed: row.get(24)?,
+                files: row
+                    .get::<_, Option<String>>(18)?
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
                 timestamp: DateTime::parse_from_rfc3339(&timestamp_str)
                     .map(|dt| dt.with_timezone(&Utc))
                     .unwrap_or_else(|_| Utc::now()),
@@ -719,6 +1244,18 @@ impl<'db> Sessions<'db> {
                     .map(|dt| dt.with_timezone(&Utc))
                     .ok()
             }),
+            prompt_files: row
+                .get::<_, Option<String>>(15)?
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            branch: row.get(16)?,
+            seed_feedback: row.get(17)?,
+            context_files: row
+                .get::<_, Option<String>>(18)?
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+            notes: row.get(19)?,
+            verify_baseline: row.get(20)?,
             iterations: Vec::new(), // Populated by get()
         })
     }
@@ -729,10 +1266,7 @@ impl<'db> Sessions<'db> {
         let working_dir = PathBuf::from(&working_dir_str);
         let started_at_str: String = row.get(9)?;
 
-        // Extract project name from working_dir
-        let project = working_dir
-            .file_name()
-            .and_then(|s| s.to_str())
+        let project = paths::project_name(&working_dir_str)
             .unwrap_or("unknown")
             .to_string();
 
@@ -757,13 +1291,20 @@ impl<'db> Sessions<'db> {
             confidence: row.get(8)?,
             actor_agent: row.get(3)?,
             critic_agent: row.get(4)?,
+            prompt_bytes: row.get::<_, Option<i64>>(10)?.map(|n| n as usize),
+            max_diff_bytes: row.get::<_, Option<i64>>(11)?.map(|n| n as usize),
+            total_files_changed: row.get::<_, Option<i64>>(12)?.map(|n| n as usize),
+            branch: row.get(13)?,
         })
     }
 
-    fn compute_sessions_over_time(summaries: &[SessionSummary]) -> Vec<DayCount> {
+    fn compute_sessions_over_time(
+        summaries: &[SessionSummary],
+        time_zone: StatsTimeZone,
+    ) -> Vec<DayCount> {
         let mut day_counts: BTreeMap<String, usize> = BTreeMap::new();
         for s in summaries {
-            let date = s.timestamp.format("%Y-%m-%d").to_string();
+            let date = time_zone.date(s.timestamp);
             *day_counts.entry(date).or_insert(0) += 1;
         }
         day_counts
@@ -777,7 +1318,7 @@ impl<'db> Sessions<'db> {
         for s in summaries {
             let entry = project_map.entry(s.project.clone()).or_insert((0, 0));
             entry.0 += 1;
-            if s.outcome.as_deref() == Some("success") {
+            if s.outcome.is_some_and(|o| o.is_success()) {
                 entry.1 += 1;
             }
         }
@@ -789,22 +1330,50 @@ impl<'db> Sessions<'db> {
                 success_rate: successes as f64 / total as f64,
             })
             .collect();
-        by_project.sort_by(|a, b| b.total.cmp(&a.total));
+        by_project.sort_by_key(|p| std::cmp::Reverse(p.total));
         by_project
     }
 
-    fn compute_critic_metrics(
-        &self,
-        summaries: &[SessionSummary],
-    ) -> Result<(usize, f64, f64, f64), rusqlite::Error> {
+    fn compute_by_project_branch(summaries: &[SessionSummary]) -> Vec<ProjectBranchStats> {
+        let mut groups: HashMap<(String, Option<String>), (usize, usize)> = HashMap::new();
+        for s in summaries {
+            let entry = groups
+                .entry((s.project.clone(), s.branch.clone()))
+                .or_insert((0, 0));
+            entry.0 += 1;
+            if s.outcome.is_some_and(|o| o.is_success()) {
+                entry.1 += 1;
+            }
+        }
+        let mut by_project_branch: Vec<ProjectBranchStats> = groups
+            .into_iter()
+            .map(
+                |((project, branch), (total, successes))| ProjectBranchStats {
+                    project,
+                    branch,
+                    total,
+                    successes,
+                    success_rate: successes as f64 / total as f64,
+                },
+            )
+            .collect();
+        by_project_branch.sort_by(|a, b| {
+            b.total
+                .cmp(&a.total)
+                .then_with(|| a.project.cmp(&b.project))
+                .then_with(|| a.branch.cmp(&b.branch))
+        });
+        by_project_branch
+    }
+
+    fn compute_critic_metrics(sessions: &[Vec<Iteration>]) -> (usize, f64, f64, f64) {
         let mut total_iterations = 0usize;
         let mut approvals = 0usize;
         let mut rejections = 0usize;
         let mut total_feedback_length = 0usize;
         let mut improvement_count = 0usize;
 
-        for summary in summaries {
-            let iterations = self.get_iterations(&summary.id)?;
+        for iterations in sessions {
             total_iterations += iterations.len();
 
             for (i, iteration) in iterations.iter().enumerate() {
@@ -858,11 +1427,77 @@ impl<'db> Sessions<'db> {
             0.0
         };
 
-        Ok((
+        (
             total_iterations,
             critic_approval_rate,
             avg_feedback_length,
             improvement_rate,
-        ))
+        )
+    }
+
+    /// The ten files changed in the most iterations of `summaries`' sessions.
+    /// Only the `files_changed` column is read.
+    fn compute_most_touched_files(
+        &self,
+        summaries: &[SessionSummary],
+    ) -> Result<Vec<FileTouches>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT files_changed FROM iterations \
+             WHERE session_id = ?1 AND files_changed IS NOT NULL",
+        )?;
+        let mut counts: HashMap<(&str, String), (usize, usize)> = HashMap::new();
+        for summary in summaries {
+            let rows = stmt.query_map(params![summary.id], |row| row.get::<_, String>(0))?;
+            let mut seen = std::collections::HashSet::new();
+            for json in rows {
+                let paths: Vec<String> = serde_json::from_str(&json?).unwrap_or_default();
+                for path in paths {
+                    let entry = counts
+                        .entry((summary.project.as_str(), path.clone()))
+                        .or_insert((0, 0));
+                    entry.0 += 1;
+                    if seen.insert(path) {
+                        entry.1 += 1;
+                    }
+                }
+            }
+        }
+
+        let mut files: Vec<FileTouches> = counts
+            .into_iter()
+            .map(|((project, path), (iterations, sessions))| FileTouches {
+                project: project.to_string(),
+                path,
+                iterations,
+                sessions,
+            })
+            .collect();
+        files.sort_by(|a, b| {
+            b.iterations
+                .cmp(&a.iterations)
+                .then_with(|| b.sessions.cmp(&a.sessions))
+                .then_with(|| a.project.cmp(&b.project))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        files.truncate(10);
+        Ok(files)
+    }
+}
+
+/// Checklist or rubric results as stored in the `critic_checklist` and
+/// `critic_rubric` columns; NULL when empty.
+fn checklist_json<T: Serialize>(checklist: &[T]) -> Option<String> {
+    if checklist.is_empty() {
+        return None;
+    }
+    serde_json::to_string(checklist).ok()
+}
+
+/// A list of paths as stored in a JSON column (`prompt_files`,
+/// `context_files`, `files_changed`); NULL when empty.
+fn string_list_json(files: &[String]) -> Option<String> {
+    if files.is_empty() {
+        return None;
     }
+    serde_json::to_string(files).ok()
 }
@@ -0,0 +1,128 @@
+//! Time zone for grouping sessions by day in stats and metrics.
+//!
+//! Timestamps are stored in UTC. Grouping them by UTC date puts an evening
+//! run in a zone west of UTC on the next day, so the daily counts are built
+//! from each timestamp converted to the chosen zone instead.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, Local, Utc};
+
+/// Whose calendar days `sessions_over_time` counts by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum StatsTimeZone {
+    #[default]
+    Utc,
+    /// The system zone, which honors `TZ`; daylight saving time is applied
+    /// per timestamp
+    Local,
+    /// A fixed offset from UTC, such as `+05:30`
+    Fixed(FixedOffset),
+}
+
+impl StatsTimeZone {
+    /// The `%Y-%m-%d` date of `timestamp` in this zone.
+    pub fn date(&self, timestamp: DateTime<Utc>) -> String {
+        const FORMAT: &str = "%Y-%m-%d";
+        match self {
+            StatsTimeZone::Utc => timestamp.format(FORMAT).to_string(),
+            StatsTimeZone::Local => timestamp.with_timezone(&Local).format(FORMAT).to_string(),
+            StatsTimeZone::Fixed(offset) => {
+                timestamp.with_timezone(offset).format(FORMAT).to_string()
+            }
+        }
+    }
+}
+
+impl fmt::Display for StatsTimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsTimeZone::Utc => f.write_str("UTC"),
+            StatsTimeZone::Local => f.write_str("local"),
+            StatsTimeZone::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+/// Error returned when a string is not a time zone [`StatsTimeZone`] knows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTimeZoneError(pub String);
+
+impl fmt::Display for ParseTimeZoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown time zone '{}' (expected local, UTC or an offset like +05:30; for a named \
+             zone, set TZ and use local)",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseTimeZoneError {}
+
+impl FromStr for StatsTimeZone {
+    type Err = ParseTimeZoneError;
+
+    /// `local`, `UTC` (or `Z`), or an offset: `+05:30`, `-0800`, `+02`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        match trimmed.to_ascii_lowercase().as_str() {
+            "local" => return Ok(StatsTimeZone::Local),
+            "utc" | "z" => return Ok(StatsTimeZone::Utc),
+            _ => {}
+        }
+        parse_offset(trimmed)
+            .map(StatsTimeZone::Fixed)
+            .ok_or_else(|| ParseTimeZoneError(s.to_string()))
+    }
+}
+
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let (sign, rest) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let number = |digits: &str| {
+        let valid = (1..=2).contains(&digits.len()) && digits.bytes().all(|b| b.is_ascii_digit());
+        valid.then(|| digits.parse::<i32>().ok()).flatten()
+    };
+    let (hours, minutes) = (number(hours)?, number(minutes)?);
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_and_offsets() {
+        assert_eq!("local".parse(), Ok(StatsTimeZone::Local));
+        assert_eq!("UTC".parse(), Ok(StatsTimeZone::Utc));
+        let offset = |secs| StatsTimeZone::Fixed(FixedOffset::east_opt(secs).unwrap());
+        assert_eq!("+05:30".parse(), Ok(offset(5 * 3600 + 30 * 60)));
+        assert_eq!("-0800".parse(), Ok(offset(-8 * 3600)));
+        assert_eq!("+2".parse(), Ok(offset(2 * 3600)));
+        for bad in ["America/New_York", "+5:75", "05:00", "+", ""] {
+            assert!(bad.parse::<StatsTimeZone>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn dates_follow_the_zone() {
+        let evening: DateTime<Utc> = "2025-01-28T02:30:00Z".parse().unwrap();
+        assert_eq!(StatsTimeZone::Utc.date(evening), "2025-01-28");
+        let new_york: StatsTimeZone = "-05:00".parse().unwrap();
+        assert_eq!(new_york.date(evening), "2025-01-27");
+    }
+}
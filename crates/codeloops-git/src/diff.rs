@@ -1,8 +1,10 @@
-use git2::{DiffOptions, Repository, StatusOptions};
+use git2::build::CheckoutBuilder;
+use git2::{DiffOptions, IndexAddOption, Oid, Repository, StatusOptions};
 use std::path::Path;
 use thiserror::Error;
 use tracing::debug;
 
+use crate::patch::patch_path;
 use crate::GitStatus;
 
 #[derive(Error, Debug)]
@@ -20,6 +22,13 @@ pub enum GitError {
     NoCommits,
 }
 
+/// Name of the checked-out branch, including one with no commits yet.
+fn current_branch(repo: &Repository) -> Option<String> {
+    let head = repo.find_reference("HEAD").ok()?;
+    let target = head.symbolic_target()?;
+    target.strip_prefix("refs/heads/").map(str::to_string)
+}
+
 /// Summary of diff statistics
 #[derive(Debug, Clone, Default)]
 pub struct DiffSummary {
@@ -62,7 +71,10 @@ impl DiffCapture {
 
         let statuses = repo.statuses(Some(&mut opts))?;
 
-        let mut status = GitStatus::default();
+        let mut status = GitStatus {
+            branch: current_branch(&repo),
+            ..GitStatus::default()
+        };
 
         for entry in statuses.iter() {
             let path = entry.path().unwrap_or("").to_string();
@@ -83,6 +95,7 @@ impl DiffCapture {
         }
 
         debug!(
+            branch = status.branch.as_deref(),
             modified = status.modified.len(),
             added = status.added.len(),
             deleted = status.deleted.len(),
@@ -166,4 +179,247 @@ impl DiffCapture {
             deletions: stats.deletions(),
         })
     }
+
+    /// Record the working tree and index as they are now, so
+    /// [`restore`](Self::restore) can put them back later.
+    ///
+    /// Untracked files are included unless untracked files are excluded
+    /// from this capture; ignored files never are. Only git objects are
+    /// written: no ref, no file in the working tree and not the index file.
+    pub fn snapshot(&self, working_dir: &Path) -> Result<Snapshot, GitError> {
+        let repo = Repository::discover(working_dir)?;
+        let mut index = repo.index()?;
+        let staged = index.write_tree()?;
+
+        // Stage everything in the in-memory index only; it is read back
+        // from disk below without being written
+        if self.include_untracked {
+            index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+        }
+        index.update_all(["*"], None)?;
+        let worktree = index.write_tree()?;
+        index.read(true)?;
+
+        debug!(%worktree, "Snapshotted working tree");
+        Ok(Snapshot {
+            worktree,
+            index: staged,
+            include_untracked: self.include_untracked,
+        })
+    }
+
+    /// Paths of the files that differ between `snapshot` and the working
+    /// tree now, relative to the repository root, in path order. Changes made
+    /// before the snapshot, such as the user's own work in progress, are not
+    /// listed.
+    pub fn files_changed_since(
+        &self,
+        working_dir: &Path,
+        snapshot: &Snapshot,
+    ) -> Result<Vec<String>, GitError> {
+        let repo = Repository::discover(working_dir)?;
+        let diff = self.diff_since(&repo, working_dir, snapshot)?;
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect())
+    }
+
+    /// Like [`capture_summary`](Self::capture_summary), counting only what
+    /// changed since `snapshot` rather than since the last commit.
+    pub fn summary_since(
+        &self,
+        working_dir: &Path,
+        snapshot: &Snapshot,
+    ) -> Result<DiffSummary, GitError> {
+        let repo = Repository::discover(working_dir)?;
+        let stats = self.diff_since(&repo, working_dir, snapshot)?.stats()?;
+        Ok(DiffSummary {
+            files_changed: stats.files_changed(),
+            insertions: stats.insertions(),
+            deletions: stats.deletions(),
+        })
+    }
+
+    /// The diff from `snapshot` to the working tree now.
+    fn diff_since<'repo>(
+        &self,
+        repo: &'repo Repository,
+        working_dir: &Path,
+        snapshot: &Snapshot,
+    ) -> Result<git2::Diff<'repo>, GitError> {
+        let now = self.snapshot(working_dir)?;
+        Ok(repo.diff_tree_to_tree(
+            Some(&repo.find_tree(snapshot.worktree)?),
+            Some(&repo.find_tree(now.worktree)?),
+            None,
+        )?)
+    }
+
+    /// Put the working tree and index back the way `snapshot` found them.
+    ///
+    /// Files changed since are overwritten, files deleted since come back,
+    /// and files created since are deleted. Ignored files are never touched.
+    pub fn restore(&self, working_dir: &Path, snapshot: &Snapshot) -> Result<(), GitError> {
+        let repo = Repository::discover(working_dir)?;
+        let worktree = repo.find_tree(snapshot.worktree)?;
+
+        let mut checkout = CheckoutBuilder::new();
+        checkout
+            .force()
+            .remove_untracked(snapshot.include_untracked);
+        repo.checkout_tree(worktree.as_object(), Some(&mut checkout))?;
+
+        // The checkout staged the snapshot; stage what was staged then
+        let mut index = repo.index()?;
+        index.read_tree(&repo.find_tree(snapshot.index)?)?;
+        index.write()?;
+
+        debug!(worktree = %snapshot.worktree, "Restored working tree");
+        Ok(())
+    }
+}
+
+/// The working tree and index at one moment, kept as git tree objects.
+/// Made by [`DiffCapture::snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Files as they were on disk
+    worktree: Oid,
+    /// Files as they were staged
+    index: Oid,
+    include_untracked: bool,
+}
+
+/// Paths of the files a git diff touches, in order, read from its file
+/// headers.
+///
+/// Each file is taken from its `+++` line, or from its `---` line when the
+/// file was deleted. Files without those lines (binary files, mode changes)
+/// fall back to the `diff --git` header.
+pub fn diff_files(diff: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    let mut in_header = false;
+    let mut old_path = None;
+
+    for line in diff.lines() {
+        if let Some(spec) = line.strip_prefix("diff --git ") {
+            in_header = true;
+            old_path = None;
+            let path = spec.rsplit_once(" b/").map_or(spec, |(_, path)| path);
+            files.push(path.to_string());
+        } else if line.starts_with("@@") {
+            in_header = false;
+        } else if !in_header {
+            continue;
+        } else if let Some(spec) = line.strip_prefix("--- ") {
+            old_path = patch_path(spec);
+        } else if let Some(spec) = line.strip_prefix("+++ ") {
+            if let (Some(path), Some(last)) = (patch_path(spec).or(old_path), files.last_mut()) {
+                *last = path.to_string();
+            }
+        }
+    }
+
+    files.dedup();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_files_from_diff_headers() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,2 @@
+ fn a() {}
+-++ not a header
++++ still not a header
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/logo.png b/logo.png
+new file mode 100644
+Binary files /dev/null and b/logo.png differ
+";
+        assert_eq!(diff_files(diff), vec!["src/lib.rs", "old.txt", "logo.png"]);
+        assert!(diff_files("").is_empty());
+    }
+
+    #[test]
+    fn restore_keeps_changes_made_before_the_snapshot() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let repo = Repository::init(dir).unwrap();
+        let write = |name: &str, text: &str| std::fs::write(dir.join(name), text).unwrap();
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).ok();
+
+        write("tracked.txt", "committed\n");
+        write("deleted.txt", "committed\n");
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("tracked.txt")).unwrap();
+        index.add_path(Path::new("deleted.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+
+        // The user's own work in progress: an edit, a deletion, a staged
+        // new file and an untracked one
+        write("tracked.txt", "user edit\n");
+        std::fs::remove_file(dir.join("deleted.txt")).unwrap();
+        write("staged.txt", "staged\n");
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+        write("notes.txt", "untracked\n");
+        write(".gitignore", "*.log\n");
+
+        let capture = DiffCapture::new();
+        let snapshot = capture.snapshot(dir).unwrap();
+
+        // The actor's changes
+        write("tracked.txt", "actor edit\n");
+        write("deleted.txt", "actor restored\n");
+        std::fs::remove_file(dir.join("notes.txt")).unwrap();
+        write("new.txt", "actor file\n");
+        write("build.log", "ignored\n");
+
+        assert_eq!(
+            capture.files_changed_since(dir, &snapshot).unwrap(),
+            vec!["deleted.txt", "new.txt", "notes.txt", "tracked.txt"]
+        );
+        let summary = capture.summary_since(dir, &snapshot).unwrap();
+        assert_eq!(
+            (summary.files_changed, summary.insertions, summary.deletions),
+            (4, 3, 2)
+        );
+
+        capture.restore(dir, &snapshot).unwrap();
+
+        assert_eq!(read("tracked.txt").as_deref(), Some("user edit\n"));
+        assert_eq!(read("deleted.txt"), None);
+        assert_eq!(read("staged.txt").as_deref(), Some("staged\n"));
+        assert_eq!(read("notes.txt").as_deref(), Some("untracked\n"));
+        assert_eq!(read("new.txt"), None);
+        assert_eq!(read("build.log").as_deref(), Some("ignored\n"));
+
+        let status = capture.capture_status(dir).unwrap();
+        assert_eq!(status.added, vec!["staged.txt"]);
+        assert_eq!(status.modified, vec!["tracked.txt"]);
+        assert_eq!(status.deleted, vec!["deleted.txt"]);
+        let mut untracked = status.untracked;
+        untracked.sort();
+        assert_eq!(untracked, vec![".gitignore", "notes.txt"]);
+    }
 }
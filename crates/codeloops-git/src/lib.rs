@@ -17,6 +17,9 @@
 //! - [`DiffCapture`] - Utility for capturing git diffs
 //! - [`DiffSummary`] - Summary of captured diff
 //! - [`GitStatus`] - Current git repository status
+//! - [`Snapshot`] - The working tree at one moment, to restore after a critic reset
+//! - [`extract_patch`] / [`apply_patch`] - Apply a diff an agent printed instead of editing files
+//! - [`DiffScope`] - Narrow a captured diff to the files matching some globs
 //!
 //! ## Usage
 //!
@@ -45,7 +48,11 @@
 //! standard diff tools and easy for the critic agent to parse.
 
 mod diff;
+mod patch;
+mod scope;
 mod status;
 
-pub use diff::{DiffCapture, DiffSummary, GitError};
+pub use diff::{diff_files, DiffCapture, DiffSummary, GitError, Snapshot};
+pub use patch::{apply_patch, extract_patch};
+pub use scope::{DiffScope, ScopedDiff};
 pub use status::GitStatus;
@@ -0,0 +1,273 @@
+use git2::{ApplyLocation, ApplyOptions, Diff, Repository};
+use std::path::Path;
+use tracing::debug;
+
+use crate::{DiffSummary, GitError};
+
+/// Pull the unified diff out of an agent's output.
+///
+/// Looks for fenced code blocks tagged `diff` or `patch`. Several blocks are
+/// joined in order into one patch. `None` when the output has no such block.
+pub fn extract_patch(output: &str) -> Option<String> {
+    let mut patch = String::new();
+    let mut in_block = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        if in_block {
+            if trimmed.starts_with("```") {
+                in_block = false;
+            } else {
+                patch.push_str(line);
+                patch.push('\n');
+            }
+        } else if let Some(info) = trimmed.strip_prefix("```") {
+            let lang = info.split_whitespace().next().unwrap_or("");
+            in_block = lang == "diff" || lang == "patch";
+        }
+    }
+
+    (!patch.trim().is_empty()).then_some(patch)
+}
+
+/// Apply a unified diff to the working tree of the repository containing
+/// `working_dir`. Paths in the patch are relative to `working_dir`, where
+/// the agent that wrote it ran.
+///
+/// The whole patch is checked before anything is written, so a patch that
+/// does not apply cleanly leaves the working tree untouched.
+pub fn apply_patch(working_dir: &Path, patch: &str) -> Result<DiffSummary, GitError> {
+    let repo = Repository::discover(working_dir)?;
+    let prefix = repo_prefix(&repo, working_dir)?;
+    let diff = Diff::from_buffer(with_git_headers(patch, &prefix).as_bytes())?;
+
+    let mut check = ApplyOptions::new();
+    check.check(true);
+    repo.apply(&diff, ApplyLocation::WorkDir, Some(&mut check))?;
+    repo.apply(&diff, ApplyLocation::WorkDir, None)?;
+
+    let stats = diff.stats()?;
+    debug!(files_changed = stats.files_changed(), "Applied patch");
+    Ok(DiffSummary {
+        files_changed: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
+}
+
+/// Where `working_dir` is inside the repository's working tree, as a path
+/// prefix ending in `/`; empty at the root.
+fn repo_prefix(repo: &Repository, working_dir: &Path) -> Result<String, GitError> {
+    let root = repo
+        .workdir()
+        .ok_or_else(|| GitError::NotARepo(working_dir.display().to_string()))?
+        .canonicalize()?;
+    let dir = working_dir.canonicalize()?;
+    let relative = dir.strip_prefix(&root).unwrap_or(Path::new(""));
+    Ok(relative
+        .components()
+        .map(|c| format!("{}/", c.as_os_str().to_string_lossy()))
+        .collect())
+}
+
+/// Add the `diff --git` header libgit2 needs in front of each file of a
+/// plain unified diff (one starting at `--- path`). Paths get the `a/` and
+/// `b/` prefixes git uses; `/dev/null` marks an added or deleted file.
+/// `prefix` is put in front of every path, including those of files that
+/// already have a git header.
+fn with_git_headers(patch: &str, prefix: &str) -> String {
+    let lines: Vec<&str> = patch.lines().collect();
+    let mut out = String::with_capacity(patch.len());
+    let mut in_git_header = false;
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.starts_with("diff --git ") {
+            in_git_header = true;
+        } else if line.starts_with("@@") {
+            in_git_header = false;
+        }
+        if in_git_header && !prefix.is_empty() {
+            out.push_str(&with_header_prefix(line, prefix));
+            out.push('\n');
+            i += 1;
+            continue;
+        }
+
+        let old = line.strip_prefix("--- ");
+        let new = lines.get(i + 1).and_then(|next| next.strip_prefix("+++ "));
+        if let (false, Some(old), Some(new)) = (in_git_header, old, new) {
+            let old = patch_path(old);
+            let new = patch_path(new);
+            let old = old.map(|path| format!("{prefix}{path}"));
+            let new = new.map(|path| format!("{prefix}{path}"));
+            let path = new.as_deref().or(old.as_deref()).unwrap_or_default();
+            out.push_str(&format!("diff --git a/{path} b/{path}\n"));
+            if old.is_none() {
+                out.push_str("new file mode 100644\n");
+            } else if new.is_none() {
+                out.push_str("deleted file mode 100644\n");
+            }
+            let side = |side: &str, path: Option<String>| match path {
+                Some(path) => format!("{side}/{path}"),
+                None => "/dev/null".to_string(),
+            };
+            out.push_str(&format!("--- {}\n+++ {}\n", side("a", old), side("b", new)));
+            i += 2;
+            continue;
+        }
+
+        out.push_str(line);
+        out.push('\n');
+        i += 1;
+    }
+    out
+}
+
+/// A line of a file's git header with `prefix` put in front of its paths.
+/// Lines without a path are returned unchanged.
+fn with_header_prefix(line: &str, prefix: &str) -> String {
+    if let Some(spec) = line.strip_prefix("diff --git a/") {
+        if let Some((old, new)) = spec.rsplit_once(" b/") {
+            return format!("diff --git a/{prefix}{old} b/{prefix}{new}");
+        }
+    }
+    for marker in [
+        "--- a/",
+        "+++ b/",
+        "rename from ",
+        "rename to ",
+        "copy from ",
+        "copy to ",
+    ] {
+        if let Some(path) = line.strip_prefix(marker) {
+            return format!("{marker}{prefix}{path}");
+        }
+    }
+    line.to_string()
+}
+
+/// The file path on a `---`/`+++` line without its `a/`/`b/` prefix or a
+/// trailing timestamp. `None` for `/dev/null`.
+pub(crate) fn patch_path(spec: &str) -> Option<&str> {
+    let path = spec.split('\t').next().unwrap_or(spec).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(
+        path.strip_prefix("a/")
+            .or_else(|| path.strip_prefix("b/"))
+            .unwrap_or(path),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_fenced_diff_blocks() {
+        let output = "\
+Here is the fix:
+
+```diff
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-old
++new
+```
+
+And a second file:
+
+```patch
+--- a/README.md
++++ b/README.md
+@@ -1 +1 @@
+-a
++b
+```
+";
+        let patch = extract_patch(output).unwrap();
+        assert!(patch.starts_with("--- a/src/lib.rs\n"));
+        assert!(patch.contains("+new\n--- a/README.md\n"));
+        assert!(patch.ends_with("+b\n"));
+    }
+
+    #[test]
+    fn adds_git_headers_to_plain_diffs() {
+        let plain = "--- a/src/lib.rs\t2024-01-01\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-old\n+new\n\
+                     --- /dev/null\n+++ notes.txt\n@@ -0,0 +1 @@\n+hi\n";
+        assert_eq!(
+            with_git_headers(plain, ""),
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1 +1 @@\n-old\n+new\n\
+             diff --git a/notes.txt b/notes.txt\nnew file mode 100644\n\
+             --- /dev/null\n+++ b/notes.txt\n@@ -0,0 +1 @@\n+hi\n"
+        );
+
+        let git = "diff --git a/x b/x\n--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n";
+        assert_eq!(with_git_headers(git, ""), git);
+        assert_eq!(
+            with_git_headers(git, "sub/"),
+            "diff --git a/sub/x b/sub/x\n--- a/sub/x\n+++ b/sub/x\n@@ -1 +1 @@\n-a\n+b\n"
+        );
+    }
+
+    #[test]
+    fn applies_whole_patch_or_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        Repository::init(tmp.path()).unwrap();
+        let read = |name| std::fs::read_to_string(tmp.path().join(name)).unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "one\ntwo\n").unwrap();
+        std::fs::write(tmp.path().join("b.txt"), "x\n").unwrap();
+
+        let summary = apply_patch(
+            tmp.path(),
+            "--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n one\n-two\n+three\n",
+        )
+        .unwrap();
+        assert_eq!(summary.files_changed, 1);
+        assert_eq!(read("a.txt"), "one\nthree\n");
+
+        // The first file would apply, the second does not: neither is written
+        let result = apply_patch(
+            tmp.path(),
+            "--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n one\n-three\n+four\n\
+             --- a/b.txt\n+++ b/b.txt\n@@ -1 +1 @@\n-nope\n+y\n",
+        );
+        assert!(result.is_err());
+        assert_eq!(read("a.txt"), "one\nthree\n");
+        assert_eq!(read("b.txt"), "x\n");
+    }
+
+    #[test]
+    fn resolves_paths_against_the_working_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        Repository::init(tmp.path()).unwrap();
+        let sub = tmp.path().join("crates/app");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::write(sub.join("a.txt"), "one\n").unwrap();
+
+        apply_patch(
+            &sub,
+            "--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-one\n+two\n\
+             --- /dev/null\n+++ b/new.txt\n@@ -0,0 +1 @@\n+hi\n",
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(sub.join("a.txt")).unwrap(), "two\n");
+        assert_eq!(
+            std::fs::read_to_string(sub.join("new.txt")).unwrap(),
+            "hi\n"
+        );
+        assert!(!tmp.path().join("a.txt").exists());
+    }
+
+    #[test]
+    fn ignores_other_code_blocks() {
+        assert_eq!(extract_patch("```rust\nfn main() {}\n```\n"), None);
+        assert_eq!(extract_patch("I edited src/lib.rs directly."), None);
+        assert_eq!(extract_patch("```diff\n\n```"), None);
+    }
+}
@@ -0,0 +1,155 @@
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::diff_files;
+
+/// Globs selecting the part of a diff someone should look at.
+///
+/// Patterns are matched against repository-relative paths, with `*` staying
+/// within one directory and `**` crossing them. A pattern without glob
+/// characters also matches everything under it, so `src/api` selects the
+/// same files as `src/api/**`.
+#[derive(Debug, Clone)]
+pub struct DiffScope {
+    patterns: Vec<String>,
+    globs: GlobSet,
+}
+
+/// A diff narrowed by a [`DiffScope`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScopedDiff {
+    /// The file sections of the diff whose path is in scope
+    pub diff: String,
+    /// Changed files left out, in diff order
+    pub omitted: Vec<String>,
+}
+
+impl DiffScope {
+    /// Fails on an invalid glob, naming it.
+    pub fn new(patterns: &[String]) -> Result<Self, String> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let pattern = pattern.trim().trim_end_matches('/');
+            let mut add = |glob: &str| {
+                let glob = GlobBuilder::new(glob)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|e| e.to_string())?;
+                builder.add(glob);
+                Ok::<_, String>(())
+            };
+            add(pattern)?;
+            add(&format!("{pattern}/**"))?;
+        }
+        let globs = builder.build().map_err(|e| e.to_string())?;
+        Ok(Self {
+            patterns: patterns.to_vec(),
+            globs,
+        })
+    }
+
+    /// The patterns as given.
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        self.globs.is_match(path)
+    }
+
+    /// Keep the file sections of a unified diff (each starting at
+    /// `diff --git`) whose path is in scope, in their original order.
+    pub fn apply(&self, diff: &str) -> ScopedDiff {
+        let mut scoped = ScopedDiff::default();
+        for section in file_sections(diff) {
+            let in_scope = match diff_files(section).first() {
+                Some(path) if !self.matches(path) => {
+                    scoped.omitted.push(path.clone());
+                    false
+                }
+                _ => true,
+            };
+            if in_scope {
+                scoped.diff.push_str(section);
+            }
+        }
+        scoped
+    }
+}
+
+/// Split a diff before each `diff --git` line. Text ahead of the first one
+/// comes back as its own section.
+fn file_sections(diff: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff --git ") && offset > start {
+            sections.push(&diff[start..offset]);
+            start = offset;
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        sections.push(&diff[start..offset]);
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/api/users.rs b/src/api/users.rs
+--- a/src/api/users.rs
++++ b/src/api/users.rs
+@@ -1 +1 @@
+-old
++new
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -1 +1 @@
+-a
++b
+diff --git a/src/api/old.rs b/src/api/old.rs
+deleted file mode 100644
+--- a/src/api/old.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+";
+
+    fn scope(patterns: &[&str]) -> DiffScope {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        DiffScope::new(&patterns).unwrap()
+    }
+
+    #[test]
+    fn keeps_only_files_in_scope() {
+        let scoped = scope(&["src/api/**"]).apply(DIFF);
+        assert_eq!(scoped.omitted, ["README.md"]);
+        assert_eq!(
+            diff_files(&scoped.diff),
+            ["src/api/users.rs", "src/api/old.rs"]
+        );
+        assert!(scoped.diff.contains("+new\ndiff --git a/src/api/old.rs"));
+
+        let scoped = scope(&["*.md"]).apply(DIFF);
+        assert_eq!(diff_files(&scoped.diff), ["README.md"]);
+        assert_eq!(scoped.omitted, ["src/api/users.rs", "src/api/old.rs"]);
+    }
+
+    #[test]
+    fn matches_paths_like_git() {
+        let scope = scope(&["src/api", "docs/*.md"]);
+        assert!(scope.matches("src/api/users.rs"));
+        assert!(scope.matches("src/api/v2/routes.rs"));
+        assert!(!scope.matches("src/apis.rs"));
+        assert!(scope.matches("docs/intro.md"));
+        // `*` does not cross directories
+        assert!(!scope.matches("docs/guide/intro.md"));
+
+        assert!(DiffScope::new(&["src/[".to_string()]).is_err());
+    }
+}
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 /// Status of the git working directory
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GitStatus {
+    /// Checked-out branch; `None` for a detached HEAD
+    pub branch: Option<String>,
     pub modified: Vec<String>,
     pub added: Vec<String>,
     pub deleted: Vec<String>,
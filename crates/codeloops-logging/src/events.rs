@@ -1,10 +1,12 @@
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
+use crate::status::StatusLine;
+
 /// Role of the agent producing output
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -13,6 +15,16 @@ pub enum AgentRole {
     Critic,
 }
 
+impl AgentRole {
+    /// Lowercase role name for log lines.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AgentRole::Actor => "actor",
+            AgentRole::Critic => "critic",
+        }
+    }
+}
+
 /// Type of output stream
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -65,6 +77,37 @@ pub enum LogEvent {
         insertions: usize,
         deletions: usize,
     },
+    /// The diff in the actor's output was applied to the working tree
+    PatchApplied {
+        iteration: usize,
+        files_changed: usize,
+    },
+    /// The diff in the actor's output did not apply; nothing was written
+    PatchRejected {
+        iteration: usize,
+        error: String,
+    },
+    /// A person ruled on the critic's decision in an interactive run:
+    /// `accepted`, `rejected` or `feedback`
+    IterationReviewed {
+        iteration: usize,
+        verdict: String,
+    },
+    /// Verify command finished; `iteration` is `None` for the session baseline
+    VerifyCompleted {
+        iteration: Option<usize>,
+        passed: bool,
+        exit_code: i32,
+        regression: bool,
+    },
+    /// Coverage command finished; `iteration` is `None` for the session
+    /// baseline. `percent` is `None` when its output had no percentage.
+    CoverageMeasured {
+        iteration: Option<usize>,
+        percent: Option<f64>,
+        /// Change against the baseline in percentage points
+        delta: Option<f64>,
+    },
     CriticStarted {
         iteration: usize,
     },
@@ -72,14 +115,79 @@ pub enum LogEvent {
         iteration: usize,
         decision: String,
     },
+    /// The critic's decision and the start of its reasoning
+    CriticDecision {
+        iteration: usize,
+        /// `DONE`, `CONTINUE`, `ERROR` or `RESET`
+        decision: String,
+        confidence: Option<f64>,
+        /// First ~200 characters of the critic's summary or feedback
+        rationale_preview: String,
+    },
     LoopCompleted {
         iterations: usize,
         summary: String,
         duration_secs: f64,
     },
+    /// Size of the run's changes when it ends, whatever the outcome: the
+    /// working tree against `HEAD`
+    RunSummary {
+        files_changed: usize,
+        insertions: usize,
+        deletions: usize,
+        iterations: usize,
+    },
+    /// The loop is about to run an iteration close to the cap (1-based)
+    ApproachingMaxIterations {
+        iteration: usize,
+        max_iterations: usize,
+    },
     MaxIterationsReached {
         iterations: usize,
     },
+    /// Critic confidence did not rise over the last `window` iterations
+    NoProgress {
+        iterations: usize,
+        window: usize,
+        best_confidence: f64,
+    },
+    /// The last `unchanged` iterations left the cumulative diff as it was
+    ChangesStalled {
+        iterations: usize,
+        unchanged: usize,
+    },
+    /// `--max-cost` is set but no iteration has reported a cost; logged once
+    CostBudgetIgnored {
+        max_cost_usd: f64,
+    },
+    /// The next iteration would likely push spending past the budget
+    CostBudgetExceeded {
+        iterations: usize,
+        spent_usd: f64,
+        max_cost_usd: f64,
+    },
+    /// An agent has streamed no output for `idle_secs`
+    AgentIdle {
+        iteration: usize,
+        role: AgentRole,
+        idle_secs: u64,
+    },
+    /// An agent run failed and is being run again; `attempt` counts the
+    /// retries so far, up to `retries`
+    AgentRetrying {
+        iteration: usize,
+        role: AgentRole,
+        attempt: usize,
+        retries: usize,
+    },
+    /// The loop is holding before an iteration until resumed (1-based)
+    LoopPaused {
+        iteration: usize,
+    },
+    /// A paused loop is continuing with this iteration (1-based)
+    LoopResumed {
+        iteration: usize,
+    },
     ErrorEncountered {
         iteration: usize,
         error: String,
@@ -92,6 +200,26 @@ pub enum LogEvent {
     },
 }
 
+/// A run's change size on one line, as shown for [`LogEvent::RunSummary`]:
+/// "3 files changed, +120 -14 lines in 2 iterations".
+pub fn run_summary_line(
+    files_changed: usize,
+    insertions: usize,
+    deletions: usize,
+    iterations: usize,
+) -> String {
+    let plural = |n: usize, one: &'static str, many: &'static str| if n == 1 { one } else { many };
+    format!(
+        "{} {} changed, +{} -{} lines in {} {}",
+        files_changed,
+        plural(files_changed, "file", "files"),
+        insertions,
+        deletions,
+        iterations,
+        plural(iterations, "iteration", "iterations")
+    )
+}
+
 impl LogEvent {
     /// Add a timestamp to serialize with the event
     fn with_timestamp(&self) -> serde_json::Value {
@@ -141,6 +269,8 @@ pub struct Logger {
     /// When set, Pretty rendering is delegated to this callback (e.g., TUI renderer).
     /// The Logger skips its own pretty output and calls this instead.
     event_callback: Option<EventCallback>,
+    /// Spinner shown while an agent runs; Pretty mode on a TTY only.
+    status_line: Option<StatusLine>,
 }
 
 impl Logger {
@@ -149,9 +279,14 @@ impl Logger {
             format,
             file_writer: None,
             event_callback: None,
+            status_line: Self::status_line_for(format),
         }
     }
 
+    fn status_line_for(format: LogFormat) -> Option<StatusLine> {
+        (format == LogFormat::Pretty && std::io::stderr().is_terminal()).then(StatusLine::new)
+    }
+
     /// Create a logger with file output in addition to console
     pub fn with_file(format: LogFormat, log_path: &Path) -> std::io::Result<Self> {
         // Create parent directory if it doesn't exist
@@ -168,6 +303,7 @@ impl Logger {
             format,
             file_writer: Some(Mutex::new(file)),
             event_callback: None,
+            status_line: Self::status_line_for(format),
         })
     }
 
@@ -175,6 +311,7 @@ impl Logger {
     /// the callback handles rendering instead of the built-in pretty formatter.
     pub fn set_event_callback(&mut self, callback: EventCallback) {
         self.event_callback = Some(callback);
+        self.status_line = None;
     }
 
     pub fn log(&self, event: &LogEvent) {
@@ -197,7 +334,10 @@ impl Logger {
         // Log to console based on format
         match self.format {
             LogFormat::Json => self.log_json(event),
-            LogFormat::Pretty => self.log_pretty(event),
+            LogFormat::Pretty => match self.status_line {
+                Some(ref status) => status.around(event, || self.log_pretty(event)),
+                None => self.log_pretty(event),
+            },
             LogFormat::Compact => self.log_compact(event),
         }
     }
@@ -327,6 +467,117 @@ impl Logger {
                 }
                 let _ = writeln!(stderr);
             }
+            LogEvent::VerifyCompleted {
+                iteration,
+                passed,
+                exit_code,
+                regression,
+            } => {
+                let label = if iteration.is_some() {
+                    "Verify:"
+                } else {
+                    "Baseline verify:"
+                };
+                if *passed {
+                    let _ = writeln!(
+                        stderr,
+                        "    {} {} passed",
+                        "✓".bright_green(),
+                        label.dimmed()
+                    );
+                } else if *regression {
+                    let _ = writeln!(
+                        stderr,
+                        "    {} {} {} (exit {}), passed at baseline",
+                        "✗".bright_red(),
+                        label.dimmed(),
+                        "REGRESSION".bright_red().bold(),
+                        exit_code
+                    );
+                } else {
+                    let _ = writeln!(
+                        stderr,
+                        "    {} {} failed (exit {})",
+                        "⚠".bright_yellow(),
+                        label.dimmed(),
+                        exit_code
+                    );
+                }
+                let _ = writeln!(stderr);
+            }
+            LogEvent::PatchApplied { files_changed, .. } => {
+                let _ = writeln!(
+                    stderr,
+                    "    {} {} {} {}",
+                    "✓".bright_green(),
+                    "Patch applied:".dimmed(),
+                    files_changed,
+                    if *files_changed == 1 { "file" } else { "files" }
+                );
+            }
+            LogEvent::PatchRejected { error, .. } => {
+                let _ = writeln!(
+                    stderr,
+                    "    {} {} {}",
+                    "✗".bright_red(),
+                    "Patch did not apply:".dimmed(),
+                    error
+                );
+            }
+            LogEvent::IterationReviewed { verdict, .. } => {
+                let _ = writeln!(stderr, "    {} {}", "Reviewer:".dimmed(), verdict);
+            }
+            LogEvent::CoverageMeasured {
+                iteration,
+                percent,
+                delta,
+            } => {
+                let label = if iteration.is_some() {
+                    "Coverage:"
+                } else {
+                    "Baseline coverage:"
+                };
+                match (percent, delta) {
+                    (None, _) => {
+                        let _ = writeln!(
+                            stderr,
+                            "    {} {} no percentage in output",
+                            "⚠".bright_yellow(),
+                            label.dimmed()
+                        );
+                    }
+                    (Some(percent), Some(delta)) if *delta < 0.0 => {
+                        let _ = writeln!(
+                            stderr,
+                            "    {} {} {:.1}% ({})",
+                            "✗".bright_red(),
+                            label.dimmed(),
+                            percent,
+                            format!("{:+.1}", delta).bright_red()
+                        );
+                    }
+                    (Some(percent), Some(delta)) => {
+                        let _ = writeln!(
+                            stderr,
+                            "    {} {} {:.1}% ({:+.1})",
+                            "✓".bright_green(),
+                            label.dimmed(),
+                            percent,
+                            delta
+                        );
+                    }
+                    (Some(percent), None) => {
+                        let _ = writeln!(
+                            stderr,
+                            "    {} {} {:.1}%",
+                            "✓".bright_green(),
+                            label.dimmed(),
+                            percent
+                        );
+                    }
+                }
+                let _ = writeln!(stderr);
+            }
             LogEvent::CriticStarted { .. } => {
                 let _ = writeln!(
                     stderr,
@@ -335,6 +586,26 @@ impl Logger {
                     "CRITIC".bright_magenta().bold()
                 );
             }
+            LogEvent::CriticDecision {
+                decision,
+                confidence,
+                rationale_preview,
+                ..
+            } => {
+                if !rationale_preview.is_empty() {
+                    let label = match confidence {
+                        Some(confidence) => format!("Why ({:.0}%):", confidence * 100.0),
+                        None => "Why:".to_string(),
+                    };
+                    let label = match decision.as_str() {
+                        "DONE" => label.bright_green(),
+                        "ERROR" => label.bright_red(),
+                        "RESET" => label.bright_magenta(),
+                        _ => label.bright_yellow(),
+                    };
+                    let _ = writeln!(stderr, "    {} {}", label.bold(), rationale_preview);
+                }
+            }
             LogEvent::CriticCompleted { decision, .. } => {
                 // Parse decision to show appropriate styling
                 let styled_decision = if decision.contains("DONE") {
@@ -364,6 +635,74 @@ impl Logger {
                 // This is handled by the final outcome printing in main.rs
                 // We skip it here to avoid duplication
             }
+            LogEvent::RunSummary {
+                files_changed,
+                insertions,
+                deletions,
+                iterations,
+            } => {
+                let _ = writeln!(stderr);
+                let _ = writeln!(
+                    stderr,
+                    "{} {}",
+                    "Σ".bright_blue(),
+                    run_summary_line(*files_changed, *insertions, *deletions, *iterations)
+                );
+            }
+            LogEvent::ApproachingMaxIterations {
+                iteration,
+                max_iterations,
+            } => {
+                let _ = writeln!(
+                    stderr,
+                    "{} Iteration {} of {} — approaching limit",
+                    "⚠".bright_yellow(),
+                    iteration,
+                    max_iterations
+                );
+            }
+            LogEvent::AgentIdle {
+                role, idle_secs, ..
+            } => {
+                let _ = writeln!(
+                    stderr,
+                    "{} The {} has produced no output for {}s",
+                    "⚠".bright_yellow(),
+                    role.as_str(),
+                    idle_secs
+                );
+            }
+            LogEvent::AgentRetrying {
+                role,
+                attempt,
+                retries,
+                ..
+            } => {
+                let _ = writeln!(
+                    stderr,
+                    "{} The {} failed; retrying ({}/{})",
+                    "⚠".bright_yellow(),
+                    role.as_str(),
+                    attempt,
+                    retries
+                );
+            }
+            LogEvent::LoopPaused { iteration } => {
+                let _ = writeln!(
+                    stderr,
+                    "{} Paused before iteration {} — press r to resume, q to quit",
+                    "⏸".bright_yellow(),
+                    iteration
+                );
+            }
+            LogEvent::LoopResumed { iteration } => {
+                let _ = writeln!(
+                    stderr,
+                    "{} Resumed with iteration {}",
+                    "▶".bright_green(),
+                    iteration
+                );
+            }
             LogEvent::MaxIterationsReached { iterations } => {
                 let _ = writeln!(stderr);
                 let _ = writeln!(
@@ -373,6 +712,59 @@ impl Logger {
                     iterations
                 );
             }
+            LogEvent::NoProgress {
+                iterations,
+                window,
+                best_confidence,
+            } => {
+                let _ = writeln!(stderr);
+                let _ = writeln!(
+                    stderr,
+                    "{} No progress after {} iterations: critic confidence has not risen in the \
+                     last {} (best {:.0}%)",
+                    "⚠".bright_yellow(),
+                    iterations,
+                    window,
+                    best_confidence * 100.0
+                );
+            }
+            LogEvent::ChangesStalled {
+                iterations,
+                unchanged,
+            } => {
+                let _ = writeln!(stderr);
+                let _ = writeln!(
+                    stderr,
+                    "{} No progress after {} iterations: the changes have stayed the same for the \
+                     last {}",
+                    "⚠".bright_yellow(),
+                    iterations,
+                    unchanged
+                );
+            }
+            LogEvent::CostBudgetIgnored { max_cost_usd } => {
+                let _ = writeln!(
+                    stderr,
+                    "{} The agents report no costs; ignoring the ${:.2} budget",
+                    "⚠".bright_yellow(),
+                    max_cost_usd
+                );
+            }
+            LogEvent::CostBudgetExceeded {
+                iterations,
+                spent_usd,
+                max_cost_usd,
+            } => {
+                let _ = writeln!(stderr);
+                let _ = writeln!(
+                    stderr,
+                    "{} Cost budget reached after {} iterations: spent about ${:.2} of ${:.2}",
+                    "⚠".bright_yellow(),
+                    iterations,
+                    spent_usd,
+                    max_cost_usd
+                );
+            }
             LogEvent::ErrorEncountered { iteration, error } => {
                 let _ = writeln!(stderr);
                 let _ = writeln!(
@@ -435,6 +827,22 @@ impl Logger {
                 iteration,
                 decision,
             } => format!("[{}] critic:done:{} {}", timestamp, iteration + 1, decision),
+            LogEvent::CriticDecision {
+                iteration,
+                decision,
+                confidence,
+                rationale_preview,
+            } => {
+                let confidence = confidence.map(|c| format!(" {:.2}", c)).unwrap_or_default();
+                format!(
+                    "[{}] critic:decision:{} {}{} {}",
+                    timestamp,
+                    iteration + 1,
+                    decision,
+                    confidence,
+                    rationale_preview
+                )
+            }
             LogEvent::LoopCompleted {
                 iterations,
                 duration_secs,
@@ -443,9 +851,81 @@ impl Logger {
                 "[{}] loop:done:{} {:.1}s",
                 timestamp, iterations, duration_secs
             ),
+            LogEvent::RunSummary {
+                files_changed,
+                insertions,
+                deletions,
+                iterations,
+            } => format!(
+                "[{}] run:summary:{} {}f +{} -{}",
+                timestamp, iterations, files_changed, insertions, deletions
+            ),
+            LogEvent::ApproachingMaxIterations {
+                iteration,
+                max_iterations,
+            } => format!(
+                "[{}] loop:near-limit:{}/{}",
+                timestamp, iteration, max_iterations
+            ),
             LogEvent::MaxIterationsReached { iterations } => {
                 format!("[{}] loop:limit:{}", timestamp, iterations)
             }
+            LogEvent::NoProgress {
+                iterations,
+                best_confidence,
+                ..
+            } => format!(
+                "[{}] loop:no-progress:{} best={:.2}",
+                timestamp, iterations, best_confidence
+            ),
+            LogEvent::ChangesStalled {
+                iterations,
+                unchanged,
+            } => format!(
+                "[{}] loop:changes-stalled:{} unchanged={}",
+                timestamp, iterations, unchanged
+            ),
+            LogEvent::CostBudgetIgnored { max_cost_usd } => {
+                format!("[{}] cost:ignored max={:.2}", timestamp, max_cost_usd)
+            }
+            LogEvent::CostBudgetExceeded {
+                iterations,
+                spent_usd,
+                max_cost_usd,
+            } => format!(
+                "[{}] loop:budget:{} spent={:.2} max={:.2}",
+                timestamp, iterations, spent_usd, max_cost_usd
+            ),
+            LogEvent::AgentIdle {
+                iteration,
+                role,
+                idle_secs,
+            } => format!(
+                "[{}] {}:idle:{} {}s",
+                timestamp,
+                role.as_str(),
+                iteration + 1,
+                idle_secs
+            ),
+            LogEvent::AgentRetrying {
+                iteration,
+                role,
+                attempt,
+                retries,
+            } => format!(
+                "[{}] {}:retry:{} {}/{}",
+                timestamp,
+                role.as_str(),
+                iteration + 1,
+                attempt,
+                retries
+            ),
+            LogEvent::LoopPaused { iteration } => {
+                format!("[{}] loop:paused:{}", timestamp, iteration)
+            }
+            LogEvent::LoopResumed { iteration } => {
+                format!("[{}] loop:resumed:{}", timestamp, iteration)
+            }
             LogEvent::ErrorEncountered { iteration, error } => {
                 format!("[{}] error:{}:{}", timestamp, iteration + 1, error)
             }
@@ -458,6 +938,56 @@ impl Logger {
                 "[{}] git:{} {}f +{} -{}",
                 timestamp, iteration, files_changed, insertions, deletions
             ),
+            LogEvent::VerifyCompleted {
+                iteration,
+                passed,
+                exit_code,
+                regression,
+            } => {
+                let at = iteration
+                    .map(|i| (i + 1).to_string())
+                    .unwrap_or_else(|| "baseline".to_string());
+                let result = if *passed {
+                    "pass"
+                } else if *regression {
+                    "regression"
+                } else {
+                    "fail"
+                };
+                format!(
+                    "[{}] verify:{} {} exit={}",
+                    timestamp, at, result, exit_code
+                )
+            }
+            LogEvent::PatchApplied {
+                iteration,
+                files_changed,
+            } => format!(
+                "[{}] patch:{} applied {}f",
+                timestamp,
+                iteration + 1,
+                files_changed
+            ),
+            LogEvent::PatchRejected { iteration, error } => {
+                format!("[{}] patch:{} rejected {}", timestamp, iteration + 1, error)
+            }
+            LogEvent::IterationReviewed { iteration, verdict } => {
+                format!("[{}] review:{} {}", timestamp, iteration + 1, verdict)
+            }
+            LogEvent::CoverageMeasured {
+                iteration,
+                percent,
+                delta,
+            } => {
+                let at = iteration
+                    .map(|i| (i + 1).to_string())
+                    .unwrap_or_else(|| "baseline".to_string());
+                let percent = percent
+                    .map(|p| format!("{:.1}%", p))
+                    .unwrap_or_else(|| "none".to_string());
+                let delta = delta.map(|d| format!(" {:+.1}", d)).unwrap_or_default();
+                format!("[{}] coverage:{} {}{}", timestamp, at, percent, delta)
+            }
             LogEvent::AgentStreamLine { role, line, .. } => {
                 let role_str = match role {
                     AgentRole::Actor => "A",
@@ -500,3 +1030,20 @@ impl Logger {
         format!("{}{}│", truncated, " ".repeat(padding_needed))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_summary_line_pluralizes() {
+        assert_eq!(
+            run_summary_line(3, 120, 14, 2),
+            "3 files changed, +120 -14 lines in 2 iterations"
+        );
+        assert_eq!(
+            run_summary_line(1, 1, 0, 1),
+            "1 file changed, +1 -0 lines in 1 iteration"
+        );
+    }
+}
@@ -17,9 +17,11 @@
 //! - `Compact` - Minimal text output
 
 mod events;
+mod status;
 
 pub use events::{
-    AgentRole, EventCallback, FileChangeType, LogEvent, LogFormat, Logger, StreamType,
+    run_summary_line, AgentRole, EventCallback, FileChangeType, LogEvent, LogFormat, Logger,
+    StreamType,
 };
 
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
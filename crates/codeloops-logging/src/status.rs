@@ -0,0 +1,167 @@
+//! Animated status line for the built-in pretty output.
+//!
+//! While an agent runs, pretty mode would otherwise print nothing until the
+//! first output line arrives. [`StatusLine`] keeps a single
+//! `⠋ actor running... 12s` line at the bottom of stderr, redrawn by a
+//! background thread. The logger clears it before writing anything else, so
+//! it never interleaves with real output.
+
+use std::io::Write;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+
+use crate::events::LogEvent;
+
+const FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const TICK: Duration = Duration::from_millis(100);
+
+#[derive(Default)]
+struct State {
+    /// Label and start time of the running phase, if any
+    active: Option<(&'static str, Instant)>,
+    /// Whether the line is currently on screen
+    drawn: bool,
+    frame: usize,
+}
+
+impl State {
+    fn clear(&mut self, out: &mut impl Write) {
+        if self.drawn {
+            let _ = write!(out, "\r\x1b[2K");
+            let _ = out.flush();
+            self.drawn = false;
+        }
+    }
+
+    fn draw(&mut self, out: &mut impl Write) {
+        if let Some((label, started)) = self.active {
+            let frame = FRAMES[self.frame % FRAMES.len()];
+            self.frame += 1;
+            let text = format!("{} {} {}s", frame, label, started.elapsed().as_secs());
+            let _ = write!(out, "\r\x1b[2K    {}", text.dimmed());
+            let _ = out.flush();
+            self.drawn = true;
+        }
+    }
+}
+
+/// Spinner line shown while the actor or critic is running.
+pub(crate) struct StatusLine {
+    state: Arc<Mutex<State>>,
+}
+
+impl StatusLine {
+    /// Start the redraw thread. It exits once the status line is dropped.
+    pub(crate) fn new() -> Self {
+        let state = Arc::new(Mutex::new(State::default()));
+        let weak = Arc::downgrade(&state);
+        std::thread::spawn(move || redraw_loop(weak));
+        Self { state }
+    }
+
+    /// Clear the line, run `print` (which writes `event`), then start or
+    /// stop the spinner according to the event.
+    pub(crate) fn around(&self, event: &LogEvent, print: impl FnOnce()) {
+        let Ok(mut state) = self.state.lock() else {
+            print();
+            return;
+        };
+        state.clear(&mut std::io::stderr());
+        print();
+        match transition(event) {
+            Some(Some(label)) => {
+                state.active = Some((label, Instant::now()));
+                state.frame = 0;
+            }
+            Some(None) => state.active = None,
+            None => {}
+        }
+    }
+}
+
+impl Drop for StatusLine {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.active = None;
+            state.clear(&mut std::io::stderr());
+        }
+    }
+}
+
+fn redraw_loop(state: Weak<Mutex<State>>) {
+    loop {
+        std::thread::sleep(TICK);
+        let Some(shared) = state.upgrade() else {
+            return;
+        };
+        if let Ok(mut guard) = shared.lock() {
+            guard.draw(&mut std::io::stderr());
+        };
+    }
+}
+
+/// How `event` changes the spinner: `Some(Some(label))` starts it,
+/// `Some(None)` stops it, `None` leaves it as is.
+fn transition(event: &LogEvent) -> Option<Option<&'static str>> {
+    match event {
+        LogEvent::ActorStarted { .. } => Some(Some("actor running...")),
+        LogEvent::CriticStarted { .. } => Some(Some("critic running...")),
+        LogEvent::ActorCompleted { .. }
+        | LogEvent::CriticCompleted { .. }
+        | LogEvent::LoopCompleted { .. }
+        | LogEvent::MaxIterationsReached { .. }
+        | LogEvent::NoProgress { .. }
+        | LogEvent::ChangesStalled { .. }
+        | LogEvent::CostBudgetExceeded { .. }
+        | LogEvent::ErrorEncountered { .. } => Some(None),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_on_agent_start_and_stops_on_completion() {
+        assert_eq!(
+            transition(&LogEvent::ActorStarted {
+                iteration: 0,
+                prompt_preview: String::new(),
+            }),
+            Some(Some("actor running..."))
+        );
+        assert_eq!(
+            transition(&LogEvent::CriticCompleted {
+                iteration: 0,
+                decision: "DONE".to_string(),
+            }),
+            Some(None)
+        );
+        assert_eq!(
+            transition(&LogEvent::CriticStarted { iteration: 0 }),
+            Some(Some("critic running..."))
+        );
+    }
+
+    #[test]
+    fn clears_only_after_drawing() {
+        let mut state = State {
+            active: Some(("actor running...", Instant::now())),
+            ..State::default()
+        };
+        let mut out = Vec::new();
+        state.clear(&mut out);
+        assert!(out.is_empty());
+
+        state.draw(&mut out);
+        assert!(String::from_utf8_lossy(&out).contains("actor running... 0s"));
+
+        out.clear();
+        state.clear(&mut out);
+        assert_eq!(out, b"\r\x1b[2K");
+        assert!(!state.drawn);
+    }
+}
@@ -40,6 +40,8 @@ pub enum CriticVerdict {
 pub enum FinalKind {
     Success,
     MaxIterations,
+    NoProgress,
+    CostBudgetExceeded,
     Interrupted,
     Failed,
 }
@@ -77,6 +79,8 @@ pub enum ScrollbackLine {
         n: usize,
         message: Option<String>,
     },
+    /// A one-line status message, e.g. the loop was paused
+    Notice(String),
     Final {
         kind: FinalKind,
         total_elapsed: Duration,
@@ -123,6 +127,7 @@ pub enum RenderEvent {
     CriticError {
         message: Option<String>,
     },
+    Notice(String),
     FinalSuccess {
         iterations: usize,
         total_duration_secs: f64,
@@ -133,6 +138,16 @@ pub enum RenderEvent {
         iterations: usize,
         total_duration_secs: f64,
     },
+    FinalNoProgress {
+        iterations: usize,
+        total_duration_secs: f64,
+        reason: String,
+    },
+    FinalCostBudgetExceeded {
+        iterations: usize,
+        total_duration_secs: f64,
+        spent_usd: f64,
+    },
     FinalInterrupted {
         iterations: usize,
         total_duration_secs: f64,
@@ -317,6 +332,8 @@ impl AppState {
                 });
             }
 
+            RenderEvent::Notice(text) => out.push(ScrollbackLine::Notice(text)),
+
             RenderEvent::FinalSuccess {
                 iterations,
                 total_duration_secs,
@@ -351,6 +368,40 @@ impl AppState {
                 });
             }
 
+            RenderEvent::FinalNoProgress {
+                iterations,
+                total_duration_secs,
+                reason,
+            } => {
+                self.phase = Phase::Done;
+                out.push(ScrollbackLine::Final {
+                    kind: FinalKind::NoProgress,
+                    total_elapsed: Duration::from_secs_f64(total_duration_secs.max(0.0)),
+                    iterations,
+                    prompt: self.prompt.clone(),
+                    error: None,
+                    summary: Some(reason),
+                    confidence: None,
+                });
+            }
+
+            RenderEvent::FinalCostBudgetExceeded {
+                iterations,
+                total_duration_secs,
+                spent_usd,
+            } => {
+                self.phase = Phase::Done;
+                out.push(ScrollbackLine::Final {
+                    kind: FinalKind::CostBudgetExceeded,
+                    total_elapsed: Duration::from_secs_f64(total_duration_secs.max(0.0)),
+                    iterations,
+                    prompt: self.prompt.clone(),
+                    error: None,
+                    summary: Some(format!("spent about ${:.2}", spent_usd)),
+                    confidence: None,
+                });
+            }
+
             RenderEvent::FinalInterrupted {
                 iterations,
                 total_duration_secs,
@@ -448,6 +499,16 @@ mod tests {
         assert_eq!(s.total_events_this_iter, 0);
     }
 
+    #[test]
+    fn apply_notice_goes_to_scrollback_without_changing_phase() {
+        let mut s = AppState::new();
+        s.apply(RenderEvent::IterationStart { iteration: 1 });
+        let phase = s.phase;
+        let out = s.apply(RenderEvent::Notice("paused".into()));
+        assert!(matches!(out.as_slice(), [ScrollbackLine::Notice(text)] if text == "paused"));
+        assert_eq!(s.phase, phase);
+    }
+
     #[test]
     fn apply_file_change_pushes_and_caps_recent() {
         let mut s = AppState::new();
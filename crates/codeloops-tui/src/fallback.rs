@@ -166,6 +166,9 @@ impl FallbackRenderer {
                     let _ = writeln!(w, "critic: error");
                 }
             },
+            RenderEvent::Notice(text) => {
+                let _ = writeln!(w, "{}", text);
+            }
             RenderEvent::FinalSuccess {
                 iterations,
                 total_duration_secs,
@@ -207,6 +210,34 @@ impl FallbackRenderer {
                     format_elapsed(*total_duration_secs as u64),
                 );
             }
+            RenderEvent::FinalNoProgress {
+                iterations,
+                total_duration_secs,
+                reason,
+            } => {
+                let _ = writeln!(
+                    w,
+                    "=== {} · {} iterations · {} ===",
+                    yellow("stalled"),
+                    iterations,
+                    format_elapsed(*total_duration_secs as u64),
+                );
+                let _ = writeln!(w, "{}", dim(reason));
+            }
+            RenderEvent::FinalCostBudgetExceeded {
+                iterations,
+                total_duration_secs,
+                spent_usd,
+            } => {
+                let _ = writeln!(
+                    w,
+                    "=== {} · {} iterations · {} · ${:.2} ===",
+                    yellow("over budget"),
+                    iterations,
+                    format_elapsed(*total_duration_secs as u64),
+                    spent_usd,
+                );
+            }
             RenderEvent::FinalInterrupted {
                 iterations,
                 total_duration_secs,
@@ -36,7 +36,7 @@ use ratatui::{
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
-use codeloops_logging::LogEvent;
+use codeloops_logging::{run_summary_line, LogEvent};
 
 pub use app::{
     AppState, CriticVerdict, DiffStats, FileEvent, FinalKind, Phase, RenderEvent, ScrollbackLine,
@@ -144,6 +144,81 @@ impl SessionRenderer {
             LogEvent::CriticStarted { .. } => {
                 let _ = self.tx.send(Msg::Event(RenderEvent::CriticStart));
             }
+            LogEvent::AgentIdle {
+                role, idle_secs, ..
+            } => {
+                let _ = self.tx.send(Msg::Event(RenderEvent::Notice(format!(
+                    "⚠ {} has produced no output for {}s",
+                    role.as_str(),
+                    idle_secs
+                ))));
+            }
+            LogEvent::AgentRetrying {
+                role,
+                attempt,
+                retries,
+                ..
+            } => {
+                let _ = self.tx.send(Msg::Event(RenderEvent::Notice(format!(
+                    "⚠ {} failed; retrying ({}/{})",
+                    role.as_str(),
+                    attempt,
+                    retries
+                ))));
+            }
+            LogEvent::PatchRejected { error, .. } => {
+                let _ = self.tx.send(Msg::Event(RenderEvent::Notice(format!(
+                    "✗ patch did not apply: {}",
+                    error
+                ))));
+            }
+            LogEvent::IterationReviewed { verdict, .. } => {
+                let _ = self.tx.send(Msg::Event(RenderEvent::Notice(format!(
+                    "reviewer: {}",
+                    verdict
+                ))));
+            }
+            LogEvent::CostBudgetIgnored { max_cost_usd } => {
+                let _ = self.tx.send(Msg::Event(RenderEvent::Notice(format!(
+                    "⚠ the agents report no costs; ignoring the ${:.2} budget",
+                    max_cost_usd
+                ))));
+            }
+            LogEvent::LoopPaused { iteration } => {
+                let _ = self.tx.send(Msg::Event(RenderEvent::Notice(format!(
+                    "⏸ paused before iteration {} — press r to resume, q to quit",
+                    iteration
+                ))));
+            }
+            LogEvent::LoopResumed { iteration } => {
+                let _ = self.tx.send(Msg::Event(RenderEvent::Notice(format!(
+                    "▶ resumed with iteration {}",
+                    iteration
+                ))));
+            }
+            LogEvent::RunSummary {
+                files_changed,
+                insertions,
+                deletions,
+                iterations,
+            } => {
+                let _ = self.tx.send(Msg::Event(RenderEvent::Notice(format!(
+                    "Σ {}",
+                    run_summary_line(*files_changed, *insertions, *deletions, *iterations)
+                ))));
+            }
+            LogEvent::CriticDecision {
+                decision,
+                confidence,
+                rationale_preview,
+                ..
+            } => {
+                if let Some(notice) =
+                    critic_decision_notice(decision, *confidence, rationale_preview)
+                {
+                    let _ = self.tx.send(Msg::Event(RenderEvent::Notice(notice)));
+                }
+            }
             LogEvent::CriticCompleted { decision, .. } => {
                 let ev = if decision.contains("DONE") {
                     RenderEvent::CriticDone
@@ -163,9 +238,16 @@ impl SessionRenderer {
             // which never emit log events.
             LogEvent::LoopCompleted { .. }
             | LogEvent::MaxIterationsReached { .. }
+            | LogEvent::NoProgress { .. }
+            | LogEvent::ChangesStalled { .. }
+            | LogEvent::CostBudgetExceeded { .. }
+            | LogEvent::ApproachingMaxIterations { .. }
             | LogEvent::ErrorEncountered { .. }
             | LogEvent::AgentStreamLine { .. }
-            | LogEvent::ActorOutput { .. } => {}
+            | LogEvent::ActorOutput { .. }
+            | LogEvent::VerifyCompleted { .. }
+            | LogEvent::CoverageMeasured { .. }
+            | LogEvent::PatchApplied { .. } => {}
         }
     }
 
@@ -192,6 +274,25 @@ impl Drop for SessionRenderer {
     }
 }
 
+/// Scrollback line for the critic's reasoning, e.g.
+/// `critic DONE (85%): all tests pass`. None without a rationale.
+fn critic_decision_notice(
+    decision: &str,
+    confidence: Option<f64>,
+    rationale_preview: &str,
+) -> Option<String> {
+    if rationale_preview.is_empty() {
+        return None;
+    }
+    let confidence = confidence
+        .map(|c| format!(" ({:.0}%)", c * 100.0))
+        .unwrap_or_default();
+    Some(format!(
+        "critic {}{}: {}",
+        decision, confidence, rationale_preview
+    ))
+}
+
 fn is_dumb_terminal() -> bool {
     matches!(
         std::env::var("TERM").as_deref(),
@@ -448,6 +549,10 @@ fn render_scrollback_line(line: &ScrollbackLine) -> Vec<Line<'static>> {
             }
             lines
         }
+        ScrollbackLine::Notice(text) => vec![Line::from(vec![
+            Span::raw("  "),
+            Span::styled(text.clone(), Style::default().fg(Color::Yellow)),
+        ])],
         ScrollbackLine::Final {
             kind,
             total_elapsed,
@@ -461,6 +566,8 @@ fn render_scrollback_line(line: &ScrollbackLine) -> Vec<Line<'static>> {
             let (sigil, color, label) = match kind {
                 FinalKind::Success => ("✓", Color::Green, "codeloops done"),
                 FinalKind::MaxIterations => ("⚠", Color::Yellow, "codeloops incomplete"),
+                FinalKind::NoProgress => ("⚠", Color::Yellow, "codeloops stalled"),
+                FinalKind::CostBudgetExceeded => ("⚠", Color::Yellow, "codeloops over budget"),
                 FinalKind::Interrupted => ("⏸", Color::Yellow, "codeloops interrupted"),
                 FinalKind::Failed => ("✗", Color::Red, "codeloops failed"),
             };
@@ -636,4 +743,17 @@ mod tests {
 
         assert_eq!(state.phase, Phase::Idle);
     }
+
+    #[test]
+    fn critic_decisions_show_their_rationale() {
+        assert_eq!(
+            critic_decision_notice("DONE", Some(0.85), "all tests pass").as_deref(),
+            Some("critic DONE (85%): all tests pass")
+        );
+        assert_eq!(
+            critic_decision_notice("CONTINUE", None, "handle the empty case").as_deref(),
+            Some("critic CONTINUE: handle the empty case")
+        );
+        assert_eq!(critic_decision_notice("DONE", Some(0.9), ""), None);
+    }
 }
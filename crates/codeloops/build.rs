@@ -0,0 +1,44 @@
+//! Embeds the commit codeloops was built from as `CODELOOPS_GIT_SHA`, for
+//! `codeloops --version --json`.
+//!
+//! A `CODELOOPS_GIT_SHA` set in the build environment wins, for packaged
+//! builds made outside a git checkout. Otherwise the SHA comes from `git`,
+//! and is `unknown` when that fails.
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=CODELOOPS_GIT_SHA");
+
+    let sha = std::env::var("CODELOOPS_GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(git_sha)
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=CODELOOPS_GIT_SHA={}", sha);
+}
+
+fn git_sha() -> Option<String> {
+    let git_dir = git(&["rev-parse", "--absolute-git-dir"])?;
+    // Rebuild when HEAD moves: a checkout changes HEAD, a commit changes the
+    // branch it points to
+    let head = Path::new(&git_dir).join("HEAD");
+    println!("cargo:rerun-if-changed={}", head.display());
+    if let Some(branch) = git(&["symbolic-ref", "-q", "HEAD"]) {
+        let branch = Path::new(&git_dir).join(branch);
+        if branch.exists() {
+            println!("cargo:rerun-if-changed={}", branch.display());
+        }
+    }
+    git(&["rev-parse", "--short=12", "HEAD"])
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let out = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!out.is_empty()).then_some(out)
+}
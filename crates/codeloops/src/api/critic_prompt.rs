@@ -0,0 +1,194 @@
+//! `GET /api/critic-prompt`: the prompt the critic agent is given, rendered
+//! from a recorded iteration so the UI can show how reviews are instructed.
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde::{Deserialize, Serialize};
+
+use codeloops_core::PromptDraft;
+use codeloops_critic::{CoverageChange, CriticEvaluationInput, CriticPrompts};
+use codeloops_db::{Iteration, Session};
+
+use super::{internal_error, AppState};
+
+#[derive(Debug, Deserialize)]
+pub struct CriticPromptParams {
+    /// Session to render from; the latest one when absent
+    pub session: Option<String>,
+    /// Iteration number as stored; the session's last iteration when absent
+    pub iteration: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CriticPromptResponse {
+    pub prompt: String,
+    /// Session the placeholders were filled from; `None` for the sample
+    pub session_id: Option<String>,
+    pub iteration: Option<usize>,
+}
+
+/// Render the critic prompt for an iteration of `session` (or the latest
+/// session). With no sessions recorded, placeholders show what goes where.
+///
+/// Only what the session records is filled in: run options such as a rubric,
+/// `--allow-critic-reset` or `--critic-diff-scope` are not stored, so their
+/// sections are left out.
+pub async fn get_critic_prompt(
+    State(state): State<AppState>,
+    Query(params): Query<CriticPromptParams>,
+) -> Result<Json<CriticPromptResponse>, (StatusCode, String)> {
+    let sessions = state.db()?.sessions();
+
+    let session_id = match params.session {
+        Some(id) => Some(id),
+        None => sessions.latest_id().map_err(internal_error)?,
+    };
+    let Some(session_id) = session_id else {
+        return Ok(Json(CriticPromptResponse {
+            prompt: sample_prompt(params.iteration.unwrap_or(0)),
+            session_id: None,
+            iteration: None,
+        }));
+    };
+    let session = sessions
+        .get(&session_id)
+        .map_err(internal_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Session not found: {}", session_id),
+            )
+        })?;
+
+    let iteration = match params.iteration {
+        Some(number) => session
+            .iterations
+            .iter()
+            .find(|iter| iter.iteration_number == number)
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Session {} has no iteration {}", session_id, number),
+                )
+            })?,
+        None => session.iterations.last().ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Session {} has no iterations", session_id),
+            )
+        })?,
+    };
+
+    Ok(Json(CriticPromptResponse {
+        prompt: session_prompt(&session, iteration),
+        iteration: Some(iteration.iteration_number),
+        session_id: Some(session_id),
+    }))
+}
+
+/// The prompt the critic got (or would get) for `iteration` of `session`.
+fn session_prompt(session: &Session, iteration: &Iteration) -> String {
+    let acceptance_criteria = PromptDraft::from_markdown(&session.prompt).acceptance_criteria;
+    let coverage = iteration.coverage_percent.map(|current| CoverageChange {
+        baseline: iteration.coverage_delta.map(|delta| current - delta),
+        current,
+    });
+    CriticPrompts::build_evaluation_prompt(&CriticEvaluationInput {
+        original_task: &session.prompt,
+        actor_stdout: iteration.actor_output.as_deref().unwrap_or_default(),
+        actor_stderr: iteration.actor_stderr.as_deref().unwrap_or_default(),
+        git_diff: iteration.git_diff.as_deref().unwrap_or_default(),
+        iteration: iteration.iteration_number,
+        allow_reset: false,
+        acceptance_criteria: &acceptance_criteria,
+        coverage,
+        reference: None,
+        rubric: None,
+        diff_scope: None,
+        scope_adherence: iteration.scope_adherence.as_ref(),
+        regression: None,
+    })
+}
+
+/// The prompt with placeholders in place of a real iteration's data.
+fn sample_prompt(iteration: usize) -> String {
+    CriticPrompts::build_evaluation_prompt(&CriticEvaluationInput {
+        original_task: "<the task prompt>",
+        actor_stdout: "<what the actor printed>",
+        actor_stderr: "<the actor's error output>",
+        git_diff: "<the iteration's git diff>",
+        iteration,
+        allow_reset: false,
+        acceptance_criteria: &[],
+        coverage: None,
+        reference: None,
+        rubric: None,
+        diff_scope: None,
+        scope_adherence: None,
+        regression: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use codeloops_db::{Database, SessionStart};
+
+    #[tokio::test]
+    async fn renders_the_latest_iteration_or_a_sample() {
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let state = AppState {
+            db: Ok(db.clone()),
+            sessions_dir: Default::default(),
+            interview: Default::default(),
+            in_flight: Default::default(),
+            stats_cache: Default::default(),
+        };
+        let render = |session: Option<&str>, iteration: Option<usize>| {
+            let params = CriticPromptParams {
+                session: session.map(str::to_string),
+                iteration,
+            };
+            get_critic_prompt(State(state.clone()), Query(params))
+        };
+
+        let Json(sample) = render(None, None).await.unwrap();
+        assert_eq!(sample.session_id, None);
+        assert!(sample.prompt.contains("<the iteration's git diff>"));
+
+        let id = db
+            .sessions()
+            .create(&SessionStart {
+                prompt: "Fix the parser".to_string(),
+                working_dir: PathBuf::from("/work/api"),
+                actor_agent: "Claude Code".to_string(),
+                critic_agent: "Claude Code".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        for (iteration, diff) in [(0, "+first"), (1, "+second")] {
+            db.sessions().start_iteration(&id, iteration).unwrap();
+            db.sessions()
+                .complete_diff(&id, iteration, diff, 1, &[])
+                .unwrap();
+        }
+
+        let Json(latest) = render(None, None).await.unwrap();
+        assert_eq!(latest.session_id.as_deref(), Some(id.as_str()));
+        assert_eq!(latest.iteration, Some(1));
+        assert!(latest.prompt.contains("Fix the parser"));
+        assert!(latest.prompt.contains("+second"));
+        assert!(latest.prompt.contains("This is iteration 2"));
+
+        let Json(first) = render(Some(&id), Some(0)).await.unwrap();
+        assert!(first.prompt.contains("+first"));
+
+        let missing = render(Some(&id), Some(5)).await.unwrap_err();
+        assert_eq!(missing.0, StatusCode::NOT_FOUND);
+    }
+}
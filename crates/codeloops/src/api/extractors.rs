@@ -50,8 +50,10 @@ impl FromRequestParts<AppState> for ProjectExtractor {
             )
         })?;
 
-        let project = state
-            .db
+        let db = state
+            .db()
+            .map_err(|(status, error)| (status, Json(ErrorResponse { error })))?;
+        let project = db
             .projects()
             .get(project_id)
             .map_err(|e| {
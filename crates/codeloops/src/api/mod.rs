@@ -1,12 +1,23 @@
+mod critic_prompt;
 pub mod extractors;
 mod projects;
 mod prompt;
 mod prompt_instructions;
+mod scanner;
 mod sessions;
 mod stats;
 
-use std::sync::Arc;
+pub use prompt::InterviewOverrides;
+pub(crate) use prompt::{render_transcript, SessionStatePayload};
 
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::Request;
+use axum::http::{Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post, put};
 use axum::Router;
 use tower_http::cors::CorsLayer;
@@ -15,13 +26,56 @@ use codeloops_db::Database;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub db: Arc<Database>,
+    /// The database, or why it could not be opened. Read it with
+    /// [`AppState::db`].
+    pub db: Result<Arc<Database>, Arc<str>>,
+    /// Where per-session live output files are read from
+    pub sessions_dir: Arc<PathBuf>,
+    /// Interview agent chosen on the `codeloops ui` command line
+    pub interview: Arc<InterviewOverrides>,
+    /// Prompt sessions with an agent reply in progress in this process
+    pub in_flight: Arc<Mutex<HashSet<String>>>,
+    /// Dashboard stats and metrics, kept until the database changes
+    pub stats_cache: Arc<stats::StatsCache>,
+}
+
+impl AppState {
+    /// The database, or a 503 when it could not be opened. Routes that do
+    /// not need it keep working, so a broken database does not take the
+    /// whole server down.
+    pub fn db(&self) -> Result<&Arc<Database>, (StatusCode, String)> {
+        self.db.as_ref().map_err(|reason| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("Database unavailable: {}", reason),
+            )
+        })
+    }
+}
+
+/// A 500 carrying `e`'s message.
+fn internal_error(e: impl ToString) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
 }
 
-pub fn create_router(db: Arc<Database>) -> Router {
-    let state = AppState { db };
+/// Build the API router. `db` is the error message when the database could
+/// not be opened. A `read_only` router answers 403 to every request that
+/// could change data or start an agent.
+pub fn create_router(
+    db: Result<Arc<Database>, String>,
+    sessions_dir: PathBuf,
+    interview: InterviewOverrides,
+    read_only: bool,
+) -> Router {
+    let state = AppState {
+        db: db.map_err(Arc::from),
+        sessions_dir: Arc::new(sessions_dir),
+        interview: Arc::new(interview),
+        in_flight: Arc::default(),
+        stats_cache: Arc::default(),
+    };
 
-    Router::new()
+    let router = Router::new()
         // Project CRUD (not scoped — top-level resource)
         .route("/api/projects", get(projects::list_projects))
         .route("/api/projects", post(projects::create_project))
@@ -43,16 +97,22 @@ pub fn create_router(db: Arc<Database>) -> Router {
         )
         .route(
             "/api/projects/{project_id}/sessions/{id}",
-            get(sessions::get_session),
+            get(sessions::get_session).patch(sessions::update_session),
         )
         .route(
             "/api/projects/{project_id}/sessions/{id}/diff",
             get(sessions::get_session_diff),
         )
+        .route(
+            "/api/projects/{project_id}/sessions/{id}/export",
+            get(sessions::export_session),
+        )
         .route(
             "/api/projects/{project_id}/sessions/{id}/output/{iteration}/{phase}",
             get(sessions::stream_output),
         )
+        // Live session events, optionally filtered with `?project=`
+        .route("/api/sessions/live", get(sessions::stream_live))
         // Project-scoped stats
         .route("/api/projects/{project_id}/stats", get(stats::get_stats))
         .route(
@@ -61,6 +121,8 @@ pub fn create_router(db: Arc<Database>) -> Router {
         )
         // Project-scoped prompt builder
         .route("/api/skills", get(prompt::list_skills))
+        // Critic prompt rendered from a recorded iteration, `?session=&iteration=`
+        .route("/api/critic-prompt", get(critic_prompt::get_critic_prompt))
         .route(
             "/api/projects/{project_id}/prompt-session",
             post(prompt::create_session),
@@ -90,7 +152,106 @@ pub fn create_router(db: Arc<Database>) -> Router {
         .route(
             "/api/projects/{project_id}/prompts/{id}/resolved",
             get(prompt::get_resolved_prompt),
+        );
+    let router = if read_only {
+        router.layer(middleware::from_fn(reject_writes))
+    } else {
+        router
+    };
+    router.layer(CorsLayer::permissive()).with_state(state)
+}
+
+/// Let through only requests that read: every route that writes or runs an
+/// agent uses another method. OPTIONS is kept for CORS preflight.
+async fn reject_writes(request: Request, next: Next) -> Response {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) {
+        next.run(request).await
+    } else {
+        (StatusCode::FORBIDDEN, "The server is read-only").into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::extract::{Path, Query, State};
+    use std::collections::HashMap;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn routes_needing_the_database_answer_503_without_it() {
+        let state = AppState {
+            db: Err(Arc::from("unable to open database file")),
+            sessions_dir: Arc::new(PathBuf::new()),
+            interview: Arc::default(),
+            in_flight: Arc::default(),
+            stats_cache: Arc::default(),
+        };
+        let (status, message) = stats::get_stats(
+            State(state),
+            Path(HashMap::new()),
+            Query(stats::StatsParams { tz: None }),
         )
-        .layer(CorsLayer::permissive())
-        .with_state(state)
+        .await
+        .err()
+        .unwrap();
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            message,
+            "Database unavailable: unable to open database file"
+        );
+    }
+
+    async fn status(router: &Router, method: Method, uri: &str) -> StatusCode {
+        let request = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from("{}"))
+            .unwrap();
+        router.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn read_only_router_blocks_writes_but_serves_reads() {
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let router = create_router(Ok(db), PathBuf::new(), Default::default(), true);
+
+        assert_eq!(
+            status(&router, Method::GET, "/api/projects").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&router, Method::GET, "/api/projects/p/stats").await,
+            StatusCode::OK
+        );
+        for (method, uri) in [
+            (Method::POST, "/api/projects"),
+            (Method::DELETE, "/api/projects/p"),
+            (Method::PATCH, "/api/projects/p/sessions/s"),
+            (Method::POST, "/api/projects/p/prompt-session"),
+            (Method::POST, "/api/projects/p/prompt-session/s/message"),
+            (Method::DELETE, "/api/projects/p/prompts/1"),
+        ] {
+            assert_eq!(
+                status(&router, method.clone(), uri).await,
+                StatusCode::FORBIDDEN,
+                "{} {}",
+                method,
+                uri
+            );
+        }
+
+        // The same write goes through to the handler without the flag
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let router = create_router(Ok(db), PathBuf::new(), Default::default(), false);
+        assert_ne!(
+            status(&router, Method::DELETE, "/api/projects/p").await,
+            StatusCode::FORBIDDEN
+        );
+    }
 }
@@ -44,7 +44,7 @@ pub async fn list_projects(
     State(state): State<AppState>,
 ) -> Result<Json<ProjectListResponse>, (StatusCode, String)> {
     let projects = state
-        .db
+        .db()?
         .projects()
         .list()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -69,7 +69,7 @@ pub async fn create_project(
 
     // Check for duplicate
     if state
-        .db
+        .db()?
         .projects()
         .get_by_path(&path_str)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -91,7 +91,7 @@ pub async fn create_project(
     });
 
     let project = state
-        .db
+        .db()?
         .projects()
         .add(&NewProject {
             path: path_str,
@@ -109,7 +109,7 @@ pub async fn get_project(
     Path(project_id): Path<String>,
 ) -> Result<Json<ProjectRecord>, (StatusCode, String)> {
     let project = state
-        .db
+        .db()?
         .projects()
         .get(&project_id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -131,7 +131,7 @@ pub async fn update_project(
     };
 
     let project = state
-        .db
+        .db()?
         .projects()
         .update(&project_id, &update)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -146,7 +146,7 @@ pub async fn delete_project(
     Path(project_id): Path<String>,
 ) -> Result<StatusCode, (StatusCode, String)> {
     let deleted = state
-        .db
+        .db()?
         .projects()
         .remove(&project_id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -164,7 +164,7 @@ pub async fn get_project_context(
     Path(project_id): Path<String>,
 ) -> Result<Json<ProjectRecord>, (StatusCode, String)> {
     let project = state
-        .db
+        .db()?
         .projects()
         .get(&project_id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
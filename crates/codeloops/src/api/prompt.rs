@@ -16,7 +16,7 @@
 //! - `GET /api/prompts` - List all prompts
 //! - `GET /api/prompts/{id}` - Get single prompt
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
@@ -26,8 +26,12 @@ use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::sse::{Event, Sse};
 use axum::response::Json;
-use chrono::Utc;
-use codeloops_agent::{create_agent, AgentConfig, AgentType, OutputCallback, OutputType};
+use chrono::{DateTime, Utc};
+use codeloops_agent::{
+    create_agent, Agent, AgentConfig, AgentType, CustomAgent, CustomCommand, IdleMonitor,
+    OutputCallback, OutputCallbacks, OutputType,
+};
+use codeloops_core::{PromptDraft, DEFAULT_IDLE_WARNING_SECS};
 use codeloops_db::{Database, PromptFilter, PromptRecord};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
@@ -35,7 +39,9 @@ use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 
 use super::prompt_instructions::get_system_instructions;
+use super::scanner::scan_project;
 use super::AppState;
+use crate::config::{GlobalConfig, ProjectConfig};
 use codeloops_skills::skills;
 
 // ============================================================================
@@ -61,6 +67,10 @@ pub struct CreateSessionResponse {
 pub struct SendMessageRequest {
     pub content: String,
     pub enabled_skills: Option<Vec<String>>,
+    /// Ask again for a reply to the last user message instead of sending a
+    /// new one (see `GetPromptResponse::dangling_message`)
+    #[serde(default)]
+    pub resend: bool,
 }
 
 #[derive(Deserialize)]
@@ -68,11 +78,24 @@ pub struct SendMessageRequest {
 pub struct SavePromptRequest {
     pub working_dir: String,
     pub content: String,
+    /// Return the path and content without writing, for a confirm step
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SavePromptResponse {
     pub path: String,
+    /// Content that would be written; only set for a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Whether a prompt.md already exists there; only set for a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exists: Option<bool>,
+    /// Where the overwritten prompt.md was copied to, if there was one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_path: Option<String>,
 }
 
 // ============================================================================
@@ -90,6 +113,13 @@ pub struct SavePromptSessionRequest {
     pub project_name: String,
     pub content: Option<String>,
     pub session_state: SessionStatePayload,
+    /// Fold into an existing prompt with identical content in the same project
+    #[serde(default)]
+    pub dedup: bool,
+    /// `updatedAt` the client's copy is based on. When set, the save is
+    /// rejected with 409 if the prompt has changed since.
+    #[serde(default)]
+    pub expected_updated_at: Option<DateTime<Utc>>,
 }
 
 /// Session state payload from frontend.
@@ -116,6 +146,8 @@ pub struct MessagePayload {
 pub struct SavePromptSessionResponse {
     pub id: String,
     pub updated_at: String,
+    /// Prompt completeness score (0-100)
+    pub completion: u8,
 }
 
 /// Query parameters for listing prompts.
@@ -161,6 +193,14 @@ pub struct GetPromptResponse {
     pub content: Option<String>,
     pub session_state: SessionStatePayload,
     pub parent_ids: Vec<String>,
+    /// Prompt completeness score (0-100)
+    pub completion: u8,
+    /// Whether the agent is still writing a reply to the last message
+    pub reply_in_progress: bool,
+    /// Last user message, when its reply was lost (e.g. the server restarted
+    /// mid-response). Send a message with `resend` to ask again.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dangling_message: Option<MessagePayload>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -222,7 +262,7 @@ pub async fn create_session(
         .ok_or_else(|| (StatusCode::BAD_REQUEST, "Missing project_id".to_string()))?;
 
     let project = state
-        .db
+        .db()?
         .projects()
         .get(project_id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -262,13 +302,13 @@ pub async fn create_session(
     };
 
     state
-        .db
+        .db()?
         .prompts()
         .save(&record)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     // Touch last_accessed_at on write action
-    let _ = state.db.projects().touch(project_id);
+    let _ = state.db()?.projects().touch(project_id);
 
     Ok(Json(CreateSessionResponse { session_id }))
 }
@@ -304,7 +344,7 @@ pub async fn send_message(
         .clone();
     // Load prompt from DB
     let record = state
-        .db
+        .db()?
         .prompts()
         .get(&session_id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -322,6 +362,25 @@ pub async fn send_message(
     let working_dir = record.project_path.clone();
     let work_type = record.work_type.clone();
 
+    // Reconcile with an earlier request whose reply never arrived. Re-sending
+    // the unanswered message replies to it where it is, rather than appending
+    // it a second time.
+    let guard = InFlightGuard::claim(&state, &session_id)?;
+    let dangling = dangling_message(&session_state);
+    let resend = req.resend || dangling.is_some_and(|m| m.content == req.content);
+    let (content, history) = if resend {
+        let message = dangling.ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                "No unanswered message to re-send".to_string(),
+            )
+        })?;
+        let history = &session_state.messages[..session_state.messages.len() - 1];
+        (message.content.clone(), history)
+    } else {
+        (req.content, session_state.messages.as_slice())
+    };
+
     // Resolve enabled skills
     let enabled_skill_ids = req
         .enabled_skills
@@ -334,8 +393,7 @@ pub async fn send_message(
         .collect();
 
     // Convert session messages to ChatMessage format
-    let messages: Vec<ChatMessage> = session_state
-        .messages
+    let messages: Vec<ChatMessage> = history
         .iter()
         .map(|m| ChatMessage {
             role: m.role.clone(),
@@ -343,8 +401,12 @@ pub async fn send_message(
         })
         .collect();
 
+    let interview_agent =
+        resolve_interview_agent(&state.interview, std::path::Path::new(&working_dir))
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
     // Build agent prompt
-    let agent_prompt = if req.content == "__INIT__" {
+    let agent_prompt = if content == "__INIT__" {
         build_init_prompt(&work_type, &working_dir, &enabled_skills)
     } else {
         // Build prompt with existing history plus new user message
@@ -352,18 +414,22 @@ pub async fn send_message(
             &work_type,
             &working_dir,
             &messages,
-            &req.content,
+            &content,
             &enabled_skills,
+            interview_agent.max_turns,
         )
     };
 
     // Stream agent response with DB persistence
     stream_agent_response(
-        state.db.clone(),
+        state.db()?.clone(),
+        guard,
         session_id,
-        req.content,
+        content,
+        !resend,
         agent_prompt,
         working_dir,
+        interview_agent,
     )
     .await
 }
@@ -374,11 +440,29 @@ pub async fn save_prompt(
 ) -> Result<Json<SavePromptResponse>, (StatusCode, String)> {
     let path = std::path::Path::new(&req.working_dir).join("prompt.md");
 
+    if req.dry_run {
+        return Ok(Json(SavePromptResponse {
+            path: path.to_string_lossy().to_string(),
+            exists: Some(path.exists()),
+            content: Some(req.content),
+            backup_path: None,
+        }));
+    }
+
+    let backup = crate::backup::backup_existing(&path).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to back up {}: {}", path.display(), e),
+        )
+    })?;
     std::fs::write(&path, &req.content)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     Ok(Json(SavePromptResponse {
         path: path.to_string_lossy().to_string(),
+        content: None,
+        exists: None,
+        backup_path: backup.map(|p| p.to_string_lossy().to_string()),
     }))
 }
 
@@ -395,7 +479,7 @@ pub async fn save_prompt_session(
     let project_id = path_params.get("project_id");
     // Touch last_accessed_at on write action
     if let Some(pid) = project_id {
-        let _ = state.db.projects().touch(pid);
+        let _ = state.db()?.projects().touch(pid);
     }
     let now = Utc::now();
 
@@ -406,36 +490,37 @@ pub async fn save_prompt_session(
         )
     })?;
 
-    // Check if this is an update or insert
-    let existing = state
-        .db
-        .prompts()
-        .get(&req.id)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-
-    let created_at = existing.map(|e| e.created_at).unwrap_or(now);
+    // created_at is only used on insert; updates keep the stored value
+    let completion = prompt_completion(req.content.as_deref(), &req.session_state);
 
     let record = PromptRecord {
-        id: req.id.clone(),
+        id: req.id,
         title: req.title,
         work_type: req.work_type,
         project_path: req.project_path,
         project_name: req.project_name,
         content: req.content,
         session_state: session_state_json,
-        created_at,
+        created_at: now,
         updated_at: now,
     };
 
-    state
-        .db
+    let id = state
+        .db()?
         .prompts()
-        .save(&record)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .save_checked(&record, req.expected_updated_at, req.dedup)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                "Prompt was modified since it was loaded; reload and retry".to_string(),
+            )
+        })?;
 
     Ok(Json(SavePromptSessionResponse {
-        id: req.id,
+        id,
         updated_at: now.to_rfc3339(),
+        completion,
     }))
 }
 
@@ -453,13 +538,13 @@ pub async fn list_prompts(
     };
 
     let records = state
-        .db
+        .db()?
         .prompts()
         .list(&filter)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     let projects = state
-        .db
+        .db()?
         .prompts()
         .list_projects()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -500,7 +585,7 @@ pub async fn get_prompt(
         .get("id")
         .ok_or_else(|| (StatusCode::BAD_REQUEST, "Missing prompt id".to_string()))?;
     let record = state
-        .db
+        .db()?
         .prompts()
         .get(id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -515,10 +600,17 @@ pub async fn get_prompt(
         })?;
 
     let parent_ids = state
-        .db
+        .db()?
         .prompts()
         .get_parent_ids(id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let completion = prompt_completion(record.content.as_deref(), &session_state);
+    let reply_in_progress = is_in_flight(&state, id);
+    let dangling_message = if reply_in_progress {
+        None
+    } else {
+        dangling_message(&session_state).cloned()
+    };
 
     Ok(Json(GetPromptResponse {
         id: record.id,
@@ -529,6 +621,9 @@ pub async fn get_prompt(
         content: record.content,
         session_state,
         parent_ids,
+        completion,
+        reply_in_progress,
+        dangling_message,
         created_at: record.created_at.to_rfc3339(),
         updated_at: record.updated_at.to_rfc3339(),
     }))
@@ -543,7 +638,7 @@ pub async fn delete_prompt(
         .get("id")
         .ok_or_else(|| (StatusCode::BAD_REQUEST, "Missing prompt id".to_string()))?;
     let deleted = state
-        .db
+        .db()?
         .prompts()
         .delete(id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -567,7 +662,7 @@ pub async fn update_prompt_parents(
 
     // Verify the prompt exists
     state
-        .db
+        .db()?
         .prompts()
         .get(id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -575,7 +670,7 @@ pub async fn update_prompt_parents(
 
     // Set the parent IDs
     state
-        .db
+        .db()?
         .prompts()
         .set_parent_ids(id, &parent_ids)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -594,7 +689,7 @@ pub async fn get_resolved_prompt(
 
     // Verify the prompt exists
     state
-        .db
+        .db()?
         .prompts()
         .get(id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -602,7 +697,7 @@ pub async fn get_resolved_prompt(
 
     // Resolve the inheritance chain
     let chain = state
-        .db
+        .db()?
         .prompts()
         .resolve_chain(id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -647,6 +742,29 @@ pub async fn get_resolved_prompt(
     }))
 }
 
+/// Completeness score for a saved prompt, preferring the final content over
+/// the in-progress draft.
+fn prompt_completion(content: Option<&str>, session_state: &SessionStatePayload) -> u8 {
+    let markdown = content
+        .filter(|c| !c.trim().is_empty())
+        .unwrap_or(&session_state.prompt_draft);
+    PromptDraft::from_markdown(markdown).completion_percentage()
+}
+
+/// Render an interview's messages as Markdown, one bold speaker label per turn.
+pub(crate) fn render_transcript(messages: &[MessagePayload]) -> String {
+    let mut out = String::new();
+    for msg in messages {
+        let role = if msg.role == "user" {
+            "User"
+        } else {
+            "Assistant"
+        };
+        out.push_str(&format!("**{}**: {}\n\n", role, msg.content.trim()));
+    }
+    out
+}
+
 // ============================================================================
 // Agent Integration
 // ============================================================================
@@ -657,7 +775,8 @@ fn build_init_prompt(
     working_dir: &str,
     enabled_skills: &[&skills::SkillInfo],
 ) -> String {
-    let system = get_system_instructions(work_type, working_dir, enabled_skills);
+    let project = scan_project(std::path::Path::new(working_dir));
+    let system = get_system_instructions(work_type, working_dir, enabled_skills, &project);
     format!(
         "{}\n\n---\n\n\
         The user has selected '{}' as the work type and is ready to start.\n\
@@ -682,8 +801,10 @@ fn build_agent_prompt_from_messages(
     messages: &[ChatMessage],
     new_message: &str,
     enabled_skills: &[&skills::SkillInfo],
+    max_turns: usize,
 ) -> String {
-    let system = get_system_instructions(work_type, working_dir, enabled_skills);
+    let project = scan_project(std::path::Path::new(working_dir));
+    let system = get_system_instructions(work_type, working_dir, enabled_skills, &project);
 
     let mut prompt = String::new();
 
@@ -694,7 +815,12 @@ fn build_agent_prompt_from_messages(
     // Conversation history
     if !messages.is_empty() || !new_message.is_empty() {
         prompt.push_str("## Conversation so far:\n\n");
-        for msg in messages {
+        let (summary, recent) = window_history(messages, max_turns);
+        if let Some(summary) = summary {
+            prompt.push_str(&summary);
+            prompt.push_str("\n\n");
+        }
+        for msg in recent {
             let role = if msg.role == "user" {
                 "User"
             } else {
@@ -730,22 +856,63 @@ fn build_agent_prompt_from_messages(
     prompt
 }
 
+/// Split `messages` into a summary line for all but the last `max_turns`,
+/// and those last messages. A `max_turns` of 0 keeps everything.
+///
+/// The summary names the topics the user raised in the dropped messages;
+/// the agent can read the current prompt.md for what was settled.
+fn window_history(messages: &[ChatMessage], max_turns: usize) -> (Option<String>, &[ChatMessage]) {
+    if max_turns == 0 || messages.len() <= max_turns {
+        return (None, messages);
+    }
+    let (older, recent) = messages.split_at(messages.len() - max_turns);
+    let topics: Vec<String> = older
+        .iter()
+        .filter(|m| m.role == "user")
+        .filter_map(|m| m.content.lines().map(str::trim).find(|l| !l.is_empty()))
+        .map(|line| {
+            if line.chars().count() > SUMMARY_TOPIC_CHARS {
+                let cut: String = line.chars().take(SUMMARY_TOPIC_CHARS - 3).collect();
+                format!("\"{}...\"", cut.trim_end())
+            } else {
+                format!("\"{}\"", line)
+            }
+        })
+        .collect();
+    let mut summary = format!(
+        "*Earlier conversation ({} messages) omitted; see prompt.md for the draft so far.",
+        older.len()
+    );
+    if !topics.is_empty() {
+        summary.push_str(&format!(" The user raised: {}.", topics.join("; ")));
+    }
+    summary.push('*');
+    (Some(summary), recent)
+}
+
 /// Stream response from agent to SSE.
 ///
-/// Stream agent response. User message is persisted before streaming starts.
+/// Stream agent response. User message is persisted before streaming starts,
+/// unless `append_user_message` is false because it is already stored.
+#[allow(clippy::too_many_arguments)]
 async fn stream_agent_response(
     db: Arc<Database>,
+    guard: InFlightGuard,
     session_id: String,
     user_message: String,
+    append_user_message: bool,
     prompt: String,
     working_dir: String,
+    interview_agent: InterviewAgent,
 ) -> Result<Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)>
 {
     // IMPORTANT: Save user message BEFORE streaming starts.
     // This ensures the message survives page refresh during agent processing.
-    if let Err(e) = save_user_message_to_prompt(&db, &session_id, &user_message) {
-        eprintln!("Failed to save user message before streaming: {}", e);
-        // Continue anyway - the message will still be visible in the UI
+    if append_user_message {
+        if let Err(e) = save_user_message_to_prompt(&db, &session_id, &user_message) {
+            eprintln!("Failed to save user message before streaming: {}", e);
+            // Continue anyway - the message will still be visible in the UI
+        }
     }
 
     // Create channel for streaming
@@ -757,7 +924,9 @@ async fn stream_agent_response(
     let user_message_clone = user_message.clone();
     let working_dir_ref = working_dir.clone();
     tokio::spawn(async move {
-        let result = execute_agent(prompt, working_dir, tx.clone()).await;
+        // Released once the reply is saved or has failed
+        let _guard = guard;
+        let result = execute_agent(prompt, working_dir, interview_agent, tx.clone()).await;
 
         match result {
             Ok(full_response) => {
@@ -807,6 +976,10 @@ async fn stream_agent_response(
             let data = serde_json::json!({ "error": err });
             Ok(Event::default().data(serde_json::to_string(&data).unwrap_or_default()))
         }
+        StreamMessage::Warning(warning) => {
+            let data = serde_json::json!({ "warning": warning });
+            Ok(Event::default().data(serde_json::to_string(&data).unwrap_or_default()))
+        }
         StreamMessage::Done => Ok(Event::default().data("[DONE]")),
     });
 
@@ -817,38 +990,211 @@ async fn stream_agent_response(
     ))
 }
 
+/// Marks a prompt session as having a reply in progress until dropped.
+///
+/// The mark lives in memory only: after a restart no session is in flight,
+/// so a trailing user message without a reply shows up as dangling.
+struct InFlightGuard {
+    in_flight: Arc<Mutex<HashSet<String>>>,
+    session_id: String,
+}
+
+impl InFlightGuard {
+    /// Mark `session_id`, or fail with 409 if it already has a reply in
+    /// progress.
+    fn claim(state: &AppState, session_id: &str) -> Result<Self, (StatusCode, String)> {
+        let mut in_flight = state.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        if !in_flight.insert(session_id.to_string()) {
+            return Err((
+                StatusCode::CONFLICT,
+                "A reply to this prompt session is already in progress".to_string(),
+            ));
+        }
+        Ok(Self {
+            in_flight: state.in_flight.clone(),
+            session_id: session_id.to_string(),
+        })
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.session_id);
+    }
+}
+
+fn is_in_flight(state: &AppState, session_id: &str) -> bool {
+    state
+        .in_flight
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains(session_id)
+}
+
+/// The last message, if it is a user message that never got a reply.
+fn dangling_message(session_state: &SessionStatePayload) -> Option<&MessagePayload> {
+    session_state
+        .messages
+        .last()
+        .filter(|message| message.role == "user")
+}
+
 /// Messages sent through the streaming channel.
 #[derive(Debug)]
 enum StreamMessage {
     Content(String),
     PromptDraft(String),
     Error(String),
+    /// Non-fatal notice, e.g. the agent has gone quiet
+    Warning(String),
     Done,
 }
 
+/// Interview settings selected with `codeloops ui --interview-agent/--interview-model/--interview-max-turns/--interview-timeout-secs`.
+#[derive(Debug, Clone, Default)]
+pub struct InterviewOverrides {
+    pub agent: Option<AgentType>,
+    pub model: Option<String>,
+    pub max_turns: Option<usize>,
+    pub timeout: Option<Duration>,
+}
+
+/// Conversation messages an interview agent sees verbatim by default.
+pub const DEFAULT_INTERVIEW_MAX_TURNS: usize = 40;
+
+/// Characters of each older user message kept in the history summary line.
+const SUMMARY_TOPIC_CHARS: usize = 60;
+
+/// The agent and model that run an interview, and how much history it sees.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InterviewAgent {
+    agent_type: AgentType,
+    model: Option<String>,
+    /// Messages passed verbatim; 0 keeps all
+    max_turns: usize,
+    /// Longest a reply may take; `None` waits as long as it takes
+    timeout: Option<Duration>,
+    /// Command for [`AgentType::Custom`], from config
+    custom_command: Option<CustomCommand>,
+}
+
+/// Pick the interview agent for a project.
+///
+/// Precedence: `codeloops ui` flags > project `[interview]` > global
+/// `[defaults.interview]` > Claude Code. Agent, model and `max_turns`
+/// resolve independently, like the loop's actor and critic. Fails when the
+/// custom agent is picked without a valid `[custom_agent]` section.
+fn resolve_interview_agent(
+    overrides: &InterviewOverrides,
+    working_dir: &std::path::Path,
+) -> Result<InterviewAgent, String> {
+    let project = ProjectConfig::load(working_dir).unwrap_or_else(|e| {
+        eprintln!("Ignoring project config for interview: {:#}", e);
+        None
+    });
+    let global = GlobalConfig::load().unwrap_or_else(|e| {
+        eprintln!("Ignoring global config for interview: {:#}", e);
+        None
+    });
+    let parse = |name: &str| crate::parse_agent_choice(name).map(AgentType::from);
+
+    let agent_type = overrides
+        .agent
+        .or_else(|| {
+            project
+                .as_ref()
+                .and_then(|c| c.interview_agent())
+                .and_then(parse)
+        })
+        .or_else(|| {
+            global
+                .as_ref()
+                .and_then(|c| c.interview_agent())
+                .and_then(parse)
+        })
+        .unwrap_or(AgentType::ClaudeCode);
+    let model = overrides
+        .model
+        .clone()
+        .or_else(|| {
+            project
+                .as_ref()
+                .and_then(|c| c.interview_model())
+                .map(String::from)
+        })
+        .or_else(|| {
+            global
+                .as_ref()
+                .and_then(|c| c.interview_model())
+                .map(String::from)
+        });
+    let max_turns = overrides
+        .max_turns
+        .or_else(|| project.as_ref().and_then(|c| c.interview_max_turns()))
+        .or_else(|| global.as_ref().and_then(|c| c.interview_max_turns()))
+        .unwrap_or(DEFAULT_INTERVIEW_MAX_TURNS);
+    let timeout = overrides.timeout.or_else(|| {
+        project
+            .as_ref()
+            .and_then(|c| c.interview_timeout_secs())
+            .or_else(|| global.as_ref().and_then(|c| c.interview_timeout_secs()))
+            .map(Duration::from_secs)
+    });
+
+    let custom_command = match agent_type {
+        AgentType::Custom => Some(
+            crate::config::custom_agent_command(project.as_ref(), global.as_ref())
+                .map_err(|e| format!("Invalid custom interview agent: {:#}", e))?,
+        ),
+        _ => None,
+    };
+
+    Ok(InterviewAgent {
+        agent_type,
+        model,
+        max_turns,
+        timeout,
+        custom_command,
+    })
+}
+
 /// Execute the agent and stream output.
 async fn execute_agent(
     prompt: String,
     working_dir: String,
+    interview_agent: InterviewAgent,
     tx: mpsc::Sender<StreamMessage>,
 ) -> Result<String, String> {
-    let agent = create_agent(AgentType::ClaudeCode);
-    let config = AgentConfig::new(PathBuf::from(&working_dir));
+    let agent: Box<dyn Agent> = match interview_agent.custom_command {
+        Some(command) => Box::new(CustomAgent::new(command)),
+        None => create_agent(interview_agent.agent_type).map_err(|e| e.to_string())?,
+    };
+    let mut config = AgentConfig::new(PathBuf::from(&working_dir));
+    if let Some(model) = interview_agent.model {
+        config = config.with_model(model);
+    }
+    if let Some(limit) = interview_agent.timeout {
+        config = config.with_timeout(limit);
+    }
 
     // Check if agent is available
     if !agent.is_available().await {
-        return Err(
-            "Claude Code agent not available. Please ensure 'claude' CLI is installed.".to_string(),
-        );
+        return Err(format!(
+            "{} agent not available. Install it or choose another with --interview-agent.",
+            agent.name()
+        ));
     }
 
-    // Create callback for streaming output
+    // Collect the full response line by line
     let tx_clone = tx.clone();
     let accumulated = Arc::new(Mutex::new(String::new()));
     let accumulated_clone = accumulated.clone();
 
-    let callback: OutputCallback = Arc::new(move |line: &str, output_type: OutputType| {
-        // Only stream stdout (agent's actual response)
+    let on_line: OutputCallback = Arc::new(move |line: &str, output_type: OutputType| {
+        // Only keep stdout (agent's actual response)
         if output_type == OutputType::Stdout {
             // Accumulate the full response
             if let Ok(mut acc) = accumulated_clone.lock() {
@@ -857,20 +1203,50 @@ async fn execute_agent(
                 }
                 acc.push_str(line);
             }
+        }
+    });
 
-            // Stream the line to frontend
-            let tx = tx_clone.clone();
-            let line = line.to_string();
-            // Use try_send to avoid blocking in the callback
-            let _ = tx.try_send(StreamMessage::Content(format!("{}\n", line)));
+    // Stream partial output as it arrives rather than waiting for whole
+    // lines, so tokens show up as the agent prints them
+    let unsent = Arc::new(Mutex::new(String::new()));
+    let unsent_clone = unsent.clone();
+    let on_chunk: OutputCallback = Arc::new(move |chunk: &str, output_type: OutputType| {
+        if output_type == OutputType::Stdout {
+            if let Ok(mut unsent) = unsent_clone.lock() {
+                send_content(&tx_clone, &mut unsent, chunk);
+            }
         }
     });
 
+    // Tell the user when the agent goes quiet, so a stuck agent is visible
+    let idle_tx = tx.clone();
+    let (on_chunk, idle_monitor) = IdleMonitor::watch(
+        Duration::from_secs(DEFAULT_IDLE_WARNING_SECS),
+        Some(on_chunk),
+        move |idle| {
+            let _ = idle_tx.try_send(StreamMessage::Warning(format!(
+                "The agent has produced no output for {}s",
+                idle.as_secs()
+            )));
+        },
+    );
+
     // Execute agent
     let output = agent
-        .execute_with_callback(&prompt, &config, Some(callback))
+        .execute_with_callbacks(
+            &prompt,
+            &config,
+            OutputCallbacks::lines(Some(on_line)).with_chunks(on_chunk),
+        )
         .await
         .map_err(|e| format!("Agent execution failed: {}", e))?;
+    drop(idle_monitor);
+
+    // Output held back while the channel was full
+    let unsent = std::mem::take(&mut *unsent.lock().unwrap_or_else(|e| e.into_inner()));
+    if !unsent.is_empty() {
+        let _ = tx.send(StreamMessage::Content(unsent)).await;
+    }
 
     // Return the full response (prefer accumulated, fall back to output.stdout)
     let full_response = accumulated
@@ -887,6 +1263,18 @@ async fn execute_agent(
     Ok(full_response)
 }
 
+/// Send `chunk` as content, after any output still `unsent`. The callback
+/// that streams output cannot wait, so when the channel is full the text is
+/// kept in `unsent` and goes out with the next chunk instead of being lost.
+fn send_content(tx: &mpsc::Sender<StreamMessage>, unsent: &mut String, chunk: &str) {
+    unsent.push_str(chunk);
+    let message = StreamMessage::Content(std::mem::take(unsent));
+    if let Err(mpsc::error::TrySendError::Full(StreamMessage::Content(text))) = tx.try_send(message)
+    {
+        *unsent = text;
+    }
+}
+
 // ============================================================================
 // Database Persistence
 // ============================================================================
@@ -903,37 +1291,33 @@ fn save_messages_to_prompt(
     assistant_message: &str,
     prompt_draft: &str,
 ) -> Result<(), String> {
-    let mut record = db
-        .prompts()
-        .get(prompt_id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Prompt not found".to_string())?;
-
-    let mut session_state: SessionStatePayload =
-        serde_json::from_str(&record.session_state).map_err(|e| e.to_string())?;
-
-    // Note: User message is already saved by save_user_message_to_prompt before streaming
-
-    // Add assistant message
-    session_state.messages.push(MessagePayload {
-        id: format!("msg-{}", uuid::Uuid::new_v4()),
-        role: "assistant".to_string(),
-        content: assistant_message.to_string(),
-    });
-
-    // Update prompt draft if provided
-    if !prompt_draft.is_empty() {
-        session_state.prompt_draft = prompt_draft.to_string();
-        record.content = Some(prompt_draft.to_string());
-    }
-
-    // Save back
-    record.session_state = serde_json::to_string(&session_state).map_err(|e| e.to_string())?;
-    record.updated_at = Utc::now();
+    // Read-modify-write in one transaction so a concurrent save can't drop
+    // this message (or have its own dropped)
+    db.prompts()
+        .update(prompt_id, |record| {
+            let mut session_state: SessionStatePayload =
+                serde_json::from_str(&record.session_state)?;
+
+            // Note: User message is already saved by save_user_message_to_prompt before streaming
+
+            // Add assistant message
+            session_state.messages.push(MessagePayload {
+                id: format!("msg-{}", uuid::Uuid::new_v4()),
+                role: "assistant".to_string(),
+                content: assistant_message.to_string(),
+            });
 
-    db.prompts().save(&record).map_err(|e| e.to_string())?;
+            // Update prompt draft if provided
+            if !prompt_draft.is_empty() {
+                session_state.prompt_draft = prompt_draft.to_string();
+                record.content = Some(prompt_draft.to_string());
+            }
 
-    Ok(())
+            record.session_state = serde_json::to_string(&session_state)?;
+            Ok::<_, anyhow::Error>(())
+        })
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Prompt not found".to_string())
 }
 
 /// Save user message immediately before streaming starts.
@@ -951,46 +1335,217 @@ fn save_user_message_to_prompt(
         return Ok(());
     }
 
-    let mut record = db
-        .prompts()
-        .get(prompt_id)
+    db.prompts()
+        .update(prompt_id, |record| {
+            let mut session_state: SessionStatePayload =
+                serde_json::from_str(&record.session_state)?;
+
+            // Add user message
+            session_state.messages.push(MessagePayload {
+                id: format!("msg-{}", uuid::Uuid::new_v4()),
+                role: "user".to_string(),
+                content: user_message.to_string(),
+            });
+
+            // Auto-generate title from first user message (if no title yet)
+            if record.title.is_none() {
+                let title = user_message
+                    .lines()
+                    .next()
+                    .unwrap_or(user_message)
+                    .chars()
+                    .take(50)
+                    .collect::<String>();
+                record.title = Some(title);
+            }
+
+            record.session_state = serde_json::to_string(&session_state)?;
+            Ok::<_, anyhow::Error>(())
+        })
         .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Prompt not found".to_string())?;
+        .ok_or_else(|| "Prompt not found".to_string())
+}
 
-    let mut session_state: SessionStatePayload =
-        serde_json::from_str(&record.session_state).map_err(|e| e.to_string())?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Add user message
-    session_state.messages.push(MessagePayload {
-        id: format!("msg-{}", uuid::Uuid::new_v4()),
-        role: "user".to_string(),
-        content: user_message.to_string(),
-    });
+    #[test]
+    fn content_is_held_back_while_the_channel_is_full() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut unsent = String::new();
+        send_content(&tx, &mut unsent, "a");
+        send_content(&tx, &mut unsent, "b");
+        send_content(&tx, &mut unsent, "c");
+        assert_eq!(unsent, "bc");
+
+        let received = |message| match message {
+            Ok(StreamMessage::Content(text)) => text,
+            other => panic!("expected content, got {:?}", other),
+        };
+        assert_eq!(received(rx.try_recv()), "a");
+        send_content(&tx, &mut unsent, "d");
+        assert_eq!(unsent, "");
+        assert_eq!(received(rx.try_recv()), "bcd");
+    }
 
-    // Auto-generate title from first user message (if no title yet)
-    if record.title.is_none() {
-        let title = user_message
-            .lines()
-            .next()
-            .unwrap_or(user_message)
-            .chars()
-            .take(50)
-            .collect::<String>();
-        record.title = Some(title);
+    #[test]
+    fn test_interview_agent_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("codeloops.toml"),
+            "agent = \"cursor\"\n\n[interview]\nagent = \"opencode\"\nmodel = \"opus\"\n",
+        )
+        .unwrap();
+
+        let resolved = resolve_interview_agent(&InterviewOverrides::default(), dir.path()).unwrap();
+        assert_eq!(resolved.agent_type, AgentType::OpenCode);
+        assert_eq!(resolved.model.as_deref(), Some("opus"));
+
+        assert_eq!(resolved.max_turns, DEFAULT_INTERVIEW_MAX_TURNS);
+        assert_eq!(resolved.timeout, None);
+
+        let overrides = InterviewOverrides {
+            agent: Some(AgentType::ClaudeCode),
+            model: Some("sonnet".to_string()),
+            max_turns: Some(6),
+            timeout: Some(Duration::from_secs(90)),
+        };
+        let resolved = resolve_interview_agent(&overrides, dir.path()).unwrap();
+        assert_eq!(resolved.agent_type, AgentType::ClaudeCode);
+        assert_eq!(resolved.model.as_deref(), Some("sonnet"));
+        assert_eq!(resolved.max_turns, 6);
+        assert_eq!(resolved.timeout, Some(Duration::from_secs(90)));
+
+        // The custom agent without its command is an error, not a stub
+        let overrides = InterviewOverrides {
+            agent: Some(AgentType::Custom),
+            ..Default::default()
+        };
+        let error = resolve_interview_agent(&overrides, dir.path()).unwrap_err();
+        assert!(error.contains("[custom_agent]"), "{}", error);
     }
 
-    // Save back
-    record.session_state = serde_json::to_string(&session_state).map_err(|e| e.to_string())?;
-    record.updated_at = Utc::now();
+    #[test]
+    fn test_window_history_summarizes_older_turns() {
+        let message = |role: &str, content: &str| ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        };
+        let long = format!("\n{}\nsecond line", "x".repeat(80));
+        let messages = vec![
+            message("user", "Add a login button"),
+            message("assistant", "Where should it go?"),
+            message("user", &long),
+            message("assistant", "Got it."),
+            message("user", "Also a logout link"),
+        ];
 
-    db.prompts().save(&record).map_err(|e| e.to_string())?;
+        let (summary, recent) = window_history(&messages, 2);
+        let summary = summary.unwrap();
+        assert!(summary.starts_with("*Earlier conversation (3 messages) omitted"));
+        assert!(summary.contains("\"Add a login button\"; \"xxx"));
+        assert!(summary.contains("...\".*"));
+        assert!(!summary.contains("second line"));
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[1].content, "Also a logout link");
+
+        assert!(window_history(&messages, 5).0.is_none());
+        assert_eq!(window_history(&messages, 0).1.len(), 5);
+
+        let prompt =
+            build_agent_prompt_from_messages("feature", "/project", &messages, "Ok", &[], 2);
+        assert!(prompt.contains("Earlier conversation (3 messages) omitted"));
+        assert!(!prompt.contains("**User**: Add a login button"));
+        assert!(prompt.contains("**User**: Also a logout link"));
+    }
 
-    Ok(())
-}
+    #[test]
+    fn test_dangling_message_and_in_flight_guard() {
+        let message = |role: &str, content: &str| MessagePayload {
+            id: format!("msg-{content}"),
+            role: role.to_string(),
+            content: content.to_string(),
+        };
+        let mut session_state = SessionStatePayload {
+            messages: vec![message("user", "hi"), message("assistant", "hello")],
+            prompt_draft: String::new(),
+            enabled_skills: Vec::new(),
+        };
+        assert!(dangling_message(&session_state).is_none());
+        session_state.messages.push(message("user", "add tests"));
+        assert_eq!(
+            dangling_message(&session_state).map(|m| m.content.as_str()),
+            Some("add tests")
+        );
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let state = AppState {
+            db: Ok(Arc::new(Database::open_in_memory().unwrap())),
+            sessions_dir: Arc::new(PathBuf::new()),
+            interview: Arc::default(),
+            in_flight: Arc::default(),
+            stats_cache: Arc::default(),
+        };
+        let guard = InFlightGuard::claim(&state, "p1").unwrap();
+        assert!(is_in_flight(&state, "p1"));
+        let (status, _) = InFlightGuard::claim(&state, "p1").err().unwrap();
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(InFlightGuard::claim(&state, "p2").is_ok());
+        drop(guard);
+        assert!(!is_in_flight(&state, "p1"));
+    }
+
+    #[tokio::test]
+    async fn test_save_prompt_dry_run_does_not_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let request = |dry_run| SavePromptRequest {
+            working_dir: dir.path().to_string_lossy().to_string(),
+            content: "# Prompt".to_string(),
+            dry_run,
+        };
+        let path = dir.path().join("prompt.md");
+
+        let Json(preview) = save_prompt(Path(HashMap::new()), Json(request(true)))
+            .await
+            .unwrap();
+        assert_eq!(preview.content.as_deref(), Some("# Prompt"));
+        assert_eq!(preview.exists, Some(false));
+        assert!(!path.exists());
+
+        let Json(saved) = save_prompt(Path(HashMap::new()), Json(request(false)))
+            .await
+            .unwrap();
+        assert_eq!(saved.path, path.to_string_lossy());
+        assert_eq!(saved.backup_path, None);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "# Prompt");
+
+        let Json(preview) = save_prompt(Path(HashMap::new()), Json(request(true)))
+            .await
+            .unwrap();
+        assert_eq!(preview.exists, Some(true));
+
+        // Saving again keeps the previous file as a backup
+        let Json(saved) = save_prompt(Path(HashMap::new()), Json(request(false)))
+            .await
+            .unwrap();
+        let backup = saved.backup_path.expect("existing prompt.md is backed up");
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), "# Prompt");
+    }
+
+    #[test]
+    fn test_prompt_completion_prefers_content() {
+        let state = SessionStatePayload {
+            messages: vec![],
+            prompt_draft: "# Draft\n\n## Goal\nSomething.\n".to_string(),
+            enabled_skills: vec![],
+        };
+        assert_eq!(prompt_completion(None, &state), 25);
+        assert_eq!(prompt_completion(Some("  "), &state), 25);
+        assert_eq!(
+            prompt_completion(Some("## Acceptance Criteria\n- Works\n"), &state),
+            30
+        );
+    }
 
     #[test]
     fn test_build_init_prompt() {
@@ -1020,6 +1575,7 @@ mod tests {
             &messages,
             "On the header",
             &[],
+            DEFAULT_INTERVIEW_MAX_TURNS,
         );
         assert!(prompt.contains("Conversation so far"));
         assert!(prompt.contains("I want to add a login button"));
@@ -1030,4 +1586,65 @@ mod tests {
         assert!(prompt.contains("Write tool"));
         assert!(!prompt.contains("<prompt>"));
     }
+
+    #[test]
+    fn render_transcript_labels_speakers() {
+        let messages = vec![
+            MessagePayload {
+                id: "1".to_string(),
+                role: "assistant".to_string(),
+                content: "What are we building?\n".to_string(),
+            },
+            MessagePayload {
+                id: "2".to_string(),
+                role: "user".to_string(),
+                content: "A CLI flag.".to_string(),
+            },
+        ];
+        assert_eq!(
+            render_transcript(&messages),
+            "**Assistant**: What are we building?\n\n**User**: A CLI flag.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_concurrent_message_saves_are_not_lost() {
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let now = Utc::now();
+        let state = SessionStatePayload {
+            messages: vec![],
+            prompt_draft: String::new(),
+            enabled_skills: vec![],
+        };
+        db.prompts()
+            .save(&PromptRecord {
+                id: "p1".to_string(),
+                title: None,
+                work_type: "feature".to_string(),
+                project_path: "/project".to_string(),
+                project_name: "project".to_string(),
+                content: None,
+                session_state: serde_json::to_string(&state).unwrap(),
+                created_at: now,
+                updated_at: now,
+            })
+            .unwrap();
+
+        let user = {
+            let db = db.clone();
+            std::thread::spawn(move || save_user_message_to_prompt(&db, "p1", "hello"))
+        };
+        let assistant = {
+            let db = db.clone();
+            std::thread::spawn(move || save_messages_to_prompt(&db, "p1", "", "hi there", ""))
+        };
+        user.join().unwrap().unwrap();
+        assistant.join().unwrap().unwrap();
+
+        let record = db.prompts().get("p1").unwrap().unwrap();
+        let saved: SessionStatePayload = serde_json::from_str(&record.session_state).unwrap();
+        let mut contents: Vec<&str> = saved.messages.iter().map(|m| m.content.as_str()).collect();
+        contents.sort();
+        assert_eq!(contents, vec!["hello", "hi there"]);
+    }
 }
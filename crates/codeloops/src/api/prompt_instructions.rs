@@ -7,16 +7,21 @@
 
 use codeloops_skills::skills::SkillInfo;
 
+use super::scanner::{ProjectContext, ProjectType};
+
 /// Get system instructions for the given work type.
 ///
 /// These instructions guide the AI agent on how to conduct the interview,
 /// what topics to cover, and when to generate the final prompt.
 /// When `enabled_skills` is non-empty, appends a section listing the
-/// available skills and how the agent should reference them.
+/// available skills and how the agent should reference them. A scanned
+/// project description and stack, when found, are included as starting
+/// context.
 pub fn get_system_instructions(
     work_type: &str,
     working_dir: &str,
     enabled_skills: &[&SkillInfo],
+    project: &ProjectContext,
 ) -> String {
     let type_instructions = match work_type {
         "feature" => FEATURE_INSTRUCTIONS,
@@ -41,12 +46,53 @@ pub fn get_system_instructions(
         section
     };
 
+    let project_section = match project.project_description {
+        Some(ref description) => format!(
+            "\n\n## Project Description\n\
+             From the project's README or manifest (verify before relying on it):\n{}\n",
+            description
+        ),
+        None => String::new(),
+    };
+
     format!(
-        "{}\n\n{}{}\n\nWorking directory: {}",
-        BASE_INSTRUCTIONS, type_instructions, skills_section, working_dir
+        "{}\n\n{}{}{}{}\n\nWorking directory: {}",
+        BASE_INSTRUCTIONS,
+        type_instructions,
+        skills_section,
+        project_section,
+        stack_section(project),
+        working_dir
     )
 }
 
+/// What the scanner found in the project's manifests, one fact per line.
+fn stack_section(project: &ProjectContext) -> String {
+    if project.project_type == ProjectType::Unknown {
+        return String::new();
+    }
+    let mut section = format!(
+        "\n\n## Project Stack\n\
+         From the project's manifests (verify before relying on it):\n\
+         - Project type: {}\n",
+        project.project_type.name()
+    );
+    if let Some(ref module) = project.module_path {
+        section.push_str(&format!("- Go module: {}\n", module));
+    }
+    let lists = [
+        ("Languages", &project.languages),
+        ("Frameworks", &project.frameworks),
+        ("Key files", &project.key_files),
+    ];
+    for (label, items) in lists {
+        if !items.is_empty() {
+            section.push_str(&format!("- {}: {}\n", label, items.join(", ")));
+        }
+    }
+    section
+}
+
 const BASE_INSTRUCTIONS: &str = r#"
 You are an expert software architect helping the user produce a high-quality prompt.md for an implementation agent.
 
@@ -377,7 +423,12 @@ mod tests {
 
     #[test]
     fn test_get_system_instructions_feature() {
-        let instructions = get_system_instructions("feature", "/path/to/project", &[]);
+        let instructions = get_system_instructions(
+            "feature",
+            "/path/to/project",
+            &[],
+            &ProjectContext::default(),
+        );
         assert!(instructions.contains("FEATURE"));
         assert!(instructions.contains("Problem statement"));
         assert!(instructions.contains("/path/to/project"));
@@ -387,7 +438,8 @@ mod tests {
 
     #[test]
     fn test_get_system_instructions_defect() {
-        let instructions = get_system_instructions("defect", "/project", &[]);
+        let instructions =
+            get_system_instructions("defect", "/project", &[], &ProjectContext::default());
         assert!(instructions.contains("Observed behavior"));
         assert!(instructions.contains("Suspected area"));
         assert!(instructions.contains("CODEBASE EXPLORATION PROTOCOL"));
@@ -396,7 +448,8 @@ mod tests {
 
     #[test]
     fn test_get_system_instructions_risk() {
-        let instructions = get_system_instructions("risk", "/project", &[]);
+        let instructions =
+            get_system_instructions("risk", "/project", &[], &ProjectContext::default());
         assert!(instructions.contains("Impact"));
         assert!(instructions.contains("security"));
         assert!(instructions.contains("CODEBASE EXPLORATION PROTOCOL"));
@@ -405,7 +458,8 @@ mod tests {
 
     #[test]
     fn test_get_system_instructions_debt() {
-        let instructions = get_system_instructions("debt", "/project", &[]);
+        let instructions =
+            get_system_instructions("debt", "/project", &[], &ProjectContext::default());
         assert!(instructions.contains("Target state"));
         assert!(instructions.contains("TECHNICAL DEBT"));
         assert!(instructions.contains("CODEBASE EXPLORATION PROTOCOL"));
@@ -414,7 +468,12 @@ mod tests {
 
     #[test]
     fn test_get_system_instructions_custom() {
-        let instructions = get_system_instructions("something-else", "/project", &[]);
+        let instructions = get_system_instructions(
+            "something-else",
+            "/project",
+            &[],
+            &ProjectContext::default(),
+        );
         assert!(instructions.contains("CUSTOM"));
         assert!(instructions.contains("Goal statement"));
         assert!(instructions.contains("CODEBASE EXPLORATION PROTOCOL"));
@@ -430,15 +489,53 @@ mod tests {
             source_dir: "~/.claude/skills".to_string(),
         };
         let skills = vec![&skill];
-        let instructions = get_system_instructions("feature", "/project", &skills);
+        let instructions =
+            get_system_instructions("feature", "/project", &skills, &ProjectContext::default());
         assert!(instructions.contains("Available Skills"));
         assert!(instructions.contains("/brainstorming"));
         assert!(instructions.contains("Explore user intent and design"));
     }
 
+    #[test]
+    fn test_get_system_instructions_with_project_description() {
+        let project = ProjectContext {
+            project_description: Some("Renders dashboards from SQL".to_string()),
+            ..ProjectContext::default()
+        };
+        let instructions = get_system_instructions("feature", "/project", &[], &project);
+        assert!(instructions.contains("## Project Description"));
+        assert!(instructions.contains("Renders dashboards from SQL"));
+
+        let instructions =
+            get_system_instructions("feature", "/project", &[], &ProjectContext::default());
+        assert!(!instructions.contains("## Project Description"));
+    }
+
+    #[test]
+    fn test_get_system_instructions_with_project_stack() {
+        let project = ProjectContext {
+            project_type: ProjectType::Go,
+            languages: vec!["Go".to_string()],
+            frameworks: vec!["Gin".to_string(), "GORM".to_string()],
+            key_files: vec!["go.mod".to_string()],
+            module_path: Some("example.com/shop".to_string()),
+            ..ProjectContext::default()
+        };
+        let instructions = get_system_instructions("feature", "/project", &[], &project);
+        assert!(instructions.contains("## Project Stack"));
+        assert!(instructions.contains("- Project type: Go\n"));
+        assert!(instructions.contains("- Go module: example.com/shop\n"));
+        assert!(instructions.contains("- Frameworks: Gin, GORM\n"));
+
+        let instructions =
+            get_system_instructions("feature", "/project", &[], &ProjectContext::default());
+        assert!(!instructions.contains("## Project Stack"));
+    }
+
     #[test]
     fn test_get_system_instructions_without_skills() {
-        let instructions = get_system_instructions("feature", "/project", &[]);
+        let instructions =
+            get_system_instructions("feature", "/project", &[], &ProjectContext::default());
         assert!(!instructions.contains("Available Skills"));
     }
 }
@@ -0,0 +1,487 @@
+//! Lightweight project scan used to seed the interview.
+//!
+//! The interviewer explores the codebase itself, but its first turn is much
+//! better when it already knows what the project is for. [`scan_project`]
+//! pulls a short description from the README or the package manifest, and
+//! reads the manifests for the language, frameworks and key files.
+
+use std::path::Path;
+
+/// Descriptions longer than this many characters are cut off.
+const MAX_DESCRIPTION_CHARS: usize = 300;
+
+/// Python packages worth naming to the interviewer, by normalized name.
+const PYTHON_FRAMEWORKS: &[(&str, &str)] = &[
+    ("django", "Django"),
+    ("flask", "Flask"),
+    ("fastapi", "FastAPI"),
+    ("starlette", "Starlette"),
+    ("sqlalchemy", "SQLAlchemy"),
+    ("pydantic", "Pydantic"),
+    ("celery", "Celery"),
+    ("pytest", "pytest"),
+    ("pandas", "pandas"),
+    ("numpy", "NumPy"),
+    ("torch", "PyTorch"),
+];
+
+/// Go modules worth naming to the interviewer, by module path prefix.
+const GO_FRAMEWORKS: &[(&str, &str)] = &[
+    ("github.com/gin-gonic/gin", "Gin"),
+    ("github.com/labstack/echo", "Echo"),
+    ("github.com/gofiber/fiber", "Fiber"),
+    ("github.com/go-chi/chi", "chi"),
+    ("github.com/gorilla/mux", "Gorilla mux"),
+    ("github.com/spf13/cobra", "Cobra"),
+    ("gorm.io/gorm", "GORM"),
+    ("google.golang.org/grpc", "gRPC"),
+    ("github.com/stretchr/testify", "testify"),
+];
+
+/// Main language ecosystem of a project, from its manifest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProjectType {
+    Rust,
+    Go,
+    Python,
+    Node,
+    #[default]
+    Unknown,
+}
+
+impl ProjectType {
+    pub fn name(self) -> &'static str {
+        match self {
+            ProjectType::Rust => "Rust",
+            ProjectType::Go => "Go",
+            ProjectType::Python => "Python",
+            ProjectType::Node => "Node.js",
+            ProjectType::Unknown => "unknown",
+        }
+    }
+}
+
+/// What the scanner learned about a project directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectContext {
+    /// One-paragraph summary of the project's purpose, if one was found
+    pub project_description: Option<String>,
+    /// Ecosystem of the first manifest found
+    pub project_type: ProjectType,
+    /// Languages with a manifest in the project root
+    pub languages: Vec<String>,
+    /// Known frameworks and libraries among the dependencies
+    pub frameworks: Vec<String>,
+    /// Manifests and entry points present, relative to the project root
+    pub key_files: Vec<String>,
+    /// Go module path from `go.mod`
+    pub module_path: Option<String>,
+}
+
+/// Scan `dir` for project context.
+///
+/// The description comes from the first prose paragraph of `README.md`,
+/// then the `description` field of `Cargo.toml` or `package.json`. The
+/// project type is the first of Rust, Go, Python and Node whose manifest is
+/// present; every ecosystem found adds its language, frameworks and files.
+pub fn scan_project(dir: &Path) -> ProjectContext {
+    let project_description = readme_description(dir)
+        .or_else(|| cargo_description(dir))
+        .or_else(|| package_json_description(dir))
+        .map(|d| truncate(&d));
+    let mut context = ProjectContext {
+        project_description,
+        ..ProjectContext::default()
+    };
+    scan_rust(dir, &mut context);
+    scan_go(dir, &mut context);
+    scan_python(dir, &mut context);
+    scan_node(dir, &mut context);
+    context
+}
+
+impl ProjectContext {
+    /// Record an ecosystem found in the project. The first one found is the
+    /// project type.
+    fn add_language(&mut self, project_type: ProjectType, language: &str) {
+        if self.project_type == ProjectType::Unknown {
+            self.project_type = project_type;
+        }
+        if !self.languages.iter().any(|l| l == language) {
+            self.languages.push(language.to_string());
+        }
+    }
+
+    /// Add the files among `candidates` that exist in `dir`.
+    fn add_key_files(&mut self, dir: &Path, candidates: &[&str]) {
+        for file in candidates {
+            if dir.join(file).exists() && !self.key_files.iter().any(|f| f == file) {
+                self.key_files.push(file.to_string());
+            }
+        }
+    }
+
+    /// Add the display names from `table` whose key matches a dependency.
+    fn add_frameworks<'a>(
+        &mut self,
+        dependencies: impl IntoIterator<Item = &'a str>,
+        table: &[(&str, &str)],
+        matches: impl Fn(&str, &str) -> bool,
+    ) {
+        for dependency in dependencies {
+            for (key, name) in table {
+                if matches(dependency, key) && !self.frameworks.iter().any(|f| f == name) {
+                    self.frameworks.push(name.to_string());
+                }
+            }
+        }
+    }
+}
+
+fn scan_rust(dir: &Path, context: &mut ProjectContext) {
+    if dir.join("Cargo.toml").is_file() {
+        context.add_language(ProjectType::Rust, "Rust");
+        context.add_key_files(dir, &["Cargo.toml", "src/main.rs", "src/lib.rs"]);
+    }
+}
+
+fn scan_go(dir: &Path, context: &mut ProjectContext) {
+    let Ok(content) = std::fs::read_to_string(dir.join("go.mod")) else {
+        return;
+    };
+    context.add_language(ProjectType::Go, "Go");
+    context.add_key_files(dir, &["go.mod", "main.go", "cmd"]);
+
+    let mut requires = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if let Some(module) = line.strip_prefix("module ") {
+            context.module_path = non_empty(module.trim_matches('"'));
+        } else if line == "require (" {
+            in_block = true;
+        } else if in_block && line == ")" {
+            in_block = false;
+        } else if let Some(spec) = line.strip_prefix("require ").or(in_block.then_some(line)) {
+            if let Some(path) = spec.split_whitespace().next() {
+                requires.push(path.to_string());
+            }
+        }
+    }
+    context.add_frameworks(
+        requires.iter().map(String::as_str),
+        GO_FRAMEWORKS,
+        |path, prefix| {
+            path == prefix
+                || path
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        },
+    );
+}
+
+fn scan_python(dir: &Path, context: &mut ProjectContext) {
+    let manifests = ["pyproject.toml", "requirements.txt", "setup.py"];
+    if !manifests.iter().any(|m| dir.join(m).is_file()) {
+        return;
+    }
+    context.add_language(ProjectType::Python, "Python");
+    context.add_key_files(dir, &manifests);
+    context.add_key_files(dir, &["manage.py", "main.py", "app.py"]);
+
+    let read = |name| std::fs::read_to_string(dir.join(name)).unwrap_or_default();
+    let mut requirements = pyproject_dependencies(&read("pyproject.toml"));
+    requirements.extend(
+        read("requirements.txt")
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('-'))
+            .map(String::from),
+    );
+    requirements.extend(setup_py_requires(&read("setup.py")));
+
+    let names: Vec<String> = requirements.iter().map(|r| python_package(r)).collect();
+    context.add_frameworks(
+        names.iter().map(String::as_str),
+        PYTHON_FRAMEWORKS,
+        |a, b| a == b,
+    );
+}
+
+fn scan_node(dir: &Path, context: &mut ProjectContext) {
+    if !dir.join("package.json").is_file() {
+        return;
+    }
+    let language = if dir.join("tsconfig.json").is_file() {
+        "TypeScript"
+    } else {
+        "JavaScript"
+    };
+    context.add_language(ProjectType::Node, language);
+    context.add_key_files(dir, &["package.json", "tsconfig.json"]);
+}
+
+/// Requirement strings from PEP 621 `[project]` and Poetry's
+/// `[tool.poetry]` dependency tables.
+fn pyproject_dependencies(content: &str) -> Vec<String> {
+    let Ok(manifest) = toml::from_str::<toml::Value>(content) else {
+        return Vec::new();
+    };
+    let mut requirements: Vec<String> = manifest
+        .get("project")
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|d| d.as_str().map(String::from))
+        .collect();
+    let poetry = manifest.get("tool").and_then(|t| t.get("poetry"));
+    for table in ["dependencies", "dev-dependencies"] {
+        if let Some(deps) = poetry.and_then(|p| p.get(table)).and_then(|d| d.as_table()) {
+            requirements.extend(deps.keys().filter(|k| *k != "python").cloned());
+        }
+    }
+    requirements
+}
+
+/// Quoted strings in the `install_requires=[...]` list of a `setup.py`.
+fn setup_py_requires(content: &str) -> Vec<String> {
+    let Some(start) = content.find("install_requires") else {
+        return Vec::new();
+    };
+    let rest = &content[start..];
+    let Some(open) = rest.find('[') else {
+        return Vec::new();
+    };
+    let list = &rest[open + 1..];
+    let list = &list[..list.find(']').unwrap_or(list.len())];
+    list.split(['"', '\''])
+        .skip(1)
+        .step_by(2)
+        .map(String::from)
+        .collect()
+}
+
+/// Normalized package name of a requirement (`Flask[async]>=2.0` -> `flask`).
+fn python_package(requirement: &str) -> String {
+    requirement
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect::<String>()
+        .to_ascii_lowercase()
+        .replace('_', "-")
+}
+
+/// First paragraph of `README.md` that is not a heading, badge, HTML, or code.
+fn readme_description(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("README.md")).ok()?;
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut in_code = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        let skip = in_code
+            || line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with('<')
+            || line.starts_with("![")
+            || line.starts_with("[![")
+            || line.starts_with("---");
+        if skip {
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+        paragraph.push(line);
+    }
+
+    (!paragraph.is_empty()).then(|| paragraph.join(" "))
+}
+
+fn cargo_description(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    let manifest: toml::Value = toml::from_str(&content).ok()?;
+    let description = manifest
+        .get("package")
+        .or_else(|| manifest.get("workspace").and_then(|w| w.get("package")))?
+        .get("description")?
+        .as_str()?;
+    non_empty(description)
+}
+
+fn package_json_description(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+    non_empty(manifest.get("description")?.as_str()?)
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim();
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+fn truncate(s: &str) -> String {
+    if s.chars().count() <= MAX_DESCRIPTION_CHARS {
+        return s.to_string();
+    }
+    let cut: String = s.chars().take(MAX_DESCRIPTION_CHARS - 3).collect();
+    format!("{}...", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readme_first_prose_paragraph() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("README.md"),
+            "# widget\n\n[![CI](https://ci/badge.svg)](https://ci)\n\n\
+             Widget renders dashboards\nfrom SQL queries.\n\nSecond paragraph.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"widget\"\ndescription = \"from cargo\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            scan_project(dir.path()).project_description.as_deref(),
+            Some("Widget renders dashboards from SQL queries.")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_cargo_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("README.md"),
+            "# widget\n\n```sh\nmake\n```\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[workspace.package]\ndescription = \"Dashboards from SQL\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            scan_project(dir.path()).project_description.as_deref(),
+            Some("Dashboards from SQL")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_package_json() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"name": "widget", "description": "Dashboard UI"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            scan_project(dir.path()).project_description.as_deref(),
+            Some("Dashboard UI")
+        );
+    }
+
+    #[test]
+    fn detects_python_from_pyproject() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "[project]\nname = \"shop\"\n\
+             dependencies = [\"FastAPI>=0.110\", \"SQLAlchemy[asyncio]~=2.0\", \"httpx\"]\n\n\
+             [tool.poetry.dev-dependencies]\npytest = \"^8\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("main.py"), "").unwrap();
+
+        let context = scan_project(dir.path());
+        assert_eq!(context.project_type, ProjectType::Python);
+        assert_eq!(context.languages, ["Python"]);
+        assert_eq!(context.frameworks, ["FastAPI", "SQLAlchemy", "pytest"]);
+        assert_eq!(context.key_files, ["pyproject.toml", "main.py"]);
+    }
+
+    #[test]
+    fn detects_python_from_requirements_and_setup_py() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("requirements.txt"),
+            "# web\n-r base.txt\nDjango==5.0\ncelery>=5\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("setup.py"),
+            "setup(\n    name='shop',\n    install_requires=['flask_sqlalchemy', 'Flask>=3'],\n)\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("manage.py"), "").unwrap();
+
+        let context = scan_project(dir.path());
+        assert_eq!(context.project_type, ProjectType::Python);
+        assert_eq!(context.frameworks, ["Django", "Celery", "Flask"]);
+        assert_eq!(
+            context.key_files,
+            ["requirements.txt", "setup.py", "manage.py"]
+        );
+    }
+
+    #[test]
+    fn detects_go_module_and_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("go.mod"),
+            "module example.com/shop // storefront\n\ngo 1.22\n\n\
+             require github.com/spf13/cobra v1.8.0\n\n\
+             require (\n\tgithub.com/gin-gonic/gin v1.9.1\n\
+             \tgithub.com/gin-gonic/ginx v0.1.0\n\
+             \tgorm.io/gorm v1.25.7 // indirect\n)\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("cmd")).unwrap();
+
+        let context = scan_project(dir.path());
+        assert_eq!(context.project_type, ProjectType::Go);
+        assert_eq!(context.languages, ["Go"]);
+        assert_eq!(context.module_path.as_deref(), Some("example.com/shop"));
+        assert_eq!(context.frameworks, ["Cobra", "Gin", "GORM"]);
+        assert_eq!(context.key_files, ["go.mod", "cmd"]);
+    }
+
+    #[test]
+    fn first_manifest_sets_the_project_type() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"x\"\n").unwrap();
+        std::fs::write(dir.path().join("requirements.txt"), "maturin\n").unwrap();
+
+        let context = scan_project(dir.path());
+        assert_eq!(context.project_type, ProjectType::Rust);
+        assert_eq!(context.languages, ["Rust", "Python"]);
+        assert!(context.frameworks.is_empty());
+    }
+
+    #[test]
+    fn long_descriptions_are_truncated() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("README.md"), "word ".repeat(200)).unwrap();
+
+        let description = scan_project(dir.path()).project_description.unwrap();
+        assert_eq!(description.chars().count(), MAX_DESCRIPTION_CHARS);
+        assert!(description.ends_with("..."));
+        assert_eq!(
+            scan_project(&dir.path().join("missing")),
+            ProjectContext::default()
+        );
+    }
+}
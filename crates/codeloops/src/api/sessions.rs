@@ -4,15 +4,17 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
+use axum::http::{header, StatusCode};
 use axum::response::sse::{Event, Sse};
-use axum::response::Json;
+use axum::response::{IntoResponse, Json, Response};
 use serde::Deserialize;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 
-use codeloops_db::{Session, SessionFilter, SessionSummary};
+use codeloops_db::{
+    session_html, ChangeMarker, Session, SessionFilter, SessionOutcome, SessionSummary,
+};
 
 use super::AppState;
 
@@ -23,6 +25,8 @@ pub struct ListParams {
     pub before: Option<String>,
     pub search: Option<String>,
     pub project: Option<String>,
+    pub min_confidence: Option<f64>,
+    pub max_confidence: Option<f64>,
 }
 
 pub async fn list_sessions(
@@ -34,7 +38,7 @@ pub async fn list_sessions(
     let filter = build_filter(params).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
 
     let summaries = state
-        .db
+        .db()?
         .sessions()
         .list(&filter)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -50,7 +54,7 @@ pub async fn get_session(
         .get("id")
         .ok_or_else(|| (StatusCode::BAD_REQUEST, "Missing session id".to_string()))?;
     let session = state
-        .db
+        .db()?
         .sessions()
         .get(id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -59,6 +63,37 @@ pub async fn get_session(
     Ok(Json(session))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UpdateSessionRequest {
+    /// Replaces the session's notes; `null` or blank text clears them
+    pub notes: Option<String>,
+}
+
+/// `PATCH /api/projects/{project_id}/sessions/{id}`: edit a session after
+/// the run. Returns the updated session.
+pub async fn update_session(
+    State(state): State<AppState>,
+    Path(params): Path<HashMap<String, String>>,
+    Json(req): Json<UpdateSessionRequest>,
+) -> Result<Json<Session>, (StatusCode, String)> {
+    let id = params
+        .get("id")
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, "Missing session id".to_string()))?;
+    let sessions = state.db()?.sessions();
+    let found = sessions
+        .set_notes(id, req.notes.as_deref())
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !found {
+        return Err((StatusCode::NOT_FOUND, format!("Session not found: {}", id)));
+    }
+    let session = sessions
+        .get(id)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Session not found: {}", id)))?;
+
+    Ok(Json(session))
+}
+
 pub async fn get_session_diff(
     State(state): State<AppState>,
     Path(params): Path<HashMap<String, String>>,
@@ -67,7 +102,7 @@ pub async fn get_session_diff(
         .get("id")
         .ok_or_else(|| (StatusCode::BAD_REQUEST, "Missing session id".to_string()))?;
     let diff = state
-        .db
+        .db()?
         .sessions()
         .get_diff(id)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -76,6 +111,50 @@ pub async fn get_session_diff(
     Ok(diff)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    /// `html` (default) or `json`
+    pub format: Option<String>,
+}
+
+/// `GET /api/projects/{project_id}/sessions/{id}/export?format=html|json`
+///
+/// HTML is a standalone report with inline styles, served as an attachment.
+pub async fn export_session(
+    State(state): State<AppState>,
+    Path(params): Path<HashMap<String, String>>,
+    Query(export): Query<ExportParams>,
+) -> Result<Response, (StatusCode, String)> {
+    let id = params
+        .get("id")
+        .ok_or_else(|| (StatusCode::BAD_REQUEST, "Missing session id".to_string()))?;
+    let session = state
+        .db()?
+        .sessions()
+        .get(id)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Session not found: {}", id)))?;
+
+    match export.format.as_deref().unwrap_or("html") {
+        "html" => Ok((
+            [
+                (header::CONTENT_TYPE, "text/html; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"session-{}.html\"", id),
+                ),
+            ],
+            session_html(&session),
+        )
+            .into_response()),
+        "json" => Ok(Json(session).into_response()),
+        other => Err((
+            StatusCode::BAD_REQUEST,
+            format!("Unknown export format '{}' (expected html or json)", other),
+        )),
+    }
+}
+
 /// SSE endpoint for streaming live agent output from temp files.
 ///
 /// `GET /api/sessions/{id}/output/{iteration}/{phase}`
@@ -108,12 +187,15 @@ pub async fn stream_output(
     }
 
     let (tx, rx) = mpsc::channel::<OutputEvent>(1000);
-    let db = state.db.clone();
+    let db = state.db()?.clone();
+    let sessions_dir = state.sessions_dir.clone();
 
     tokio::spawn(async move {
-        let output_dir = output_dir_for_session(&id);
-        let stdout_path = output_dir.join(format!("iter_{}_{}.stdout", iteration, phase));
-        let stderr_path = output_dir.join(format!("iter_{}_{}.stderr", iteration, phase));
+        let path = |stream| {
+            codeloops_db::paths::live_output_path(&sessions_dir, &id, iteration, &phase, stream)
+        };
+        let stdout_path = path("stdout");
+        let stderr_path = path("stderr");
 
         let mut stdout_pos: u64 = 0;
         let mut stderr_pos: u64 = 0;
@@ -216,12 +298,184 @@ enum OutputEvent {
     Done,
 }
 
-fn output_dir_for_session(session_id: &str) -> PathBuf {
-    dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("codeloops")
-        .join("output")
-        .join(session_id)
+#[derive(Debug, Deserialize)]
+pub struct LiveParams {
+    /// Only forward events for sessions whose working dir ends in this name
+    pub project: Option<String>,
+}
+
+/// SSE endpoint for session lifecycle events.
+///
+/// `GET /api/sessions/live?project=<name>`
+///
+/// Polls the database and emits `session_created`, `session_updated` (one per
+/// completed iteration) and `session_completed` events for changes made after
+/// the client connected. Without `project`, events for every session are sent.
+/// Sessions are only reloaded once the database changed.
+pub async fn stream_live(
+    State(state): State<AppState>,
+    Query(params): Query<LiveParams>,
+) -> Result<Sse<impl tokio_stream::Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)>
+{
+    let (tx, rx) = mpsc::channel::<LiveEvent>(1000);
+    let db = state.db()?.clone();
+    let filter = SessionFilter {
+        project: params.project,
+        ..SessionFilter::default()
+    };
+
+    tokio::spawn(async move {
+        let mut feed = LiveFeed::default();
+
+        loop {
+            if let Ok(events) = feed.poll(&db, &filter) {
+                for event in events {
+                    if tx.send(event).await.is_err() {
+                        return; // Client disconnected
+                    }
+                }
+            }
+            if tx.is_closed() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    });
+
+    let stream = ReceiverStream::new(rx).map(|event| {
+        Ok(Event::default()
+            .event(event.name)
+            .data(serde_json::to_string(&event.data).unwrap_or_default()))
+    });
+
+    Ok(Sse::new(stream).keep_alive(
+        axum::response::sse::KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LiveEvent {
+    name: &'static str,
+    data: serde_json::Value,
+}
+
+/// What the live stream last reported for a session.
+#[derive(Debug, Default)]
+struct SeenSession {
+    iterations: usize,
+    completed: bool,
+}
+
+/// What the live stream has reported to one client.
+#[derive(Debug, Default)]
+struct LiveFeed {
+    seen: HashMap<String, SeenSession>,
+    /// The database as of the last poll; sessions are not reloaded until it
+    /// changes
+    marker: Option<ChangeMarker>,
+    primed: bool,
+}
+
+impl LiveFeed {
+    /// Events for what changed since the last call. The first call only
+    /// records the sessions there are.
+    fn poll(
+        &mut self,
+        db: &codeloops_db::Database,
+        filter: &SessionFilter,
+    ) -> anyhow::Result<Vec<LiveEvent>> {
+        // Read before the sessions, so a write made while they load is
+        // picked up by the next poll
+        let marker = db.change_marker()?;
+        if self.marker == Some(marker) {
+            return Ok(Vec::new());
+        }
+        let events = poll_live(db, filter, &mut self.seen, self.primed)?;
+        self.marker = Some(marker);
+        self.primed = true;
+        Ok(events)
+    }
+}
+
+/// Diff the sessions matching `filter` against `seen` and return the events
+/// for what changed. With `emit` false, only `seen` is updated, so a client
+/// does not get the whole history replayed on connect.
+fn poll_live(
+    db: &codeloops_db::Database,
+    filter: &SessionFilter,
+    seen: &mut HashMap<String, SeenSession>,
+    emit: bool,
+) -> anyhow::Result<Vec<LiveEvent>> {
+    let mut events = Vec::new();
+    let summaries = db.sessions().list(filter)?;
+
+    for summary in summaries {
+        let entry = match seen.entry(summary.id.clone()) {
+            std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
+            std::collections::hash_map::Entry::Vacant(e) => {
+                if emit {
+                    events.push(LiveEvent {
+                        name: "session_created",
+                        data: serde_json::json!({
+                            "id": summary.id,
+                            "project": summary.project,
+                            "timestamp": summary.timestamp,
+                            "prompt_preview": summary.prompt_preview,
+                            "actor_agent": summary.actor_agent,
+                            "critic_agent": summary.critic_agent,
+                        }),
+                    });
+                }
+                e.insert(SeenSession::default())
+            }
+        };
+        if entry.completed {
+            continue;
+        }
+
+        let Some(session) = db.sessions().get(&summary.id)? else {
+            continue;
+        };
+        for iteration in &session.iterations {
+            if iteration.phase != "critic_completed"
+                || iteration.iteration_number < entry.iterations
+            {
+                continue;
+            }
+            if emit {
+                events.push(LiveEvent {
+                    name: "session_updated",
+                    data: serde_json::json!({
+                        "id": summary.id,
+                        "project": summary.project,
+                        "iteration": iteration.iteration_number,
+                        "critic_decision": iteration.critic_decision,
+                        "critic_confidence": iteration.critic_confidence,
+                    }),
+                });
+            }
+            entry.iterations = iteration.iteration_number + 1;
+        }
+
+        if let Some(outcome) = session.outcome {
+            if emit {
+                events.push(LiveEvent {
+                    name: "session_completed",
+                    data: serde_json::json!({
+                        "id": summary.id,
+                        "project": summary.project,
+                        "outcome": outcome,
+                        "iterations": session.iteration_count.unwrap_or(entry.iterations),
+                    }),
+                });
+            }
+            entry.completed = true;
+        }
+    }
+
+    Ok(events)
 }
 
 /// Read new data from a file starting at the given byte position.
@@ -309,11 +563,132 @@ fn build_filter(params: ListParams) -> anyhow::Result<SessionFilter> {
         })
         .transpose()?;
 
+    for (name, value) in [
+        ("min_confidence", params.min_confidence),
+        ("max_confidence", params.max_confidence),
+    ] {
+        if value.is_some_and(|v| !(0.0..=1.0).contains(&v)) {
+            anyhow::bail!("{} must be between 0.0 and 1.0", name);
+        }
+    }
+
+    let outcome = params
+        .outcome
+        .map(|s| s.parse::<SessionOutcome>())
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("Invalid outcome: {}", e))?;
+
     Ok(SessionFilter {
-        outcome: params.outcome,
+        outcome,
         after,
         before,
         search: params.search,
         project: params.project,
+        min_confidence: params.min_confidence,
+        max_confidence: params.max_confidence,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codeloops_db::{Database, SessionEnd, SessionStart};
+
+    fn start(db: &Database, working_dir: &str) -> String {
+        db.sessions()
+            .create(&SessionStart {
+                prompt: "Fix the bug".to_string(),
+                working_dir: PathBuf::from(working_dir),
+                actor_agent: "Claude Code".to_string(),
+                critic_agent: "Claude Code".to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn live_events_are_filtered_by_project() {
+        let db = Database::open_in_memory().unwrap();
+        let filter = SessionFilter {
+            project: Some("api".to_string()),
+            ..SessionFilter::default()
+        };
+        let mut feed = LiveFeed::default();
+
+        let existing = start(&db, "/work/api");
+        assert!(feed.poll(&db, &filter).unwrap().is_empty());
+
+        let id = start(&db, "/work/api");
+        start(&db, "/work/web");
+        db.sessions().start_iteration(&existing, 0).unwrap();
+        db.sessions()
+            .complete_critic(&existing, 0, "DONE", None, None)
+            .unwrap();
+        db.sessions()
+            .end(
+                &existing,
+                &SessionEnd {
+                    outcome: SessionOutcome::Success,
+                    iterations: 1,
+                    summary: None,
+                    confidence: None,
+                    duration_secs: 1.0,
+                },
+            )
+            .unwrap();
+
+        let events = feed.poll(&db, &filter).unwrap();
+        let names: Vec<_> = events
+            .iter()
+            .map(|e| (e.name, e.data["id"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("session_created", id.as_str()),
+                ("session_updated", existing.as_str()),
+                ("session_completed", existing.as_str()),
+            ]
+        );
+        assert!(events.iter().all(|e| e.data["project"] == "api"));
+
+        assert!(feed.poll(&db, &filter).unwrap().is_empty());
+
+        // Nothing is reloaded until the database changes
+        feed.seen.clear();
+        assert!(feed.poll(&db, &filter).unwrap().is_empty());
+        let added = start(&db, "/work/api");
+        let events = feed.poll(&db, &filter).unwrap();
+        assert!(events.iter().any(|e| e.data["id"] == added.as_str()));
+    }
+
+    #[tokio::test]
+    async fn patch_replaces_notes() {
+        let db = Database::open_in_memory().unwrap();
+        let id = start(&db, "/work/api");
+        let state = AppState {
+            db: Ok(std::sync::Arc::new(db)),
+            sessions_dir: Default::default(),
+            interview: Default::default(),
+            in_flight: Default::default(),
+            stats_cache: Default::default(),
+        };
+        let patch = |id: &str, notes: Option<&str>| {
+            update_session(
+                State(state.clone()),
+                Path(HashMap::from([("id".to_string(), id.to_string())])),
+                Json(UpdateSessionRequest {
+                    notes: notes.map(str::to_string),
+                }),
+            )
+        };
+
+        let Json(session) = patch(&id, Some("Shipped in v2")).await.unwrap();
+        assert_eq!(session.notes.as_deref(), Some("Shipped in v2"));
+        let Json(session) = patch(&id, None).await.unwrap();
+        assert_eq!(session.notes, None);
+
+        let (status, _) = patch("missing", Some("x")).await.err().unwrap();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
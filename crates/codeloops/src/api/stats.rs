@@ -1,22 +1,89 @@
 use std::collections::HashMap;
+use std::sync::Mutex;
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::Json;
+use serde::Deserialize;
 
-use codeloops_db::{AgenticMetrics, SessionFilter, SessionStats};
+use codeloops_db::{
+    AgenticMetrics, ChangeMarker, Database, SessionFilter, SessionStats, StatsTimeZone,
+};
 
-use super::AppState;
+use super::{internal_error, AppState};
+
+/// What a cached value was computed for: the database state and the
+/// request's options, such as the time zone of its daily counts.
+type CacheKey<K> = (ChangeMarker, K);
+
+/// Stats and metrics from the last request, reused until the database
+/// changes. Both read every session, which gets slow as history grows.
+#[derive(Default)]
+pub struct StatsCache {
+    stats: Mutex<Option<(CacheKey<StatsTimeZone>, SessionStats)>>,
+    metrics: Mutex<Option<(CacheKey<MetricsOptions>, AgenticMetrics)>>,
+}
+
+/// Time zone and recent-sessions limit the metrics were computed with.
+type MetricsOptions = (StatsTimeZone, Option<usize>);
+
+#[derive(Debug, Deserialize)]
+pub struct StatsParams {
+    /// Time zone for the daily counts: `local`, `UTC` (the default) or an
+    /// offset like `+05:30`
+    pub tz: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MetricsParams {
+    /// Time zone for the daily counts, as for [`StatsParams`]
+    pub tz: Option<String>,
+    /// Compute the critic and file metrics from only this many of the most
+    /// recent sessions; faster, but blind to older history
+    pub recent: Option<usize>,
+}
+
+/// `tz` as a time zone, UTC when absent.
+fn time_zone(tz: Option<&str>) -> Result<StatsTimeZone, (StatusCode, String)> {
+    match tz {
+        Some(tz) => tz.parse().map_err(|e: codeloops_db::ParseTimeZoneError| {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }),
+        None => Ok(StatsTimeZone::Utc),
+    }
+}
+
+/// The value in `slot` if the database has not changed since it was
+/// computed with `options`, else a fresh one from `compute`, which is
+/// stored.
+fn cached<K: PartialEq, T: Clone, E: ToString>(
+    slot: &Mutex<Option<(CacheKey<K>, T)>>,
+    db: &Database,
+    options: K,
+    compute: impl FnOnce(&Database) -> Result<T, E>,
+) -> Result<T, (StatusCode, String)> {
+    // Taken before computing: a write racing the computation leaves a stale
+    // marker, so the next request recomputes rather than serving old data
+    let key = (db.change_marker().map_err(internal_error)?, options);
+    if let Some((cached_for, value)) = slot.lock().expect("Stats cache poisoned").as_ref() {
+        if *cached_for == key {
+            return Ok(value.clone());
+        }
+    }
+    let value = compute(db).map_err(internal_error)?;
+    *slot.lock().expect("Stats cache poisoned") = Some((key, value.clone()));
+    Ok(value)
+}
 
 pub async fn get_stats(
     State(state): State<AppState>,
     Path(_path_params): Path<HashMap<String, String>>,
+    Query(params): Query<StatsParams>,
 ) -> Result<Json<SessionStats>, (StatusCode, String)> {
-    let stats = state
-        .db
-        .sessions()
-        .stats(&SessionFilter::default())
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let time_zone = time_zone(params.tz.as_deref())?;
+    let stats = cached(&state.stats_cache.stats, state.db()?, time_zone, |db| {
+        db.sessions().stats(&SessionFilter::default(), time_zone)
+    })?;
 
     Ok(Json(stats))
 }
@@ -24,12 +91,96 @@ pub async fn get_stats(
 pub async fn get_metrics(
     State(state): State<AppState>,
     Path(_path_params): Path<HashMap<String, String>>,
+    Query(params): Query<MetricsParams>,
 ) -> Result<Json<AgenticMetrics>, (StatusCode, String)> {
-    let metrics = state
-        .db
-        .sessions()
-        .agentic_metrics(&SessionFilter::default())
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let options = (time_zone(params.tz.as_deref())?, params.recent);
+    let metrics = cached(&state.stats_cache.metrics, state.db()?, options, |db| {
+        db.sessions()
+            .agentic_metrics(&SessionFilter::default(), options.0, options.1)
+    })?;
 
     Ok(Json(metrics))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use codeloops_db::SessionStart;
+
+    #[tokio::test]
+    async fn cache_is_invalidated_by_a_new_session() {
+        let db = Arc::new(Database::open_in_memory().unwrap());
+        let state = AppState {
+            db: Ok(db.clone()),
+            sessions_dir: Default::default(),
+            interview: Default::default(),
+            in_flight: Default::default(),
+            stats_cache: Default::default(),
+        };
+        let params = |tz: Option<&str>| {
+            Query(StatsParams {
+                tz: tz.map(str::to_string),
+            })
+        };
+        let metrics = |recent: Option<usize>| {
+            let params = MetricsParams { tz: None, recent };
+            let request = get_metrics(State(state.clone()), Path(HashMap::new()), Query(params));
+            async move { request.await.unwrap().0 }
+        };
+        let total = || async {
+            let Json(stats) = get_stats(State(state.clone()), Path(HashMap::new()), params(None))
+                .await
+                .unwrap();
+            (stats.total_sessions, metrics(None).await.total_sessions)
+        };
+
+        assert_eq!(total().await, (0, 0));
+        let marker = db.change_marker().unwrap();
+        assert!(matches!(
+            *state.stats_cache.stats.lock().unwrap(),
+            Some((cached_for, _)) if cached_for == (marker, StatsTimeZone::Utc)
+        ));
+
+        db.sessions()
+            .create(&SessionStart {
+                prompt: "Fix the bug".to_string(),
+                working_dir: PathBuf::from("/work/api"),
+                actor_agent: "Claude Code".to_string(),
+                critic_agent: "Claude Code".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(total().await, (1, 1));
+
+        // A recent-sessions limit is part of what the metrics are cached for
+        assert_eq!(metrics(Some(0)).await.iteration_sessions, 0);
+        assert_eq!(metrics(None).await.iteration_sessions, 1);
+
+        // Another time zone is computed rather than served from the cache
+        let offset = "+14:00";
+        let Json(stats) = get_stats(
+            State(state.clone()),
+            Path(HashMap::new()),
+            params(Some(offset)),
+        )
+        .await
+        .unwrap();
+        let today = offset
+            .parse::<StatsTimeZone>()
+            .unwrap()
+            .date(chrono::Utc::now());
+        assert_eq!(stats.sessions_over_time[0].date, today);
+        assert!(matches!(
+            get_stats(
+                State(state.clone()),
+                Path(HashMap::new()),
+                params(Some("Mars/Olympus"))
+            )
+            .await,
+            Err((StatusCode::BAD_REQUEST, _))
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+//! Timestamped backups of files about to be overwritten.
+
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+
+/// Backup path for `path` taken at `now`: `prompt.md` becomes
+/// `prompt.md.2025-01-27T15-30-45.bak` next to it. Later backups taken in
+/// the same second are numbered from 2, as in `prompt.md.2025-01-27T15-30-45.2.bak`.
+fn backup_path(path: &Path, now: DateTime<Local>, number: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", now.format("%Y-%m-%dT%H-%M-%S")));
+    if number > 1 {
+        name.push(format!(".{}", number));
+    }
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Copy `path` to a new timestamped backup before it is overwritten; an
+/// earlier backup is never replaced. Returns the backup path, or `None`
+/// when there was no file to back up.
+pub fn backup_existing(path: &Path) -> std::io::Result<Option<PathBuf>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let now = Local::now();
+    let mut number = 1;
+    loop {
+        let backup = backup_path(path, now, number);
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&backup)
+        {
+            Ok(mut file) => {
+                std::io::copy(&mut File::open(path)?, &mut file)?;
+                return Ok(Some(backup));
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => number += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_keeps_the_previous_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompt.md");
+        assert_eq!(backup_existing(&path).unwrap(), None);
+
+        std::fs::write(&path, "old").unwrap();
+        let backup = backup_existing(&path).unwrap().unwrap();
+        assert_eq!(backup.parent(), Some(dir.path()));
+        let name = backup.file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("prompt.md.") && name.ends_with(".bak"));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "old");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "old");
+    }
+
+    #[test]
+    fn backups_in_the_same_second_get_numbers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompt.md");
+        let now = Local::now();
+        assert_ne!(backup_path(&path, now, 1), backup_path(&path, now, 2));
+        assert!(backup_path(&path, now, 2)
+            .to_string_lossy()
+            .ends_with(".2.bak"));
+
+        // Taking more backups than fit in a second keeps every one
+        let backups: Vec<PathBuf> = ["one", "two", "three"]
+            .iter()
+            .map(|text| {
+                std::fs::write(&path, text).unwrap();
+                backup_existing(&path).unwrap().unwrap()
+            })
+            .collect();
+        let mut contents: Vec<String> = backups
+            .iter()
+            .map(|b| std::fs::read_to_string(b).unwrap())
+            .collect();
+        contents.sort();
+        assert_eq!(contents, ["one", "three", "two"]);
+    }
+}
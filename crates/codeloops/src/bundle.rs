@@ -0,0 +1,90 @@
+//! `--output-dir`: every artifact of a run in one directory.
+//!
+//! The prompt is copied in before the loop starts, so it is there even if
+//! the run dies. The log goes to `run.log` unless `--log-file` points
+//! elsewhere. Once the loop finishes, the final diff, the outcome and the
+//! stored session are written next to them.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use codeloops_core::LoopOutcome;
+use codeloops_db::Session;
+
+pub const PROMPT_FILE: &str = "prompt.md";
+pub const LOG_FILE: &str = "run.log";
+pub const DIFF_FILE: &str = "final.diff";
+pub const RESULT_FILE: &str = "result.json";
+pub const SESSION_FILE: &str = "session.json";
+
+/// Directory collecting the artifacts of one run.
+pub struct RunBundle {
+    dir: PathBuf,
+}
+
+impl RunBundle {
+    /// Create `dir` (and its parents) if missing.
+    pub fn create(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create output directory {}", dir.display()))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Where the run log goes when `--log-file` is not given.
+    pub fn log_path(&self) -> PathBuf {
+        self.dir.join(LOG_FILE)
+    }
+
+    /// Copy of the prompt as resolved from `--prompt` or the prompt files.
+    pub fn write_prompt(&self, prompt: &str) -> Result<()> {
+        self.write(PROMPT_FILE, prompt)
+    }
+
+    /// Write the final diff, the outcome and, when the run was recorded,
+    /// the session.
+    pub fn write_results(
+        &self,
+        final_diff: &str,
+        outcome: &LoopOutcome,
+        session: Option<&Session>,
+    ) -> Result<()> {
+        self.write(DIFF_FILE, final_diff)?;
+        self.write(RESULT_FILE, &serde_json::to_string_pretty(outcome)?)?;
+        if let Some(session) = session {
+            self.write(SESSION_FILE, &serde_json::to_string_pretty(session)?)?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, name: &str, contents: &str) -> Result<()> {
+        let path = self.dir.join(name);
+        std::fs::write(&path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_every_artifact_into_a_new_directory() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("artifacts/run-1");
+        let bundle = RunBundle::create(&dir).unwrap();
+        assert_eq!(bundle.log_path(), dir.join(LOG_FILE));
+
+        bundle.write_prompt("Fix the bug").unwrap();
+        let outcome = LoopOutcome::failed(1, "boom".to_string(), vec![], Default::default());
+        bundle.write_results("+fixed\n", &outcome, None).unwrap();
+
+        let read = |name| std::fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read(PROMPT_FILE), "Fix the bug");
+        assert_eq!(read(DIFF_FILE), "+fixed\n");
+        let result: serde_json::Value = serde_json::from_str(&read(RESULT_FILE)).unwrap();
+        assert_eq!(result["status"], "failed");
+        assert!(!dir.join(SESSION_FILE).exists());
+    }
+}
@@ -2,14 +2,23 @@
 //!
 //! Supports two levels of configuration:
 //! - Global: `~/.config/codeloops/config.toml`
-//! - Project: `codeloops.toml` in the working directory
+//! - Project: `codeloops.toml` in the working directory or the nearest
+//!   parent that has one, up to the git root
+//!
+//! Either can be JSON instead (`config.json`, `.codeloops.json`) with the
+//! same keys. When both formats exist the TOML file is used.
 //!
 //! Precedence: CLI flags > project config > global config > defaults
 
 use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use std::path::{Path, PathBuf};
 
+use codeloops_agent::CustomCommand;
+
 /// Project-level configuration loaded from `codeloops.toml`
 #[derive(Debug, Deserialize, Default)]
 #[serde(deny_unknown_fields)]
@@ -24,9 +33,25 @@ pub struct ProjectConfig {
     /// Critic-specific configuration
     #[serde(default)]
     pub critic: RoleConfig,
+    /// Agent for the prompt builder interview (`codeloops ui`)
+    #[serde(default)]
+    pub interview: InterviewConfig,
+    /// Remind the actor of files changed in earlier iterations
+    pub cumulative_diff: Option<bool>,
+    /// Cap agent response length in tokens
+    pub max_output_tokens: Option<u32>,
+    /// Cap the critic feedback in the actor's prompt, in characters
+    pub max_feedback_chars: Option<usize>,
+    /// Template for the actor's prompt, relative to this file's directory
+    pub actor_prompt_file: Option<PathBuf>,
+    /// Rubric the critic checks on every iteration, relative to this file's
+    /// directory
+    pub rubric_file: Option<PathBuf>,
+    /// Command for `agent = "custom"`
+    pub custom_agent: Option<CustomAgentConfig>,
 }
 
-/// Configuration for a specific role (actor or critic)
+/// Configuration for a specific role (actor, critic, or interview)
 #[derive(Debug, Deserialize, Default, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct RoleConfig {
@@ -36,15 +61,82 @@ pub struct RoleConfig {
     pub model: Option<String>,
 }
 
+/// Configuration for the prompt builder interview
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct InterviewConfig {
+    /// Agent to run the interview
+    pub agent: Option<String>,
+    /// Model to run the interview
+    pub model: Option<String>,
+    /// Conversation messages passed to the agent verbatim; older ones are
+    /// collapsed into a summary line (0 keeps all)
+    pub max_turns: Option<usize>,
+    /// Stop an interview reply that takes longer than this many seconds
+    pub timeout_secs: Option<u64>,
+}
+
+/// A CLI without a built-in agent, run from a command template when the
+/// agent is `custom`
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CustomAgentConfig {
+    /// Program to run, looked up on PATH
+    pub binary: String,
+    /// Command line with {binary}, {prompt}, {prompt_file} and {cwd}
+    /// placeholders (default: "{binary} {prompt}")
+    pub command: Option<String>,
+    /// Write the prompt to stdin instead of substituting {prompt}
+    #[serde(default)]
+    pub prompt_via_stdin: bool,
+}
+
+impl CustomAgentConfig {
+    pub fn to_command(&self) -> Result<CustomCommand> {
+        Ok(CustomCommand::new(
+            &self.binary,
+            self.command
+                .as_deref()
+                .unwrap_or(CustomCommand::DEFAULT_TEMPLATE),
+            self.prompt_via_stdin,
+        )?)
+    }
+}
+
+/// The custom agent's command: the project's `[custom_agent]`, else the
+/// global `[defaults.custom_agent]`.
+pub fn custom_agent_command(
+    project: Option<&ProjectConfig>,
+    global: Option<&GlobalConfig>,
+) -> Result<CustomCommand> {
+    project
+        .and_then(|c| c.custom_agent.as_ref())
+        .or_else(|| global.and_then(|c| c.defaults.custom_agent.as_ref()))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "The custom agent needs a [custom_agent] section naming its binary in {} \
+                 (or [defaults.custom_agent] in the global config)",
+                CONFIG_FILE_NAME
+            )
+        })?
+        .to_command()
+}
+
 /// The project config file name
 pub const CONFIG_FILE_NAME: &str = "codeloops.toml";
 
+/// The project config file name in JSON format
+pub const JSON_CONFIG_FILE_NAME: &str = ".codeloops.json";
+
 /// The global config directory name
 pub const GLOBAL_CONFIG_DIR: &str = "codeloops";
 
 /// The global config file name
 pub const GLOBAL_CONFIG_FILE: &str = "config.toml";
 
+/// The global config file name in JSON format
+pub const GLOBAL_JSON_CONFIG_FILE: &str = "config.json";
+
 /// Global-level configuration loaded from ~/.config/codeloops/config.toml
 #[derive(Debug, Deserialize, Default)]
 pub struct GlobalConfig {
@@ -63,6 +155,21 @@ pub struct GlobalDefaults {
     pub actor: Option<RoleConfig>,
     /// Critic-specific defaults
     pub critic: Option<RoleConfig>,
+    /// Prompt builder interview defaults
+    pub interview: Option<InterviewConfig>,
+    /// Remind the actor of files changed in earlier iterations
+    pub cumulative_diff: Option<bool>,
+    /// Cap agent response length in tokens
+    pub max_output_tokens: Option<u32>,
+    /// Cap the critic feedback in the actor's prompt, in characters
+    pub max_feedback_chars: Option<usize>,
+    /// Template for the actor's prompt, relative to the global config directory
+    pub actor_prompt_file: Option<PathBuf>,
+    /// Rubric the critic checks on every iteration, relative to the global
+    /// config directory
+    pub rubric_file: Option<PathBuf>,
+    /// Command for `agent = "custom"`
+    pub custom_agent: Option<CustomAgentConfig>,
 }
 
 impl GlobalConfig {
@@ -73,21 +180,13 @@ impl GlobalConfig {
     /// - `Ok(None)` if file does not exist
     /// - `Err(...)` if file exists but fails to parse (hard error)
     pub fn load() -> Result<Option<Self>> {
-        let Some(path) = Self::config_path() else {
-            return Ok(None);
-        };
-
-        if !path.exists() {
-            return Ok(None);
+        match Self::existing_path() {
+            Some(path) => {
+                warn_ignored_json(&path, GLOBAL_JSON_CONFIG_FILE);
+                parse_file(&path).map(Some)
+            }
+            None => Ok(None),
         }
-
-        let content = std::fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read {}", path.display()))?;
-
-        let config: GlobalConfig = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse {}", path.display()))?;
-
-        Ok(Some(config))
     }
 
     /// Returns the path where global config would be stored
@@ -95,9 +194,23 @@ impl GlobalConfig {
         dirs::config_dir().map(|d| d.join(GLOBAL_CONFIG_DIR).join(GLOBAL_CONFIG_FILE))
     }
 
+    /// The global config file that `load` reads: `config.toml`, or
+    /// `config.json` when there is no TOML file. `None` when neither exists.
+    pub fn existing_path() -> Option<PathBuf> {
+        let toml = Self::config_path()?;
+        let json = toml.with_file_name(GLOBAL_JSON_CONFIG_FILE);
+        pick_file(toml, json)
+    }
+
+    /// The file `load` reads, or where `config.toml` would go when there
+    /// is none. `None` when the platform has no config directory.
+    pub fn resolved_path() -> Option<PathBuf> {
+        Self::existing_path().or_else(Self::config_path)
+    }
+
     /// Check if global config exists
     pub fn exists() -> bool {
-        Self::config_path().map(|p| p.exists()).unwrap_or(false)
+        Self::existing_path().is_some()
     }
 
     /// Get the effective agent for the actor role.
@@ -139,6 +252,37 @@ impl GlobalConfig {
             .and_then(|c| c.model.as_deref())
             .or(self.defaults.model.as_deref())
     }
+
+    /// Get the agent for the prompt builder interview.
+    /// Only [defaults.interview].agent applies; the interview does not
+    /// inherit the loop's default agent.
+    pub fn interview_agent(&self) -> Option<&str> {
+        self.defaults
+            .interview
+            .as_ref()
+            .and_then(|i| i.agent.as_deref())
+    }
+
+    /// Get the model for the prompt builder interview ([defaults.interview].model).
+    pub fn interview_model(&self) -> Option<&str> {
+        self.defaults
+            .interview
+            .as_ref()
+            .and_then(|i| i.model.as_deref())
+    }
+
+    /// Get the interview history window ([defaults.interview].max_turns).
+    pub fn interview_max_turns(&self) -> Option<usize> {
+        self.defaults.interview.as_ref().and_then(|i| i.max_turns)
+    }
+
+    /// Get the interview reply timeout ([defaults.interview].timeout_secs).
+    pub fn interview_timeout_secs(&self) -> Option<u64> {
+        self.defaults
+            .interview
+            .as_ref()
+            .and_then(|i| i.timeout_secs)
+    }
 }
 
 impl ProjectConfig {
@@ -149,19 +293,39 @@ impl ProjectConfig {
     /// - `Ok(None)` if file does not exist
     /// - `Err(...)` if file exists but fails to parse (hard error)
     pub fn load(working_dir: &Path) -> Result<Option<Self>> {
-        let config_path = working_dir.join(CONFIG_FILE_NAME);
-
-        if !config_path.exists() {
-            return Ok(None);
+        match Self::existing_path(working_dir) {
+            Some(path) => {
+                warn_ignored_json(&path, JSON_CONFIG_FILE_NAME);
+                parse_file(&path).map(Some)
+            }
+            None => Ok(None),
         }
+    }
 
-        let content = std::fs::read_to_string(&config_path)
-            .with_context(|| format!("Failed to read {}", config_path.display()))?;
-
-        let config: ProjectConfig = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse {}", config_path.display()))?;
+    /// The project config file that `load` reads: `codeloops.toml`, or
+    /// `.codeloops.json` when there is no TOML file, in `working_dir` or
+    /// the nearest parent that has either. The search stops at the git root
+    /// (the first directory with a `.git`), or at the filesystem root
+    /// outside a repository. `None` when no file is found.
+    pub fn existing_path(working_dir: &Path) -> Option<PathBuf> {
+        let working_dir =
+            std::path::absolute(working_dir).unwrap_or_else(|_| working_dir.to_path_buf());
+        for dir in working_dir.ancestors() {
+            let found = pick_file(dir.join(CONFIG_FILE_NAME), dir.join(JSON_CONFIG_FILE_NAME));
+            if found.is_some() {
+                return found;
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        None
+    }
 
-        Ok(Some(config))
+    /// The file `load` reads, or where `codeloops.toml` would go in
+    /// `working_dir` when there is none.
+    pub fn resolved_path(working_dir: &Path) -> PathBuf {
+        Self::existing_path(working_dir).unwrap_or_else(|| working_dir.join(CONFIG_FILE_NAME))
     }
 
     /// Get the effective agent for the actor role.
@@ -187,6 +351,102 @@ impl ProjectConfig {
     pub fn critic_model(&self) -> Option<&str> {
         self.critic.model.as_deref().or(self.model.as_deref())
     }
+
+    /// Get the agent for the prompt builder interview ([interview].agent).
+    pub fn interview_agent(&self) -> Option<&str> {
+        self.interview.agent.as_deref()
+    }
+
+    /// Get the model for the prompt builder interview ([interview].model).
+    pub fn interview_model(&self) -> Option<&str> {
+        self.interview.model.as_deref()
+    }
+
+    /// Get the interview history window ([interview].max_turns).
+    pub fn interview_max_turns(&self) -> Option<usize> {
+        self.interview.max_turns
+    }
+
+    /// Get the interview reply timeout ([interview].timeout_secs).
+    pub fn interview_timeout_secs(&self) -> Option<u64> {
+        self.interview.timeout_secs
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print the config files codeloops reads and whether each exists
+    Path {
+        /// Directory whose project config to look for (default: current directory)
+        #[arg(long)]
+        working_dir: Option<PathBuf>,
+    },
+}
+
+pub async fn handle_config_command(action: ConfigAction) -> Result<()> {
+    match action {
+        ConfigAction::Path { working_dir } => {
+            let working_dir = match working_dir {
+                Some(dir) => dir,
+                None => std::env::current_dir().context("Failed to get current directory")?,
+            };
+            match GlobalConfig::resolved_path() {
+                Some(path) => print_config_path("Global config: ", &path),
+                None => println!("Global config:  {}", "(no config directory)".dimmed()),
+            }
+            print_config_path(
+                "Project config:",
+                &ProjectConfig::resolved_path(&working_dir),
+            );
+        }
+    }
+    Ok(())
+}
+
+fn print_config_path(label: &str, path: &Path) {
+    let status = if path.exists() {
+        "found".bright_green()
+    } else {
+        "not found".dimmed()
+    };
+    println!("{} {} ({})", label, path.display(), status);
+}
+
+/// The TOML file if it exists, else the JSON file if it exists.
+fn pick_file(toml: PathBuf, json: PathBuf) -> Option<PathBuf> {
+    match (toml.exists(), json.exists()) {
+        (true, _) => Some(toml),
+        (false, true) => Some(json),
+        (false, false) => None,
+    }
+}
+
+/// Warn that the JSON file named `json_name` next to the loaded `path` is
+/// ignored, when `path` is the TOML file and both exist. Only loading warns,
+/// so looking up the path for display does not repeat it.
+fn warn_ignored_json(path: &Path, json_name: &str) {
+    let json = path.with_file_name(json_name);
+    if json != path && json.exists() {
+        eprintln!(
+            "{} Ignoring {}: {} takes precedence",
+            "⚠".bright_yellow(),
+            json.display(),
+            path.display()
+        );
+    }
+}
+
+/// Read a config file, parsing it as JSON for a `.json` extension and as
+/// TOML otherwise.
+fn parse_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let parsed = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&content).map_err(anyhow::Error::from)
+    } else {
+        toml::from_str(&content).map_err(anyhow::Error::from)
+    };
+    parsed.with_context(|| format!("Failed to parse {}", path.display()))
 }
 
 #[cfg(test)]
@@ -229,6 +489,222 @@ agent = "cursor"
         assert_eq!(config.critic_model(), None);
     }
 
+    #[test]
+    fn test_cumulative_diff_flag() {
+        let project: ProjectConfig = toml::from_str("cumulative_diff = true").unwrap();
+        assert_eq!(project.cumulative_diff, Some(true));
+
+        let global: GlobalConfig = toml::from_str("[defaults]\ncumulative_diff = false").unwrap();
+        assert_eq!(global.defaults.cumulative_diff, Some(false));
+    }
+
+    #[test]
+    fn test_max_output_tokens() {
+        let project: ProjectConfig = toml::from_str("max_output_tokens = 8000").unwrap();
+        assert_eq!(project.max_output_tokens, Some(8000));
+
+        let global: GlobalConfig = toml::from_str("[defaults]\nmax_output_tokens = 4000").unwrap();
+        assert_eq!(global.defaults.max_output_tokens, Some(4000));
+    }
+
+    #[test]
+    fn test_max_feedback_chars() {
+        let project: ProjectConfig = toml::from_str("max_feedback_chars = 6000").unwrap();
+        assert_eq!(project.max_feedback_chars, Some(6000));
+
+        let global: GlobalConfig =
+            toml::from_str("[defaults]\nmax_feedback_chars = 12000").unwrap();
+        assert_eq!(global.defaults.max_feedback_chars, Some(12000));
+    }
+
+    #[test]
+    fn test_custom_agent() {
+        let project: ProjectConfig = toml::from_str(
+            "agent = \"custom\"\n\n[custom_agent]\nbinary = \"mycli\"\n\
+             command = \"{binary} --cwd {cwd}\"\nprompt_via_stdin = true",
+        )
+        .unwrap();
+        let global: GlobalConfig =
+            toml::from_str("[defaults.custom_agent]\nbinary = \"othercli\"").unwrap();
+
+        let command = custom_agent_command(Some(&project), Some(&global)).unwrap();
+        assert_eq!(command.binary(), "mycli");
+        assert!(command.prompt_via_stdin());
+        let command = custom_agent_command(None, Some(&global)).unwrap();
+        assert_eq!(command.binary(), "othercli");
+        assert!(custom_agent_command(None, None).is_err());
+
+        // Without stdin, the default template passes the prompt as an argument
+        let no_prompt: ProjectConfig =
+            toml::from_str("[custom_agent]\nbinary = \"mycli\"\ncommand = \"{binary}\"").unwrap();
+        assert!(custom_agent_command(Some(&no_prompt), None).is_err());
+    }
+
+    #[test]
+    fn test_actor_prompt_file() {
+        let project: ProjectConfig =
+            toml::from_str("actor_prompt_file = \".codeloops/actor.md\"").unwrap();
+        assert_eq!(
+            project.actor_prompt_file,
+            Some(PathBuf::from(".codeloops/actor.md"))
+        );
+
+        let global: GlobalConfig =
+            toml::from_str("[defaults]\nactor_prompt_file = \"actor.md\"").unwrap();
+        assert_eq!(
+            global.defaults.actor_prompt_file,
+            Some(PathBuf::from("actor.md"))
+        );
+    }
+
+    #[test]
+    fn test_rubric_file() {
+        let project: ProjectConfig = toml::from_str("rubric_file = \"RUBRIC.md\"").unwrap();
+        assert_eq!(project.rubric_file, Some(PathBuf::from("RUBRIC.md")));
+
+        let global: GlobalConfig =
+            toml::from_str("[defaults]\nrubric_file = \"rubric.md\"").unwrap();
+        assert_eq!(
+            global.defaults.rubric_file,
+            Some(PathBuf::from("rubric.md"))
+        );
+    }
+
+    #[test]
+    fn test_interview_role() {
+        let project: ProjectConfig =
+            toml::from_str("agent = \"opencode\"\n\n[interview]\nmodel = \"opus\"").unwrap();
+        assert_eq!(project.interview_agent(), None); // no fallback to the loop agent
+        assert_eq!(project.interview_model(), Some("opus"));
+
+        let global: GlobalConfig = toml::from_str(
+            "[defaults]\nagent = \"cursor\"\n\n[defaults.interview]\nagent = \"claude\"",
+        )
+        .unwrap();
+        assert_eq!(global.interview_agent(), Some("claude"));
+        assert_eq!(global.interview_model(), None);
+        assert_eq!(global.interview_max_turns(), None);
+
+        let project: ProjectConfig =
+            toml::from_str("[interview]\nmax_turns = 12\ntimeout_secs = 300").unwrap();
+        assert_eq!(project.interview_max_turns(), Some(12));
+        assert_eq!(project.interview_timeout_secs(), Some(300));
+        assert_eq!(global.interview_timeout_secs(), None);
+        assert!(toml::from_str::<ProjectConfig>("[actor]\nmax_turns = 12").is_err());
+    }
+
+    #[test]
+    fn test_project_config_json_matches_toml() {
+        let toml_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            toml_dir.path().join(CONFIG_FILE_NAME),
+            "agent = \"claude\"\ncumulative_diff = true\n\n\
+             [critic]\nmodel = \"opus\"\n\n[interview]\nmax_turns = 10\n",
+        )
+        .unwrap();
+        let json_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            json_dir.path().join(JSON_CONFIG_FILE_NAME),
+            r#"{"agent": "claude", "cumulative_diff": true,
+                "critic": {"model": "opus"}, "interview": {"max_turns": 10}}"#,
+        )
+        .unwrap();
+
+        for dir in [toml_dir.path(), json_dir.path()] {
+            let config = ProjectConfig::load(dir).unwrap().unwrap();
+            assert_eq!(config.actor_agent(), Some("claude"));
+            assert_eq!(config.critic_model(), Some("opus"));
+            assert_eq!(config.cumulative_diff, Some(true));
+            assert_eq!(config.interview_max_turns(), Some(10));
+        }
+
+        std::fs::write(
+            json_dir.path().join(JSON_CONFIG_FILE_NAME),
+            r#"{"agnt": "x"}"#,
+        )
+        .unwrap();
+        let err = ProjectConfig::load(json_dir.path()).unwrap_err();
+        assert!(format!("{:#}", err).contains("Failed to parse"));
+    }
+
+    #[test]
+    fn test_toml_config_wins_over_json() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE_NAME), "agent = \"cursor\"\n").unwrap();
+        std::fs::write(
+            dir.path().join(JSON_CONFIG_FILE_NAME),
+            r#"{"agent": "opencode"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            ProjectConfig::existing_path(dir.path()),
+            Some(dir.path().join(CONFIG_FILE_NAME))
+        );
+        let config = ProjectConfig::load(dir.path()).unwrap().unwrap();
+        assert_eq!(config.agent.as_deref(), Some("cursor"));
+        // Found from a subdirectory too
+        assert_eq!(
+            ProjectConfig::existing_path(&dir.path().join("missing")),
+            Some(dir.path().join(CONFIG_FILE_NAME))
+        );
+    }
+
+    #[test]
+    fn test_project_config_found_in_parent_up_to_git_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        let nested = repo.join("crates/api/src");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir(repo.join(".git")).unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE_NAME), "agent = \"cursor\"\n").unwrap();
+
+        // Outside the repository, so not found from inside it
+        assert_eq!(ProjectConfig::existing_path(&nested), None);
+
+        std::fs::write(repo.join(CONFIG_FILE_NAME), "agent = \"opencode\"\n").unwrap();
+        assert_eq!(
+            ProjectConfig::existing_path(&nested),
+            Some(repo.join(CONFIG_FILE_NAME))
+        );
+        let config = ProjectConfig::load(&nested).unwrap().unwrap();
+        assert_eq!(config.agent.as_deref(), Some("opencode"));
+
+        // The nearest file wins
+        let crate_config = repo.join("crates/api").join(JSON_CONFIG_FILE_NAME);
+        std::fs::write(&crate_config, "{}").unwrap();
+        assert_eq!(ProjectConfig::existing_path(&nested), Some(crate_config));
+    }
+
+    #[test]
+    fn test_project_resolved_path() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            ProjectConfig::resolved_path(dir.path()),
+            dir.path().join(CONFIG_FILE_NAME)
+        );
+
+        std::fs::write(dir.path().join(JSON_CONFIG_FILE_NAME), "{}").unwrap();
+        assert_eq!(
+            ProjectConfig::resolved_path(dir.path()),
+            dir.path().join(JSON_CONFIG_FILE_NAME)
+        );
+    }
+
+    #[test]
+    fn test_global_config_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(GLOBAL_JSON_CONFIG_FILE);
+        std::fs::write(
+            &path,
+            r#"{"defaults": {"agent": "claude", "critic": {"model": "opus"}}}"#,
+        )
+        .unwrap();
+        let config: GlobalConfig = parse_file(&path).unwrap();
+        assert_eq!(config.actor_agent(), Some("claude"));
+        assert_eq!(config.critic_model(), Some("opus"));
+    }
+
     #[test]
     fn test_global_config_empty() {
         let toml = "";
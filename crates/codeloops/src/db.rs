@@ -0,0 +1,107 @@
+use anyhow::Result;
+use clap::Subcommand;
+use colored::Colorize;
+
+use codeloops_db::{Database, QueryResult};
+
+/// Cells wider than this are truncated in table output.
+const MAX_CELL_WIDTH: usize = 40;
+
+#[derive(Subcommand, Debug)]
+pub enum DbAction {
+    /// Run a read-only SELECT against the sessions database
+    Query {
+        /// SQL statement (SELECT or WITH ... SELECT only)
+        sql: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+pub async fn handle_db_command(action: DbAction) -> Result<()> {
+    let db = Database::open()?;
+
+    match action {
+        DbAction::Query { sql, json } => {
+            // Non-SELECT statements surface as "Query is not read-only"
+            let result = db
+                .query_readonly(&sql)
+                .map_err(|e| anyhow::anyhow!("Query failed: {}", e))?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else if result.rows.is_empty() {
+                eprintln!("{}", "No rows.".dimmed());
+            } else {
+                print_table(&result);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_table(result: &QueryResult) {
+    let cells: Vec<Vec<String>> = result
+        .rows
+        .iter()
+        .map(|row| row.iter().map(format_cell).collect())
+        .collect();
+
+    let widths: Vec<usize> = result
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(name.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let header: Vec<String> = result
+        .columns
+        .iter()
+        .zip(&widths)
+        .map(|(name, w)| format!("{:<w$}", name.to_uppercase(), w = *w))
+        .collect();
+    println!("{}", header.join("  ").dimmed());
+
+    for row in &cells {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, w)| format!("{:<w$}", cell, w = *w))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+
+    eprintln!(
+        "{}",
+        format!(
+            "({} row{})",
+            cells.len(),
+            if cells.len() == 1 { "" } else { "s" }
+        )
+        .dimmed()
+    );
+}
+
+fn format_cell(value: &serde_json::Value) -> String {
+    let text = match value {
+        serde_json::Value::Null => "NULL".to_string(),
+        serde_json::Value::String(s) => s.replace('\n', " "),
+        other => other.to_string(),
+    };
+    if text.chars().count() > MAX_CELL_WIDTH {
+        let truncated: String = text.chars().take(MAX_CELL_WIDTH - 3).collect();
+        format!("{}...", truncated)
+    } else {
+        text
+    }
+}
@@ -0,0 +1,294 @@
+//! Environment diagnostics for codeloops.
+//!
+//! Runs a series of independent checks (agents, database, data directories,
+//! config files, git) and prints a pass/fail checklist with remediation hints.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use colored::Colorize;
+use tokio::process::Command;
+
+use codeloops_agent::create_agent;
+use codeloops_db::Database;
+
+use crate::config::{GlobalConfig, ProjectConfig};
+use crate::init::AGENTS;
+
+/// Result of a single diagnostic check.
+struct Check {
+    name: String,
+    status: CheckStatus,
+    detail: String,
+    hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Check {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+pub async fn handle_doctor_command(sessions_dir: Option<&Path>) -> Result<()> {
+    eprintln!("{}", "Checking your codeloops environment...".bold());
+    eprintln!();
+
+    let working_dir = std::env::current_dir()?;
+
+    let mut checks = Vec::new();
+    checks.extend(check_agents().await);
+    checks.push(check_git().await);
+    checks.push(check_database());
+    checks.extend(check_data_dirs(sessions_dir));
+    checks.push(check_global_config());
+    checks.push(check_project_config(&working_dir));
+
+    for check in &checks {
+        print_check(check);
+    }
+
+    let failures = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+    let any_agent = checks
+        .iter()
+        .any(|c| c.name.starts_with("Agent:") && c.status == CheckStatus::Pass);
+
+    eprintln!();
+    if !any_agent {
+        eprintln!(
+            "{} No agents are available. Install at least one, then run {}.",
+            "✗".bright_red(),
+            "codeloops init".bright_cyan()
+        );
+        std::process::exit(1);
+    }
+    if failures > 0 {
+        eprintln!(
+            "{} {} check{} failed.",
+            "✗".bright_red(),
+            failures,
+            if failures == 1 { "" } else { "s" }
+        );
+        std::process::exit(1);
+    }
+    eprintln!("{} All checks passed.", "✓".bright_green());
+    Ok(())
+}
+
+fn print_check(check: &Check) {
+    let marker = match check.status {
+        CheckStatus::Pass => "✓".bright_green(),
+        CheckStatus::Warn => "⚠".bright_yellow(),
+        CheckStatus::Fail => "✗".bright_red(),
+    };
+    eprintln!("  {} {:<22} {}", marker, check.name, check.detail.dimmed());
+    if let Some(ref hint) = check.hint {
+        eprintln!("    {} {}", "->".dimmed(), hint);
+    }
+}
+
+/// Check each known agent for availability and report its version.
+///
+/// A missing agent is only a warning; the overall verdict requires at least
+/// one agent to be available.
+async fn check_agents() -> Vec<Check> {
+    let mut checks = Vec::new();
+    for info in AGENTS {
+        let agent = create_agent(info.agent_type).expect("AGENTS lists only built-in agents");
+        let name = format!("Agent: {}", info.config_name);
+        // Diagnostics should reflect the current state, not a cached result
+        if agent.check_available(true).await {
+            let version = binary_version(agent.binary_path())
+                .await
+                .unwrap_or_else(|| "version unknown".to_string());
+            checks.push(Check::pass(name, version));
+        } else {
+            checks.push(Check::warn(
+                name,
+                format!("{} not found on PATH", agent.binary_path().display()),
+                format!("Install {} or ignore if unused", info.display_name),
+            ));
+        }
+    }
+    checks
+}
+
+async fn check_git() -> Check {
+    match binary_version(Path::new("git")).await {
+        Some(version) => Check::pass("Git", version),
+        None => Check::fail(
+            "Git",
+            "git not found on PATH",
+            "Install git; codeloops captures diffs between iterations",
+        ),
+    }
+}
+
+/// Check the database without creating or migrating it: diagnosing the
+/// environment should not change it.
+fn check_database() -> Check {
+    let path = Database::default_path();
+    if !path.exists() {
+        return Check::pass(
+            "Database",
+            format!(
+                "{} (not created yet; the first run creates it)",
+                path.display()
+            ),
+        );
+    }
+    if let Err(e) = Database::check_writable_at(&path) {
+        return Check::fail(
+            "Database",
+            format!("{} is not writable: {}", path.display(), e),
+            "Check file permissions; sessions will not be recorded",
+        );
+    }
+    match Database::open_read_only(&path).and_then(|db| db.schema_version()) {
+        Ok(version) => Check::pass(
+            "Database",
+            format!("{} (schema v{})", path.display(), version),
+        ),
+        Err(e) => match Database::stored_schema_version(&path) {
+            Ok(version) if version < Database::SCHEMA_VERSION => Check::warn(
+                "Database",
+                format!(
+                    "{} (schema v{}, this build uses v{})",
+                    path.display(),
+                    version,
+                    Database::SCHEMA_VERSION
+                ),
+                "The next run migrates it to the current schema",
+            ),
+            _ => Check::fail(
+                "Database",
+                format!("failed to open {}: {}", path.display(), e),
+                "Check permissions on the data directory, or remove a corrupted database file",
+            ),
+        },
+    }
+}
+
+/// Check the data directories codeloops writes to.
+fn check_data_dirs(sessions_dir: Option<&Path>) -> Vec<Check> {
+    let data_dir = Database::default_path()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let sessions_dir = codeloops_db::paths::sessions_dir(sessions_dir);
+
+    let mut sessions_check = check_dir_writable("Sessions directory", &sessions_dir);
+    if sessions_check.status == CheckStatus::Pass {
+        if let Ok(ids) = codeloops_db::paths::list_session_ids(&sessions_dir) {
+            sessions_check.detail =
+                format!("{} ({} with output)", sessions_check.detail, ids.len());
+        }
+    }
+
+    vec![
+        check_dir_writable("Data directory", &data_dir),
+        sessions_check,
+    ]
+}
+
+fn check_dir_writable(name: &str, dir: &Path) -> Check {
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        return Check::fail(
+            name,
+            format!("cannot create {}: {}", dir.display(), e),
+            "Check permissions on the parent directory",
+        );
+    }
+    let probe = dir.join(".codeloops-doctor-probe");
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            Check::pass(name, dir.display().to_string())
+        }
+        Err(e) => Check::fail(
+            name,
+            format!("{} is not writable: {}", dir.display(), e),
+            "Check permissions on the directory",
+        ),
+    }
+}
+
+fn check_global_config() -> Check {
+    let path = GlobalConfig::resolved_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "(no config directory)".to_string());
+    match GlobalConfig::load() {
+        Ok(Some(_)) => Check::pass("Global config", path),
+        Ok(None) => Check::warn(
+            "Global config",
+            format!("{} not found", path),
+            format!(
+                "Run {} to set your defaults",
+                "codeloops init".bright_cyan()
+            ),
+        ),
+        Err(e) => Check::fail(
+            "Global config",
+            format!("{:#}", e),
+            format!("Fix or remove {}", path),
+        ),
+    }
+}
+
+fn check_project_config(working_dir: &Path) -> Check {
+    let path = ProjectConfig::resolved_path(working_dir);
+    match ProjectConfig::load(working_dir) {
+        Ok(Some(_)) => Check::pass("Project config", path.display().to_string()),
+        Ok(None) => Check::pass("Project config", "none in current directory or its parents"),
+        Err(e) => Check::fail(
+            "Project config",
+            format!("{:#}", e),
+            format!("Fix or remove {}", path.display()),
+        ),
+    }
+}
+
+/// Run `<binary> --version` and return the first line of output.
+async fn binary_version(binary: &Path) -> Option<String> {
+    let output = Command::new(binary).arg("--version").output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(String::from)
+}
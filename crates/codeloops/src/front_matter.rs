@@ -0,0 +1,245 @@
+//! Run options embedded in `prompt.md`.
+//!
+//! A prompt file may start with a `---`-delimited block of `key: value`
+//! lines so the task carries its own settings:
+//!
+//! ```text
+//! ---
+//! agent: opencode
+//! max_iterations: 5
+//! verify_cmd: "cargo test"
+//! ---
+//! Fix the flaky login test.
+//! ```
+//!
+//! Only flat scalar values are supported. Lines that cannot be understood
+//! produce a warning and are skipped; they never fail the run.
+
+use crate::{parse_agent_choice, AgentChoice};
+
+const DELIMITER: &str = "---";
+
+/// Run options read from front-matter. They sit between CLI flags and the
+/// project config in precedence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptOptions {
+    pub agent: Option<AgentChoice>,
+    pub actor_agent: Option<AgentChoice>,
+    pub critic_agent: Option<AgentChoice>,
+    pub model: Option<String>,
+    pub max_iterations: Option<usize>,
+    pub verify_cmd: Option<String>,
+    pub coverage_cmd: Option<String>,
+}
+
+impl PromptOptions {
+    /// Combine with the options of a later prompt file; its values win.
+    pub fn overridden_by(self, later: PromptOptions) -> Self {
+        Self {
+            agent: later.agent.or(self.agent),
+            actor_agent: later.actor_agent.or(self.actor_agent),
+            critic_agent: later.critic_agent.or(self.critic_agent),
+            model: later.model.or(self.model),
+            max_iterations: later.max_iterations.or(self.max_iterations),
+            verify_cmd: later.verify_cmd.or(self.verify_cmd),
+            coverage_cmd: later.coverage_cmd.or(self.coverage_cmd),
+        }
+    }
+}
+
+/// A prompt file split into its body and front-matter options.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptFile {
+    pub body: String,
+    pub options: PromptOptions,
+    /// Problems found in the front-matter, for the caller to report
+    pub warnings: Vec<String>,
+}
+
+/// Split a leading front-matter block off `content`.
+///
+/// Content without front-matter is returned as the body unchanged (apart
+/// from trimming). An unclosed block is treated as ordinary prompt text.
+pub fn parse(content: &str) -> PromptFile {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut lines = content.split_inclusive('\n');
+
+    let Some(first) = lines.next().filter(|first| first.trim_end() == DELIMITER) else {
+        return PromptFile {
+            body: content.trim().to_string(),
+            ..PromptFile::default()
+        };
+    };
+
+    let mut block = Vec::new();
+    let mut body_start = first.len();
+    let mut closed = false;
+    for line in lines {
+        body_start += line.len();
+        if line.trim_end() == DELIMITER {
+            closed = true;
+            break;
+        }
+        block.push(line.trim());
+    }
+
+    if !closed {
+        return PromptFile {
+            body: content.trim().to_string(),
+            warnings: vec![
+                "front-matter is missing its closing `---`; using the whole file as the prompt"
+                    .to_string(),
+            ],
+            ..PromptFile::default()
+        };
+    }
+
+    let mut options = PromptOptions::default();
+    let mut warnings = Vec::new();
+    for line in block {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Err(warning) = apply(&mut options, line) {
+            warnings.push(warning);
+        }
+    }
+
+    PromptFile {
+        body: content[body_start..].trim().to_string(),
+        options,
+        warnings,
+    }
+}
+
+/// Apply one `key: value` line to `options`.
+fn apply(options: &mut PromptOptions, line: &str) -> Result<(), String> {
+    let (key, raw) = line.split_once(':').ok_or_else(|| {
+        format!(
+            "ignoring front-matter line `{}`: expected `key: value`",
+            line
+        )
+    })?;
+    let key = key.trim();
+    let value = scalar(raw);
+    if value.is_empty() {
+        return Err(format!("ignoring front-matter key `{}`: empty value", key));
+    }
+
+    let agent = |value: &str| {
+        parse_agent_choice(value).ok_or_else(|| {
+            format!(
+                "ignoring front-matter key `{}`: unknown agent `{}`",
+                key, value
+            )
+        })
+    };
+
+    match key {
+        "agent" => options.agent = Some(agent(&value)?),
+        "actor_agent" => options.actor_agent = Some(agent(&value)?),
+        "critic_agent" => options.critic_agent = Some(agent(&value)?),
+        "model" => options.model = Some(value),
+        "max_iterations" => {
+            let n = value.parse().map_err(|_| {
+                format!(
+                    "ignoring front-matter key `max_iterations`: `{}` is not a number",
+                    value
+                )
+            })?;
+            options.max_iterations = Some(n);
+        }
+        "verify_cmd" => options.verify_cmd = Some(value),
+        "coverage_cmd" => options.coverage_cmd = Some(value),
+        _ => return Err(format!("ignoring unknown front-matter key `{}`", key)),
+    }
+    Ok(())
+}
+
+/// Unquote a scalar value, dropping a trailing comment from unquoted ones.
+fn scalar(raw: &str) -> String {
+    let raw = raw.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = raw
+            .strip_prefix(quote)
+            .and_then(|rest| rest.split_once(quote))
+            .map(|(inner, _)| inner)
+        {
+            return inner.to_string();
+        }
+    }
+    match raw.find(" #") {
+        Some(i) => raw[..i].trim_end().to_string(),
+        None => raw.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_strips_front_matter() {
+        let parsed = parse(
+            "---\nagent: opencode\nmax_iterations: 5 # keep it short\n\
+             verify_cmd: \"cargo test --workspace\"\nmodel: 'sonnet'\n\
+             coverage_cmd: cargo tarpaulin\n---\n\nFix the bug.\n",
+        );
+        assert_eq!(parsed.body, "Fix the bug.");
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(
+            parsed.options,
+            PromptOptions {
+                agent: Some(AgentChoice::Opencode),
+                model: Some("sonnet".to_string()),
+                max_iterations: Some(5),
+                verify_cmd: Some("cargo test --workspace".to_string()),
+                coverage_cmd: Some("cargo tarpaulin".to_string()),
+                ..PromptOptions::default()
+            }
+        );
+    }
+
+    #[test]
+    fn without_front_matter_the_content_is_the_body() {
+        let parsed = parse("  Fix the bug.\n\n---\nnot: options\n");
+        assert_eq!(parsed.body, "Fix the bug.\n\n---\nnot: options");
+        assert_eq!(parsed.options, PromptOptions::default());
+    }
+
+    #[test]
+    fn bad_lines_warn_and_are_skipped() {
+        let parsed = parse(
+            "---\nagent: gpt\nmax_iterations: lots\ncolour: blue\njust text\n\
+             critic_agent: cursor\n---\nBody\n",
+        );
+        assert_eq!(parsed.body, "Body");
+        assert_eq!(parsed.warnings.len(), 4);
+        assert_eq!(
+            parsed.options,
+            PromptOptions {
+                critic_agent: Some(AgentChoice::Cursor),
+                ..PromptOptions::default()
+            }
+        );
+
+        let unclosed = parse("---\nagent: opencode\nBody\n");
+        assert_eq!(unclosed.body, "---\nagent: opencode\nBody");
+        assert_eq!(unclosed.options, PromptOptions::default());
+        assert_eq!(unclosed.warnings.len(), 1);
+    }
+
+    #[test]
+    fn later_files_override_earlier_options() {
+        let context = parse("---\nagent: opencode\nmax_iterations: 5\n---\nContext\n");
+        let task = parse("---\nmax_iterations: 2\n---\nTask\n");
+        assert_eq!(
+            context.options.overridden_by(task.options),
+            PromptOptions {
+                agent: Some(AgentChoice::Opencode),
+                max_iterations: Some(2),
+                ..PromptOptions::default()
+            }
+        );
+    }
+}
@@ -6,19 +6,24 @@ use anyhow::Result;
 use colored::Colorize;
 use dialoguer::Select;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 use codeloops_agent::{create_agent, AgentType};
 
 use crate::config::{GlobalConfig, GLOBAL_CONFIG_DIR, GLOBAL_CONFIG_FILE};
 
+/// Marker file in the global config directory, written by
+/// `--skip-init-hint`, that stops the first-run hint without a config
+pub const INIT_HINT_DISMISSED_FILE: &str = ".init-hint-dismissed";
+
 /// Agent info for display and config
-struct AgentInfo {
-    display_name: &'static str,
-    config_name: &'static str,
-    agent_type: AgentType,
+pub(crate) struct AgentInfo {
+    pub(crate) display_name: &'static str,
+    pub(crate) config_name: &'static str,
+    pub(crate) agent_type: AgentType,
 }
 
-const AGENTS: &[AgentInfo] = &[
+pub(crate) const AGENTS: &[AgentInfo] = &[
     AgentInfo {
         display_name: "Claude Code",
         config_name: "claude",
@@ -51,7 +56,7 @@ pub async fn handle_init() -> Result<()> {
     let mut available: Vec<&AgentInfo> = Vec::new();
 
     for info in AGENTS {
-        let agent = create_agent(info.agent_type);
+        let agent = create_agent(info.agent_type).expect("AGENTS lists only built-in agents");
         if agent.is_available().await {
             eprintln!(
                 "  {} {} ({})",
@@ -188,7 +193,50 @@ pub fn print_getting_started() {
     );
 }
 
-/// Check if this appears to be first run (no global config)
+/// Check if this appears to be first run: no global config, and the hint
+/// has not been dismissed
 pub fn is_first_run() -> bool {
-    !GlobalConfig::exists()
+    !GlobalConfig::exists() && !global_config_dir().is_some_and(|dir| hint_dismissed(&dir))
+}
+
+/// Stop showing the first-run hint for good, for users who choose not to
+/// run `init`. Returns the marker file written.
+pub fn dismiss_init_hint() -> Result<PathBuf> {
+    let config_dir = global_config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+    write_hint_marker(&config_dir)
+}
+
+fn global_config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join(GLOBAL_CONFIG_DIR))
+}
+
+fn hint_dismissed(config_dir: &Path) -> bool {
+    config_dir.join(INIT_HINT_DISMISSED_FILE).exists()
+}
+
+fn write_hint_marker(config_dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(config_dir)?;
+    let path = config_dir.join(INIT_HINT_DISMISSED_FILE);
+    fs::write(&path, "")?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marker_dismisses_the_hint() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().join(GLOBAL_CONFIG_DIR);
+        assert!(!hint_dismissed(&config_dir));
+
+        let path = write_hint_marker(&config_dir).unwrap();
+        assert_eq!(path, config_dir.join(INIT_HINT_DISMISSED_FILE));
+        assert!(hint_dismissed(&config_dir));
+        // Dismissing again is harmless
+        write_hint_marker(&config_dir).unwrap();
+        assert!(hint_dismissed(&config_dir));
+    }
 }
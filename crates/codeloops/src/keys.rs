@@ -0,0 +1,151 @@
+//! Keyboard control of a running loop.
+//!
+//! `p` pauses the loop before its next iteration, `r` resumes it, and `q`
+//! stops it the way Ctrl+C does. Ctrl+C is also handled here because the
+//! TUI's raw mode delivers it as a key press instead of a signal. The
+//! listener only runs when stdin and stderr are terminals.
+//!
+//! Without the TUI, the terminal would buffer keys until Enter and echo
+//! them, so the listener turns line buffering and echo off while it runs.
+//! Output processing and signal keys are left alone, so log lines print as
+//! usual and Ctrl+C still sends SIGINT. This is Unix only; the Windows
+//! console delivers key events without it.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use colored::Colorize;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+#[cfg(unix)]
+use nix::sys::termios::{self, LocalFlags, SetArg, SpecialCharacterIndices, Termios};
+
+/// How long each poll for a key press blocks before checking for shutdown.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Ask the loop to stop after the current iteration.
+pub fn interrupt(flag: &AtomicBool) {
+    eprintln!(
+        "\n{} Interrupted. Finishing current iteration...",
+        "⚠".bright_yellow()
+    );
+    flag.store(true, Ordering::SeqCst);
+}
+
+/// Like [`interrupt`], for a SIGTERM from a process manager or container
+/// runtime.
+pub fn terminate(flag: &AtomicBool) {
+    eprintln!(
+        "\n{} Terminated. Finishing current iteration...",
+        "⚠".bright_yellow()
+    );
+    flag.store(true, Ordering::SeqCst);
+}
+
+/// Background thread reading key presses. Stops when dropped, so keys typed
+/// after the run are left for the shell.
+pub struct KeyListener {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+    /// Dropped after the thread stops, restoring the terminal
+    _mode: Option<KeyMode>,
+}
+
+impl KeyListener {
+    /// Start listening, or return `None` when not attached to a terminal.
+    /// `raw_mode` says the TUI already has the terminal in raw mode, which
+    /// it restores itself.
+    pub fn spawn(
+        paused: Arc<AtomicBool>,
+        interrupted: Arc<AtomicBool>,
+        raw_mode: bool,
+    ) -> Option<Self> {
+        if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+            return None;
+        }
+        let mode = if raw_mode { None } else { KeyMode::enable() };
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let handle = std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::SeqCst) {
+                match event::poll(POLL_INTERVAL) {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    // The terminal went away; the loop runs on without keys
+                    Err(_) => break,
+                }
+                let Ok(Event::Key(key)) = event::read() else {
+                    continue;
+                };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                let ctrl_c =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if ctrl_c || key.code == KeyCode::Char('q') {
+                    paused.store(false, Ordering::SeqCst);
+                    interrupt(&interrupted);
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('p') => paused.store(true, Ordering::SeqCst),
+                    KeyCode::Char('r') => paused.store(false, Ordering::SeqCst),
+                    _ => {}
+                }
+            }
+        });
+        Some(Self {
+            stop,
+            handle: Some(handle),
+            _mode: mode,
+        })
+    }
+}
+
+/// The terminal with line buffering and echo off, restored when dropped.
+#[cfg(unix)]
+struct KeyMode {
+    original: Termios,
+}
+
+#[cfg(unix)]
+impl KeyMode {
+    fn enable() -> Option<Self> {
+        let original = termios::tcgetattr(std::io::stdin()).ok()?;
+        let mut keys = original.clone();
+        keys.local_flags
+            .remove(LocalFlags::ICANON | LocalFlags::ECHO);
+        keys.control_chars[SpecialCharacterIndices::VMIN as usize] = 1;
+        keys.control_chars[SpecialCharacterIndices::VTIME as usize] = 0;
+        termios::tcsetattr(std::io::stdin(), SetArg::TCSANOW, &keys).ok()?;
+        Some(Self { original })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for KeyMode {
+    fn drop(&mut self) {
+        let _ = termios::tcsetattr(std::io::stdin(), SetArg::TCSANOW, &self.original);
+    }
+}
+
+#[cfg(not(unix))]
+struct KeyMode;
+
+#[cfg(not(unix))]
+impl KeyMode {
+    fn enable() -> Option<Self> {
+        None
+    }
+}
+
+impl Drop for KeyListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
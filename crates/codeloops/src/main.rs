@@ -1,45 +1,85 @@
 mod api;
+mod backup;
+mod bundle;
 mod config;
+mod db;
+mod doctor;
+mod front_matter;
 mod init;
+mod keys;
+mod notify;
 pub mod projects;
+mod prompt;
+mod rerun;
+mod resolve;
+mod result_file;
+mod review;
+mod run_error;
 mod sessions;
 mod ui;
+mod version;
 
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 
-use codeloops_agent::{create_agent, AgentType};
-use codeloops_core::{LoopContext, LoopOutcome, LoopRunner};
+use codeloops_agent::{create_agent, Agent, AgentType, CommandAgent, CustomAgent};
+use codeloops_core::{
+    ActorPrompts, ContextFile, ConvergenceCheck, CriticBackend, IterationReviewer, LoopContext,
+    LoopOutcome, LoopRunner, RetryBackoff, DEFAULT_AGENT_RETRIES, DEFAULT_IDLE_WARNING_SECS,
+    DEFAULT_ITERATION_WARNING_PERCENT, DEFAULT_MIN_CONFIDENCE_GAIN, DEFAULT_RETRY_DELAY,
+    RULES_CRITIC_NAME,
+};
+use codeloops_critic::{Rubric, RuleConfig};
 use codeloops_db::Database;
-use codeloops_git::DiffCapture;
+use codeloops_git::{DiffCapture, DiffScope};
 use codeloops_logging::{LogFormat, Logger};
 use codeloops_tui::{RenderEvent, SessionRenderer};
 
+use bundle::RunBundle;
 use config::{GlobalConfig, ProjectConfig};
+use rerun::RerunInputs;
+use resolve::{Resolved, Source};
+use result_file::{PartialRunError, RunResult};
+use run_error::{ErrorKind, WithKind};
 
 #[derive(Parser, Debug)]
 #[command(
     name = "codeloops",
     about = "Actor-critic harness for coding agents",
     version,
+    disable_version_flag = true,
     author
 )]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
 
+    /// Print version
+    #[arg(short = 'V', long)]
+    version: bool,
+
+    /// With --version, print version and capabilities as JSON
+    #[arg(long, requires = "version")]
+    json: bool,
+
+    /// Stop showing the "First time?" hint for good without running init
+    #[arg(long, global = true)]
+    skip_init_hint: bool,
+
     /// Task prompt (or reads from prompt.md if not provided)
     #[arg(short, long)]
     prompt: Option<String>,
 
-    /// Path to prompt file (default: ./prompt.md)
-    #[arg(long, default_value = "prompt.md")]
-    prompt_file: PathBuf,
+    /// Path to prompt file (default: ./prompt.md). Repeat or comma-separate
+    /// to concatenate several files in order
+    #[arg(long, default_value = DEFAULT_PROMPT_FILE, value_delimiter = ',')]
+    prompt_file: Vec<PathBuf>,
 
     /// Working directory (default: current directory)
     #[arg(short = 'd', long)]
@@ -84,8 +124,143 @@ struct Cli {
     /// Disable colored output
     #[arg(long)]
     no_color: bool,
+
+    /// Directory for per-session output files (overrides CODELOOPS_SESSIONS_DIR)
+    #[arg(long)]
+    sessions_dir: Option<PathBuf>,
+
+    /// Shell command run at session start and after each iteration (e.g. "cargo test")
+    #[arg(long)]
+    verify_cmd: Option<String>,
+
+    /// Treat a verify command that passed at baseline but fails after an iteration as a blocker
+    #[arg(long, requires = "verify_cmd")]
+    fail_on_regression: bool,
+
+    /// Take over the working directory lock if another run appears to hold it
+    #[arg(long)]
+    force: bool,
+
+    /// Let the critic discard all uncommitted changes when it judges the approach wrong
+    #[arg(long)]
+    allow_critic_reset: bool,
+
+    /// Print how the actor/critic agent and model were chosen
+    #[arg(long)]
+    explain: bool,
+
+    /// Sync the actor's streamed output to disk after every line, so it survives a system crash
+    #[arg(long)]
+    stream_to_disk: bool,
+
+    /// Cap agent response length in tokens (honored by Claude agents; ignored by others)
+    #[arg(long)]
+    max_output_tokens: Option<u32>,
+
+    /// Warn when this percentage of --max-iterations is reached (0 disables)
+    #[arg(long, default_value_t = DEFAULT_ITERATION_WARNING_PERCENT, value_parser = clap::value_parser!(u8).range(0..=100))]
+    iteration_warning_percent: u8,
+
+    /// Run the actor without critic review: one pass, or --max-iterations passes
+    #[arg(long)]
+    no_critic: bool,
+
+    /// How iterations are reviewed: the critic agent (default), or fixed rules (verify command passed and diff non-empty)
+    #[arg(long, value_enum, conflicts_with = "no_critic")]
+    critic: Option<CriticChoice>,
+
+    /// Warn when an agent streams no output for this many seconds (0 disables)
+    #[arg(long, default_value_t = DEFAULT_IDLE_WARNING_SECS)]
+    idle_warning_secs: u64,
+
+    /// Stop with no progress when critic confidence has not risen over this many iterations (off by default)
+    #[arg(long, value_name = "K", conflicts_with = "no_critic")]
+    confidence_window: Option<usize>,
+
+    /// Confidence gain (0.0-1.0) that counts as progress for --confidence-window
+    #[arg(long, value_name = "DELTA", default_value_t = DEFAULT_MIN_CONFIDENCE_GAIN)]
+    min_confidence_gain: f64,
+
+    /// Write the prompt, logs, session, final diff and result of the run into this directory
+    #[arg(long, value_name = "PATH")]
+    output_dir: Option<PathBuf>,
+
+    /// Shell command printing a coverage percentage, run at session start and after each iteration (e.g. "cargo tarpaulin")
+    #[arg(long)]
+    coverage_cmd: Option<String>,
+
+    /// Stop before an iteration that would push the estimated cost past this many USD (needs agents that report costs)
+    #[arg(long, value_name = "USD")]
+    max_cost: Option<f64>,
+
+    /// Don't record the session in the database or write per-session output files (also CODELOOPS_NO_SESSION)
+    #[arg(long)]
+    no_session: bool,
+
+    /// Apply the unified diff in the actor's output to the working tree (for agents that print patches instead of editing files)
+    #[arg(long)]
+    apply_patches: bool,
+
+    /// Start as if the critic had already asked for this, so the first actor prompt addresses it
+    #[arg(long, value_name = "TEXT")]
+    seed_feedback: Option<String>,
+
+    /// Write a compact summary of the outcome as JSON for CI, on success or failure (default path: codeloops-result.json)
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = result_file::DEFAULT_RESULT_FILE)]
+    result_file: Option<PathBuf>,
+
+    /// Reference file (e.g. CONVENTIONS.md) shown to both actor and critic before their prompts; repeatable
+    #[arg(long, value_name = "PATH")]
+    context_file: Vec<PathBuf>,
+
+    /// Rubric the critic checks on every iteration; must-pass criteria block done (overrides rubric_file in config)
+    #[arg(long, value_name = "PATH")]
+    rubric_file: Option<PathBuf>,
+
+    /// Only show the critic changes to files matching this glob (e.g. "src/api/**"); repeatable. The full diff is still recorded
+    #[arg(long, value_name = "GLOB")]
+    critic_diff_scope: Vec<String>,
+
+    /// Shell command run when the run ends, with CODELOOPS_OUTCOME, CODELOOPS_ITERATIONS and CODELOOPS_SESSION_ID set; killed after 30s
+    #[arg(long, value_name = "COMMAND")]
+    notify_cmd: Option<String>,
+
+    /// Cap the critic feedback in the actor's prompt at this many characters, keeping its head and tail
+    #[arg(long)]
+    max_feedback_chars: Option<usize>,
+
+    /// After the critic decides, show the diff and ask whether to accept, reject or replace its call (needs a terminal)
+    #[arg(long)]
+    interactive: bool,
+
+    /// Start a fresh session with an earlier session's prompt, working directory, agents and models; flags still override them
+    #[arg(long, value_name = "SESSION_ID", conflicts_with = "prompt_file")]
+    like: Option<String>,
+
+    /// Stop with no progress after this many iterations in a row leave the changes exactly as they were (off by default)
+    #[arg(long, value_name = "N")]
+    max_unchanged_iterations: Option<usize>,
+
+    /// Run this shell command as the actor instead of an agent; {prompt_file} is replaced by a file holding the prompt, and its stdout is the actor output
+    #[arg(long, value_name = "COMMAND", conflicts_with = "actor_agent")]
+    actor_cmd: Option<String>,
+
+    /// Kill an actor or critic run after this many seconds, failing the loop (default: no limit)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    agent_timeout_secs: Option<u64>,
+
+    /// Run a failed actor or critic again up to this many times (0 disables); refusals are never retried
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_AGENT_RETRIES)]
+    agent_retries: usize,
+
+    /// Base wait before retrying a failed agent run; each retry waits a random time up to this doubled per attempt, at most 60s
+    #[arg(long, value_name = "SECS", default_value_t = DEFAULT_RETRY_DELAY.as_secs())]
+    agent_retry_delay_secs: u64,
 }
 
+// `Run` mirrors every top-level flag; the enum is parsed once, so its size
+// does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Run the actor-critic loop (default behavior)
@@ -94,9 +269,10 @@ enum Commands {
         #[arg(short, long)]
         prompt: Option<String>,
 
-        /// Path to prompt file (default: ./prompt.md)
-        #[arg(long, default_value = "prompt.md")]
-        prompt_file: PathBuf,
+        /// Path to prompt file (default: ./prompt.md). Repeat or comma-separate
+        /// to concatenate several files in order
+        #[arg(long, default_value = DEFAULT_PROMPT_FILE, value_delimiter = ',')]
+        prompt_file: Vec<PathBuf>,
 
         /// Working directory (default: current directory)
         #[arg(short = 'd', long)]
@@ -141,6 +317,138 @@ enum Commands {
         /// Disable colored output
         #[arg(long)]
         no_color: bool,
+
+        /// Directory for per-session output files (overrides CODELOOPS_SESSIONS_DIR)
+        #[arg(long)]
+        sessions_dir: Option<PathBuf>,
+
+        /// Shell command run at session start and after each iteration (e.g. "cargo test")
+        #[arg(long)]
+        verify_cmd: Option<String>,
+
+        /// Treat a verify command that passed at baseline but fails after an iteration as a blocker
+        #[arg(long, requires = "verify_cmd")]
+        fail_on_regression: bool,
+
+        /// Take over the working directory lock if another run appears to hold it
+        #[arg(long)]
+        force: bool,
+
+        /// Let the critic discard all uncommitted changes when it judges the approach wrong
+        #[arg(long)]
+        allow_critic_reset: bool,
+
+        /// Print how the actor/critic agent and model were chosen
+        #[arg(long)]
+        explain: bool,
+
+        /// Sync the actor's streamed output to disk after every line, so it survives a system crash
+        #[arg(long)]
+        stream_to_disk: bool,
+
+        /// Cap agent response length in tokens (honored by Claude agents; ignored by others)
+        #[arg(long)]
+        max_output_tokens: Option<u32>,
+
+        /// Warn when this percentage of --max-iterations is reached (0 disables)
+        #[arg(long, default_value_t = DEFAULT_ITERATION_WARNING_PERCENT, value_parser = clap::value_parser!(u8).range(0..=100))]
+        iteration_warning_percent: u8,
+
+        /// Run the actor without critic review: one pass, or --max-iterations passes
+        #[arg(long)]
+        no_critic: bool,
+
+        /// How iterations are reviewed: the critic agent (default), or fixed rules (verify command passed and diff non-empty)
+        #[arg(long, value_enum, conflicts_with = "no_critic")]
+        critic: Option<CriticChoice>,
+
+        /// Warn when an agent streams no output for this many seconds (0 disables)
+        #[arg(long, default_value_t = DEFAULT_IDLE_WARNING_SECS)]
+        idle_warning_secs: u64,
+
+        /// Stop with no progress when critic confidence has not risen over this many iterations (off by default)
+        #[arg(long, value_name = "K", conflicts_with = "no_critic")]
+        confidence_window: Option<usize>,
+
+        /// Confidence gain (0.0-1.0) that counts as progress for --confidence-window
+        #[arg(long, value_name = "DELTA", default_value_t = DEFAULT_MIN_CONFIDENCE_GAIN)]
+        min_confidence_gain: f64,
+
+        /// Write the prompt, logs, session, final diff and result of the run into this directory
+        #[arg(long, value_name = "PATH")]
+        output_dir: Option<PathBuf>,
+
+        /// Shell command printing a coverage percentage, run at session start and after each iteration (e.g. "cargo tarpaulin")
+        #[arg(long)]
+        coverage_cmd: Option<String>,
+
+        /// Stop before an iteration that would push the estimated cost past this many USD (needs agents that report costs)
+        #[arg(long, value_name = "USD")]
+        max_cost: Option<f64>,
+
+        /// Don't record the session in the database or write per-session output files (also CODELOOPS_NO_SESSION)
+        #[arg(long)]
+        no_session: bool,
+
+        /// Apply the unified diff in the actor's output to the working tree (for agents that print patches instead of editing files)
+        #[arg(long)]
+        apply_patches: bool,
+
+        /// Start as if the critic had already asked for this, so the first actor prompt addresses it
+        #[arg(long, value_name = "TEXT")]
+        seed_feedback: Option<String>,
+
+        /// Write a compact summary of the outcome as JSON for CI, on success or failure (default path: codeloops-result.json)
+        #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = result_file::DEFAULT_RESULT_FILE)]
+        result_file: Option<PathBuf>,
+
+        /// Reference file (e.g. CONVENTIONS.md) shown to both actor and critic before their prompts; repeatable
+        #[arg(long, value_name = "PATH")]
+        context_file: Vec<PathBuf>,
+
+        /// Rubric the critic checks on every iteration; must-pass criteria block done (overrides rubric_file in config)
+        #[arg(long, value_name = "PATH")]
+        rubric_file: Option<PathBuf>,
+
+        /// Only show the critic changes to files matching this glob (e.g. "src/api/**"); repeatable. The full diff is still recorded
+        #[arg(long, value_name = "GLOB")]
+        critic_diff_scope: Vec<String>,
+
+        /// Shell command run when the run ends, with CODELOOPS_OUTCOME, CODELOOPS_ITERATIONS and CODELOOPS_SESSION_ID set; killed after 30s
+        #[arg(long, value_name = "COMMAND")]
+        notify_cmd: Option<String>,
+
+        /// Cap the critic feedback in the actor's prompt at this many characters, keeping its head and tail
+        #[arg(long)]
+        max_feedback_chars: Option<usize>,
+
+        /// After the critic decides, show the diff and ask whether to accept, reject or replace its call (needs a terminal)
+        #[arg(long)]
+        interactive: bool,
+
+        /// Start a fresh session with an earlier session's prompt, working directory, agents and models; flags still override them
+        #[arg(long, value_name = "SESSION_ID", conflicts_with = "prompt_file")]
+        like: Option<String>,
+
+        /// Stop with no progress after this many iterations in a row leave the changes exactly as they were (off by default)
+        #[arg(long, value_name = "N")]
+        max_unchanged_iterations: Option<usize>,
+
+        /// Run this shell command as the actor instead of an agent; {prompt_file} is replaced by a file holding the prompt, and its stdout is the actor output
+        #[arg(long, value_name = "COMMAND", conflicts_with = "actor_agent")]
+        actor_cmd: Option<String>,
+
+        /// Kill an actor or critic run after this many seconds, failing the loop (default: no limit)
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        agent_timeout_secs: Option<u64>,
+
+        /// Run a failed actor or critic again up to this many times (0 disables); refusals are never retried
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_AGENT_RETRIES)]
+        agent_retries: usize,
+
+        /// Base wait before retrying a failed agent run; each retry waits a random time up to this doubled per attempt, at most 60s
+        #[arg(long, value_name = "SECS", default_value_t = DEFAULT_RETRY_DELAY.as_secs())]
+        agent_retry_delay_secs: u64,
     },
 
     /// Browse and inspect sessions
@@ -162,6 +470,30 @@ enum Commands {
         /// Port for the UI server
         #[arg(long, default_value = "3101")]
         ui_port: u16,
+
+        /// Directory for per-session output files (overrides CODELOOPS_SESSIONS_DIR)
+        #[arg(long)]
+        sessions_dir: Option<PathBuf>,
+
+        /// Agent for the prompt builder interview (default: claude)
+        #[arg(long, value_enum)]
+        interview_agent: Option<AgentChoice>,
+
+        /// Model for the prompt builder interview
+        #[arg(long)]
+        interview_model: Option<String>,
+
+        /// Interview messages passed to the agent verbatim; older ones are summarized (0 keeps all)
+        #[arg(long, value_name = "N")]
+        interview_max_turns: Option<usize>,
+
+        /// Stop an interview reply that takes longer than this many seconds (default: no limit)
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        interview_timeout_secs: Option<u64>,
+
+        /// Only serve reads: the API refuses anything that writes data or starts an agent (for shared dashboards)
+        #[arg(long, alias = "audit")]
+        read_only: bool,
     },
 
     /// Manage registered projects
@@ -172,6 +504,32 @@ enum Commands {
 
     /// Set up codeloops with interactive configuration
     Init,
+
+    /// Diagnose environment problems (agents, database, directories, config)
+    Doctor {
+        /// Directory for per-session output files (overrides CODELOOPS_SESSIONS_DIR)
+        #[arg(long)]
+        sessions_dir: Option<PathBuf>,
+    },
+
+    /// Work with prompt files and saved prompts
+    #[command(alias = "prompts")]
+    Prompt {
+        #[command(subcommand)]
+        action: prompt::PromptAction,
+    },
+
+    /// Query the sessions database directly
+    Db {
+        #[command(subcommand)]
+        action: db::DbAction,
+    },
+
+    /// Inspect configuration files
+    Config {
+        #[command(subcommand)]
+        action: config::ConfigAction,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -203,12 +561,29 @@ enum ProjectAction {
     },
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 enum AgentChoice {
     Claude,
     ClaudeGateway,
     Opencode,
     Cursor,
+    /// The CLI configured in `[custom_agent]`
+    Custom,
+}
+
+impl TryFrom<AgentType> for AgentChoice {
+    type Error = String;
+
+    fn try_from(agent_type: AgentType) -> Result<Self, Self::Error> {
+        match agent_type {
+            AgentType::ClaudeCode => Ok(AgentChoice::Claude),
+            AgentType::ClaudeGateway => Ok(AgentChoice::ClaudeGateway),
+            AgentType::OpenCode => Ok(AgentChoice::Opencode),
+            AgentType::Cursor => Ok(AgentChoice::Cursor),
+            AgentType::Custom => Ok(AgentChoice::Custom),
+            AgentType::Command => Err("the command agent is chosen with --actor-cmd".to_string()),
+        }
+    }
 }
 
 impl From<AgentChoice> for AgentType {
@@ -218,10 +593,17 @@ impl From<AgentChoice> for AgentType {
             AgentChoice::ClaudeGateway => AgentType::ClaudeGateway,
             AgentChoice::Opencode => AgentType::OpenCode,
             AgentChoice::Cursor => AgentType::Cursor,
+            AgentChoice::Custom => AgentType::Custom,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CriticChoice {
+    Agent,
+    Rules,
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum LogFormatChoice {
     Pretty,
@@ -239,14 +621,44 @@ impl From<LogFormatChoice> for LogFormat {
     }
 }
 
-/// Parse agent string from config file to AgentChoice
+/// Parse agent string from config file to AgentChoice. Accepts the same
+/// names as [`AgentType`]'s `FromStr`.
 fn parse_agent_choice(s: &str) -> Option<AgentChoice> {
-    match s.to_lowercase().as_str() {
-        "claude" | "claude-code" => Some(AgentChoice::Claude),
-        "claude-gateway" | "claudegateway" => Some(AgentChoice::ClaudeGateway),
-        "opencode" | "open-code" => Some(AgentChoice::Opencode),
-        "cursor" => Some(AgentChoice::Cursor),
-        _ => None,
+    s.parse::<AgentType>().and_then(AgentChoice::try_from).ok()
+}
+
+/// Whether the environment variable `var` is set to a true value (`1`,
+/// `true` or `yes`).
+fn env_flag(var: &str) -> bool {
+    std::env::var(var).is_ok_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes"
+        )
+    })
+}
+
+/// Agent named by the environment variable `var`, if set. An unknown agent
+/// is reported and ignored.
+fn env_agent(var: &'static str) -> Option<AgentChoice> {
+    let value = std::env::var(var).ok()?;
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    match value.parse::<AgentType>().and_then(AgentChoice::try_from) {
+        Ok(choice) => Some(choice),
+        Err(e) => {
+            eprintln!("{} Ignoring {}: {}", "⚠".bright_yellow(), var, e);
+            None
+        }
+    }
+}
+
+fn print_model_resolution(label: &str, model: &Option<Resolved<String>>) {
+    match model {
+        Some(r) => eprintln!("{} {} ({})", label.dimmed(), r.value, r.source),
+        None => eprintln!("{} {}", label.dimmed(), "(agent default)".dimmed()),
     }
 }
 
@@ -254,8 +666,21 @@ fn parse_agent_choice(s: &str) -> Option<AgentChoice> {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    if cli.version {
+        return version::print_version(cli.json);
+    }
+
     // First-run hint (non-blocking) for commands that benefit from config
-    if init::is_first_run() {
+    if cli.skip_init_hint {
+        if init::is_first_run() {
+            let marker = init::dismiss_init_hint()?;
+            eprintln!(
+                "{} The init hint won't be shown again (remove {} to bring it back).\n",
+                "->".dimmed(),
+                marker.display()
+            );
+        }
+    } else if init::is_first_run() {
         let should_hint = matches!(
             &cli.command,
             None | Some(Commands::Run { .. }) | Some(Commands::Sessions { .. })
@@ -271,13 +696,42 @@ async fn main() -> Result<()> {
 
     match cli.command {
         Some(Commands::Init) => init::handle_init().await,
+        Some(Commands::Doctor { sessions_dir }) => {
+            doctor::handle_doctor_command(sessions_dir.as_deref()).await
+        }
+        Some(Commands::Prompt { action }) => prompt::handle_prompt_command(action).await,
+        Some(Commands::Db { action }) => db::handle_db_command(action).await,
+        Some(Commands::Config { action }) => config::handle_config_command(action).await,
         Some(Commands::Project { action }) => handle_project_command(action).await,
         Some(Commands::Sessions { action }) => sessions::handle_sessions_command(action).await,
         Some(Commands::Ui {
             dev,
             api_port,
             ui_port,
-        }) => ui::handle_ui_command(dev, api_port, ui_port).await,
+            sessions_dir,
+            interview_agent,
+            interview_model,
+            interview_max_turns,
+            interview_timeout_secs,
+            read_only,
+        }) => {
+            let interview = api::InterviewOverrides {
+                agent: interview_agent.map(AgentType::from),
+                model: interview_model,
+                max_turns: interview_max_turns,
+                timeout: interview_timeout_secs.map(Duration::from_secs),
+            };
+            ui::handle_ui_command(
+                dev,
+                api_port,
+                ui_port,
+                sessions_dir,
+                interview,
+                read_only,
+                "",
+            )
+            .await
+        }
         Some(Commands::Run {
             prompt,
             prompt_file,
@@ -292,6 +746,39 @@ async fn main() -> Result<()> {
             json_output,
             dry_run,
             no_color,
+            sessions_dir,
+            verify_cmd,
+            fail_on_regression,
+            force,
+            allow_critic_reset,
+            explain,
+            stream_to_disk,
+            max_output_tokens,
+            iteration_warning_percent,
+            no_critic,
+            critic,
+            idle_warning_secs,
+            confidence_window,
+            min_confidence_gain,
+            output_dir,
+            coverage_cmd,
+            max_cost,
+            no_session,
+            apply_patches,
+            seed_feedback,
+            result_file,
+            context_file,
+            rubric_file,
+            critic_diff_scope,
+            notify_cmd,
+            max_feedback_chars,
+            interactive,
+            like,
+            max_unchanged_iterations,
+            actor_cmd,
+            agent_timeout_secs,
+            agent_retries,
+            agent_retry_delay_secs,
         }) => {
             run_loop(RunArgs {
                 prompt,
@@ -307,6 +794,39 @@ async fn main() -> Result<()> {
                 json_output,
                 dry_run,
                 no_color,
+                sessions_dir,
+                verify_cmd,
+                fail_on_regression,
+                force,
+                allow_critic_reset,
+                explain,
+                stream_to_disk,
+                max_output_tokens,
+                iteration_warning_percent,
+                no_critic,
+                critic,
+                idle_warning_secs,
+                confidence_window,
+                min_confidence_gain,
+                output_dir,
+                coverage_cmd,
+                max_cost,
+                no_session,
+                apply_patches,
+                seed_feedback,
+                result_file,
+                context_file,
+                rubric_file,
+                critic_diff_scope,
+                notify_cmd,
+                max_feedback_chars,
+                interactive,
+                like,
+                max_unchanged_iterations,
+                actor_cmd,
+                agent_timeout_secs,
+                agent_retries,
+                agent_retry_delay_secs,
             })
             .await
         }
@@ -326,6 +846,39 @@ async fn main() -> Result<()> {
                 json_output: cli.json_output,
                 dry_run: cli.dry_run,
                 no_color: cli.no_color,
+                sessions_dir: cli.sessions_dir,
+                verify_cmd: cli.verify_cmd,
+                fail_on_regression: cli.fail_on_regression,
+                force: cli.force,
+                allow_critic_reset: cli.allow_critic_reset,
+                explain: cli.explain,
+                stream_to_disk: cli.stream_to_disk,
+                max_output_tokens: cli.max_output_tokens,
+                iteration_warning_percent: cli.iteration_warning_percent,
+                no_critic: cli.no_critic,
+                critic: cli.critic,
+                idle_warning_secs: cli.idle_warning_secs,
+                confidence_window: cli.confidence_window,
+                min_confidence_gain: cli.min_confidence_gain,
+                output_dir: cli.output_dir,
+                coverage_cmd: cli.coverage_cmd,
+                max_cost: cli.max_cost,
+                no_session: cli.no_session,
+                apply_patches: cli.apply_patches,
+                seed_feedback: cli.seed_feedback,
+                result_file: cli.result_file,
+                context_file: cli.context_file,
+                rubric_file: cli.rubric_file,
+                critic_diff_scope: cli.critic_diff_scope,
+                notify_cmd: cli.notify_cmd,
+                max_feedback_chars: cli.max_feedback_chars,
+                interactive: cli.interactive,
+                like: cli.like,
+                max_unchanged_iterations: cli.max_unchanged_iterations,
+                actor_cmd: cli.actor_cmd,
+                agent_timeout_secs: cli.agent_timeout_secs,
+                agent_retries: cli.agent_retries,
+                agent_retry_delay_secs: cli.agent_retry_delay_secs,
             })
             .await
         }
@@ -442,7 +995,7 @@ async fn handle_project_command(action: ProjectAction) -> Result<()> {
 
 struct RunArgs {
     prompt: Option<String>,
-    prompt_file: PathBuf,
+    prompt_file: Vec<PathBuf>,
     working_dir: Option<PathBuf>,
     agent: Option<AgentChoice>,
     actor_agent: Option<AgentChoice>,
@@ -454,134 +1007,374 @@ struct RunArgs {
     json_output: bool,
     dry_run: bool,
     no_color: bool,
+    sessions_dir: Option<PathBuf>,
+    verify_cmd: Option<String>,
+    fail_on_regression: bool,
+    force: bool,
+    allow_critic_reset: bool,
+    explain: bool,
+    stream_to_disk: bool,
+    max_output_tokens: Option<u32>,
+    iteration_warning_percent: u8,
+    no_critic: bool,
+    critic: Option<CriticChoice>,
+    idle_warning_secs: u64,
+    confidence_window: Option<usize>,
+    min_confidence_gain: f64,
+    output_dir: Option<PathBuf>,
+    coverage_cmd: Option<String>,
+    max_cost: Option<f64>,
+    no_session: bool,
+    apply_patches: bool,
+    seed_feedback: Option<String>,
+    result_file: Option<PathBuf>,
+    context_file: Vec<PathBuf>,
+    rubric_file: Option<PathBuf>,
+    critic_diff_scope: Vec<String>,
+    notify_cmd: Option<String>,
+    max_feedback_chars: Option<usize>,
+    interactive: bool,
+    like: Option<String>,
+    max_unchanged_iterations: Option<usize>,
+    actor_cmd: Option<String>,
+    agent_timeout_secs: Option<u64>,
+    agent_retries: usize,
+    agent_retry_delay_secs: u64,
 }
 
+/// Run the loop. With `--json-output`, errors that stop the run before it
+/// produces an outcome are printed to stdout as structured JSON. With
+/// `--result-file`, they are also recorded there.
 async fn run_loop(args: RunArgs) -> Result<()> {
+    let json_output = args.json_output;
+    let result_file = args.result_file.clone();
+    let notify_cmd = args.notify_cmd.clone();
+    let working_dir = args
+        .working_dir
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("."));
+    let result = execute_run(args).await;
+    if let Err(ref e) = result {
+        let run_result = RunResult::from_error(e);
+        if let Some(ref path) = result_file {
+            if let Err(write_err) = run_result.write(path) {
+                eprintln!("{} {:#}", "⚠".bright_yellow(), write_err);
+            }
+        }
+        if let Some(ref command) = notify_cmd {
+            notify::notify(command, &working_dir, &run_result, notify::NOTIFY_TIMEOUT).await;
+        }
+    }
+    match result {
+        Err(e) if json_output => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&run_error::error_json(&e))?
+            );
+            std::process::exit(run_error::STARTUP_FAILURE_EXIT_CODE);
+        }
+        result => result,
+    }
+}
+
+async fn execute_run(mut args: RunArgs) -> Result<()> {
     // Handle no-color flag
     if args.no_color {
         colored::control::set_override(false);
     }
 
+    // An earlier session's inputs, used where no flag is given
+    let like = match args.like {
+        Some(ref id) => {
+            let db = Database::open()
+                .context("Failed to open database")
+                .kind(ErrorKind::Config)?;
+            Some(RerunInputs::load(&db, id).kind(ErrorKind::Config)?)
+        }
+        None => None,
+    };
+    if let Some(ref like) = like {
+        let actor_given =
+            args.actor_cmd.is_some() || args.actor_agent.is_some() || args.agent.is_some();
+        if like.actor_command && !actor_given {
+            return Err(anyhow::anyhow!(
+                "Session {} ran a shell command as the actor, and the command is not recorded.\n\n  \
+                 Pass it again with --actor-cmd, or pick an agent with --actor-agent.",
+                like.session_id
+            ))
+            .kind(ErrorKind::Config);
+        }
+    }
+    // A session reviewed by the rules critic reruns with it unless --critic
+    // says otherwise
+    let critic_choice = args.critic.unwrap_or(match like {
+        Some(ref like) if like.rules_critic => CriticChoice::Rules,
+        _ => CriticChoice::Agent,
+    });
+
     // Determine working directory
     let working_dir = args
         .working_dir
         .clone()
+        .or_else(|| like.as_ref().map(|like| like.working_dir.clone()))
         .unwrap_or_else(|| std::env::current_dir().expect("Failed to get current directory"));
 
     // Load global config (hard error if file exists but is invalid)
-    let global_config = GlobalConfig::load().context("Failed to load global configuration")?;
+    let global_config = GlobalConfig::load()
+        .context("Failed to load global configuration")
+        .kind(ErrorKind::Config)?;
 
     // Load project config (hard error if file exists but is invalid)
-    let project_config =
-        ProjectConfig::load(&working_dir).context("Failed to load project configuration")?;
-
+    let project_config = ProjectConfig::load(&working_dir)
+        .context("Failed to load project configuration")
+        .kind(ErrorKind::Config)?;
+
+    // Relative paths in the project config resolve against its directory,
+    // which may be a parent of the working directory
+    let project_config_path = ProjectConfig::resolved_path(&working_dir);
+    let project_dir = project_config_path
+        .parent()
+        .unwrap_or(&working_dir)
+        .to_path_buf();
     if project_config.is_some() {
         eprintln!(
             "{} Loaded config from {}",
             "->".dimmed(),
-            working_dir.join(config::CONFIG_FILE_NAME).display()
+            project_config_path.display()
         );
     }
 
-    // Get prompt
-    let prompt = get_prompt(&args.prompt, &args.prompt_file, &working_dir)?;
+    // Get prompt, plus any run options from its front-matter
+    let prompt_arg = args
+        .prompt
+        .clone()
+        .or_else(|| like.as_ref().map(|like| like.prompt.clone()));
+    let (prompt, prompt_options, prompt_files) =
+        get_prompt(&prompt_arg, &args.prompt_file, &working_dir).kind(ErrorKind::Prompt)?;
+    let context_files =
+        read_context_files(&args.context_file, &working_dir).kind(ErrorKind::Prompt)?;
+    let critic_diff_scope = (!args.critic_diff_scope.is_empty())
+        .then(|| DiffScope::new(&args.critic_diff_scope))
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("--critic-diff-scope: {}", e))
+        .kind(ErrorKind::Config)?;
+    args.max_iterations = args.max_iterations.or(prompt_options.max_iterations);
+    args.verify_cmd = args.verify_cmd.or(prompt_options.verify_cmd);
+    args.coverage_cmd = args.coverage_cmd.or(prompt_options.coverage_cmd);
+    if critic_choice == CriticChoice::Rules && args.verify_cmd.is_none() {
+        return Err(anyhow::anyhow!(
+            "--critic rules needs a verify command to judge iterations.\n\n  \
+             Pass one with --verify-cmd, e.g.:\n    \
+             codeloops --critic rules --verify-cmd \"cargo test\""
+        ))
+        .kind(ErrorKind::Config);
+    }
+
+    let no_session = args.no_session || env_flag("CODELOOPS_NO_SESSION");
+
+    // Collect the run's artifacts in --output-dir, starting with the prompt.
+    // The log goes there too unless --log-file says otherwise.
+    let bundle = match args.output_dir {
+        Some(ref dir) if !args.dry_run => {
+            let bundle = RunBundle::create(dir).kind(ErrorKind::Config)?;
+            bundle.write_prompt(&prompt).kind(ErrorKind::Config)?;
+            args.log_file.get_or_insert_with(|| bundle.log_path());
+            Some(bundle)
+        }
+        _ => None,
+    };
 
     // Create logger (with optional file output)
     let log_format: LogFormat = args.log_format.into();
     let mut logger = if let Some(ref log_path) = args.log_file {
-        Logger::with_file(log_format, log_path).context("Failed to create file logger")?
+        Logger::with_file(log_format, log_path)
+            .context("Failed to create file logger")
+            .kind(ErrorKind::Config)?
     } else {
         Logger::new(log_format)
     };
 
+    // Reviewing needs the terminal to itself, so the TUI and key controls
+    // stay off; the logger's own output is used instead
+    let interactive = args.interactive
+        && if std::io::stdin().is_terminal() && std::io::stderr().is_terminal() {
+            true
+        } else {
+            eprintln!(
+                "{} --interactive needs a terminal; running without review",
+                "⚠".bright_yellow()
+            );
+            false
+        };
+
     // Create TUI renderer in Pretty mode only (auto-detects TTY vs pipe).
     // Json/Compact formats use the logger's own output; no TUI to manage.
-    let tui_renderer: Option<Arc<SessionRenderer>> = if log_format == LogFormat::Pretty {
-        let r = Arc::new(SessionRenderer::new());
-        r.set_max_iterations(args.max_iterations);
-        let r2 = r.clone();
-        logger.set_event_callback(Box::new(move |event| {
-            r2.on_log_event(event);
-        }));
-        Some(r)
-    } else {
-        None
-    };
-
-    // Determine actor agent
-    // Precedence: CLI flags > project config > global config > default (Claude)
-    let actor_agent = args
-        .actor_agent
-        .or(args.agent)
-        .or_else(|| {
+    let tui_renderer: Option<Arc<SessionRenderer>> =
+        if log_format == LogFormat::Pretty && !interactive {
+            let r = Arc::new(SessionRenderer::new());
+            r.set_max_iterations(args.max_iterations);
+            let r2 = r.clone();
+            logger.set_event_callback(Box::new(move |event| {
+                r2.on_log_event(event);
+            }));
+            Some(r)
+        } else {
+            None
+        };
+
+    // Determine agents and models, remembering where each came from.
+    // Precedence: CLI flags > --like session > prompt front-matter >
+    // environment (agents only) > project config > global config > default
+    let agent_env = env_agent("CODELOOPS_AGENT");
+    let global_config_path = GlobalConfig::resolved_path();
+    let project_source = || Source::ProjectConfig(project_config_path.clone());
+    let global_source = || Source::GlobalConfig(global_config_path.clone());
+    let session_source = || Source::Session(args.like.clone().unwrap_or_default());
+
+    let actor_agent = resolve::first_of([
+        (args.actor_agent, Source::Cli("--actor-agent")),
+        (args.agent, Source::Cli("--agent")),
+        (
+            like.as_ref().and_then(|like| like.actor_agent),
+            session_source(),
+        ),
+        (prompt_options.actor_agent, Source::FrontMatter),
+        (prompt_options.agent, Source::FrontMatter),
+        (
+            env_agent("CODELOOPS_ACTOR_AGENT"),
+            Source::Env("CODELOOPS_ACTOR_AGENT"),
+        ),
+        (agent_env, Source::Env("CODELOOPS_AGENT")),
+        (
             project_config
                 .as_ref()
                 .and_then(|c| c.actor_agent())
-                .and_then(parse_agent_choice)
-        })
-        .or_else(|| {
+                .and_then(parse_agent_choice),
+            project_source(),
+        ),
+        (
             global_config
                 .as_ref()
                 .and_then(|c| c.actor_agent())
-                .and_then(parse_agent_choice)
-        })
-        .unwrap_or(AgentChoice::Claude);
-
-    // Determine critic agent
-    // Precedence: CLI flags > project config > global config > default (Claude)
-    let critic_agent = args
-        .critic_agent
-        .or(args.agent)
-        .or_else(|| {
+                .and_then(parse_agent_choice),
+            global_source(),
+        ),
+    ])
+    .unwrap_or_else(|| Resolved::default_value(AgentChoice::Claude));
+
+    let critic_agent = resolve::first_of([
+        (args.critic_agent, Source::Cli("--critic-agent")),
+        (args.agent, Source::Cli("--agent")),
+        (
+            like.as_ref().and_then(|like| like.critic_agent),
+            session_source(),
+        ),
+        (prompt_options.critic_agent, Source::FrontMatter),
+        (prompt_options.agent, Source::FrontMatter),
+        (
+            env_agent("CODELOOPS_CRITIC_AGENT"),
+            Source::Env("CODELOOPS_CRITIC_AGENT"),
+        ),
+        (agent_env, Source::Env("CODELOOPS_AGENT")),
+        (
             project_config
                 .as_ref()
                 .and_then(|c| c.critic_agent())
-                .and_then(parse_agent_choice)
-        })
-        .or_else(|| {
+                .and_then(parse_agent_choice),
+            project_source(),
+        ),
+        (
             global_config
                 .as_ref()
                 .and_then(|c| c.critic_agent())
-                .and_then(parse_agent_choice)
-        })
-        .unwrap_or(AgentChoice::Claude);
-
-    let actor_type: AgentType = actor_agent.into();
-    let critic_type: AgentType = critic_agent.into();
-
-    // Determine models
-    // Precedence: CLI flags > project config > global config > None
-    let actor_model = args
-        .model
-        .clone()
-        .or_else(|| {
+                .and_then(parse_agent_choice),
+            global_source(),
+        ),
+    ])
+    .unwrap_or_else(|| Resolved::default_value(AgentChoice::Claude));
+
+    // --actor-cmd replaces the actor agent outright
+    let (actor_type, actor_source) = match args.actor_cmd {
+        Some(_) => (AgentType::Command, Source::Cli("--actor-cmd")),
+        None => (actor_agent.value.into(), actor_agent.source),
+    };
+    let critic_type: AgentType = critic_agent.value.into();
+
+    // Models have no built-in default; None lets the agent pick. A --like
+    // session's model only carries over to the agent it was meant for.
+    let actor_model_resolved = resolve::first_of([
+        (args.model.clone(), Source::Cli("--model")),
+        (
+            like.as_ref()
+                .filter(|like| {
+                    args.actor_cmd.is_none() && like.actor_agent == Some(actor_agent.value)
+                })
+                .and_then(|like| like.actor_model.clone()),
+            session_source(),
+        ),
+        (prompt_options.model.clone(), Source::FrontMatter),
+        (
             project_config
                 .as_ref()
                 .and_then(|c| c.actor_model())
-                .map(String::from)
-        })
-        .or_else(|| {
+                .map(String::from),
+            project_source(),
+        ),
+        (
             global_config
                 .as_ref()
                 .and_then(|c| c.actor_model())
-                .map(String::from)
-        });
-
-    let critic_model = args
-        .model
-        .clone()
-        .or_else(|| {
+                .map(String::from),
+            global_source(),
+        ),
+    ]);
+
+    let critic_model_resolved = resolve::first_of([
+        (args.model.clone(), Source::Cli("--model")),
+        (
+            like.as_ref()
+                .filter(|like| like.critic_agent == Some(critic_agent.value))
+                .and_then(|like| like.critic_model.clone()),
+            session_source(),
+        ),
+        (prompt_options.model.clone(), Source::FrontMatter),
+        (
             project_config
                 .as_ref()
                 .and_then(|c| c.critic_model())
-                .map(String::from)
-        })
-        .or_else(|| {
+                .map(String::from),
+            project_source(),
+        ),
+        (
             global_config
                 .as_ref()
                 .and_then(|c| c.critic_model())
-                .map(String::from)
-        });
+                .map(String::from),
+            global_source(),
+        ),
+    ]);
+
+    if args.explain {
+        eprintln!("{}", "=== Agent Resolution ===".bright_blue().bold());
+        eprintln!(
+            "{} {} ({})",
+            "actor_agent:".dimmed(),
+            actor_type,
+            actor_source
+        );
+        print_model_resolution("actor_model:", &actor_model_resolved);
+        eprintln!(
+            "{} {} ({})",
+            "critic_agent:".dimmed(),
+            critic_type,
+            critic_agent.source
+        );
+        print_model_resolution("critic_model:", &critic_model_resolved);
+        eprintln!();
+    }
+
+    let actor_model = actor_model_resolved.map(|r| r.value);
+    let critic_model = critic_model_resolved.map(|r| r.value);
 
     if args.dry_run {
         println!("{}", "=== Dry Run ===".bright_blue().bold());
@@ -595,13 +1388,23 @@ async fn run_loop(args: RunArgs) -> Result<()> {
             }
         );
         println!("{}  {}", "Dir:".dimmed(), working_dir.display());
+        if let Some(ref like) = like {
+            println!("{}  {}", "Like session:".dimmed(), like.session_id);
+        }
         println!("{}  {}", "Actor:".dimmed(), actor_type);
+        if let Some(ref command) = args.actor_cmd {
+            println!("{}  {}", "Actor command:".dimmed(), command);
+        }
         if let Some(ref model) = actor_model {
             println!("{}  {}", "Actor model:".dimmed(), model);
         }
-        println!("{}  {}", "Critic:".dimmed(), critic_type);
-        if let Some(ref model) = critic_model {
-            println!("{}  {}", "Critic model:".dimmed(), model);
+        if critic_choice == CriticChoice::Rules {
+            println!("{}  {}", "Critic:".dimmed(), RULES_CRITIC_NAME);
+        } else {
+            println!("{}  {}", "Critic:".dimmed(), critic_type);
+            if let Some(ref model) = critic_model {
+                println!("{}  {}", "Critic model:".dimmed(), model);
+            }
         }
         println!(
             "{}  {}",
@@ -613,53 +1416,201 @@ async fn run_loop(args: RunArgs) -> Result<()> {
         if let Some(ref log_path) = args.log_file {
             println!("{}  {}", "Log file:".dimmed(), log_path.display());
         }
+        if let Some(ref dir) = args.output_dir {
+            println!("{}  {}", "Output dir:".dimmed(), dir.display());
+        }
+        if let Some(ref feedback) = args.seed_feedback {
+            println!("{}  {}", "Seed feedback:".dimmed(), feedback);
+        }
+        for file in &context_files {
+            println!("{}  {}", "Context file:".dimmed(), file.path.display());
+        }
+        if let Some(ref command) = args.notify_cmd {
+            println!("{}  {}", "Notify command:".dimmed(), command);
+        }
+        if let Some(ref scope) = critic_diff_scope {
+            println!(
+                "{}  {}",
+                "Critic diff scope:".dimmed(),
+                scope.patterns().join(", ")
+            );
+        }
+        if no_session {
+            println!("{}  not recorded", "Session:".dimmed());
+        }
         return Ok(());
     }
 
-    // Create agents
-    let actor = create_agent(actor_type);
-    let critic = create_agent(critic_type);
+    // Create agents; the custom agent's command comes from config
+    let build_agent = |agent_type: AgentType| -> Result<Box<dyn Agent>> {
+        Ok(match agent_type {
+            AgentType::Custom => Box::new(CustomAgent::new(
+                config::custom_agent_command(project_config.as_ref(), global_config.as_ref())
+                    .kind(ErrorKind::Config)?,
+            )),
+            other => create_agent(other)?,
+        })
+    };
+    let actor: Box<dyn Agent> = match args.actor_cmd {
+        Some(ref command) => Box::new(CommandAgent::new(command.as_str()).kind(ErrorKind::Config)?),
+        None => build_agent(actor_type)?,
+    };
+    let critic = build_agent(critic_type)?;
+
+    let rules_critic = critic_choice == CriticChoice::Rules;
 
     // Set agent names on the TUI renderer
     if let Some(ref r) = tui_renderer {
-        r.set_agent_names(actor.name(), critic.name());
+        let critic_name = if rules_critic {
+            RULES_CRITIC_NAME
+        } else {
+            critic.name()
+        };
+        r.set_agent_names(actor.name(), critic_name);
     }
 
     // Verify agents are available
     if !actor.is_available().await {
-        anyhow::bail!(
+        return Err(anyhow::anyhow!(
             "Agent '{}' is not available.\n\n  \
              Install it or choose a different agent:\n    \
              codeloops --agent opencode\n\n  \
-             Available agents: claude, claude-gateway, opencode, cursor",
+             Available agents: claude, claude-gateway, opencode, cursor, custom",
             actor.name()
-        );
+        ))
+        .kind(ErrorKind::AgentUnavailable);
     }
-    if !critic.is_available().await {
-        anyhow::bail!(
+    if !args.no_critic && !rules_critic && !critic.is_available().await {
+        return Err(anyhow::anyhow!(
             "Agent '{}' is not available.\n\n  \
              Install it or choose a different agent:\n    \
              codeloops --critic-agent opencode\n\n  \
-             Available agents: claude, claude-gateway, opencode, cursor",
+             Available agents: claude, claude-gateway, opencode, cursor, custom",
             critic.name()
-        );
+        ))
+        .kind(ErrorKind::AgentUnavailable);
     }
 
-    // Open database for session storage
-    let db = match Database::open() {
-        Ok(db) => Some(Arc::new(db)),
-        Err(e) => {
-            eprintln!("{} Failed to open database: {}", "⚠".bright_yellow(), e);
-            None
+    // Open database for session storage; without it nothing is recorded
+    let db = if no_session {
+        None
+    } else {
+        match Database::open() {
+            Ok(db) => Some(Arc::new(db)),
+            Err(e) => {
+                eprintln!("{} Failed to open database: {}", "⚠".bright_yellow(), e);
+                None
+            }
         }
     };
 
     // Create loop context
-    let mut context = LoopContext::new(prompt, working_dir.clone());
+    let mut context = LoopContext::new(prompt, working_dir.clone())
+        .with_prompt_files(prompt_files)
+        .with_context_files(context_files)
+        .with_seed_feedback(args.seed_feedback);
     if let Some(max) = args.max_iterations {
         context = context.with_max_iterations(max);
     }
 
+    // Precedence: project config > global config > off
+    let cumulative_diff = project_config
+        .as_ref()
+        .and_then(|c| c.cumulative_diff)
+        .or_else(|| {
+            global_config
+                .as_ref()
+                .and_then(|c| c.defaults.cumulative_diff)
+        })
+        .unwrap_or(false);
+    context = context.with_cumulative_diff(cumulative_diff);
+
+    // Precedence: CLI flag > project config > global config > no cap
+    let max_feedback_chars = args
+        .max_feedback_chars
+        .or_else(|| project_config.as_ref().and_then(|c| c.max_feedback_chars))
+        .or_else(|| {
+            global_config
+                .as_ref()
+                .and_then(|c| c.defaults.max_feedback_chars)
+        });
+    context = context.with_max_feedback_chars(max_feedback_chars);
+
+    // Precedence: project config > global config > built-in template
+    let actor_prompt_file = project_config
+        .as_ref()
+        .and_then(|c| c.actor_prompt_file.as_ref())
+        .map(|path| project_dir.join(path))
+        .or_else(|| {
+            let path = global_config
+                .as_ref()?
+                .defaults
+                .actor_prompt_file
+                .as_ref()?;
+            let dir = GlobalConfig::existing_path()?.parent()?.to_path_buf();
+            Some(dir.join(path))
+        });
+    if let Some(path) = actor_prompt_file {
+        let template = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read actor prompt {}", path.display()))
+            .kind(ErrorKind::Config)?;
+        let prompts = ActorPrompts::with_template(template)
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+            .kind(ErrorKind::Config)?;
+        context = context.with_actor_prompts(prompts);
+    }
+
+    // Precedence: CLI flag > project config > global config > no rubric.
+    // The flag is relative to the working directory.
+    let rubric_file = args
+        .rubric_file
+        .as_ref()
+        .map(|path| working_dir.join(path))
+        .or_else(|| {
+            let path = project_config.as_ref()?.rubric_file.as_ref()?;
+            Some(project_dir.join(path))
+        })
+        .or_else(|| {
+            let path = global_config.as_ref()?.defaults.rubric_file.as_ref()?;
+            let dir = GlobalConfig::existing_path()?.parent()?.to_path_buf();
+            Some(dir.join(path))
+        });
+    let rubric = match rubric_file {
+        Some(path) => {
+            let markdown = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read rubric {}", path.display()))
+                .kind(ErrorKind::Config)?;
+            let rubric = Rubric::parse(&markdown)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+                .kind(ErrorKind::Config)?;
+            if args.no_critic || rules_critic {
+                eprintln!(
+                    "{} The rubric is only checked by the critic agent; ignoring {}",
+                    "⚠".bright_yellow(),
+                    path.display()
+                );
+            }
+            Some(rubric)
+        }
+        None => None,
+    };
+    if critic_diff_scope.is_some() && args.no_critic {
+        eprintln!(
+            "{} --no-critic skips the critic; ignoring --critic-diff-scope",
+            "⚠".bright_yellow()
+        );
+    }
+
+    // Precedence: CLI flag > project config > global config > agent default
+    let max_output_tokens = args
+        .max_output_tokens
+        .or_else(|| project_config.as_ref().and_then(|c| c.max_output_tokens))
+        .or_else(|| {
+            global_config
+                .as_ref()
+                .and_then(|c| c.defaults.max_output_tokens)
+        });
+
     // Create loop runner
     let diff_capture = DiffCapture::new();
     let logger = Arc::new(logger);
@@ -671,29 +1622,116 @@ async fn run_loop(args: RunArgs) -> Result<()> {
         db.clone(),
         actor_model,
         critic_model,
+    )
+    .with_sessions_dir(codeloops_db::paths::sessions_dir(
+        args.sessions_dir.as_deref(),
+    ))
+    .with_verify_command(args.verify_cmd)
+    .with_coverage_command(args.coverage_cmd)
+    .with_apply_patches(args.apply_patches)
+    .with_fail_on_regression(args.fail_on_regression)
+    .with_force_lock(args.force)
+    .with_allow_critic_reset(args.allow_critic_reset)
+    .with_stream_to_disk(args.stream_to_disk)
+    .with_max_output_tokens(max_output_tokens)
+    .with_iteration_warning_percent(args.iteration_warning_percent)
+    .with_skip_critic(args.no_critic)
+    .with_idle_warning(
+        (args.idle_warning_secs > 0).then(|| Duration::from_secs(args.idle_warning_secs)),
+    )
+    .with_agent_timeout(args.agent_timeout_secs.map(Duration::from_secs))
+    .with_agent_retries(args.agent_retries)
+    .with_retry_backoff(RetryBackoff::new(Duration::from_secs(
+        args.agent_retry_delay_secs,
+    )))
+    .with_convergence_check(
+        args.confidence_window
+            .map(|window| ConvergenceCheck::new(window, args.min_confidence_gain)),
+    )
+    .with_max_unchanged_iterations(args.max_unchanged_iterations)
+    .with_max_cost(args.max_cost)
+    .with_critic_backend(if rules_critic {
+        CriticBackend::Rules(RuleConfig::default())
+    } else {
+        CriticBackend::Agent
+    })
+    .with_rubric(rubric)
+    .with_critic_diff_scope(critic_diff_scope)
+    .with_reviewer(
+        interactive.then(|| Arc::new(review::TerminalReviewer) as Arc<dyn IterationReviewer>),
     );
 
     // Handle Ctrl+C gracefully. The TUI render task keeps running until the
     // loop unwinds; final cleanup happens after the runner returns.
     let interrupt_handle = runner.interrupt_handle();
-    ctrlc::set_handler(move || {
-        eprintln!(
-            "\n{} Interrupted. Finishing current iteration...",
-            "⚠".bright_yellow()
-        );
-        interrupt_handle.store(true, Ordering::SeqCst);
-    })
-    .context("Failed to set Ctrl+C handler")?;
+    ctrlc::set_handler(move || keys::interrupt(&interrupt_handle))
+        .context("Failed to set Ctrl+C handler")?;
+
+    // Process managers and containers stop with SIGTERM instead; treat it the
+    // same so the session still gets an end record
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sigterm =
+            signal(SignalKind::terminate()).context("Failed to set SIGTERM handler")?;
+        let interrupt_handle = runner.interrupt_handle();
+        tokio::spawn(async move {
+            if sigterm.recv().await.is_some() {
+                keys::terminate(&interrupt_handle);
+            }
+        });
+    }
 
-    // Run the loop
-    let outcome = runner.run(context).await?;
+    // p/r/q keys pause, resume and stop the loop; the listener stops when
+    // dropped after the run
+    let key_listener = if interactive {
+        None
+    } else {
+        keys::KeyListener::spawn(
+            runner.pause_handle(),
+            runner.interrupt_handle(),
+            tui_renderer.is_some(),
+        )
+    };
+
+    // Run the loop. Errors here (e.g. the working directory is locked by
+    // another run) happen before any output, so restore the terminal first.
+    let outcome = match runner.run(context).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            if let Some(r) = tui_renderer {
+                r.cleanup().await;
+            }
+            let Some(session_id) = runner.session_id() else {
+                return Err(e.into());
+            };
+            let iterations = db
+                .as_ref()
+                .and_then(|db| db.sessions().get(session_id).ok().flatten())
+                .map_or(0, |session| session.iterations.len());
+            let final_diff = capture_final_diff(&working_dir);
+            return Err(PartialRunError::wrap(
+                e.into(),
+                session_id,
+                iterations,
+                runner.files_changed(&working_dir, &final_diff),
+            ));
+        }
+    };
+    drop(key_listener);
 
     // Output result
     if args.json_output {
         let json = serde_json::to_string_pretty(&outcome)?;
         println!("{}", json);
     } else {
-        print_outcome(&outcome, tui_renderer.as_deref(), log_format);
+        print_outcome(
+            &outcome,
+            tui_renderer.as_deref(),
+            log_format,
+            args.no_critic,
+        );
     }
 
     // Async cleanup: drains the channel and restores the terminal.
@@ -701,6 +1739,37 @@ async fn run_loop(args: RunArgs) -> Result<()> {
         r.cleanup().await;
     }
 
+    let final_diff = if bundle.is_some() || args.result_file.is_some() || args.notify_cmd.is_some()
+    {
+        capture_final_diff(&working_dir)
+    } else {
+        String::new()
+    };
+
+    if let Some(ref bundle) = bundle {
+        let session = match (&db, runner.session_id()) {
+            (Some(db), Some(id)) => db.sessions().get(id).ok().flatten(),
+            _ => None,
+        };
+        if let Err(e) = bundle.write_results(&final_diff, &outcome, session.as_ref()) {
+            eprintln!("{} {:#}", "⚠".bright_yellow(), e);
+        }
+    }
+
+    let run_result = RunResult::from_outcome(
+        &outcome,
+        runner.session_id(),
+        runner.files_changed(&working_dir, &final_diff),
+    );
+    if let Some(ref path) = args.result_file {
+        if let Err(e) = run_result.write(path) {
+            eprintln!("{} {:#}", "⚠".bright_yellow(), e);
+        }
+    }
+    if let Some(ref command) = args.notify_cmd {
+        notify::notify(command, &working_dir, &run_result, notify::NOTIFY_TIMEOUT).await;
+    }
+
     // Print session ID and hints
     if let Some(session_id) = runner.session_id() {
         eprintln!("{} Session: {}", "->".dimmed(), session_id);
@@ -716,22 +1785,59 @@ async fn run_loop(args: RunArgs) -> Result<()> {
     std::process::exit(outcome.exit_code());
 }
 
-fn get_prompt(prompt: &Option<String>, prompt_file: &Path, working_dir: &Path) -> Result<String> {
+/// The working tree's diff when the run ended; empty, with a warning, if it
+/// cannot be captured.
+fn capture_final_diff(working_dir: &Path) -> String {
+    DiffCapture::new()
+        .capture_diff(working_dir)
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "{} Failed to capture final diff: {}",
+                "⚠".bright_yellow(),
+                e
+            );
+            String::new()
+        })
+}
+
+/// Placed between prompt files when several are given.
+const PROMPT_FILE_SEPARATOR: &str = "\n\n---\n\n";
+
+/// The prompt file read when `--prompt-file` is not given.
+const DEFAULT_PROMPT_FILE: &str = "prompt.md";
+
+/// Read the prompt from `--prompt` or the prompt files, returning the files
+/// used. Several files are joined in order with [`PROMPT_FILE_SEPARATOR`].
+/// Front-matter is only read from files; later files override earlier ones,
+/// and problems in it are reported as warnings.
+fn get_prompt(
+    prompt: &Option<String>,
+    prompt_files: &[PathBuf],
+    working_dir: &Path,
+) -> Result<(String, front_matter::PromptOptions, Vec<PathBuf>)> {
     if let Some(ref prompt) = prompt {
-        return Ok(prompt.clone());
+        return Ok((
+            prompt.clone(),
+            front_matter::PromptOptions::default(),
+            Vec::new(),
+        ));
     }
 
-    let prompt_path = if prompt_file.is_absolute() {
-        prompt_file.to_path_buf()
-    } else {
-        working_dir.join(prompt_file)
-    };
+    let prompt_paths: Vec<PathBuf> = prompt_files
+        .iter()
+        .map(|file| {
+            if file.is_absolute() {
+                file.clone()
+            } else {
+                working_dir.join(file)
+            }
+        })
+        .collect();
 
-    if prompt_path.exists() {
-        let content =
-            std::fs::read_to_string(&prompt_path).context("Failed to read prompt file")?;
-        Ok(content.trim().to_string())
-    } else {
+    if let Some(missing) = prompt_paths.iter().find(|path| !path.exists()) {
+        if prompt_files != [PathBuf::from(DEFAULT_PROMPT_FILE)] {
+            anyhow::bail!("Prompt file not found: {}", missing.display());
+        }
         anyhow::bail!(
             "No prompt provided.\n\n  \
              Create a prompt.md in your project directory:\n    \
@@ -740,11 +1846,56 @@ fn get_prompt(prompt: &Option<String>, prompt_file: &Path, working_dir: &Path) -
              codeloops --prompt \"Fix the auth bug in login.rs\""
         )
     }
+
+    let mut bodies = Vec::with_capacity(prompt_paths.len());
+    let mut options = front_matter::PromptOptions::default();
+    for prompt_path in &prompt_paths {
+        let content = std::fs::read_to_string(prompt_path)
+            .with_context(|| format!("Failed to read prompt file {}", prompt_path.display()))?;
+        let parsed = front_matter::parse(&content);
+        for warning in &parsed.warnings {
+            eprintln!(
+                "{} {}: {}",
+                "⚠".bright_yellow(),
+                prompt_path.display(),
+                warning
+            );
+        }
+        options = options.overridden_by(parsed.options);
+        bodies.push(parsed.body);
+    }
+
+    Ok((bodies.join(PROMPT_FILE_SEPARATOR), options, prompt_paths))
+}
+
+/// Read the `--context-file` files, resolving relative paths against the
+/// working directory like the prompt files.
+fn read_context_files(files: &[PathBuf], working_dir: &Path) -> Result<Vec<ContextFile>> {
+    files
+        .iter()
+        .map(|file| {
+            let path = if file.is_absolute() {
+                file.clone()
+            } else {
+                working_dir.join(file)
+            };
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read context file {}", path.display()))?;
+            Ok(ContextFile { path, content })
+        })
+        .collect()
 }
 
-fn print_outcome(outcome: &LoopOutcome, renderer: Option<&SessionRenderer>, format: LogFormat) {
+/// `critic_skipped` hides the confidence score, which is meaningless without
+/// a critic.
+fn print_outcome(
+    outcome: &LoopOutcome,
+    renderer: Option<&SessionRenderer>,
+    format: LogFormat,
+    critic_skipped: bool,
+) {
     // Pretty mode: send the outcome to the TUI which renders the final
-    // scrollback line. Covers all four LoopOutcome variants (LogEvent only
+    // scrollback line. Covers every LoopOutcome variant (LogEvent only
     // emits LoopCompleted/MaxIterationsReached, never Interrupted/Failed).
     if format == LogFormat::Pretty {
         if let Some(r) = renderer {
@@ -759,7 +1910,7 @@ fn print_outcome(outcome: &LoopOutcome, renderer: Option<&SessionRenderer>, form
                     iterations: *iterations,
                     total_duration_secs: *total_duration_secs,
                     summary: Some(summary.clone()),
-                    confidence: Some(*confidence),
+                    confidence: (!critic_skipped).then_some(*confidence),
                 },
                 LoopOutcome::MaxIterationsReached {
                     iterations,
@@ -769,6 +1920,26 @@ fn print_outcome(outcome: &LoopOutcome, renderer: Option<&SessionRenderer>, form
                     iterations: *iterations,
                     total_duration_secs: *total_duration_secs,
                 },
+                LoopOutcome::NoProgress {
+                    iterations,
+                    reason,
+                    total_duration_secs,
+                    ..
+                } => RenderEvent::FinalNoProgress {
+                    iterations: *iterations,
+                    total_duration_secs: *total_duration_secs,
+                    reason: reason.clone(),
+                },
+                LoopOutcome::CostBudgetExceeded {
+                    iterations,
+                    spent_usd,
+                    total_duration_secs,
+                    ..
+                } => RenderEvent::FinalCostBudgetExceeded {
+                    iterations: *iterations,
+                    total_duration_secs: *total_duration_secs,
+                    spent_usd: *spent_usd,
+                },
                 LoopOutcome::UserInterrupted {
                     iterations,
                     total_duration_secs,
@@ -819,12 +1990,14 @@ fn print_outcome(outcome: &LoopOutcome, renderer: Option<&SessionRenderer>, form
                 },
                 total_duration_secs
             );
-            let _ = writeln!(
-                stderr,
-                "   {} {:.0}%",
-                "Confidence:".dimmed(),
-                confidence * 100.0
-            );
+            if !critic_skipped {
+                let _ = writeln!(
+                    stderr,
+                    "   {} {:.0}%",
+                    "Confidence:".dimmed(),
+                    confidence * 100.0
+                );
+            }
             let wrapped = wrap_text(summary, 70);
             for (i, line) in wrapped.iter().enumerate() {
                 if i == 0 {
@@ -854,6 +2027,45 @@ fn print_outcome(outcome: &LoopOutcome, renderer: Option<&SessionRenderer>, form
                 "The task may not be fully complete.".dimmed()
             );
         }
+        LoopOutcome::NoProgress {
+            iterations,
+            reason,
+            total_duration_secs,
+            ..
+        } => {
+            let _ = writeln!(stderr);
+            let _ = writeln!(
+                stderr,
+                "{} {} after {} iterations ({:.1}s)",
+                "⚠".bright_yellow(),
+                "STALLED".bright_yellow().bold(),
+                iterations,
+                total_duration_secs
+            );
+            let _ = writeln!(stderr, "   {}", reason.dimmed());
+        }
+        LoopOutcome::CostBudgetExceeded {
+            iterations,
+            spent_usd,
+            total_duration_secs,
+            ..
+        } => {
+            let _ = writeln!(stderr);
+            let _ = writeln!(
+                stderr,
+                "{} {} after {} iterations ({:.1}s), spent about ${:.2}",
+                "⚠".bright_yellow(),
+                "OVER BUDGET".bright_yellow().bold(),
+                iterations,
+                total_duration_secs,
+                spent_usd
+            );
+            let _ = writeln!(
+                stderr,
+                "   {}",
+                "The task may not be fully complete.".dimmed()
+            );
+        }
         LoopOutcome::UserInterrupted {
             iterations,
             total_duration_secs,
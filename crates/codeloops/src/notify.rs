@@ -0,0 +1,130 @@
+//! `--notify-cmd`: a shell command run when a run ends, for pinging someone
+//! about an unattended run (desktop notification, chat webhook, ...).
+//!
+//! The command gets the result in the environment:
+//! - `CODELOOPS_OUTCOME`: the `outcome` of the result file (`success`,
+//!   `failed`, ..., or `error`)
+//! - `CODELOOPS_ITERATIONS`: iterations run
+//! - `CODELOOPS_SESSION_ID`: the session, empty when none was recorded
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use colored::Colorize;
+use tokio::process::Command;
+
+use crate::result_file::RunResult;
+
+/// How long the notify command may run before it is killed, so a hanging
+/// notifier does not keep the run from exiting.
+pub const NOTIFY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run `command` through the shell in `working_dir`. Failures are reported
+/// as warnings; they never change how the run ends.
+pub async fn notify(command: &str, working_dir: &Path, result: &RunResult, timeout: Duration) {
+    let mut cmd = Command::new("sh");
+    // Its own process group, so a timeout also kills what the shell started
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let child = cmd
+        .arg("-c")
+        .arg(command)
+        .current_dir(working_dir)
+        .env("CODELOOPS_OUTCOME", &result.outcome)
+        .env("CODELOOPS_ITERATIONS", result.iterations.to_string())
+        .env(
+            "CODELOOPS_SESSION_ID",
+            result.session_id.as_deref().unwrap_or_default(),
+        )
+        .stdin(Stdio::null())
+        // Keep stdout for --json-output
+        .stdout(Stdio::from(std::io::stderr()))
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            warn(&format!("Failed to run notify command: {}", e));
+            return;
+        }
+    };
+
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(status)) if status.success() => {}
+        Ok(Ok(status)) => warn(&format!("Notify command exited with {}", status)),
+        Ok(Err(e)) => warn(&format!("Failed to wait for notify command: {}", e)),
+        Err(_) => {
+            kill_group(&child);
+            let _ = child.kill().await;
+            warn(&format!(
+                "Notify command did not finish within {}s; killed it",
+                timeout.as_secs()
+            ));
+        }
+    }
+}
+
+/// Kill the process group `child` leads.
+#[cfg(unix)]
+fn kill_group(child: &tokio::process::Child) {
+    use nix::sys::signal::{killpg, Signal};
+    use nix::unistd::Pid;
+
+    if let Some(pid) = child.id() {
+        let _ = killpg(Pid::from_raw(pid as i32), Signal::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_group(_child: &tokio::process::Child) {}
+
+fn warn(message: &str) {
+    eprintln!("{} {}", "⚠".bright_yellow(), message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn passes_the_result_in_the_environment() {
+        let tmp = tempfile::tempdir().unwrap();
+        let result = RunResult::from_error(&anyhow::anyhow!("boom"));
+        notify(
+            "echo \"$CODELOOPS_OUTCOME $CODELOOPS_ITERATIONS [$CODELOOPS_SESSION_ID]\" > out.txt",
+            tmp.path(),
+            &result,
+            NOTIFY_TIMEOUT,
+        )
+        .await;
+        let out = std::fs::read_to_string(tmp.path().join("out.txt")).unwrap();
+        assert_eq!(out, "error 0 []\n");
+    }
+
+    #[tokio::test]
+    async fn kills_a_hanging_command() {
+        let tmp = tempfile::tempdir().unwrap();
+        let result = RunResult::from_error(&anyhow::anyhow!("boom"));
+        let started = Instant::now();
+        notify("sleep 10", tmp.path(), &result, Duration::from_millis(100)).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn kills_what_a_hanging_command_started() {
+        let tmp = tempfile::tempdir().unwrap();
+        let result = RunResult::from_error(&anyhow::anyhow!("boom"));
+        notify(
+            "(sleep 1; touch late.txt) & wait",
+            tmp.path(),
+            &result,
+            Duration::from_millis(100),
+        )
+        .await;
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!tmp.path().join("late.txt").exists());
+    }
+}
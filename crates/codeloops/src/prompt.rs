@@ -0,0 +1,502 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use colored::Colorize;
+use serde::Serialize;
+
+use codeloops_core::PromptDraft;
+use codeloops_db::{Database, DuplicateGroup, PromptFilter, PromptRecord};
+
+use crate::api::{render_transcript, SessionStatePayload};
+
+#[derive(Subcommand, Debug)]
+pub enum PromptAction {
+    /// Check a prompt.md for missing sections and vague language
+    Lint {
+        /// Prompt file to lint
+        #[arg(default_value = "prompt.md")]
+        file: PathBuf,
+
+        /// Minimum score (0-100) required to pass
+        #[arg(long, default_value = "60")]
+        threshold: u8,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List saved prompts, most recently updated first
+    List {
+        /// Only prompts from this project
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Search titles, content and project names
+        #[arg(long)]
+        search: Option<String>,
+
+        /// Maximum number of prompts to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print the full content of a saved prompt
+    Show {
+        /// Prompt ID (see `codeloops prompts list`)
+        id: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Write a saved prompt to disk, optionally with its interview transcript
+    Export {
+        /// Prompt ID (see `codeloops prompts list`)
+        id: String,
+
+        /// Where to write the prompt
+        #[arg(short, long, default_value = "prompt.md")]
+        output: PathBuf,
+
+        /// Also write the interview Q&A transcript to this path
+        #[arg(long)]
+        save_transcript: Option<PathBuf>,
+
+        /// Append the transcript to the prompt as an appendix section
+        #[arg(long)]
+        appendix: bool,
+    },
+
+    /// Report prompts with identical content in the same project
+    Dedup {
+        /// Merge each group into its most recently updated prompt
+        #[arg(long)]
+        apply: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Points deducted per vague phrase found, capped at `MAX_VAGUE_PENALTY`.
+const VAGUE_PENALTY: u8 = 5;
+const MAX_VAGUE_PENALTY: u8 = 25;
+
+/// Phrases that usually signal an underspecified requirement.
+const VAGUE_PHRASES: &[&str] = &[
+    "etc",
+    "and so on",
+    "somehow",
+    "something like",
+    "maybe",
+    "probably",
+    "as needed",
+    "as appropriate",
+    "if possible",
+    "tbd",
+    "todo",
+    "various",
+    "make it better",
+    "clean up",
+    "improve",
+];
+
+/// Result of linting a prompt.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintReport {
+    pub completion: u8,
+    pub score: u8,
+    pub missing_sections: Vec<&'static str>,
+    pub vague_phrases: Vec<VagueHit>,
+}
+
+/// A saved prompt as listed by `prompts list --json`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptListing {
+    pub id: String,
+    pub title: Option<String>,
+    pub work_type: String,
+    pub project_name: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VagueHit {
+    pub line: usize,
+    pub phrase: &'static str,
+}
+
+pub async fn handle_prompt_command(action: PromptAction) -> Result<()> {
+    match action {
+        PromptAction::Lint {
+            file,
+            threshold,
+            json,
+        } => {
+            let content = std::fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let report = lint(&content);
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_report(&file, &report, threshold);
+            }
+
+            if report.score < threshold {
+                std::process::exit(1);
+            }
+        }
+        PromptAction::List {
+            project,
+            search,
+            limit,
+            json,
+        } => {
+            let db = Database::open()?;
+            let filter = PromptFilter {
+                project_name: project,
+                search,
+                limit: Some(limit),
+                offset: None,
+            };
+            let records = db.prompts().list(&filter)?;
+
+            if json {
+                let listings: Vec<PromptListing> = records
+                    .into_iter()
+                    .map(|r| PromptListing {
+                        id: r.id,
+                        title: r.title,
+                        work_type: r.work_type,
+                        project_name: r.project_name,
+                        created_at: r.created_at.to_rfc3339(),
+                        updated_at: r.updated_at.to_rfc3339(),
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&listings)?);
+            } else if records.is_empty() {
+                eprintln!("{}", "No saved prompts found.".dimmed());
+            } else {
+                print_prompts_table(&records);
+            }
+        }
+        PromptAction::Show { id, json } => {
+            let db = Database::open()?;
+            let record = db
+                .prompts()
+                .get(&id)?
+                .ok_or_else(|| anyhow::anyhow!("Prompt not found: {}", id))?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&record)?);
+            } else {
+                print_prompt(&record);
+            }
+        }
+        PromptAction::Export {
+            id,
+            output,
+            save_transcript,
+            appendix,
+        } => {
+            let db = Database::open()?;
+            let record = db
+                .prompts()
+                .get(&id)?
+                .ok_or_else(|| anyhow::anyhow!("Prompt not found: {}", id))?;
+            let state: SessionStatePayload = serde_json::from_str(&record.session_state)
+                .context("Failed to parse prompt session state")?;
+
+            // Prefer the final prompt; fall back to the in-progress draft
+            let mut content = record
+                .content
+                .filter(|c| !c.trim().is_empty())
+                .unwrap_or_else(|| state.prompt_draft.clone());
+            if content.trim().is_empty() {
+                anyhow::bail!("Prompt {} has no content yet", id);
+            }
+
+            let transcript = render_transcript(&state.messages);
+            if appendix {
+                content = format!(
+                    "{}\n\n## Appendix: Interview Transcript\n\n{}",
+                    content.trim_end(),
+                    transcript
+                );
+            }
+
+            std::fs::write(&output, &content)
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            eprintln!("{} Wrote {}", "✓".bright_green(), output.display());
+
+            if let Some(path) = save_transcript {
+                let title = record.title.as_deref().unwrap_or("Untitled prompt");
+                let document = format!("# Interview Transcript: {}\n\n{}", title, transcript);
+                std::fs::write(&path, document)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                eprintln!(
+                    "{} Wrote transcript ({} messages) to {}",
+                    "✓".bright_green(),
+                    state.messages.len(),
+                    path.display()
+                );
+            }
+        }
+        PromptAction::Dedup { apply, json } => {
+            let db = Database::open()?;
+            let groups = db.prompts().find_duplicates()?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&groups)?);
+            } else if groups.is_empty() {
+                eprintln!("{}", "No duplicate prompts found.".dimmed());
+            } else {
+                print_duplicates(&groups);
+            }
+
+            if apply && !groups.is_empty() {
+                let mut deleted = 0;
+                for group in &groups {
+                    let ids: Vec<String> = group.records.iter().map(|r| r.id.clone()).collect();
+                    deleted += db.prompts().merge(&ids[0], &ids[1..])?;
+                }
+                eprintln!(
+                    "{} Merged {} duplicate prompt{}",
+                    "✓".bright_green(),
+                    deleted,
+                    if deleted == 1 { "" } else { "s" }
+                );
+            } else if !groups.is_empty() && !json {
+                eprintln!();
+                eprintln!(
+                    "  Run {} to merge them.",
+                    "codeloops prompts dedup --apply".bright_cyan()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Lint prompt markdown: score section completeness and flag vague language.
+pub fn lint(markdown: &str) -> LintReport {
+    let draft = PromptDraft::from_markdown(markdown);
+
+    let mut missing_sections = Vec::new();
+    if draft.title.is_none() {
+        missing_sections.push("Title");
+    }
+    if draft.goal.is_none() {
+        missing_sections.push("Goal / Problem");
+    }
+    if draft.requirements.is_empty() {
+        missing_sections.push("Requirements");
+    }
+    if draft.acceptance_criteria.is_empty() {
+        missing_sections.push("Acceptance Criteria");
+    }
+
+    let vague_phrases = find_vague_phrases(markdown);
+    let penalty = (vague_phrases.len() as u8)
+        .saturating_mul(VAGUE_PENALTY)
+        .min(MAX_VAGUE_PENALTY);
+    let completion = draft.completion_percentage();
+
+    LintReport {
+        completion,
+        score: completion.saturating_sub(penalty),
+        missing_sections,
+        vague_phrases,
+    }
+}
+
+fn find_vague_phrases(markdown: &str) -> Vec<VagueHit> {
+    let mut hits = Vec::new();
+    for (idx, line) in markdown.lines().enumerate() {
+        let lower = line.to_lowercase();
+        for phrase in VAGUE_PHRASES {
+            if contains_word(&lower, phrase) {
+                hits.push(VagueHit {
+                    line: idx + 1,
+                    phrase,
+                });
+            }
+        }
+    }
+    hits
+}
+
+/// Whole-word (or whole-phrase) match, so "etc" doesn't fire on "fetch".
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(start, _)| {
+        let end = start + needle.len();
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[end..].chars().next();
+        !before.is_some_and(|c| c.is_alphanumeric()) && !after.is_some_and(|c| c.is_alphanumeric())
+    })
+}
+
+fn print_report(file: &Path, report: &LintReport, threshold: u8) {
+    println!(
+        "{}",
+        format!("=== Lint: {} ===", file.display())
+            .bright_blue()
+            .bold()
+    );
+
+    if report.missing_sections.is_empty() {
+        println!("{} All core sections present", "✓".bright_green());
+    } else {
+        for section in &report.missing_sections {
+            println!("{} Missing section: {}", "✗".bright_red(), section);
+        }
+    }
+
+    for hit in &report.vague_phrases {
+        println!(
+            "{} Line {}: vague language \"{}\"",
+            "⚠".bright_yellow(),
+            hit.line,
+            hit.phrase
+        );
+    }
+
+    println!();
+    println!("{}  {}%", "Completion:".dimmed(), report.completion);
+    let score = format!("{}/100", report.score);
+    println!(
+        "{}  {} (threshold {})",
+        "Score:".dimmed(),
+        if report.score >= threshold {
+            score.bright_green()
+        } else {
+            score.bright_red()
+        },
+        threshold
+    );
+}
+
+fn print_prompts_table(records: &[PromptRecord]) {
+    println!(
+        "{:<38} {:<17} {:<10} {:<16} {}",
+        "ID".dimmed(),
+        "UPDATED".dimmed(),
+        "TYPE".dimmed(),
+        "PROJECT".dimmed(),
+        "TITLE".dimmed(),
+    );
+
+    for record in records {
+        println!(
+            "{:<38} {:<17} {:<10} {:<16} {}",
+            record.id,
+            record.updated_at.format("%Y-%m-%d %H:%M"),
+            record.work_type,
+            record.project_name,
+            record.title.as_deref().unwrap_or("(untitled)")
+        );
+    }
+}
+
+fn print_prompt(record: &PromptRecord) {
+    let title = record.title.as_deref().unwrap_or("(untitled)");
+    println!("{}", format!("=== {} ===", title).bright_blue().bold());
+    println!("{}  {}", "ID:".dimmed(), record.id);
+    println!(
+        "{}  {} ({})",
+        "Project:".dimmed(),
+        record.project_name,
+        record.project_path
+    );
+    println!("{}  {}", "Type:".dimmed(), record.work_type);
+    println!(
+        "{}  {}",
+        "Updated:".dimmed(),
+        record.updated_at.format("%Y-%m-%d %H:%M")
+    );
+    println!();
+
+    match record.content.as_deref().filter(|c| !c.trim().is_empty()) {
+        Some(content) => println!("{}", content.trim_end()),
+        None => println!("{}", "(no content yet)".dimmed()),
+    }
+}
+
+fn print_duplicates(groups: &[DuplicateGroup]) {
+    for group in groups {
+        println!(
+            "{} {} ({} copies)",
+            group.project_name.bright_blue(),
+            group.content_hash.dimmed(),
+            group.records.len()
+        );
+        for (i, record) in group.records.iter().enumerate() {
+            let marker = if i == 0 {
+                "keep".bright_green()
+            } else {
+                "dup ".bright_yellow()
+            };
+            println!(
+                "  {} {} {} {}",
+                marker,
+                record.id,
+                record.updated_at.format("%Y-%m-%d %H:%M"),
+                record.title.as_deref().unwrap_or("(untitled)")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_complete_prompt() {
+        let report = lint(
+            "# Title\n\n## Goal\nAdd caching.\n\n## Requirements\n- Cache reads\n\n\
+             ## Acceptance Criteria\n- Hit rate is logged\n\n## Edge Cases\n- Cold start\n\n\
+             ## Testing\nUnit tests for the cache.\n",
+        );
+        assert!(report.missing_sections.is_empty());
+        assert!(report.vague_phrases.is_empty());
+        assert_eq!(report.score, 100);
+    }
+
+    #[test]
+    fn lint_flags_missing_sections_and_vague_language() {
+        let report = lint("Improve the login flow, maybe add logging etc.");
+        assert_eq!(
+            report.missing_sections,
+            vec![
+                "Title",
+                "Goal / Problem",
+                "Requirements",
+                "Acceptance Criteria"
+            ]
+        );
+        let phrases: Vec<&str> = report.vague_phrases.iter().map(|h| h.phrase).collect();
+        assert_eq!(phrases, vec!["etc", "maybe", "improve"]);
+        assert_eq!(report.score, 0);
+    }
+
+    #[test]
+    fn contains_word_respects_boundaries() {
+        assert!(contains_word("logs, etc.", "etc"));
+        assert!(!contains_word("fetch the data", "etc"));
+        assert!(contains_word("and so on", "and so on"));
+    }
+}
@@ -0,0 +1,120 @@
+//! `--like <SESSION_ID>`: a fresh run with an earlier session's inputs.
+//!
+//! Nothing of the earlier run's progress carries over. Its prompt, working
+//! directory, agents and models become defaults that flags still override,
+//! and the run is recorded as a new session.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use codeloops_agent::AgentType;
+use codeloops_core::RULES_CRITIC_NAME;
+use codeloops_db::{Database, Session};
+
+use crate::AgentChoice;
+
+/// What a session was started with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RerunInputs {
+    pub session_id: String,
+    pub prompt: String,
+    pub working_dir: PathBuf,
+    /// `None` when the recorded agent is not one `--agent` can pick, such as
+    /// the rules critic
+    pub actor_agent: Option<AgentChoice>,
+    pub critic_agent: Option<AgentChoice>,
+    /// The actor ran a shell command given with `--actor-cmd`, which is not
+    /// recorded, so it cannot be rerun as is
+    pub actor_command: bool,
+    /// Iterations were reviewed by the rules critic instead of an agent
+    pub rules_critic: bool,
+    pub actor_model: Option<String>,
+    pub critic_model: Option<String>,
+}
+
+impl RerunInputs {
+    /// The inputs of session `id`.
+    pub fn load(db: &Database, id: &str) -> Result<Self> {
+        let session = db
+            .sessions()
+            .get(id)?
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", id))?;
+        Ok(Self::from(session))
+    }
+}
+
+impl From<Session> for RerunInputs {
+    fn from(session: Session) -> Self {
+        Self {
+            actor_agent: agent_choice(&session.actor_agent),
+            critic_agent: agent_choice(&session.critic_agent),
+            actor_command: agent_type(&session.actor_agent) == Some(AgentType::Command),
+            rules_critic: session.critic_agent == RULES_CRITIC_NAME,
+            session_id: session.id,
+            prompt: session.prompt,
+            working_dir: session.working_dir,
+            actor_model: session.actor_model,
+            critic_model: session.critic_model,
+        }
+    }
+}
+
+/// The agent type behind a recorded agent name such as "Claude Code".
+fn agent_type(name: &str) -> Option<AgentType> {
+    name.replace(' ', "-").parse::<AgentType>().ok()
+}
+
+/// The `--agent` choice for a recorded agent name, if it has one.
+fn agent_choice(name: &str) -> Option<AgentChoice> {
+    agent_type(name).and_then(|agent_type| AgentChoice::try_from(agent_type).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use codeloops_db::SessionStart;
+
+    #[test]
+    fn loads_the_inputs_a_session_started_with() {
+        let db = Database::open_in_memory().unwrap();
+        let start = SessionStart {
+            prompt: "Fix the parser".to_string(),
+            working_dir: PathBuf::from("/work/api"),
+            actor_agent: "Claude Code".to_string(),
+            critic_agent: RULES_CRITIC_NAME.to_string(),
+            actor_model: Some("opus".to_string()),
+            ..Default::default()
+        };
+        let id = db.sessions().create(&start).unwrap();
+
+        let inputs = RerunInputs::load(&db, &id).unwrap();
+        assert_eq!(inputs.prompt, "Fix the parser");
+        assert_eq!(inputs.working_dir, PathBuf::from("/work/api"));
+        assert_eq!(inputs.actor_agent, Some(AgentChoice::Claude));
+        assert!(!inputs.actor_command);
+        assert_eq!(inputs.critic_agent, None);
+        assert!(inputs.rules_critic);
+        assert_eq!(inputs.actor_model.as_deref(), Some("opus"));
+
+        assert_eq!(agent_choice("OpenCode"), Some(AgentChoice::Opencode));
+        assert_eq!(agent_choice("Custom"), Some(AgentChoice::Custom));
+        assert_eq!(
+            agent_choice("Claude Gateway"),
+            Some(AgentChoice::ClaudeGateway)
+        );
+        assert!(RerunInputs::load(&db, "missing").is_err());
+
+        let scripted = db
+            .sessions()
+            .create(&SessionStart {
+                actor_agent: "Command".to_string(),
+                ..start
+            })
+            .unwrap();
+        let inputs = RerunInputs::load(&db, &scripted).unwrap();
+        assert!(inputs.actor_command);
+        assert_eq!(inputs.actor_agent, None);
+    }
+}
@@ -0,0 +1,124 @@
+//! Setting resolution with provenance.
+//!
+//! Run settings come from CLI flags, the session given with `--like`,
+//! prompt front-matter, environment
+//! variables (agents only), the project config, the global config, or a
+//! built-in default, in that order. [`Resolved`]
+//! keeps the winning value together with where it came from so `--explain`
+//! can show the reasoning.
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Where a resolved setting came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// A command-line flag, e.g. `--agent`
+    Cli(&'static str),
+    /// The earlier session given with `--like`
+    Session(String),
+    /// Front-matter at the top of the prompt file
+    FrontMatter,
+    /// An environment variable, e.g. `CODELOOPS_AGENT`
+    Env(&'static str),
+    /// The project config file
+    ProjectConfig(PathBuf),
+    /// The global config file
+    GlobalConfig(Option<PathBuf>),
+    /// Built-in default
+    Default,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Cli(flag) => write!(f, "from {} flag", flag),
+            Source::Session(id) => write!(f, "from session {}", id),
+            Source::FrontMatter => write!(f, "from prompt front-matter"),
+            Source::Env(var) => write!(f, "from {} environment variable", var),
+            Source::ProjectConfig(path) => write!(f, "from project config {}", path.display()),
+            Source::GlobalConfig(Some(path)) => write!(f, "from global config {}", path.display()),
+            Source::GlobalConfig(None) => write!(f, "from global config"),
+            Source::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// A setting value and the source that supplied it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+impl<T> Resolved<T> {
+    pub fn default_value(value: T) -> Self {
+        Self {
+            value,
+            source: Source::Default,
+        }
+    }
+}
+
+/// Pick the first candidate that has a value, in precedence order.
+pub fn first_of<T>(
+    candidates: impl IntoIterator<Item = (Option<T>, Source)>,
+) -> Option<Resolved<T>> {
+    candidates
+        .into_iter()
+        .find_map(|(value, source)| value.map(|value| Resolved { value, source }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_of_respects_precedence() {
+        let project = PathBuf::from("/repo/codeloops.toml");
+        let resolved = first_of([
+            (None, Source::Cli("--agent")),
+            (Some("opencode"), Source::ProjectConfig(project.clone())),
+            (Some("cursor"), Source::GlobalConfig(None)),
+        ])
+        .unwrap();
+        assert_eq!(resolved.value, "opencode");
+        assert_eq!(resolved.source, Source::ProjectConfig(project));
+        assert_eq!(
+            resolved.source.to_string(),
+            "from project config /repo/codeloops.toml"
+        );
+    }
+
+    #[test]
+    fn env_sits_between_front_matter_and_project_config() {
+        let resolved = first_of([
+            (None, Source::Cli("--agent")),
+            (None, Source::FrontMatter),
+            (Some("opencode"), Source::Env("CODELOOPS_AGENT")),
+            (
+                Some("cursor"),
+                Source::ProjectConfig(PathBuf::from("/repo/codeloops.toml")),
+            ),
+        ])
+        .unwrap();
+        assert_eq!(resolved.value, "opencode");
+        assert_eq!(
+            resolved.source.to_string(),
+            "from CODELOOPS_AGENT environment variable"
+        );
+    }
+
+    #[test]
+    fn first_of_none_when_unset() {
+        let resolved: Option<Resolved<&str>> = first_of([
+            (None, Source::Cli("--model")),
+            (None, Source::GlobalConfig(None)),
+        ]);
+        assert!(resolved.is_none());
+        assert_eq!(
+            Resolved::default_value("claude").source.to_string(),
+            "default"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! `--result-file`: a compact summary of the run for CI to gate on.
+//!
+//! Unlike the session record or `--json-output`, the file has the same
+//! small set of keys for every ending, including runs that fail before the
+//! loop starts, so a CI step can read it without knowing how the run went.
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use codeloops_core::LoopOutcome;
+use serde::Serialize;
+
+pub const DEFAULT_RESULT_FILE: &str = "codeloops-result.json";
+
+/// `outcome` for a run that stopped with an error before reaching one.
+pub const ERROR_OUTCOME: &str = "error";
+
+/// Contents of the result file.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunResult {
+    /// `status` of the loop outcome (`success`, `failed`, ...), or `error`
+    pub outcome: String,
+    pub iterations: usize,
+    /// The critic's confidence, for a successful run
+    pub confidence: Option<f64>,
+    pub duration_secs: Option<f64>,
+    pub session_id: Option<String>,
+    /// Paths the run changed in the working tree, relative to the repository
+    /// root; changes made before it started are left out
+    pub final_diff_files: Vec<String>,
+    /// Why the run failed, for `failed` and `error`
+    pub error: Option<String>,
+}
+
+impl RunResult {
+    pub fn from_outcome(
+        outcome: &LoopOutcome,
+        session_id: Option<&str>,
+        final_diff_files: Vec<String>,
+    ) -> Self {
+        let (confidence, error) = match outcome {
+            LoopOutcome::Success { confidence, .. } => (Some(*confidence), None),
+            LoopOutcome::Failed { error, .. } => (None, Some(error.clone())),
+            _ => (None, None),
+        };
+        Self {
+            outcome: outcome.status().to_string(),
+            iterations: outcome.iterations(),
+            confidence,
+            duration_secs: Some(outcome.total_duration_secs()),
+            session_id: session_id.map(str::to_string),
+            final_diff_files,
+            error,
+        }
+    }
+
+    /// A run that stopped with `error` instead of an outcome. A
+    /// [`PartialRunError`] keeps the session and what the run got through.
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        let partial = error
+            .downcast_ref::<PartialRunError>()
+            .map(|e| e.partial.clone());
+        Self {
+            outcome: ERROR_OUTCOME.to_string(),
+            error: Some(format!("{:#}", error)),
+            ..partial.unwrap_or(Self {
+                outcome: String::new(),
+                iterations: 0,
+                confidence: None,
+                duration_secs: None,
+                session_id: None,
+                final_diff_files: Vec::new(),
+                error: None,
+            })
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Failed to write result file {}", path.display()))
+    }
+}
+
+/// An error that stopped a run after its session started, with what the
+/// run got through. Displays as the wrapped error.
+#[derive(Debug)]
+pub struct PartialRunError {
+    partial: RunResult,
+    source: anyhow::Error,
+}
+
+impl PartialRunError {
+    /// Wrap `source`, which stopped session `session_id` after `iterations`
+    /// iterations that changed `final_diff_files`.
+    pub fn wrap(
+        source: anyhow::Error,
+        session_id: &str,
+        iterations: usize,
+        final_diff_files: Vec<String>,
+    ) -> anyhow::Error {
+        let partial = RunResult {
+            outcome: ERROR_OUTCOME.to_string(),
+            iterations,
+            confidence: None,
+            duration_secs: None,
+            session_id: Some(session_id.to_string()),
+            final_diff_files,
+            error: None,
+        };
+        Self { partial, source }.into()
+    }
+
+    /// The error that stopped the run.
+    pub fn source_error(&self) -> &anyhow::Error {
+        &self.source
+    }
+}
+
+impl fmt::Display for PartialRunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.source)
+    }
+}
+
+impl std::error::Error for PartialRunError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn every_ending_has_the_same_keys() {
+        let tmp = tempfile::tempdir().unwrap();
+        let read = |result: RunResult| {
+            let path = tmp.path().join(DEFAULT_RESULT_FILE);
+            result.write(&path).unwrap();
+            let json: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            json
+        };
+
+        let success =
+            LoopOutcome::success(2, "Done".to_string(), 0.9, vec![], Duration::from_secs(30));
+        let json = read(RunResult::from_outcome(
+            &success,
+            Some("abc"),
+            vec!["src/lib.rs".to_string()],
+        ));
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "outcome": "success",
+                "iterations": 2,
+                "confidence": 0.9,
+                "duration_secs": 30.0,
+                "session_id": "abc",
+                "final_diff_files": ["src/lib.rs"],
+                "error": null,
+            })
+        );
+        let tag = serde_json::to_value(&success).unwrap()["status"].clone();
+        assert_eq!(json["outcome"], tag);
+
+        let failed = LoopOutcome::failed(1, "boom".to_string(), vec![], Default::default());
+        let json = read(RunResult::from_outcome(&failed, None, vec![]));
+        assert_eq!(json["outcome"], "failed");
+        assert_eq!(json["error"], "boom");
+        assert_eq!(json["confidence"], serde_json::Value::Null);
+
+        let json = read(RunResult::from_error(&anyhow::anyhow!(
+            "No prompt provided"
+        )));
+        assert_eq!(json["outcome"], ERROR_OUTCOME);
+        assert_eq!(json["error"], "No prompt provided");
+        let keys =
+            |v: &serde_json::Value| v.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+        assert_eq!(
+            keys(&json),
+            keys(&read(RunResult::from_outcome(&failed, None, vec![])))
+        );
+    }
+
+    #[test]
+    fn errors_after_the_session_started_keep_it() {
+        let error = PartialRunError::wrap(
+            anyhow::anyhow!("database is locked"),
+            "abc",
+            2,
+            vec!["src/lib.rs".to_string()],
+        );
+        let result = RunResult::from_error(&error);
+        assert_eq!(result.outcome, ERROR_OUTCOME);
+        assert_eq!(result.session_id.as_deref(), Some("abc"));
+        assert_eq!(result.iterations, 2);
+        assert_eq!(result.final_diff_files, ["src/lib.rs"]);
+        assert_eq!(result.error.as_deref(), Some("database is locked"));
+    }
+}
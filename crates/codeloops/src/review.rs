@@ -0,0 +1,151 @@
+//! `--interactive`: a person rules on every iteration from the terminal.
+//!
+//! After the critic decides, the iteration's diff and the decision are
+//! printed and the person answers `a` to accept the critic's call, `r` to
+//! reject it and run another iteration, or `c` to write feedback that
+//! replaces the critic's.
+
+use std::io::{self, BufRead, Write};
+
+use colored::Colorize;
+
+use codeloops_core::{IterationReviewer, ReviewVerdict};
+use codeloops_critic::CriticDecision;
+
+/// Diff lines shown before the rest is cut.
+const MAX_DIFF_LINES: usize = 200;
+
+/// Reviews iterations on stdin and stderr.
+pub struct TerminalReviewer;
+
+impl IterationReviewer for TerminalReviewer {
+    fn review(&self, iteration: usize, git_diff: &str, decision: &CriticDecision) -> ReviewVerdict {
+        let mut out = io::stderr();
+        show(&mut out, iteration, git_diff, decision);
+        ask(&mut io::stdin().lock(), &mut out)
+    }
+}
+
+fn show(out: &mut impl Write, iteration: usize, git_diff: &str, decision: &CriticDecision) {
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "{}",
+        format!("Review iteration {}", iteration + 1).bold()
+    );
+    if git_diff.trim().is_empty() {
+        let _ = writeln!(out, "  {}", "(no changes)".dimmed());
+    }
+    let lines: Vec<&str> = git_diff.lines().collect();
+    for line in lines.iter().take(MAX_DIFF_LINES) {
+        let line = if line.starts_with("+++") || line.starts_with("---") {
+            line.bold()
+        } else if line.starts_with('+') {
+            line.green()
+        } else if line.starts_with('-') {
+            line.red()
+        } else if line.starts_with("@@") {
+            line.cyan()
+        } else {
+            line.normal()
+        };
+        let _ = writeln!(out, "{}", line);
+    }
+    if lines.len() > MAX_DIFF_LINES {
+        let _ = writeln!(
+            out,
+            "{}",
+            format!("... {} more lines", lines.len() - MAX_DIFF_LINES).dimmed()
+        );
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "{} {}",
+        "Critic:".dimmed(),
+        decision.short_description().bold()
+    );
+    let text = match decision {
+        CriticDecision::Done { summary, .. } => summary,
+        CriticDecision::Continue { feedback, .. } | CriticDecision::Reset { feedback } => feedback,
+        CriticDecision::Error {
+            error_description, ..
+        } => error_description,
+    };
+    for line in text.lines() {
+        let _ = writeln!(out, "  {}", line);
+    }
+}
+
+/// Read the person's answer, asking again until it is one of the choices.
+/// End of input accepts the critic's call.
+fn ask(input: &mut impl BufRead, out: &mut impl Write) -> ReviewVerdict {
+    loop {
+        let _ = write!(
+            out,
+            "\n[a]ccept the critic's call, [r]eject and continue, [c]ustom feedback: "
+        );
+        let _ = out.flush();
+        let Some(answer) = read_line(input) else {
+            return ReviewVerdict::Accept;
+        };
+        match answer.trim().to_lowercase().as_str() {
+            "a" | "accept" => return ReviewVerdict::Accept,
+            "r" | "reject" => return ReviewVerdict::Reject,
+            "c" | "custom" => {
+                let _ = writeln!(out, "Feedback for the actor (end with an empty line):");
+                let feedback = read_paragraph(input);
+                if feedback.is_empty() {
+                    let _ = writeln!(out, "{}", "No feedback given.".dimmed());
+                    continue;
+                }
+                return ReviewVerdict::Feedback(feedback);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn read_line(input: &mut impl BufRead) -> Option<String> {
+    let mut line = String::new();
+    match input.read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line),
+    }
+}
+
+/// Lines up to the first empty one or the end of input, trimmed.
+fn read_paragraph(input: &mut impl BufRead) -> String {
+    let mut lines = Vec::new();
+    while let Some(line) = read_line(input) {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            break;
+        }
+        lines.push(line.to_string());
+    }
+    lines.join("\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(input: &str) -> ReviewVerdict {
+        ask(&mut input.as_bytes(), &mut Vec::new())
+    }
+
+    #[test]
+    fn reads_the_verdict() {
+        assert_eq!(answer("a\n"), ReviewVerdict::Accept);
+        assert_eq!(answer("x\nR\n"), ReviewVerdict::Reject);
+        assert_eq!(
+            answer("c\nKeep the old flag name.\nAnd add a test.\n\n"),
+            ReviewVerdict::Feedback("Keep the old flag name.\nAnd add a test.".to_string())
+        );
+        // Empty feedback asks again
+        assert_eq!(answer("c\n\nr\n"), ReviewVerdict::Reject);
+        assert_eq!(answer(""), ReviewVerdict::Accept);
+    }
+}
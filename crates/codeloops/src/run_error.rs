@@ -0,0 +1,118 @@
+//! Structured errors for `--json-output`.
+//!
+//! Failures after the loop starts are reported in the `LoopOutcome` JSON.
+//! Failures before it starts (bad config, no prompt, missing agent, locked
+//! working directory) would otherwise only reach stderr, so in JSON mode
+//! they are printed as `{"error": {"kind": ..., "message": ...}}` instead.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use codeloops_core::LoopError;
+
+use crate::result_file::PartialRunError;
+
+/// Exit code for a run that failed before producing an outcome; matches
+/// `LoopOutcome::Failed`.
+pub const STARTUP_FAILURE_EXIT_CODE: i32 = 2;
+
+/// Category of a startup failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Global or project config could not be loaded
+    Config,
+    /// No prompt was given or the prompt file could not be read
+    Prompt,
+    /// The actor or critic agent is not installed
+    AgentUnavailable,
+    /// Another run holds the working directory lock
+    Locked,
+    /// Anything else
+    Internal,
+}
+
+/// An error tagged with its [`ErrorKind`]. Displays as the wrapped error.
+#[derive(Debug)]
+pub struct KindedError {
+    kind: ErrorKind,
+    source: anyhow::Error,
+}
+
+impl fmt::Display for KindedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.source)
+    }
+}
+
+impl std::error::Error for KindedError {}
+
+/// Tag an error with its kind.
+pub trait WithKind<T> {
+    fn kind(self, kind: ErrorKind) -> anyhow::Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> WithKind<T> for Result<T, E> {
+    fn kind(self, kind: ErrorKind) -> anyhow::Result<T> {
+        self.map_err(|e| {
+            KindedError {
+                kind,
+                source: e.into(),
+            }
+            .into()
+        })
+    }
+}
+
+/// Classify an error that escaped `run_loop`.
+pub fn kind_of(error: &anyhow::Error) -> ErrorKind {
+    if let Some(partial) = error.downcast_ref::<PartialRunError>() {
+        return kind_of(partial.source_error());
+    }
+    if let Some(kinded) = error.downcast_ref::<KindedError>() {
+        return kinded.kind;
+    }
+    match error.downcast_ref::<LoopError>() {
+        Some(LoopError::Locked { .. }) => ErrorKind::Locked,
+        Some(LoopError::ConfigError(_)) => ErrorKind::Config,
+        _ => ErrorKind::Internal,
+    }
+}
+
+/// The JSON document printed for a startup failure.
+pub fn error_json(error: &anyhow::Error) -> serde_json::Value {
+    serde_json::json!({
+        "error": {
+            "kind": kind_of(error),
+            "message": format!("{:#}", error),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tagged_errors_keep_their_kind() {
+        let result: Result<(), _> = Err(anyhow::anyhow!("No prompt provided"));
+        let error = result.kind(ErrorKind::Prompt).unwrap_err();
+        assert_eq!(
+            error_json(&error),
+            serde_json::json!({
+                "error": { "kind": "prompt", "message": "No prompt provided" }
+            })
+        );
+    }
+
+    #[test]
+    fn loop_errors_are_classified() {
+        let locked = anyhow::Error::from(LoopError::Locked {
+            path: "/data/locks/repo-0123456789abcdef.lock".into(),
+            holder: "pid=1".to_string(),
+        });
+        assert_eq!(kind_of(&locked), ErrorKind::Locked);
+        assert_eq!(kind_of(&anyhow::anyhow!("boom")), ErrorKind::Internal);
+    }
+}
@@ -1,8 +1,14 @@
-use anyhow::Result;
-use clap::Subcommand;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Subcommand, ValueEnum};
 use colored::Colorize;
 
-use codeloops_db::{Database, Session, SessionFilter, SessionStats, SessionSummary};
+use std::path::{Path, PathBuf};
+
+use codeloops_db::{
+    session_html, Database, ImportResult, NewProject, Session, SessionFilter, SessionOutcome,
+    SessionStats, SessionSummary, StatsTimeZone,
+};
 
 #[derive(Subcommand, Debug)]
 pub enum SessionsAction {
@@ -28,6 +34,14 @@ pub enum SessionsAction {
         #[arg(long)]
         project: Option<String>,
 
+        /// Only sessions with critic confidence at least this (0.0-1.0)
+        #[arg(long)]
+        min_confidence: Option<f64>,
+
+        /// Only sessions with critic confidence at most this (0.0-1.0)
+        #[arg(long)]
+        max_confidence: Option<f64>,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -41,6 +55,41 @@ pub enum SessionsAction {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Print the full prompt each iteration's actor was given
+        #[arg(long)]
+        actor_prompts: bool,
+
+        /// Directory for per-session output files (overrides CODELOOPS_SESSIONS_DIR)
+        #[arg(long)]
+        sessions_dir: Option<PathBuf>,
+    },
+
+    /// Add notes to a session, replacing any it has
+    Note {
+        /// Session ID
+        id: String,
+
+        /// The notes; an empty string clears them
+        text: String,
+    },
+
+    /// Export a session as a standalone report
+    Export {
+        /// Session ID (launches interactive picker if omitted)
+        id: Option<String>,
+
+        /// Report format
+        #[arg(long, value_enum, default_value = "html")]
+        format: ExportFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Directory for per-session output files (overrides CODELOOPS_SESSIONS_DIR)
+        #[arg(long)]
+        sessions_dir: Option<PathBuf>,
     },
 
     /// Show cumulative git diff from a session
@@ -54,7 +103,40 @@ pub enum SessionsAction {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Time zone for the daily session counts: local (honors TZ), UTC, or an offset like +05:30
+        #[arg(long, value_name = "TZ", default_value = "local")]
+        timezone: StatsTimeZone,
     },
+
+    /// Import JSONL session files into the database
+    Import {
+        /// A .jsonl or .jsonl.gz session file, or a directory of them
+        path: PathBuf,
+    },
+
+    /// Open a session in the web UI, starting the UI if it is not running
+    Open {
+        /// Session ID (launches interactive picker if omitted)
+        id: Option<String>,
+
+        /// Port for the API server
+        #[arg(long, default_value = "3100")]
+        api_port: u16,
+
+        /// Port for the UI server
+        #[arg(long, default_value = "3101")]
+        ui_port: u16,
+    },
+}
+
+/// Formats for `sessions export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Self-contained HTML page with inline styles
+    Html,
+    /// The stored session as JSON
+    Json,
 }
 
 pub async fn handle_sessions_command(action: SessionsAction) -> Result<()> {
@@ -67,9 +149,19 @@ pub async fn handle_sessions_command(action: SessionsAction) -> Result<()> {
             before,
             search,
             project,
+            min_confidence,
+            max_confidence,
             json,
         } => {
-            let filter = build_filter(outcome, after, before, search, project)?;
+            let filter = build_filter(
+                outcome,
+                after,
+                before,
+                search,
+                project,
+                min_confidence,
+                max_confidence,
+            )?;
             let summaries = db.sessions().list(&filter)?;
 
             if json {
@@ -83,17 +175,59 @@ pub async fn handle_sessions_command(action: SessionsAction) -> Result<()> {
                 print_sessions_table(&summaries);
             }
         }
-        SessionsAction::Show { id, json } => {
+        SessionsAction::Show {
+            id,
+            json,
+            actor_prompts,
+            sessions_dir,
+        } => {
             let id = resolve_session_id(&db, id)?;
-            let session = db
+            let mut session = db
                 .sessions()
                 .get(&id)?
                 .ok_or_else(|| anyhow::anyhow!("Session not found: {}", id))?;
+            recover_partial_output(&mut session, sessions_dir.as_deref());
 
             if json {
                 println!("{}", serde_json::to_string_pretty(&session)?);
             } else {
-                print_session_detail(&session);
+                print_session_detail(&session, actor_prompts);
+            }
+        }
+        SessionsAction::Note { id, text } => {
+            if !db.sessions().set_notes(&id, Some(&text))? {
+                anyhow::bail!("Session not found: {}", id);
+            }
+            if text.trim().is_empty() {
+                eprintln!("{} Cleared notes on {}", "✓".bright_green(), id);
+            } else {
+                eprintln!("{} Saved notes on {}", "✓".bright_green(), id);
+            }
+        }
+        SessionsAction::Export {
+            id,
+            format,
+            output,
+            sessions_dir,
+        } => {
+            let id = resolve_session_id(&db, id)?;
+            let mut session = db
+                .sessions()
+                .get(&id)?
+                .ok_or_else(|| anyhow::anyhow!("Session not found: {}", id))?;
+            recover_partial_output(&mut session, sessions_dir.as_deref());
+
+            let report = match format {
+                ExportFormat::Html => session_html(&session),
+                ExportFormat::Json => serde_json::to_string_pretty(&session)?,
+            };
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, report)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    eprintln!("{} Wrote {}", "✓".bright_green(), path.display());
+                }
+                None => println!("{}", report),
             }
         }
         SessionsAction::Diff { id } => {
@@ -105,8 +239,8 @@ pub async fn handle_sessions_command(action: SessionsAction) -> Result<()> {
                 _ => println!("{}", "No diffs found for this session.".dimmed()),
             }
         }
-        SessionsAction::Stats { json } => {
-            let stats = db.sessions().stats(&SessionFilter::default())?;
+        SessionsAction::Stats { json, timezone } => {
+            let stats = db.sessions().stats(&SessionFilter::default(), timezone)?;
 
             if json {
                 println!("{}", serde_json::to_string_pretty(&stats)?);
@@ -114,17 +248,134 @@ pub async fn handle_sessions_command(action: SessionsAction) -> Result<()> {
                 print_stats(&stats);
             }
         }
+        SessionsAction::Import { path } => import_sessions(&db, &path)?,
+        SessionsAction::Open {
+            id,
+            api_port,
+            ui_port,
+        } => {
+            let id = resolve_session_id(&db, id)?;
+            let session = db
+                .sessions()
+                .get(&id)?
+                .ok_or_else(|| anyhow::anyhow!("Session not found: {}", id))?;
+            let route = session_route(&db, &session)?;
+            crate::ui::open_route(&route, api_port, ui_port).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Import one JSONL file or every `.jsonl`/`.jsonl.gz` file in a directory, reporting
+/// imported/skipped/error counts.
+fn import_sessions(db: &Database, path: &Path) -> Result<()> {
+    let files: Vec<PathBuf> = if path.is_dir() {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(path)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|p| codeloops_db::is_session_file(p))
+            .collect();
+        files.sort();
+        files
+    } else if path.exists() {
+        vec![path.to_path_buf()]
+    } else {
+        anyhow::bail!("No such file or directory: {}", path.display());
+    };
+
+    if files.is_empty() {
+        eprintln!(
+            "{}",
+            format!("No session files found in {}", path.display()).dimmed()
+        );
+        return Ok(());
+    }
+
+    let (mut imported, mut skipped, mut errors) = (0, 0, 0);
+    for file in &files {
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+        match db.sessions().import_jsonl(file) {
+            Ok(ImportResult::Imported { id, bad_lines }) => {
+                imported += 1;
+                println!("{} {} -> {}", "✓".bright_green(), name, id);
+                if bad_lines > 0 {
+                    println!(
+                        "  {}",
+                        format!("{} unparseable line(s) ignored", bad_lines).bright_yellow()
+                    );
+                }
+            }
+            Ok(ImportResult::Skipped(reason)) => {
+                skipped += 1;
+                println!(
+                    "{} {} {}",
+                    "-".dimmed(),
+                    name,
+                    format!("(skipped: {})", reason).dimmed()
+                );
+            }
+            Err(e) => {
+                errors += 1;
+                println!(
+                    "{} {} {}",
+                    "✗".bright_red(),
+                    name,
+                    format!("(error: {})", e).bright_red()
+                );
+            }
+        }
     }
 
+    println!();
+    println!("{}  {}", "Imported:".dimmed(), imported);
+    println!("{}  {}", "Skipped:".dimmed(), skipped);
+    println!("{}    {}", "Errors:".dimmed(), errors);
+
+    if errors > 0 {
+        anyhow::bail!("{} file(s) failed to import", errors);
+    }
     Ok(())
 }
 
+/// UI route for a session's detail page.
+///
+/// The page lives under a project: the registered project containing the
+/// session's working dir, else the default or most recent one. With no
+/// projects at all, the session's working dir is registered as the default,
+/// as `codeloops ui` does for the current directory.
+fn session_route(db: &Database, session: &Session) -> Result<String> {
+    let projects = db.projects().list()?;
+    let project = match crate::projects::find_project_for_path(&session.working_dir, &projects)
+        .or(db.projects().get_default()?)
+        .or_else(|| projects.first().cloned())
+    {
+        Some(project) => project,
+        None => {
+            let path = session.working_dir.to_string_lossy().to_string();
+            let name = codeloops_db::paths::project_name(&path)
+                .unwrap_or("default")
+                .to_string();
+            let project = db.projects().add(&NewProject {
+                path,
+                name,
+                config_overrides: None,
+            })?;
+            db.projects().set_default(&project.id)?;
+            project
+        }
+    };
+    Ok(format!("/projects/{}/sessions/{}", project.id, session.id))
+}
+
 fn build_filter(
     outcome: Option<String>,
     after: Option<String>,
     before: Option<String>,
     search: Option<String>,
     project: Option<String>,
+    min_confidence: Option<f64>,
+    max_confidence: Option<f64>,
 ) -> Result<SessionFilter> {
     use chrono::{NaiveDate, TimeZone, Utc};
 
@@ -144,12 +395,28 @@ fn build_filter(
         })
         .transpose()?;
 
+    let outcome = outcome
+        .map(|s| s.parse::<SessionOutcome>())
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("Invalid --outcome: {}", e))?;
+
+    for (flag, value) in [
+        ("--min-confidence", min_confidence),
+        ("--max-confidence", max_confidence),
+    ] {
+        if value.is_some_and(|v| !(0.0..=1.0).contains(&v)) {
+            anyhow::bail!("{} must be between 0.0 and 1.0", flag);
+        }
+    }
+
     Ok(SessionFilter {
         outcome,
         after,
         before,
         search,
         project,
+        min_confidence,
+        max_confidence,
     })
 }
 
@@ -168,7 +435,7 @@ fn resolve_session_id(db: &Database, id: Option<String>) -> Result<String> {
         .iter()
         .map(|s| {
             let ts = s.timestamp.format("%Y-%m-%d %H:%M");
-            let outcome = s.outcome.as_deref().unwrap_or("active").to_string();
+            let outcome = s.outcome.map_or("active", |o| o.as_str()).to_string();
             let duration = s
                 .duration_secs
                 .map(format_duration)
@@ -207,12 +474,9 @@ fn print_sessions_table(summaries: &[SessionSummary]) {
 
     for s in summaries {
         let ts = s.timestamp.format("%Y-%m-%d %H:%M").to_string();
-        let outcome = s.outcome.as_deref().unwrap_or("active");
-        let outcome_colored = match outcome {
-            "success" => outcome.bright_green().to_string(),
-            "failed" => outcome.bright_red().to_string(),
-            "active" => outcome.bright_cyan().to_string(),
-            _ => outcome.bright_yellow().to_string(),
+        let outcome_colored = match s.outcome {
+            Some(outcome) => color_outcome(outcome),
+            None => "active".bright_cyan().to_string(),
         };
         let duration = s
             .duration_secs
@@ -231,7 +495,34 @@ fn print_sessions_table(summaries: &[SessionSummary]) {
     }
 }
 
-fn print_session_detail(session: &Session) {
+/// Fill in actor output for iterations that died before it was saved, from
+/// the stdout streamed to the session directory.
+fn recover_partial_output(session: &mut Session, sessions_dir: Option<&Path>) {
+    let sessions_dir = codeloops_db::paths::sessions_dir(sessions_dir);
+    for iter in session
+        .iterations
+        .iter_mut()
+        .filter(|i| i.actor_output.is_none())
+    {
+        if let Some(partial) = codeloops_db::paths::read_actor_stdout(
+            &sessions_dir,
+            &session.id,
+            iter.iteration_number,
+        ) {
+            eprintln!(
+                "{} Recovered partial actor output for iteration {} ({} lines)",
+                "->".dimmed(),
+                iter.iteration_number,
+                partial.lines().count()
+            );
+            iter.actor_output = Some(partial);
+        }
+    }
+}
+
+/// Print a session for the terminal. Actor prompts are summarized in a line
+/// unless `actor_prompts` is set.
+fn print_session_detail(session: &Session, actor_prompts: bool) {
     println!("{}", "=== Session Detail ===".bright_blue().bold());
     println!("{}  {}", "ID:".dimmed(), session.id);
     println!(
@@ -244,6 +535,9 @@ fn print_session_detail(session: &Session) {
         "Working Dir:".dimmed(),
         session.working_dir.display()
     );
+    if let Some(ref branch) = session.branch {
+        println!("{}  {}", "Branch:".dimmed(), branch);
+    }
     println!("{}  {}", "Actor:".dimmed(), session.actor_agent);
     println!("{}  {}", "Critic:".dimmed(), session.critic_agent);
     if let Some(ref model) = session.actor_model {
@@ -252,21 +546,40 @@ fn print_session_detail(session: &Session) {
     if let Some(ref model) = session.critic_model {
         println!("{}  {}", "Critic Model:".dimmed(), model);
     }
+    if !session.prompt_files.is_empty() {
+        println!(
+            "{}  {}",
+            "Prompt Files:".dimmed(),
+            session.prompt_files.join(", ")
+        );
+    }
+    if !session.context_files.is_empty() {
+        println!(
+            "{}  {}",
+            "Context Files:".dimmed(),
+            session.context_files.join(", ")
+        );
+    }
+    if let Some(passed) = session.verify_baseline {
+        println!("{}  {}", "Verify Baseline:".dimmed(), verify_status(passed));
+    }
     println!();
     println!("{}", "Prompt:".dimmed());
     println!("  {}", session.prompt);
     println!();
+    if let Some(ref feedback) = session.seed_feedback {
+        println!("{}", "Seed Feedback:".dimmed());
+        println!("  {}", feedback);
+        println!();
+    }
+    if let Some(ref notes) = session.notes {
+        println!("{}", "Notes:".dimmed());
+        println!("  {}", notes);
+        println!();
+    }
 
-    if let Some(ref outcome) = session.outcome {
-        println!(
-            "{}  {}",
-            "Outcome:".dimmed(),
-            match outcome.as_str() {
-                "success" => outcome.bright_green().to_string(),
-                "failed" => outcome.bright_red().to_string(),
-                _ => outcome.bright_yellow().to_string(),
-            }
-        );
+    if let Some(outcome) = session.outcome {
+        println!("{}  {}", "Outcome:".dimmed(), color_outcome(outcome));
         if let Some(iterations) = session.iteration_count {
             println!("{}  {}", "Iterations:".dimmed(), iterations);
         }
@@ -297,6 +610,22 @@ fn print_session_detail(session: &Session) {
                 iter.timestamp.format("%H:%M:%S"),
                 iter.phase.bright_cyan()
             );
+            if let Some(ref prompt) = iter.actor_prompt {
+                if actor_prompts {
+                    println!("    {}", "Prompt:".dimmed());
+                    for line in prompt.lines() {
+                        println!("      {}", line);
+                    }
+                } else {
+                    println!(
+                        "    {} {} lines, {} chars {}",
+                        "Prompt:".dimmed(),
+                        prompt.lines().count(),
+                        prompt.chars().count(),
+                        "(--actor-prompts to show)".dimmed()
+                    );
+                }
+            }
             let exit_code = iter.actor_exit_code.unwrap_or(-1);
             let duration = iter.actor_duration_secs.unwrap_or(0.0);
             println!(
@@ -315,17 +644,70 @@ fn print_session_detail(session: &Session) {
                 "Diff:".dimmed(),
                 iter.git_files_changed.unwrap_or(0)
             );
-            let decision = iter.critic_decision.as_deref().unwrap_or("pending");
+            for file in &iter.files {
+                println!("      {}", file.dimmed());
+            }
+            if let Some(ref adherence) = iter.scope_adherence {
+                for file in &adherence.unexpected {
+                    println!(
+                        "    {} {} (not listed)",
+                        "Scope:".dimmed(),
+                        file.bright_yellow()
+                    );
+                }
+                for file in &adherence.untouched {
+                    println!(
+                        "    {} {} (not changed)",
+                        "Scope:".dimmed(),
+                        file.bright_yellow()
+                    );
+                }
+            }
+            match iter.patch_applied {
+                Some(true) => println!("    {} applied", "Patch:".dimmed()),
+                Some(false) => {
+                    println!("    {} {}", "Patch:".dimmed(), "did not apply".bright_red())
+                }
+                None => {}
+            }
+            if iter.actor_output_lossy {
+                println!(
+                    "    {} {}",
+                    "Output:".dimmed(),
+                    "had bytes that were not UTF-8 (shown as \u{FFFD})".bright_yellow()
+                );
+            }
+            if let Some(passed) = iter.verify_passed {
+                println!("    {} {}", "Verify:".dimmed(), verify_status(passed));
+            }
+            if let Some(percent) = iter.coverage_percent {
+                let delta = match iter.coverage_delta {
+                    Some(delta) if delta < 0.0 => {
+                        format!(" ({:+.1})", delta).bright_red().to_string()
+                    }
+                    Some(delta) => format!(" ({:+.1})", delta),
+                    None => String::new(),
+                };
+                println!("    {} {:.1}%{}", "Coverage:".dimmed(), percent, delta);
+            }
+            let decision = iter
+                .legacy_critic_decision
+                .as_deref()
+                .or(iter.critic_decision.as_deref())
+                .unwrap_or("pending");
             println!(
                 "    {} {}",
                 "Decision:".dimmed(),
-                match decision {
+                match codeloops_db::decision_kind(decision) {
                     "DONE" => decision.bright_green().to_string(),
                     "CONTINUE" => decision.bright_yellow().to_string(),
                     "pending" => decision.dimmed().to_string(),
                     _ => decision.bright_red().to_string(),
                 }
             );
+            if let Some(ref review) = iter.human_review {
+                println!("    {} {}", "Reviewer:".dimmed(), review.bright_magenta());
+            }
             if let Some(ref feedback) = iter.feedback {
                 let preview = if feedback.len() > 120 {
                     format!("{}...", &feedback[..120])
@@ -334,8 +716,108 @@ fn print_session_detail(session: &Session) {
                 };
                 println!("    {} {}", "Feedback:".dimmed(), preview);
             }
+            // Surface the raw response when the critic ran but no decision was parsed
+            if iter.critic_decision.is_none() {
+                if let Some(ref raw) = iter.critic_raw {
+                    let preview: String = raw.chars().take(120).collect();
+                    println!("    {} {}", "Critic raw:".dimmed(), preview.trim());
+                }
+            }
         }
+        print_timing(session);
+    }
+}
+
+/// Wall-clock breakdown of one iteration.
+#[derive(Debug, PartialEq)]
+struct IterationTiming {
+    number: usize,
+    actor_secs: Option<f64>,
+    /// Time after the actor finished: diff capture and the critic
+    rest_secs: Option<f64>,
+    /// Time from session start to the end of this iteration
+    elapsed_secs: Option<f64>,
+}
+
+/// Derive per-iteration timings. An iteration ends when the next one starts,
+/// or when the session ends; an unfinished iteration has no end.
+fn iteration_timings(session: &Session) -> Vec<IterationTiming> {
+    session
+        .iterations
+        .iter()
+        .enumerate()
+        .map(|(i, iter)| {
+            let end = session
+                .iterations
+                .get(i + 1)
+                .map(|next| next.timestamp)
+                .or(session.ended_at);
+            let secs = |from: DateTime<Utc>, to: DateTime<Utc>| {
+                (to - from).num_milliseconds().max(0) as f64 / 1000.0
+            };
+            let span = end.map(|end| secs(iter.timestamp, end));
+            IterationTiming {
+                number: iter.iteration_number,
+                actor_secs: iter.actor_duration_secs,
+                rest_secs: span
+                    .map(|span| (span - iter.actor_duration_secs.unwrap_or(0.0)).max(0.0)),
+                elapsed_secs: end.map(|end| secs(session.started_at, end)),
+            }
+        })
+        .collect()
+}
+
+fn print_timing(session: &Session) {
+    const BAR_WIDTH: usize = 20;
+    let timings = iteration_timings(session);
+    let longest = timings
+        .iter()
+        .map(|t| t.actor_secs.unwrap_or(0.0) + t.rest_secs.unwrap_or(0.0))
+        .fold(0.0, f64::max);
+    let cell = |secs: Option<f64>| secs.map(format_duration).unwrap_or_else(|| "-".to_string());
+
+    println!();
+    println!("{}", "--- Timing ---".dimmed());
+    println!(
+        "  {:<6} {:<9} {:<12} {:<9}",
+        "ITER".dimmed(),
+        "ACTOR".dimmed(),
+        "DIFF+CRITIC".dimmed(),
+        "ELAPSED".dimmed()
+    );
+    for t in &timings {
+        let (actor, rest) = if longest > 0.0 {
+            let width = |secs: Option<f64>| {
+                (secs.unwrap_or(0.0) / longest * BAR_WIDTH as f64).round() as usize
+            };
+            (width(t.actor_secs), width(t.rest_secs))
+        } else {
+            (0, 0)
+        };
+        println!(
+            "  {:<6} {:<9} {:<12} {:<9} {}{}",
+            t.number,
+            cell(t.actor_secs),
+            cell(t.rest_secs),
+            cell(t.elapsed_secs),
+            "█".repeat(actor).bright_blue(),
+            "░".repeat(rest).bright_yellow()
+        );
     }
+
+    let total_actor: f64 = timings.iter().filter_map(|t| t.actor_secs).sum();
+    let total_rest: f64 = timings.iter().filter_map(|t| t.rest_secs).sum();
+    println!(
+        "  {:<6} {:<9} {:<12} {:<9}",
+        "Total".bold(),
+        format_duration(total_actor),
+        format_duration(total_rest),
+        cell(
+            session
+                .duration_secs
+                .or(timings.last().and_then(|t| t.elapsed_secs))
+        )
+    );
 }
 
 fn print_stats(stats: &SessionStats) {
@@ -371,6 +853,25 @@ fn print_stats(stats: &SessionStats) {
     }
 }
 
+fn verify_status(passed: bool) -> String {
+    if passed {
+        "passed".bright_green().to_string()
+    } else {
+        "failed".bright_red().to_string()
+    }
+}
+
+fn color_outcome(outcome: SessionOutcome) -> String {
+    match outcome {
+        SessionOutcome::Success => outcome.as_str().bright_green().to_string(),
+        SessionOutcome::Failed => outcome.as_str().bright_red().to_string(),
+        SessionOutcome::Interrupted
+        | SessionOutcome::MaxIterationsReached
+        | SessionOutcome::NoProgress
+        | SessionOutcome::CostBudgetExceeded => outcome.as_str().bright_yellow().to_string(),
+    }
+}
+
 fn format_duration(secs: f64) -> String {
     if secs < 60.0 {
         format!("{:.0}s", secs)
@@ -380,3 +881,90 @@ fn format_duration(secs: f64) -> String {
         format!("{}m {}s", mins, remaining_secs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iteration(number: usize, at: &str, actor_secs: Option<f64>) -> codeloops_db::Iteration {
+        codeloops_db::Iteration {
+            iteration_number: number,
+            phase: "critic_completed".to_string(),
+            actor_output: None,
+            actor_stderr: None,
+            actor_exit_code: Some(0),
+            actor_duration_secs: actor_secs,
+            git_diff: None,
+            git_files_changed: None,
+            critic_decision: None,
+            legacy_critic_decision: None,
+            feedback: None,
+            critic_raw: None,
+            critic_confidence: None,
+            critic_checklist: Vec::new(),
+            critic_rubric: Vec::new(),
+            scope_adherence: None,
+            human_review: None,
+            actor_prompt: None,
+            actor_output_lossy: false,
+            reset_applied: false,
+            verify_passed: None,
+            files: Vec::new(),
+            diff_bytes: None,
+            coverage_percent: None,
+            coverage_delta: None,
+            patch_applied: None,
+            timestamp: at.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn iteration_timings_split_actor_and_critic_time() {
+        let mut session = Session {
+            id: "s".to_string(),
+            prompt: "p".to_string(),
+            working_dir: PathBuf::from("/tmp"),
+            actor_agent: "a".to_string(),
+            critic_agent: "c".to_string(),
+            actor_model: None,
+            critic_model: None,
+            max_iterations: None,
+            outcome: None,
+            iteration_count: None,
+            summary: None,
+            confidence: None,
+            duration_secs: None,
+            started_at: "2025-01-27T15:00:00Z".parse().unwrap(),
+            ended_at: None,
+            prompt_files: Vec::new(),
+            branch: None,
+            seed_feedback: None,
+            context_files: Vec::new(),
+            notes: None,
+            verify_baseline: None,
+            iterations: vec![
+                iteration(1, "2025-01-27T15:00:01Z", Some(40.0)),
+                iteration(2, "2025-01-27T15:01:01Z", None),
+            ],
+        };
+
+        let timings = iteration_timings(&session);
+        assert_eq!(
+            timings[0],
+            IterationTiming {
+                number: 1,
+                actor_secs: Some(40.0),
+                rest_secs: Some(20.0),
+                elapsed_secs: Some(61.0),
+            }
+        );
+        // Still running: no end yet
+        assert_eq!(timings[1].rest_secs, None);
+        assert_eq!(timings[1].elapsed_secs, None);
+
+        session.ended_at = Some("2025-01-27T15:01:31Z".parse().unwrap());
+        let timings = iteration_timings(&session);
+        assert_eq!(timings[1].rest_secs, Some(30.0));
+        assert_eq!(timings[1].elapsed_secs, Some(91.0));
+    }
+}
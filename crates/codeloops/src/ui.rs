@@ -1,3 +1,4 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
@@ -9,33 +10,58 @@ use crate::api;
 /// Path from workspace root to the UI package directory.
 const UI_PACKAGE_DIR: &str = "packages/ui";
 
-pub async fn handle_ui_command(dev: bool, api_port: u16, ui_port: u16) -> Result<()> {
-    use codeloops_db::NewProject;
+/// Start the API and UI servers and open the browser at `route` (a UI path
+/// such as `/projects/<id>/sessions/<id>`, or empty for the home page).
+/// With `read_only`, the API only serves reads; see [`api::create_router`].
+pub async fn handle_ui_command(
+    dev: bool,
+    api_port: u16,
+    ui_port: u16,
+    sessions_dir: Option<PathBuf>,
+    interview: api::InterviewOverrides,
+    read_only: bool,
+    route: &str,
+) -> Result<()> {
     use colored::Colorize;
 
     let working_dir = std::env::current_dir().context("Failed to get current directory")?;
-    let db = Arc::new(Database::open().context("Failed to initialize database")?);
+    // A database that cannot be opened for writing is still served
+    // read-only when possible. Without one at all the server still starts;
+    // routes that need it answer 503 with the reason
+    let mut read_only = read_only;
+    let db = match Database::open() {
+        Ok(db) => Ok(Arc::new(db)),
+        Err(e) => match Database::open_read_only(&Database::default_path()) {
+            Ok(db) => {
+                eprintln!(
+                    "{} Failed to open database for writing, serving it read-only: {}",
+                    "⚠".bright_yellow(),
+                    e
+                );
+                read_only = true;
+                Ok(Arc::new(db))
+            }
+            Err(read_only_err) => {
+                // Both reasons: the write error alone hides why the
+                // read-only fallback (say, an old schema) was refused
+                let reason = format!("{}; read-only: {}", e, read_only_err);
+                eprintln!(
+                    "{} Failed to open database, starting without it: {}",
+                    "⚠".bright_yellow(),
+                    reason
+                );
+                Err(reason)
+            }
+        },
+    };
 
     // Auto-register cwd as default project if no projects exist
-    let projects = db.projects().list().unwrap_or_default();
-    if projects.is_empty() {
-        let canonical = std::fs::canonicalize(&working_dir).unwrap_or_else(|_| working_dir.clone());
-        let name = canonical
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("default")
-            .to_string();
-        let path_str = canonical.to_string_lossy().to_string();
-        if let Ok(project) = db.projects().add(&NewProject {
-            path: path_str,
-            name,
-            config_overrides: None,
-        }) {
-            let _ = db.projects().set_default(&project.id);
-        }
+    if let (Ok(ref db), false) = (&db, read_only) {
+        register_default_project(db, &working_dir);
     }
 
-    let router = api::create_router(db);
+    let sessions_dir = codeloops_db::paths::sessions_dir(sessions_dir.as_deref());
+    let router = api::create_router(db, sessions_dir, interview, read_only);
 
     // Start the API server
     let api_addr = format!("0.0.0.0:{}", api_port);
@@ -57,14 +83,16 @@ pub async fn handle_ui_command(dev: bool, api_port: u16, ui_port: u16) -> Result
         "->".bright_green(),
         format!("Open http://localhost:{}", ui_port).bold()
     );
+    if read_only {
+        eprintln!(
+            "  {} Read-only: browsing only, no prompt builder or edits",
+            "->".dimmed()
+        );
+    }
     eprintln!("  {} Press {} to stop", "->".dimmed(), "Ctrl+C".bold());
     eprintln!();
 
-    // Open browser
-    let ui_url = format!("http://localhost:{}", ui_port);
-    if let Err(e) = open::that(&ui_url) {
-        eprintln!("Failed to open browser: {} (open {} manually)", e, ui_url);
-    }
+    open_browser(&format!("http://localhost:{}{}", ui_port, route));
 
     // Run API server until interrupted
     let result = axum::serve(listener, router)
@@ -77,6 +105,62 @@ pub async fn handle_ui_command(dev: bool, api_port: u16, ui_port: u16) -> Result
     result.context("API server error")
 }
 
+/// Register `working_dir` as the default project when there are no projects
+/// yet.
+fn register_default_project(db: &Database, working_dir: &Path) {
+    use codeloops_db::NewProject;
+
+    let projects = db.projects().list().unwrap_or_default();
+    if !projects.is_empty() {
+        return;
+    }
+    let canonical =
+        std::fs::canonicalize(working_dir).unwrap_or_else(|_| working_dir.to_path_buf());
+    let name = canonical
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("default")
+        .to_string();
+    let path_str = canonical.to_string_lossy().to_string();
+    if let Ok(project) = db.projects().add(&NewProject {
+        path: path_str,
+        name,
+        config_overrides: None,
+    }) {
+        let _ = db.projects().set_default(&project.id);
+    }
+}
+
+/// Open `route` in the web UI. If a UI is already listening on `ui_port`
+/// the browser is pointed at it; otherwise the servers are started first.
+pub async fn open_route(route: &str, api_port: u16, ui_port: u16) -> Result<()> {
+    if tokio::net::TcpStream::connect(("127.0.0.1", ui_port))
+        .await
+        .is_ok()
+    {
+        let url = format!("http://localhost:{}{}", ui_port, route);
+        eprintln!("Opening {}", url);
+        open_browser(&url);
+        return Ok(());
+    }
+    handle_ui_command(
+        false,
+        api_port,
+        ui_port,
+        None,
+        api::InterviewOverrides::default(),
+        false,
+        route,
+    )
+    .await
+}
+
+fn open_browser(url: &str) {
+    if let Err(e) = open::that(url) {
+        eprintln!("Failed to open browser: {} (open {} manually)", e, url);
+    }
+}
+
 async fn kill_child(child: &mut tokio::process::Child) {
     // Start kill (sends SIGKILL on unix)
     let _ = child.start_kill();
@@ -155,12 +239,10 @@ fn find_ui_dir() -> Result<std::path::PathBuf> {
         }
     }
 
-    // Check ~/.local/share/codeloops/ui/
-    if let Some(data_dir) = dirs::data_dir() {
-        let ui_dir = data_dir.join("codeloops").join("ui");
-        if ui_dir.exists() {
-            return Ok(ui_dir);
-        }
+    // Check <data dir>/ui/ (~/.local/share/codeloops/ui/ by default)
+    let ui_dir = codeloops_db::paths::data_dir().join("ui");
+    if ui_dir.exists() {
+        return Ok(ui_dir);
     }
 
     anyhow::bail!(
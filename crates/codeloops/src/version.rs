@@ -0,0 +1,68 @@
+//! `codeloops --version`, and its `--json` form for tools that check what
+//! the installed codeloops supports before invoking it.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use codeloops_db::{Database, SESSION_FORMAT_VERSION};
+
+use crate::AgentChoice;
+
+/// What `--version --json` prints.
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    /// Commit the binary was built from, or `unknown`
+    pub git_sha: &'static str,
+    /// Names accepted by `--agent`, `--actor-agent` and `--critic-agent`
+    pub agents_supported: Vec<String>,
+    pub session_format_version: u32,
+    /// Schema version the database is migrated to on open
+    pub db_schema_version: u32,
+}
+
+impl VersionInfo {
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: env!("CODELOOPS_GIT_SHA"),
+            agents_supported: AgentChoice::value_variants()
+                .iter()
+                .filter_map(|choice| choice.to_possible_value())
+                .map(|value| value.get_name().to_string())
+                .collect(),
+            session_format_version: SESSION_FORMAT_VERSION,
+            db_schema_version: Database::SCHEMA_VERSION,
+        }
+    }
+}
+
+pub fn print_version(json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(&VersionInfo::current())?);
+    } else {
+        println!("codeloops {}", env!("CARGO_PKG_VERSION"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_every_agent_choice() {
+        let info = VersionInfo::current();
+        // Each listed name is accepted wherever an agent is named
+        for name in &info.agents_supported {
+            assert!(crate::parse_agent_choice(name).is_some(), "{}", name);
+        }
+        assert_eq!(
+            info.agents_supported.len(),
+            AgentChoice::value_variants().len()
+        );
+        assert_eq!(info.db_schema_version, Database::latest_schema_version());
+        assert!(!info.git_sha.is_empty());
+    }
+}
@@ -59,8 +59,11 @@ vi.mock('@/hooks/useMetrics', () => ({
       criticApprovalRate: 0.6,
       avgFeedbackLength: 150,
       improvementRate: 0.75,
+      avgPromptBytes: 2048,
+      maxDiffBytes: 4096,
       sessionsOverTime: [],
       byProject: [],
+      byProjectBranch: [],
     },
     loading: false,
     error: null,
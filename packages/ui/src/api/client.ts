@@ -1,4 +1,4 @@
-import type { AgenticMetrics, Session, SessionFilter, SessionStats, SessionSummary } from './types'
+import type { AgenticMetrics, CriticPrompt, Session, SessionFilter, SessionStats, SessionSummary } from './types'
 import type { ProjectListResponse, ProjectRecord } from '@/types/project'
 
 const API_BASE = import.meta.env.VITE_API_URL || 'http://localhost:3100'
@@ -66,20 +66,60 @@ export async function fetchSession(projectId: string, id: string): Promise<Sessi
   return res.json()
 }
 
+/** Replace a session's notes; blank text clears them. */
+export async function updateSessionNotes(projectId: string, id: string, notes: string): Promise<Session> {
+  const res = await fetch(`${API_BASE}/api/projects/${encodeURIComponent(projectId)}/sessions/${encodeURIComponent(id)}`, {
+    method: 'PATCH',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ notes }),
+  })
+  if (!res.ok) throw new Error(`Failed to save notes: ${res.statusText}`)
+  return res.json()
+}
+
 export async function fetchSessionDiff(projectId: string, id: string): Promise<string> {
   const res = await fetch(`${API_BASE}/api/projects/${encodeURIComponent(projectId)}/sessions/${encodeURIComponent(id)}/diff`)
   if (!res.ok) throw new Error(`Failed to fetch diff: ${res.statusText}`)
   return res.text()
 }
 
+/** URL of a session's standalone HTML report (served as a download). */
+export function sessionExportUrl(projectId: string, id: string, format: 'html' | 'json' = 'html'): string {
+  return `${API_BASE}/api/projects/${encodeURIComponent(projectId)}/sessions/${encodeURIComponent(id)}/export?format=${format}`
+}
+
+/** The browser's current UTC offset, e.g. `-05:00`, so daily counts follow the viewer's days. */
+function browserTimeZone(): string {
+  const minutes = -new Date().getTimezoneOffset()
+  const sign = minutes < 0 ? '-' : '+'
+  const abs = Math.abs(minutes)
+  const pad = (n: number) => String(n).padStart(2, '0')
+  return `${sign}${pad(Math.floor(abs / 60))}:${pad(abs % 60)}`
+}
+
 export async function fetchStats(projectId: string): Promise<SessionStats> {
-  const res = await fetch(`${API_BASE}/api/projects/${encodeURIComponent(projectId)}/stats`)
+  const tz = encodeURIComponent(browserTimeZone())
+  const res = await fetch(`${API_BASE}/api/projects/${encodeURIComponent(projectId)}/stats?tz=${tz}`)
   if (!res.ok) throw new Error(`Failed to fetch stats: ${res.statusText}`)
   return res.json()
 }
 
-export async function fetchMetrics(projectId: string): Promise<AgenticMetrics> {
-  const res = await fetch(`${API_BASE}/api/projects/${encodeURIComponent(projectId)}/metrics`)
+/** Metrics; `recent` limits the critic and file metrics to that many of the latest sessions. */
+export async function fetchMetrics(projectId: string, recent?: number): Promise<AgenticMetrics> {
+  const params = new URLSearchParams({ tz: browserTimeZone() })
+  if (recent !== undefined) params.set('recent', String(recent))
+  const res = await fetch(`${API_BASE}/api/projects/${encodeURIComponent(projectId)}/metrics?${params}`)
   if (!res.ok) throw new Error(`Failed to fetch metrics: ${res.statusText}`)
   return res.json()
 }
+
+/** The critic prompt for an iteration; the latest session's last iteration by default. */
+export async function fetchCriticPrompt(sessionId?: string, iteration?: number): Promise<CriticPrompt> {
+  const params = new URLSearchParams()
+  if (sessionId) params.set('session', sessionId)
+  if (iteration !== undefined) params.set('iteration', String(iteration))
+  const query = params.toString()
+  const res = await fetch(`${API_BASE}/api/critic-prompt${query ? `?${query}` : ''}`)
+  if (!res.ok) throw new Error(`Failed to fetch critic prompt: ${res.statusText}`)
+  return res.json()
+}
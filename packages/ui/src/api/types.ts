@@ -10,6 +10,11 @@ export interface SessionSummary {
   confidence: number | null
   actorAgent: string
   criticAgent: string
+  promptBytes: number | null
+  maxDiffBytes: number | null
+  totalFilesChanged: number | null
+  /** Git branch checked out when the session started */
+  branch?: string | null
 }
 
 export interface SessionStart {
@@ -23,6 +28,27 @@ export interface SessionStart {
   maxIterations: number | null
 }
 
+export interface ChecklistItem {
+  item: string
+  met: boolean
+}
+
+export interface RubricResult {
+  item: string
+  /** Whether DONE required this criterion to be met */
+  mustPass: boolean
+  /** Null when the critic did not report the criterion */
+  met: boolean | null
+}
+
+/** Changed files against the files the prompt said to modify */
+export interface ScopeAdherence {
+  /** Changed files the prompt did not list */
+  unexpected: string[]
+  /** Listed files with no changes */
+  untouched: string[]
+}
+
 export interface Iteration {
   iterationNumber: number
   phase: string
@@ -34,6 +60,33 @@ export interface Iteration {
   gitFilesChanged: number | null
   criticDecision: string | null
   feedback: string | null
+  /** Raw critic response, kept even when the decision failed to parse */
+  criticRaw?: string | null
+  /** Confidence the critic gave with this decision, if any (0-1) */
+  criticConfidence?: number | null
+  /** Acceptance criteria status reported by the critic */
+  criticChecklist?: ChecklistItem[]
+  /** Project rubric status reported by the critic, one entry per criterion */
+  criticRubric?: RubricResult[]
+  /** Set when the prompt lists files to modify */
+  scopeAdherence?: ScopeAdherence | null
+  /** How a person ruled on the critic's call in an interactive run */
+  humanReview?: 'accepted' | 'rejected' | 'feedback' | null
+  /** The full prompt the actor was given, instructions and feedback included */
+  actorPrompt?: string | null
+  /** Whether a critic reset discarded this iteration's changes */
+  resetApplied?: boolean
+  /** Whether the verify command passed after this iteration, when one ran */
+  verifyPassed?: boolean | null
+  diffBytes?: number | null
+  /** Coverage percentage after this iteration, when a coverage command ran */
+  coveragePercent?: number | null
+  /** Change in coverage against the session baseline, in percentage points */
+  coverageDelta?: number | null
+  /** Whether the diff a patch-mode actor printed applied */
+  patchApplied?: boolean | null
+  /** Paths of the files this iteration's diff touched */
+  files?: string[]
   timestamp: string
 }
 
@@ -73,6 +126,18 @@ export interface Session {
   durationSecs: number | null
   startedAt: string
   endedAt: string | null
+  /** Files the prompt was read from, in order; empty for an inline prompt */
+  promptFiles?: string[]
+  /** Reference files given with --context-file, in order */
+  contextFiles?: string[]
+  /** Git branch checked out when the session started */
+  branch?: string | null
+  /** Feedback the actor started from, given with --seed-feedback */
+  seedFeedback?: string | null
+  /** Notes added after the run */
+  notes?: string | null
+  /** Whether the verify command passed before the first iteration */
+  verifyBaseline?: boolean | null
   iterations: Iteration[]
 }
 
@@ -87,6 +152,24 @@ export interface ProjectStats {
   successRate: number
 }
 
+export interface FileTouches {
+  project: string
+  path: string
+  /** Iterations whose diff touched the file */
+  iterations: number
+  /** Sessions with at least one such iteration */
+  sessions: number
+}
+
+export interface ProjectBranchStats {
+  project: string
+  /** null for sessions recorded without a branch */
+  branch: string | null
+  total: number
+  successes: number
+  successRate: number
+}
+
 export interface SessionStats {
   totalSessions: number
   successRate: number
@@ -96,6 +179,14 @@ export interface SessionStats {
   byProject: ProjectStats[]
 }
 
+/** Rendered critic prompt from `GET /api/critic-prompt` */
+export interface CriticPrompt {
+  prompt: string
+  /** Session the prompt was filled from; null when it shows placeholders */
+  sessionId: string | null
+  iteration: number | null
+}
+
 export interface AgenticMetrics {
   // Session metrics
   totalSessions: number
@@ -108,13 +199,22 @@ export interface AgenticMetrics {
 
   // Critic metrics
   totalIterations: number
+  /** Sessions the critic and file metrics come from; fewer than totalSessions when limited to recent ones */
+  iterationSessions: number
   criticApprovalRate: number
   avgFeedbackLength: number
   improvementRate: number
 
+  // Size metrics
+  avgPromptBytes: number
+  maxDiffBytes: number
+  /** Files changed in the most iterations, most first */
+  mostTouchedFiles?: FileTouches[]
+
   // Breakdowns
   sessionsOverTime: DayCount[]
   byProject: ProjectStats[]
+  byProjectBranch: ProjectBranchStats[]
 }
 
 export interface SessionFilter {
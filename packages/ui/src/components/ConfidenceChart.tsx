@@ -0,0 +1,68 @@
+import { LineChart, Line, XAxis, YAxis, Tooltip, ResponsiveContainer } from 'recharts'
+import type { Iteration } from '@/api/types'
+
+interface ConfidenceChartProps {
+  iterations: Iteration[]
+}
+
+/**
+ * Critic confidence per iteration. Iterations where the critic gave no
+ * confidence leave a gap in the line. Renders nothing when no iteration
+ * has a confidence.
+ */
+export function ConfidenceChart({ iterations }: ConfidenceChartProps) {
+  if (!iterations.some((iter) => iter.criticConfidence != null)) {
+    return null
+  }
+
+  const data = iterations.map((iter) => ({
+    iteration: iter.iterationNumber,
+    confidence: iter.criticConfidence != null ? Math.round(iter.criticConfidence * 100) : null,
+  }))
+
+  return (
+    <section>
+      <h2 className="text-xs text-muted-foreground uppercase tracking-wider mb-4">
+        Critic Confidence
+      </h2>
+      <div className="rounded-lg border border-border bg-card p-4">
+        <ResponsiveContainer width="100%" height={140}>
+          <LineChart data={data}>
+            <XAxis
+              dataKey="iteration"
+              tick={{ fontSize: 10, fill: '#a1a1aa' }}
+              axisLine={{ stroke: '#27272a' }}
+              tickLine={false}
+            />
+            <YAxis
+              domain={[0, 100]}
+              tick={{ fontSize: 10, fill: '#a1a1aa' }}
+              axisLine={{ stroke: '#27272a' }}
+              tickLine={false}
+              tickFormatter={(value) => `${value}%`}
+              width={36}
+            />
+            <Tooltip
+              contentStyle={{
+                backgroundColor: '#111118',
+                border: '1px solid #27272a',
+                borderRadius: '0.375rem',
+                fontSize: '0.75rem',
+              }}
+              labelFormatter={(value) => `Iteration ${value}`}
+              formatter={(value) => [`${value}%`, 'Confidence']}
+            />
+            <Line
+              type="monotone"
+              dataKey="confidence"
+              stroke="#f59e0b"
+              strokeWidth={2}
+              dot={{ r: 3, fill: '#f59e0b' }}
+              connectNulls={false}
+            />
+          </LineChart>
+        </ResponsiveContainer>
+      </div>
+    </section>
+  )
+}
@@ -42,6 +42,9 @@ export function CriticTrail({ iterations }: CriticTrailProps) {
               )}>
                 {iter.criticDecision ?? iter.phase}
               </span>
+              {iter.humanReview && (
+                <span className="text-xs text-muted-foreground">reviewer: {iter.humanReview}</span>
+              )}
             </div>
             {iter.feedback ? (
               <div className="text-sm whitespace-pre-wrap text-foreground/90">
@@ -56,6 +59,52 @@ export function CriticTrail({ iterations }: CriticTrailProps) {
             ) : (
               <div className="text-sm text-muted-foreground italic">No feedback provided</div>
             )}
+            {iter.criticChecklist && iter.criticChecklist.length > 0 && (
+              <ul className="mt-2 space-y-0.5 text-xs">
+                {iter.criticChecklist.map((c, i) => (
+                  <li key={i} className={c.met ? 'text-success' : 'text-destructive'}>
+                    {c.met ? '✓' : '✗'} <span className="text-foreground/80">{c.item}</span>
+                  </li>
+                ))}
+              </ul>
+            )}
+            {iter.criticRubric && iter.criticRubric.length > 0 && (
+              <ul className="mt-2 space-y-0.5 text-xs">
+                {iter.criticRubric.map((r, i) => (
+                  <li
+                    key={i}
+                    className={r.met === null ? 'text-muted-foreground' : r.met ? 'text-success' : 'text-destructive'}
+                  >
+                    {r.met === null ? '?' : r.met ? '✓' : '✗'}{' '}
+                    <span className="text-foreground/80">{r.item}</span>{' '}
+                    <span className="text-muted-foreground">(rubric, {r.mustPass ? 'must' : 'should'})</span>
+                  </li>
+                ))}
+              </ul>
+            )}
+            {iter.scopeAdherence &&
+              (iter.scopeAdherence.unexpected.length > 0 || iter.scopeAdherence.untouched.length > 0) && (
+              <ul className="mt-2 space-y-0.5 text-xs text-warning">
+                {iter.scopeAdherence.unexpected.map((f) => (
+                  <li key={`unexpected-${f}`}>
+                    <span className="font-mono text-foreground/80">{f}</span> (not listed)
+                  </li>
+                ))}
+                {iter.scopeAdherence.untouched.map((f) => (
+                  <li key={`untouched-${f}`}>
+                    <span className="font-mono text-foreground/80">{f}</span> (not changed)
+                  </li>
+                ))}
+              </ul>
+            )}
+            {iter.criticRaw && (
+              <details className="mt-2">
+                <summary className="cursor-pointer text-xs text-muted-foreground">
+                  Raw critic response
+                </summary>
+                <pre className="mt-2 text-xs whitespace-pre-wrap text-foreground/80">{iter.criticRaw}</pre>
+              </details>
+            )}
           </div>
         ))}
       </div>
@@ -154,6 +154,18 @@ export function IterationConversation({ iterations }: IterationConversationProps
                       Critic
                     </span>
                     <DecisionBadge decision={iter.criticDecision ?? iter.phase} />
+                    {iter.coveragePercent != null && (
+                      <span
+                        className={cn(
+                          'text-xs text-muted-foreground',
+                          iter.coverageDelta != null && iter.coverageDelta < 0 && 'text-destructive'
+                        )}
+                      >
+                        coverage {iter.coveragePercent.toFixed(1)}%
+                        {iter.coverageDelta != null &&
+                          ` (${iter.coverageDelta >= 0 ? '+' : ''}${iter.coverageDelta.toFixed(1)})`}
+                      </span>
+                    )}
                   </div>
                   {iter.feedback && <CopyButton content={iter.feedback} />}
                 </div>
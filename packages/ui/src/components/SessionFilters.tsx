@@ -11,6 +11,8 @@ const OUTCOMES = [
   { value: 'failed', label: 'failed' },
   { value: 'interrupted', label: 'interrupted' },
   { value: 'max_iterations_reached', label: 'max iter' },
+  { value: 'no_progress', label: 'no progress' },
+  { value: 'cost_budget_exceeded', label: 'over budget' },
 ]
 
 export function SessionFilters({ onFilterChange }: SessionFiltersProps) {
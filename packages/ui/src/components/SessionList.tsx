@@ -12,7 +12,11 @@ function StatusDot({ outcome }: { outcome: string | null }) {
   const isActive = outcome === null
   const isSuccess = outcome === 'success'
   const isFailed = outcome === 'failed'
-  const isWarning = outcome === 'interrupted' || outcome === 'max_iterations_reached'
+  const isWarning =
+    outcome === 'interrupted' ||
+    outcome === 'max_iterations_reached' ||
+    outcome === 'no_progress' ||
+    outcome === 'cost_budget_exceeded'
 
   return (
     <div
@@ -96,6 +100,14 @@ export function SessionList({ sessions, loading }: SessionListProps) {
                     <span>{formatDuration(s.durationSecs)}</span>
                   </>
                 )}
+                {!!s.totalFilesChanged && (
+                  <>
+                    <span className="opacity-50">/</span>
+                    <span>
+                      {s.totalFilesChanged} {s.totalFilesChanged === 1 ? 'file' : 'files'}
+                    </span>
+                  </>
+                )}
               </div>
             </div>
           </div>
@@ -0,0 +1,67 @@
+import { useState } from 'react'
+import { updateSessionNotes } from '@/api/client'
+import { cn } from '@/lib/utils'
+
+/**
+ * SessionNotes - Editable notes on a finished session.
+ *
+ * @param projectId - Project the session belongs to
+ * @param sessionId - Session to save the notes on
+ * @param notes - The session's current notes, if any
+ */
+interface SessionNotesProps {
+  projectId: string
+  sessionId: string
+  notes: string | null
+}
+
+export function SessionNotes({ projectId, sessionId, notes }: SessionNotesProps) {
+  const [saved, setSaved] = useState(notes ?? '')
+  const [draft, setDraft] = useState(notes ?? '')
+  const [saving, setSaving] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+
+  const handleSave = async () => {
+    try {
+      setSaving(true)
+      const session = await updateSessionNotes(projectId, sessionId, draft)
+      setSaved(session.notes ?? '')
+      setDraft(session.notes ?? '')
+      setError(null)
+    } catch (e) {
+      setError(e instanceof Error ? e.message : 'Failed to save notes')
+    } finally {
+      setSaving(false)
+    }
+  }
+
+  const dirty = draft !== saved
+
+  return (
+    <div className="bg-surface rounded-lg border border-border overflow-hidden">
+      <div className="flex items-center justify-between px-4 py-2 border-b border-border bg-elevated/30">
+        <span className="text-xs text-muted-foreground uppercase tracking-wider font-medium">
+          Notes
+        </span>
+        <button
+          onClick={handleSave}
+          disabled={!dirty || saving}
+          className={cn(
+            'text-xs transition-colors',
+            dirty && !saving ? 'text-amber hover:text-foreground' : 'text-muted-foreground'
+          )}
+        >
+          {saving ? 'Saving...' : 'Save'}
+        </button>
+      </div>
+      <textarea
+        value={draft}
+        onChange={(e) => setDraft(e.target.value)}
+        placeholder="What happened after the run: merged, reverted, follow-ups..."
+        rows={4}
+        className="w-full p-4 bg-transparent text-sm leading-relaxed resize-y focus:outline-none"
+      />
+      {error && <div className="px-4 pb-3 text-xs text-destructive">{error}</div>}
+    </div>
+  )
+}
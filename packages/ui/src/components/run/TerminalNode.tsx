@@ -16,7 +16,9 @@ export const TerminalNode = memo(function TerminalNode({
     success: 'border-success/50 bg-success/10 text-success',
     failed: 'border-destructive/50 bg-destructive/10 text-destructive',
     max_iterations_reached: 'border-amber/50 bg-amber-glow text-amber',
-    user_interrupted: 'border-amber/50 bg-amber-glow text-amber',
+    no_progress: 'border-amber/50 bg-amber-glow text-amber',
+    cost_budget_exceeded: 'border-amber/50 bg-amber-glow text-amber',
+    interrupted: 'border-amber/50 bg-amber-glow text-amber',
     starting: 'border-border bg-elevated/50 text-dim',
   }
 
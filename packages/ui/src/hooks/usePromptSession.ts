@@ -169,11 +169,18 @@ interface StoredSession {
   enabledSkills?: string[]
 }
 
-export function usePromptSession() {
+interface UsePromptSessionOptions {
+  /** Called with non-fatal notices from the agent stream, e.g. a long silence */
+  onWarning?: (message: string) => void
+}
+
+export function usePromptSession({ onWarning }: UsePromptSessionOptions = {}) {
   const projectId = useCurrentProject()
   const [state, setState] = useState<PromptSessionState>({ status: 'loading_context' })
   const [isSaving, setIsSaving] = useState(false)
   const abortControllerRef = useRef<AbortController | null>(null)
+  const onWarningRef = useRef(onWarning)
+  onWarningRef.current = onWarning
 
   // Load context and restore session on mount
   useEffect(() => {
@@ -341,6 +348,8 @@ export function usePromptSession() {
             },
           })
           return
+        } else if (chunk.startsWith('__WARNING__')) {
+          onWarningRef.current?.(chunk.slice('__WARNING__'.length))
         } else if (chunk.startsWith('__PROMPT_DRAFT__')) {
           currentDraft = chunk.slice('__PROMPT_DRAFT__'.length)
         } else {
@@ -413,7 +422,10 @@ export function usePromptSession() {
       content,
     }
 
-    const updatedMessages = [...messages, userMessage]
+    // Re-sending a message whose reply was lost answers it where it is
+    const last = messages[messages.length - 1]
+    const resend = last?.role === 'user' && last.content === content
+    const updatedMessages = resend ? messages : [...messages, userMessage]
 
     // Transition to streaming with user message
     setState({
@@ -433,7 +445,7 @@ export function usePromptSession() {
       const assistantId = generateId()
       let currentDraft = promptDraft
 
-      for await (const chunk of sendPromptMessage(projectId, sessionId, content, enabledSkills)) {
+      for await (const chunk of sendPromptMessage(projectId, sessionId, content, enabledSkills, resend)) {
         if (chunk.startsWith('__ERROR__')) {
           const errorMsg = chunk.slice('__ERROR__'.length)
           setState({
@@ -454,6 +466,8 @@ export function usePromptSession() {
             },
           })
           return
+        } else if (chunk.startsWith('__WARNING__')) {
+          onWarningRef.current?.(chunk.slice('__WARNING__'.length))
         } else if (chunk.startsWith('__PROMPT_DRAFT__')) {
           currentDraft = chunk.slice('__PROMPT_DRAFT__'.length)
         } else {
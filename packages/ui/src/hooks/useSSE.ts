@@ -2,8 +2,8 @@ import { useEffect, useRef } from 'react'
 
 /**
  * Hook that triggers a callback at regular intervals for auto-refresh.
- * This replaces the SSE-based approach since the /api/sessions/live endpoint
- * is not currently implemented.
+ * Used instead of the /api/sessions/live SSE stream so that list views
+ * also pick up edits and deletions, which the stream does not report.
  *
  * @param onRefresh Callback to trigger on each interval
  * @param intervalMs Refresh interval in milliseconds (default: 30000ms / 30s)
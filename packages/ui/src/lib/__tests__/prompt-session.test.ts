@@ -228,7 +228,7 @@ describe('prompt-session API client', () => {
         {
           method: 'POST',
           headers: { 'Content-Type': 'application/json' },
-          body: JSON.stringify({ workingDir: '/test/project', content: '# Test content' }),
+          body: JSON.stringify({ workingDir: '/test/project', content: '# Test content', dryRun: false }),
         }
       )
       expect(result).toEqual(mockResponse)
@@ -323,7 +323,7 @@ describe('prompt-session API client', () => {
       expect(mockFetch).toHaveBeenCalledWith(
         expect.stringContaining('/api/prompt-session/test-123/message'),
         expect.objectContaining({
-          body: JSON.stringify({ content: 'Hello', enabledSkills: ['brainstorming', 'system-design'] }),
+          body: JSON.stringify({ content: 'Hello', enabledSkills: ['brainstorming', 'system-design'], resend: false }),
         })
       )
     })
@@ -123,7 +123,11 @@ function terminalLabel(outcome: string): string {
       return 'Error'
     case 'max_iterations_reached':
       return 'Max Iterations'
-    case 'user_interrupted':
+    case 'no_progress':
+      return 'No Progress'
+    case 'cost_budget_exceeded':
+      return 'Over Budget'
+    case 'interrupted':
       return 'Interrupted'
     default:
       return outcome
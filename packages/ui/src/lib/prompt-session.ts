@@ -10,6 +10,12 @@ export interface CreateSessionResponse {
 
 export interface SavePromptResponse {
   path: string
+  /** Content that would be written; only set for a dry run */
+  content?: string
+  /** Whether prompt.md already exists; only set for a dry run */
+  exists?: boolean
+  /** Where the overwritten prompt.md was copied to, if there was one */
+  backupPath?: string
 }
 
 // ============================================================================
@@ -45,11 +51,17 @@ export interface SavePromptSessionRequest {
   projectName: string
   content?: string
   sessionState: SessionStatePayload
+  /** Fold into an existing prompt with identical content in the same project */
+  dedup?: boolean
+  /** Reject the save with 409 if the prompt changed since this timestamp */
+  expectedUpdatedAt?: string
 }
 
 export interface SavePromptSessionResponse {
   id: string
   updatedAt: string
+  /** Prompt completeness score (0-100) */
+  completion: number
 }
 
 export interface PromptSummary {
@@ -76,6 +88,12 @@ export interface GetPromptResponse {
   content?: string
   sessionState: SessionStatePayload
   parentIds: string[]
+  /** Prompt completeness score (0-100) */
+  completion: number
+  /** Whether the agent is still writing a reply to the last message */
+  replyInProgress: boolean
+  /** Last user message, when its reply was lost (e.g. the server restarted) */
+  danglingMessage?: Message
   createdAt: string
   updatedAt: string
 }
@@ -131,11 +149,12 @@ export async function* sendPromptMessage(
   sessionId: string,
   content: string,
   enabledSkills?: string[],
+  resend = false,
 ): AsyncGenerator<string, void, unknown> {
   const res = await fetch(`${API_BASE}/api/projects/${encodeURIComponent(projectId)}/prompt-session/${encodeURIComponent(sessionId)}/message`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
-    body: JSON.stringify({ content, enabledSkills }),
+    body: JSON.stringify({ content, enabledSkills, resend }),
   })
 
   if (!res.ok) throw new Error(`Failed to send message: ${res.statusText}`)
@@ -161,6 +180,8 @@ export async function* sendPromptMessage(
           const parsed = JSON.parse(data)
           if (parsed.error) {
             yield `__ERROR__${parsed.error}`
+          } else if (parsed.warning) {
+            yield `__WARNING__${parsed.warning}`
           } else if (parsed.content) {
             yield parsed.content
           }
@@ -176,12 +197,13 @@ export async function* sendPromptMessage(
 export async function savePrompt(
   projectId: string,
   workingDir: string,
-  content: string
+  content: string,
+  dryRun = false
 ): Promise<SavePromptResponse> {
   const res = await fetch(`${API_BASE}/api/projects/${encodeURIComponent(projectId)}/prompt/save`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
-    body: JSON.stringify({ workingDir, content }),
+    body: JSON.stringify({ workingDir, content, dryRun }),
   })
   if (!res.ok) throw new Error(`Failed to save prompt: ${res.statusText}`)
   return res.json()
@@ -20,6 +20,7 @@ function LoadingSkeleton() {
 }
 
 export function PromptBuilder() {
+  const { addToast } = useToast()
   const {
     state,
     session,
@@ -35,9 +36,8 @@ export function PromptBuilder() {
     clearError,
     newPrompt,
     loadPrompt,
-  } = usePromptSession()
+  } = usePromptSession({ onWarning: (message) => addToast(message, 'info') })
 
-  const { addToast } = useToast()
   const [historyOpen, setHistoryOpen] = useState(false)
   const [previewOpen, setPreviewOpen] = useState(true)
   const [splitPercent, setSplitPercent] = useState(50)
@@ -2,10 +2,13 @@ import { useParams, useNavigate } from 'react-router-dom'
 import { useCurrentProject } from '@/hooks/useProject'
 import { useSession } from '@/hooks/useSession'
 import { IterationConversation } from '@/components/IterationConversation'
+import { ConfidenceChart } from '@/components/ConfidenceChart'
 import { ContentBlock } from '@/components/ContentBlock'
 import { CopyButton } from '@/components/CopyButton'
 import { RunInsights } from '@/components/run/RunInsights'
+import { SessionNotes } from '@/components/SessionNotes'
 import { formatDuration } from '@/lib/utils'
+import { sessionExportUrl } from '@/api/client'
 import { useState } from 'react'
 import { cn } from '@/lib/utils'
 
@@ -51,6 +54,8 @@ export function SessionDetail() {
     active: 'bg-cyan',
     interrupted: 'bg-amber',
     max_iterations_reached: 'bg-amber',
+    no_progress: 'bg-amber',
+    cost_budget_exceeded: 'bg-amber',
   }
   const outcomeTextColor: Record<string, string> = {
     success: 'text-success',
@@ -58,6 +63,8 @@ export function SessionDetail() {
     active: 'text-cyan',
     interrupted: 'text-amber',
     max_iterations_reached: 'text-amber',
+    no_progress: 'text-amber',
+    cost_budget_exceeded: 'text-amber',
   }
 
   const tabs: { key: Tab; label: string }[] = [
@@ -116,6 +123,13 @@ export function SessionDetail() {
             <span className="text-muted-foreground">
               {session.iterations.length} iteration{session.iterations.length !== 1 ? 's' : ''}
             </span>
+
+            <a
+              href={sessionExportUrl(projectId, session.id)}
+              className="text-muted-foreground hover:text-foreground"
+            >
+              Export HTML
+            </a>
           </div>
         </div>
       </div>
@@ -150,19 +164,31 @@ export function SessionDetail() {
       {activeTab !== 'run' && (
         <div className="min-h-[400px]">
           {activeTab === 'prompt' && (
-            <ContentBlock
-              label="Prompt"
-              content={session.prompt}
-              markdown
-            />
+            <div className="space-y-4">
+              <ContentBlock
+                label="Prompt"
+                content={session.prompt}
+                markdown
+              />
+              {session.seedFeedback && (
+                <ContentBlock
+                  label="Seed Feedback"
+                  content={session.seedFeedback}
+                  markdown
+                />
+              )}
+            </div>
           )}
 
           {activeTab === 'iterations' && (
-            <IterationConversation iterations={session.iterations} />
+            <div className="space-y-6">
+              <ConfidenceChart iterations={session.iterations} />
+              <IterationConversation iterations={session.iterations} />
+            </div>
           )}
 
           {activeTab === 'summary' && (
-            <div>
+            <div className="space-y-4">
               {session.summary ? (
                 <div className="space-y-4">
                   <ContentBlock
@@ -181,6 +207,7 @@ export function SessionDetail() {
                   {session.endedAt ? 'No summary available.' : 'Session in progress...'}
                 </div>
               )}
+              <SessionNotes projectId={projectId} sessionId={session.id} notes={session.notes ?? null} />
             </div>
           )}
 
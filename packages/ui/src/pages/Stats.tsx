@@ -111,7 +111,9 @@ export function Stats() {
                 />
               </div>
               <div className="text-xs text-muted-foreground mt-4 text-center">
-                (across {metrics.totalIterations} iterations)
+                (across {metrics.totalIterations} iterations
+                {metrics.iterationSessions < metrics.totalSessions &&
+                  ` from the ${metrics.iterationSessions} latest sessions`})
               </div>
             </div>
           </section>
@@ -169,6 +171,48 @@ export function Stats() {
               </div>
             </section>
           )}
+
+          {/* By Project and Branch */}
+          {metrics.byProjectBranch.some((g) => g.branch !== null) && (
+            <section>
+              <h2 className="text-xs text-muted-foreground uppercase tracking-wider mb-4">
+                By Project and Branch
+              </h2>
+              <div className="rounded-lg border border-border bg-card p-4">
+                <DotChart
+                  projects={metrics.byProjectBranch.map((g) => ({
+                    project: `${g.project} @ ${g.branch ?? '(no branch)'}`,
+                    total: g.total,
+                    successRate: g.successRate,
+                  }))}
+                />
+              </div>
+            </section>
+          )}
+
+          {/* Most Touched Files */}
+          {metrics.mostTouchedFiles && metrics.mostTouchedFiles.length > 0 && (
+            <section>
+              <h2 className="text-xs text-muted-foreground uppercase tracking-wider mb-4">
+                Most Touched Files
+              </h2>
+              <div className="rounded-lg border border-border bg-card p-4">
+                <ul className="space-y-1 text-sm font-mono">
+                  {metrics.mostTouchedFiles.map((f) => (
+                    <li key={`${f.project}/${f.path}`} className="flex justify-between gap-4">
+                      <span className="truncate">
+                        <span className="text-muted-foreground">{f.project}/</span>
+                        {f.path}
+                      </span>
+                      <span className="text-muted-foreground shrink-0">
+                        {f.iterations} iterations · {f.sessions} sessions
+                      </span>
+                    </li>
+                  ))}
+                </ul>
+              </div>
+            </section>
+          )}
         </div>
       </div>
     </div>